@@ -0,0 +1,133 @@
+//! Canonical JSON output, for when the output needs to be byte-for-byte
+//! reproducible, e.g. to hash it.
+//!
+//! The canonical form is pretty-printed with two spaces of indentation and:
+//!
+//! * Object keys sorted by their UTF-8 bytes, at every level
+//! * Floats in their shortest round-trip form, always with a `.` or an exponent,
+//!   with no `+` in positive exponents, and with negative zero written as `0.0`
+//! * `\n` line endings, a trailing newline, and no trailing whitespace
+//!
+//! It doesn't depend on how the `Value` was built, so entities that fill in
+//! their `meta` in different orders still produce the same output.
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Serializes `value` into canonical JSON.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    let value = serde_json::to_value(value)?;
+
+    let mut out = String::new();
+    write_value(&mut out, &value, 0);
+    out.push('\n');
+
+    Ok(out)
+}
+
+fn write_value(out: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, n),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            if items.is_empty() {
+                out.push_str("[]");
+                return;
+            }
+
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_value(out, item, depth + 1);
+            }
+            newline(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) => {
+            if map.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_string(out, key);
+                out.push_str(": ");
+                write_value(out, value, depth + 1);
+            }
+            newline(out, depth);
+            out.push('}');
+        }
+    }
+}
+
+fn write_number(out: &mut String, n: &Number) {
+    match n.as_f64() {
+        Some(f) if n.is_f64() => {
+            // `-0.0 == 0.0`, so this normalizes negative zero
+            let f = if f == 0.0 { 0.0 } else { f };
+            // Formatted here rather than by serde_json, whose float formatting
+            // has changed between releases. Non-finite floats can't be
+            // represented in JSON, so the serializer has already turned them
+            // into nulls.
+            let mut buffer = ryu::Buffer::new();
+            out.push_str(&buffer.format_finite(f).replace("e+", "e"));
+        }
+        _ => out.push_str(&n.to_string()),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    // Escapes control characters, so carriage returns in strings don't
+    // end up as line endings in the output
+    out.push_str(&serde_json::to_string(s).expect("strings always serialize"));
+}
+
+fn newline(out: &mut String, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sorts_keys_at_every_level() {
+        let mut meta = serde_json::Map::new();
+        meta.insert("zebra".to_owned(), json!(1));
+        meta.insert("apple".to_owned(), json!({ "b": [], "a": {} }));
+
+        let output = to_string(&json!({ "meta": meta, "kind": "class" })).unwrap();
+
+        assert_eq!(
+            output,
+            "{\n  \"kind\": \"class\",\n  \"meta\": {\n    \"apple\": {\n      \"a\": {},\n      \"b\": []\n    },\n    \"zebra\": 1\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn formats_floats_and_strings_consistently() {
+        let output = to_string(&json!([1.0, -0.0, 0.1, 1e300, 2.5e-8, -7, "a\r\nb"])).unwrap();
+
+        assert_eq!(
+            output,
+            "[\n  1.0,\n  0.0,\n  0.1,\n  1e300,\n  2.5e-8,\n  -7,\n  \"a\\r\\nb\"\n]\n"
+        );
+        assert!(!output.lines().any(|l| l.ends_with(' ')));
+    }
+}
@@ -0,0 +1,89 @@
+//! The version of the JSON Dossier outputs, so that programs reading it can
+//! tell whether they understand it.
+//!
+//! The output is an object with the entities under `entities`, along with
+//! the `format_version` they are serialized in and the `generator` that
+//! produced them:
+//!
+//! ```json
+//! { "format_version": 2, "generator": "dossier 0.0.4", "entities": [] }
+//! ```
+//!
+//! Version 1 was a bare array of entities, without a version. In version 2,
+//! types that only use other types, like unions and predefined types, are
+//! also anonymous instead of getting an FQN based on where they are used.
+use serde_json::Value;
+use thiserror::Error;
+
+/// The version of the serialization of `Entity`. Bump it whenever that
+/// changes in a way readers can notice, like a field that is renamed or
+/// changes its type, and note the change in the changelog.
+pub const FORMAT_VERSION: u64 = 2;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FormatError {
+    #[error("Unsupported format version {0}, expected {FORMAT_VERSION}")]
+    UnsupportedVersion(u64),
+    #[error("Not Dossier output: {0}")]
+    Invalid(&'static str),
+}
+
+/// The entities in `output`, after checking that it is in the current
+/// format. Fails on anything else, including version 1 output, rather
+/// than reading entities that may mean something else.
+pub fn entities_of(output: &Value) -> Result<&Vec<Value>, FormatError> {
+    let object = match output {
+        Value::Array(_) => return Err(FormatError::UnsupportedVersion(1)),
+        Value::Object(object) => object,
+        _ => return Err(FormatError::Invalid("expected an object")),
+    };
+
+    let version = object
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .ok_or(FormatError::Invalid("no `format_version`"))?;
+    if version != FORMAT_VERSION {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+
+    object
+        .get("entities")
+        .and_then(|e| e.as_array())
+        .ok_or(FormatError::Invalid("no `entities`"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_entities_of_the_current_format() {
+        let output = json!({
+            "format_version": FORMAT_VERSION,
+            "generator": "dossier 0.0.4",
+            "entities": [{ "title": "Point" }],
+        });
+
+        assert_eq!(
+            entities_of(&output).unwrap(),
+            &vec![json!({ "title": "Point" })]
+        );
+    }
+
+    #[test]
+    fn fails_on_other_versions() {
+        let v1: Value =
+            serde_json::from_str(include_str!("../../fixtures/format/v1.json")).unwrap();
+        assert_eq!(entities_of(&v1), Err(FormatError::UnsupportedVersion(1)));
+
+        let future = json!({ "format_version": FORMAT_VERSION + 1, "entities": [] });
+        assert_eq!(
+            entities_of(&future),
+            Err(FormatError::UnsupportedVersion(FORMAT_VERSION + 1))
+        );
+
+        assert!(entities_of(&json!({ "entities": [] })).is_err());
+        assert!(entities_of(&json!("entities")).is_err());
+    }
+}
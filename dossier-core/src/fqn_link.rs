@@ -0,0 +1,65 @@
+//! Links from documentation to the entities it mentions.
+//!
+//! Parsers turn references in documentation comments, like TSDoc's
+//! `{@link Client}`, into Markdown links whose destination is the FQN of the
+//! entity they refer to, after `#fqn:`:
+//!
+//! ```markdown
+//! Connects with a [Client](#fqn:index.ts::Client).
+//! ```
+//!
+//! Destinations with spaces or parentheses are put in angle brackets, like
+//! `[Client](<#fqn:my lib/index.ts::Client>)`, so that they are still one
+//! Markdown link. Renderers find these links with `fqn_of` and point them at
+//! wherever they put the entity.
+
+/// What the destination of a link to an entity starts with
+pub const PREFIX: &str = "#fqn:";
+
+/// A Markdown link to the entity with the FQN `fqn`, labeled `label`
+pub fn link(label: &str, fqn: &str) -> String {
+    let destination = format!("{}{}", PREFIX, fqn);
+
+    if destination.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        format!("[{}](<{}>)", label, destination)
+    } else {
+        format!("[{}]({})", label, destination)
+    }
+}
+
+/// The FQN a link destination made by `link` points at, or `None` for any
+/// other destination
+pub fn fqn_of(destination: &str) -> Option<&str> {
+    let destination = destination
+        .strip_prefix('<')
+        .and_then(|d| d.strip_suffix('>'))
+        .unwrap_or(destination);
+
+    destination.strip_prefix(PREFIX)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn links_to_fqns() {
+        assert_eq!(
+            link("Client", "index.ts::Client"),
+            "[Client](#fqn:index.ts::Client)"
+        );
+        assert_eq!(fqn_of("#fqn:index.ts::Client"), Some("index.ts::Client"));
+        assert_eq!(fqn_of("https://example.com"), None);
+    }
+
+    #[test]
+    fn wraps_destinations_with_spaces() {
+        let link = link("Client", "my lib/index.ts::Client");
+
+        assert_eq!(link, "[Client](<#fqn:my lib/index.ts::Client>)");
+        assert_eq!(
+            fqn_of("<#fqn:my lib/index.ts::Client>"),
+            Some("my lib/index.ts::Client")
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Leaves out entities documented as internal, see `Context::strip_internal`.
+//!
+//! Unlike `retain_public`, nothing is kept for the sake of the entities that
+//! refer to it: the point of `@internal` is that it isn't documented at all.
+//! References to what was left out become anonymous instead, as if they had
+//! never resolved, so that no FQN in the output points at a missing entity.
+use crate::tree::Prune;
+use crate::Entity;
+
+/// The documentation tags that mark an entity as internal
+pub const TAGS: [&str; 2] = ["internal", "hidden"];
+
+/// Whether `entity` has one of the `TAGS` in `meta.tags`, where parsers
+/// keep the documentation tags they don't treat specially
+pub fn is_internal(entity: &Entity) -> bool {
+    entity.meta["tags"].as_array().is_some_and(|tags| {
+        tags.iter()
+            .any(|tag| tag["tag"].as_str().is_some_and(|t| TAGS.contains(&t)))
+    })
+}
+
+/// Removes the entities `is_internal` says are internal, along with their
+/// members, and makes references to any of them anonymous.
+pub fn strip_internal<F>(entities: &mut Vec<Entity>, is_internal: F)
+where
+    F: Fn(&Entity) -> bool,
+{
+    Prune::matching(is_internal).run(entities);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, fqn, reference, EntityBuilder};
+    use crate::Identity;
+    use serde_json::json;
+
+    #[test]
+    fn strips_internal_subtrees_and_references_into_them() {
+        let tagged = |tag: &str| json!({ "tags": [{ "tag": tag, "text": "" }] });
+        let mut entities = vec![
+            entity("test", fqn("index.test::Engine"))
+                .meta(tagged("internal"))
+                .members(vec![entity("test", fqn("index.test::Engine::start"))]),
+            entity("test", fqn("index.test::Car"))
+                .meta(tagged("since"))
+                .members(vec![
+                    entity("test", reference("index.test::Engine")),
+                    entity("test", reference("index.test::Engine::start")),
+                    entity("test", fqn("index.test::Car::debug")).meta(tagged("hidden")),
+                    entity("test", reference("index.test::Wheel")),
+                ]),
+        ];
+        strip_internal(&mut entities, is_internal);
+
+        assert_eq!(entities.len(), 1);
+        let car = &entities[0];
+        assert_eq!(car.identity, fqn("index.test::Car"));
+        assert_eq!(
+            car.members
+                .iter()
+                .map(|m| m.identity.clone())
+                .collect::<Vec<_>>(),
+            [
+                Identity::Anonymous,
+                Identity::Anonymous,
+                reference("index.test::Wheel")
+            ]
+        );
+    }
+}
@@ -2,11 +2,36 @@ use std::{
     fmt::Display,
     path::{Path, PathBuf},
     str::Utf8Error,
+    sync::Arc,
+    time::Duration,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod canonical;
+pub mod format;
+pub mod fqn_link;
+pub mod internal;
+pub mod locale;
+mod member_context;
+pub mod member_order;
+pub mod parser_pool;
+mod pipeline;
+pub mod root_dir;
+pub mod root_fqn;
+pub mod slug;
+pub mod source_text;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod tree;
+mod visibility;
+
+pub use format::FORMAT_VERSION;
+pub use member_context::MemberContext;
+pub use pipeline::{parse_files, FileLimits, FileMode, MinifiedFiles, ParseCache, MINIFIED_FILE};
+pub use visibility::retain_public;
+
 pub use indexmap;
 pub use serde_json;
 pub use tree_sitter;
@@ -16,6 +41,8 @@ pub type Result<T> = std::result::Result<T, DossierError>;
 #[derive(Error, Debug)]
 pub enum DossierError {
     UTF8Error(Utf8Error),
+    /// Reading an input file failed
+    IOError(PathBuf, std::io::Error),
 }
 
 impl Display for DossierError {
@@ -25,6 +52,9 @@ impl Display for DossierError {
             UTF8Error(error) => {
                 write!(f, "UTF8Error: {:?}", error)
             }
+            IOError(path, error) => {
+                write!(f, "Could not read {}: {}", path.display(), error)
+            }
         }
     }
 }
@@ -32,6 +62,8 @@ impl Display for DossierError {
 pub type MarkdownString = String;
 pub type FullyQualifiedName = String;
 
+/// Read back from the `fqn` or `refers_to` key of an entity, and anonymous
+/// if it has neither
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum Identity {
     /// The fully qualified name of an entity
@@ -50,7 +82,30 @@ impl Identity {
     }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+impl<'de> Deserialize<'de> for Identity {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Keys {
+            fqn: Option<FullyQualifiedName>,
+            refers_to: Option<FullyQualifiedName>,
+        }
+
+        let keys = Keys::deserialize(deserializer)?;
+        Ok(match (keys.fqn, keys.refers_to) {
+            (Some(fqn), _) => Identity::FQN(fqn),
+            (None, Some(fqn)) => Identity::Reference(fqn),
+            (None, None) => Identity::Anonymous,
+        })
+    }
+}
+
+/// Entities are read back from the output with `Deserialize`, e.g. to compare
+/// two versions of an API. Parsers always give entities an object as their
+/// `meta`, so a `meta` that was left out of the output for being empty is
+/// read back as an empty object.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Entity {
     /// The title for the entity. Usually the name of the class/function/module, etc.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -69,25 +124,69 @@ pub struct Entity {
     #[serde(skip_serializing_if = "Identity::is_anonymous")]
     pub identity: Identity,
     /// Child entities. E.g. classes may contain functions, modules may have child modules, etc.
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub members: Vec<Entity>,
     /// What context the entity is in. E.g. a type may be describing a parameter to a function, or a return type.
+    /// See `MemberContext` for the names it is serialized as.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub member_context: Option<String>,
+    pub member_context: Option<MemberContext>,
     /// The language of the entity
     pub language: String,
     /// The language of the entity
     pub source: Source,
     /// Arbitrary metadata different types of entities need to store
-    #[serde(skip_serializing_if = "value_is_empty")]
+    #[serde(default = "empty_meta", skip_serializing_if = "value_is_empty")]
     pub meta: serde_json::Value,
 }
 
+/// The path as a string with `/` as the separator, on every platform. Used
+/// in FQNs and in the output, so that they are the same everywhere.
+pub fn portable_path(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/")
+}
+
+/// `path` with `.` and `..` resolved without looking at the file system, so
+/// that different spellings of a path compare equal. Leading `..` that can't
+/// be resolved are kept.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized_path = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                // If there's a previous component and it's not "..", go up one level
+                if let Some(std::path::Component::Normal(_)) =
+                    normalized_path.components().next_back()
+                {
+                    normalized_path.pop();
+                } else {
+                    normalized_path.push("..");
+                }
+            }
+            std::path::Component::Normal(part) => normalized_path.push(part),
+            _ => {} // Ignore other components (RootDir, CurDir, Prefix)
+        }
+    }
+
+    normalized_path
+}
+
+fn serialize_portable_path<S: serde::Serializer>(
+    path: &Path,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&portable_path(path))
+}
+
 fn value_is_empty(value: &serde_json::Value) -> bool {
     value.is_null() || value.as_object().map(|o| o.is_empty()).unwrap_or(false)
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+fn empty_meta() -> serde_json::Value {
+    serde_json::Value::Object(Default::default())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Position in a source file.
 ///
 /// Contains the row and column number, as well as the byte offset from the start of the file,
@@ -101,31 +200,235 @@ pub struct Position {
     pub byte_offset: usize,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Metadata about the source of an `Entity`
 pub struct Source {
+    /// The path of the file, always written with `/` as the separator
+    #[serde(serialize_with = "serialize_portable_path")]
     pub file: PathBuf,
     /// Start position in the source file
     pub start: Position,
     /// End position in the source file
     pub end: Position,
-    /// Optional: Git repository URL for the file
+    /// Optional: Git repository URL for the file, pointing at the lines of the entity
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repository: Option<String>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, PartialEq)]
+/// A Git repository the parsed files are checked out from.
+///
+/// Used to generate links to the source of entities.
+pub struct Repository {
+    /// Web URL of the repository, e.g. `https://github.com/Doctave/dossier`
+    pub url: String,
+    /// The commit SHA (or branch name) to link to
+    pub revision: String,
+    /// Local directory the repository is checked out in
+    pub root: PathBuf,
+}
+
+impl Repository {
+    /// Generates a URL to the given file at the current revision, or `None`
+    /// if the file is not inside the repository.
+    ///
+    /// Relative paths are resolved from the current working directory.
+    pub fn file_url(&self, file: &Path) -> Option<String> {
+        let absolute = if file.is_absolute() {
+            file.to_owned()
+        } else {
+            std::env::current_dir().ok()?.join(file)
+        };
+        let absolute = absolute.canonicalize().unwrap_or(absolute);
+        let root = self.root.canonicalize().unwrap_or(self.root.clone());
+
+        let relative = absolute.strip_prefix(root).ok()?;
+        let relative = format!("{}", relative.display()).replace('\\', "/");
+
+        Some(format!(
+            "{}/blob/{}/{}",
+            self.url.trim_end_matches('/'),
+            self.revision,
+            relative
+        ))
+    }
+
+    /// Appends the line range between `start` and `end` to a URL generated
+    /// by `file_url`.
+    pub fn source_url(file_url: &str, start: &Position, end: &Position) -> String {
+        // Rows start from zero, but line numbers start from one
+        format!("{}#L{}-L{}", file_url, start.row + 1, end.row + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Reported when a file doesn't parse cleanly, see `helpers::syntax_error`
+pub const SYNTAX_ERROR: &str = "syntax-error";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+/// A problem found while parsing that doesn't stop us from producing output,
+/// like documentation that doesn't match the code it describes.
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable identifier for the kind of diagnostic, e.g. `unmatched-param-tag`
+    pub code: String,
+    pub message: String,
+    pub file: PathBuf,
+    /// Byte range in the file the diagnostic applies to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<std::ops::Range<usize>>,
+}
+
+impl Diagnostic {
+    pub fn warning(code: &str, message: impl Into<String>, file: &Path) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code: code.to_owned(),
+            message: message.into(),
+            file: file.to_owned(),
+            range: None,
+        }
+    }
+
+    pub fn with_range(mut self, range: std::ops::Range<usize>) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}: {}",
+            self.severity,
+            self.code,
+            self.file.display(),
+            self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
 /// A config passed into parsers.
 ///
-/// Placeholder for now, but in the future could contain information
-/// about the parsing context like the current repository, etc.
+/// Contains options that change what parsers emit. In the future could contain
+/// information about the parsing context like the current repository, etc.
 pub struct Context {
     namespace: Vec<String>,
+    /// Follow references through chains of type aliases, and record the
+    /// final target in `meta.resolves_to`. Off by default.
+    pub resolve_alias_chains: bool,
+    /// The repository the parsed files are in. If set, parsers will fill
+    /// in the `repository` field of each entity's `Source` with a link to it.
+    pub repository: Option<Repository>,
+    /// Diagnostics reported by the parsers
+    pub diagnostics: Vec<Diagnostic>,
+    /// How many threads parsers may use. Defaults to one per CPU.
+    pub jobs: Option<usize>,
+    /// When to treat input files as minified
+    pub file_limits: FileLimits,
+    /// Where parsers read the input files from. Defaults to the file system.
+    pub file_source: Arc<dyn FileSource>,
+    /// Only output the public API: exported symbols and members that aren't
+    /// private, plus whatever they refer to. Parsers may skip work on other
+    /// symbols. Off by default.
+    pub public_only: bool,
+    /// Copy the members classes and interfaces inherit into their entities,
+    /// with the `inherited` member context. Off by default.
+    pub include_inherited: bool,
+    /// For documentation written in several languages, the locale to use
+    /// the text of. See the `locale` module. Off by default.
+    pub doc_locale: Option<String>,
+    /// Record the FQN of the top-level entity each entity is nested in as
+    /// `meta.root_fqn`. See the `root_fqn` module. Off by default.
+    pub root_fqns: bool,
+    /// The `tsconfig.json` to map TypeScript imports with. If not set, each
+    /// file uses the nearest one in its directory or the ones above it.
+    pub tsconfig: Option<PathBuf>,
+    /// Where parsers report how long each file took to parse. Off by default.
+    pub instrumentation: Option<Arc<dyn Instrumentation>>,
+    /// Record the source code of each entity as `meta.source_text`. See the
+    /// `source_text` module. Off by default.
+    pub include_source_text: bool,
+    /// With `include_source_text`, cut the source text of entities off after
+    /// this many bytes. Not limited by default.
+    pub max_source_bytes: Option<usize>,
+    /// Name files relative to this directory, in FQNs and in the sources of
+    /// entities. See the `root_dir` module. Not set by default.
+    pub root: Option<PathBuf>,
+    /// Name files in FQNs by the modules they are imported as, like
+    /// `@myorg/client` or `mypkg.sub.module`, where parsers can tell. The
+    /// sources of entities keep the paths of the files. Off by default.
+    pub module_names: bool,
+    /// Leave out entities documented as `@internal` or `@hidden`, with their
+    /// members. See the `internal` module. Off by default.
+    pub strip_internal: bool,
+    /// Give up on files that take longer than this to parse, with a
+    /// `parse-timeout` warning, so that no file can hold up the others. See
+    /// the `parser_pool` module. Not limited by default.
+    pub parse_timeout: Option<Duration>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            namespace: Default::default(),
+            resolve_alias_chains: Default::default(),
+            repository: Default::default(),
+            diagnostics: Default::default(),
+            jobs: Default::default(),
+            file_limits: Default::default(),
+            file_source: Arc::new(FileSystem),
+            public_only: false,
+            include_inherited: false,
+            doc_locale: None,
+            root_fqns: false,
+            tsconfig: None,
+            instrumentation: None,
+            include_source_text: false,
+            max_source_bytes: None,
+            root: None,
+            module_names: false,
+            strip_internal: false,
+            parse_timeout: None,
+        }
+    }
 }
 
 impl<'a> Context {
     pub fn new() -> Self {
-        Self { namespace: vec![] }
+        Self::default()
+    }
+
+    /// Makes parsers read input files from `source` instead of the file system.
+    pub fn with_file_source<S: FileSource + 'static>(mut self, source: S) -> Self {
+        self.file_source = Arc::new(source);
+        self
+    }
+
+    /// `path` as files are named in FQNs and in the output: relative to
+    /// `root`, if it is set and the file is in it
+    pub fn relative_path(&self, path: &Path) -> PathBuf {
+        match &self.root {
+            Some(root) => root_dir::relative_to(path, root),
+            None => path.to_owned(),
+        }
     }
 
     /// Generates a fully qualified name (FQN) from a path, the current namespace,
@@ -135,12 +438,12 @@ impl<'a> Context {
     /// would yield a FQN of `src/foo.bar/ts::Interface::methodName`
     ///
     /// This function is operating-system independent, and will always use `/` as
-    /// the path separator.
+    /// the path separator. The path is made relative to `root`, if it is set.
     pub fn generate_fqn<T>(&self, path: &Path, parts: T) -> String
     where
         T: IntoIterator<Item = &'a str>,
     {
-        let mut fqn = format!("{}", path.display()).replace('\\', "/");
+        let mut fqn = portable_path(&self.relative_path(path));
 
         for part in &self.namespace {
             fqn.push_str(&format!("::{}", part));
@@ -162,35 +465,68 @@ impl<'a> Context {
     }
 }
 
-/// The trait for implementing language-specific parsers
+/// The trait for implementing language-specific parsers.
+///
+/// Object safe, so that parsers of different languages can be kept side by
+/// side as `Box<dyn DocsParser>`.
 pub trait DocsParser {
-    /// Given a pathname to an entry point, return a list of entities
-    fn parse<'a, P: Into<&'a Path>, T: IntoIterator<Item = P>>(
-        &self,
-        paths: T,
-        ctx: &mut Context,
-    ) -> Result<Vec<Entity>>;
+    /// Parses the files, and returns the entities from all of them
+    fn parse(&self, paths: &[&Path], ctx: &mut Context) -> Result<Vec<Entity>>;
+
+    /// The name of the language, as set in the `language` of its entities
+    fn language(&self) -> &'static str;
+
+    /// The extensions of the files the parser parses, without the dot
+    fn file_extensions(&self) -> &'static [&'static str];
+
+    /// Whether an entity the parser made is part of the public API, given its
+    /// parent. See `Context::public_only`. All of them are, unless the parser
+    /// says otherwise.
+    fn is_public(&self, _entity: &Entity, _parent: Option<&Entity>) -> bool {
+        true
+    }
 }
 
-pub trait FileSource {
-    fn read_file<'a, P: Into<&'a Path>>(&self, path: P) -> std::io::Result<String>;
+/// Receives measurements from parsers as they parse, e.g. to report where
+/// the time goes. Called from the threads files are parsed on.
+pub trait Instrumentation: std::fmt::Debug + Send + Sync {
+    /// `path`, a file of `language`, took `duration` to parse
+    fn file_parsed(&self, path: &Path, language: &str, duration: Duration);
 }
 
+/// Where parsers read source files from
+pub trait FileSource: std::fmt::Debug + Send + Sync {
+    fn read_file(&self, path: &Path) -> std::io::Result<String>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
 pub struct FileSystem;
 
 impl FileSource for FileSystem {
-    fn read_file<'a, P: Into<&'a Path>>(&self, path: P) -> std::io::Result<String> {
-        std::fs::read_to_string(path.into())
+    fn read_file(&self, path: &Path) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
     }
 }
 
+/// Serves files from memory, e.g. when embedding Dossier in a program that
+/// has the sources in editor buffers or an archive rather than on disk.
+#[derive(Debug, Clone, Default)]
 pub struct InMemoryFileSystem {
     pub files: indexmap::IndexMap<PathBuf, String>,
 }
 
+impl InMemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_file<P: Into<PathBuf>, S: Into<String>>(&mut self, path: P, contents: S) {
+        self.files.insert(path.into(), contents.into());
+    }
+}
+
 impl FileSource for InMemoryFileSystem {
-    fn read_file<'a, P: Into<&'a Path>>(&self, path: P) -> std::io::Result<String> {
-        let path: &Path = path.into();
+    fn read_file(&self, path: &Path) -> std::io::Result<String> {
         self.files
             .get(path)
             .map(|s| s.to_owned())
@@ -203,6 +539,9 @@ impl FileSource for InMemoryFileSystem {
 
 pub mod helpers {
     use super::*;
+
+    /// How much of the text around a syntax error to quote
+    const MAX_SYNTAX_ERROR_TEXT: usize = 40;
     use tree_sitter::{Node, Query, QueryCapture};
 
     pub fn node_for_capture<'a>(
@@ -216,6 +555,157 @@ pub mod helpers {
             .map(|c| c.node)
     }
 
+    /// A warning about the first syntax error in a file, if there is one.
+    /// Parsers still parse what they can of such files, but may miss or
+    /// misread the declarations around the error.
+    pub fn syntax_error(root: &Node, code: &str, file: &Path) -> Option<Diagnostic> {
+        if !root.has_error() {
+            return None;
+        }
+
+        let mut node = *root;
+        'descend: loop {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.is_error() || child.is_missing() {
+                    node = child;
+                    break 'descend;
+                }
+                if child.has_error() {
+                    node = child;
+                    continue 'descend;
+                }
+            }
+            break;
+        }
+
+        let position = node.start_position();
+        let message = if node.is_missing() {
+            format!(
+                "Syntax error at line {}, column {}: missing `{}`",
+                position.row + 1,
+                position.column + 1,
+                node.kind()
+            )
+        } else {
+            let text = node.utf8_text(code.as_bytes()).unwrap_or_default();
+            let text = text.lines().next().unwrap_or_default();
+            format!(
+                "Syntax error at line {}, column {}: unexpected `{}`",
+                position.row + 1,
+                position.column + 1,
+                text.chars().take(MAX_SYNTAX_ERROR_TEXT).collect::<String>()
+            )
+        };
+
+        Some(Diagnostic::warning(SYNTAX_ERROR, message, file).with_range(node.byte_range()))
+    }
+
+    /// The source of a declaration up to its body, on a single line: the
+    /// signature of a function, or the header of a class. Declarations
+    /// without a `body`, like type aliases, are taken whole. Leading
+    /// decorators, and a trailing `;` or `:`, are left out.
+    pub fn signature(node: &Node, code: &str) -> String {
+        let mut cursor = node.walk();
+        let start = node
+            .children(&mut cursor)
+            .find(|child| child.kind() != "decorator")
+            .map_or(node.start_byte(), |child| child.start_byte());
+        let end = node
+            .child_by_field_name("body")
+            .map_or(node.end_byte(), |body| body.start_byte());
+
+        let text = code[start..end.max(start)]
+            .trim_end()
+            .trim_end_matches([';', ':'])
+            .trim_end();
+
+        join_lines(text)
+    }
+
+    /// Joins lines by replacing each line break, with the whitespace around
+    /// it, with a space. Right after an opening `(`, `[`, or `<`, or before
+    /// a closing one, the line break is dropped, along with a trailing comma before
+    /// it, so that e.g. parameters on lines of their own end up as
+    /// `(a: A, b: B)`. Other whitespace is kept as it is.
+    pub fn join_lines(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find(|c: char| c.is_whitespace()) {
+            let (before, whitespace) = rest.split_at(start);
+            let end = whitespace
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(whitespace.len());
+            let (whitespace, after) = whitespace.split_at(end);
+            out.push_str(before);
+            rest = after;
+
+            if !whitespace.contains('\n') {
+                out.push_str(whitespace);
+                continue;
+            }
+
+            if after.starts_with([')', ']', '>']) {
+                if out.ends_with(',') {
+                    out.pop();
+                }
+            } else if !out.ends_with(['(', '[', '<']) && !after.is_empty() {
+                out.push(' ');
+            }
+        }
+        out.push_str(rest);
+
+        out
+    }
+
+    /// `text` with Windows (`\r\n`) and old Mac (`\r`) line endings replaced
+    /// by `\n`, for processing comments and docstrings line by line
+    pub fn normalize_line_endings(text: &str) -> std::borrow::Cow<'_, str> {
+        if text.contains('\r') {
+            text.replace("\r\n", "\n").replace('\r', "\n").into()
+        } else {
+            text.into()
+        }
+    }
+
+    /// Removes the leading whitespace that the lines that aren't blank have in
+    /// common, and empties blank lines. The whitespace is compared as text
+    /// rather than by width, so that lines indented with tabs lose their tabs,
+    /// and no line is indented differently relative to the others.
+    pub fn dedent<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+        let mut common: Option<&str> = None;
+        for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+            let indentation = &line[..line.len() - line.trim_start().len()];
+            common = Some(match common {
+                Some(common) => common_prefix(common, indentation),
+                None => indentation,
+            });
+        }
+        let common = common.unwrap_or_default();
+
+        lines
+            .iter()
+            .map(|line| {
+                if line.trim().is_empty() {
+                    ""
+                } else {
+                    &line[common.len()..]
+                }
+            })
+            .collect()
+    }
+
+    fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+        let end = a
+            .char_indices()
+            .zip(b.chars())
+            .find(|((_, x), y)| x != y)
+            .map_or(a.len().min(b.len()), |((i, _), _)| i);
+
+        &a[..end]
+    }
+
     pub fn get_string_from_match<'a>(
         captures: &'a [QueryCapture],
         index: u32,
@@ -229,3 +719,195 @@ pub mod helpers {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::helpers::*;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_entities_back_from_the_output() {
+        let output = json!({
+            "title": "distance",
+            "description": "",
+            "kind": "function",
+            "fqn": "index.ts::distance",
+            "members": [{
+                "description": "",
+                "kind": "identifier",
+                "refers_to": "index.ts::Point",
+                "member_context": "return_type",
+                "language": "ts",
+                "source": {
+                    "file": "index.ts",
+                    "start": { "row": 0, "column": 30, "byte_offset": 30 },
+                    "end": { "row": 0, "column": 35, "byte_offset": 35 },
+                },
+            }, {
+                "description": "",
+                "kind": "union",
+                "member_context": "type",
+                "language": "ts",
+                "source": {
+                    "file": "index.ts",
+                    "start": { "row": 0, "column": 30, "byte_offset": 30 },
+                    "end": { "row": 0, "column": 35, "byte_offset": 35 },
+                },
+            }],
+            "language": "ts",
+            "source": {
+                "file": "index.ts",
+                "start": { "row": 0, "column": 0, "byte_offset": 0 },
+                "end": { "row": 0, "column": 40, "byte_offset": 40 },
+                "repository": "https://github.com/Doctave/dossier/blob/main/index.ts#L1",
+            },
+            "meta": { "exported": true },
+        });
+
+        let entity: Entity = serde_json::from_value(output.clone()).unwrap();
+
+        assert_eq!(
+            entity.identity,
+            Identity::FQN("index.ts::distance".to_owned())
+        );
+        assert_eq!(
+            entity.members[0].identity,
+            Identity::Reference("index.ts::Point".to_owned())
+        );
+        assert_eq!(entity.members[1].identity, Identity::Anonymous);
+        assert_eq!(
+            entity.members[0].member_context,
+            Some(MemberContext::ReturnType)
+        );
+        assert_eq!(serde_json::to_value(&entity).unwrap(), output);
+        // Left out of the output for being empty
+        assert_eq!(entity.members[0].meta, json!({}));
+    }
+
+    /// A number below `n`, from a xorshift generator, so that the same trees
+    /// are made every time
+    fn next(seed: &mut u64, n: u64) -> u64 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        *seed % n
+    }
+
+    /// An entity tree with some of everything the output can have in it
+    fn arbitrary_entity(seed: &mut u64, depth: usize) -> Entity {
+        let name = format!("name{}", next(seed, 100));
+        let position = |seed: &mut u64| Position {
+            row: next(seed, 1000) as usize,
+            column: next(seed, 80) as usize,
+            byte_offset: next(seed, 100_000) as usize,
+        };
+        let members = if depth < 3 {
+            (0..next(seed, 4))
+                .map(|_| arbitrary_entity(seed, depth + 1))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        Entity {
+            title: match next(seed, 3) {
+                0 => None,
+                1 => Some(name.clone()),
+                _ => Some("名前".to_owned()),
+            },
+            description: match next(seed, 3) {
+                0 => String::new(),
+                1 => "Some *Markdown*\n\nwith \"quotes\" and `code`".to_owned(),
+                _ => "\u{1F4DA} ".to_owned(),
+            },
+            kind: ["function", "class", "identifier", "union", "parameter"][next(seed, 5) as usize]
+                .to_owned(),
+            identity: match next(seed, 3) {
+                0 => Identity::FQN(format!("src/index.ts::{}", name)),
+                1 => Identity::Reference(format!("builtin::{}", name)),
+                _ => Identity::Anonymous,
+            },
+            members,
+            member_context: match next(seed, 4) {
+                0 => None,
+                1 => Some(MemberContext::Other("decorator".to_owned())),
+                _ => {
+                    let named = MemberContext::NAMED;
+                    Some(named[next(seed, named.len() as u64) as usize].clone())
+                }
+            },
+            language: ["ts", "py"][next(seed, 2) as usize].to_owned(),
+            meta: match next(seed, 3) {
+                0 => json!({}),
+                1 => json!({ "exported": true, "default": "1" }),
+                _ => json!({
+                    "tags": [{ "name": "see", "value": null }],
+                    "ratio": 0.25,
+                    "nested": { "depth": depth },
+                }),
+            },
+            source: Source {
+                file: ["index.ts", "src/models/user.ts", "pkg/__init__.py"][next(seed, 3) as usize]
+                    .into(),
+                start: position(seed),
+                end: position(seed),
+                repository: match next(seed, 2) {
+                    0 => None,
+                    _ => {
+                        Some("https://github.com/Doctave/dossier/blob/main/index.ts#L1".to_owned())
+                    }
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn round_trips_entity_trees() {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..200 {
+            let entity = arbitrary_entity(&mut seed, 0);
+
+            let text = serde_json::to_string(&entity).unwrap();
+            let read: Entity = serde_json::from_str(&text).unwrap();
+            assert_eq!(read, entity, "{}", text);
+        }
+    }
+
+    #[test]
+    fn normalizes_line_endings() {
+        assert_eq!(normalize_line_endings("a\r\nb\rc\n"), "a\nb\nc\n");
+        assert!(matches!(
+            normalize_line_endings("a\nb"),
+            std::borrow::Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn dedents_by_common_whitespace() {
+        assert_eq!(
+            dedent(&["    a", "", "      b", "   ", "    c"]),
+            ["a", "", "  b", "", "c"]
+        );
+        assert_eq!(dedent(&["\ta", "\t\tb"]), ["a", "\tb"]);
+        // Tabs and spaces have nothing in common
+        assert_eq!(dedent(&["\ta", "    b"]), ["\ta", "    b"]);
+        assert_eq!(dedent(&["\u{3000}a", "\u{3000} b"]), ["a", " b"]);
+        assert!(dedent(&[]).is_empty());
+    }
+
+    #[test]
+    fn joins_lines_of_signatures() {
+        assert_eq!(
+            join_lines(
+                "function f<\n  T,\n>(\n    a: T,\n    b: { x: number;\n y: number },\n): T"
+            ),
+            "function f<T>(a: T, b: { x: number; y: number }): T"
+        );
+        assert_eq!(
+            join_lines("type  A =\n  | 'a  b'\n  | B"),
+            "type  A = | 'a  b' | B"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Picks one language out of documentation that is written in several, see
+//! `Context::doc_locale`.
+//!
+//! Localized documentation keeps each translation in its own section of the
+//! same comment, after a marker line naming its locale:
+//!
+//! ```text
+//! Greets the user.
+//! @locale ja
+//! ユーザーに挨拶します。
+//! ```
+//!
+//! Text before the first marker is the default. The marker is up to each
+//! language, e.g. `@locale` in TypeScript and `.. locale::` in Python.
+use crate::Entity;
+
+/// A description split into its default text and localized sections
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LocalizedText {
+    /// The text before the first marker
+    pub default: String,
+    /// Pairs of locales and their text, in the order they were written
+    pub sections: Vec<(String, String)>,
+}
+
+impl LocalizedText {
+    /// Splits `text` at lines starting with `marker` followed by a locale.
+    pub fn split(text: &str, marker: &str) -> Self {
+        let mut default = vec![];
+        let mut sections: Vec<(String, Vec<&str>)> = vec![];
+
+        for line in text.lines() {
+            if let Some(locale) = marker_locale(line, marker) {
+                sections.push((locale.to_owned(), vec![]));
+                continue;
+            }
+
+            match sections.last_mut() {
+                Some((_, lines)) => lines.push(line),
+                None => default.push(line),
+            }
+        }
+
+        Self {
+            default: default.join("\n").trim().to_owned(),
+            sections: sections
+                .into_iter()
+                .map(|(locale, lines)| (locale, lines.join("\n").trim().to_owned()))
+                .collect(),
+        }
+    }
+
+    /// The text for `locale`. Falls back to the default text, or to the first
+    /// section if there is no default.
+    pub fn select(&self, locale: &str) -> &str {
+        self.sections
+            .iter()
+            .find(|(l, _)| l == locale)
+            .or_else(|| self.sections.first().filter(|_| self.default.is_empty()))
+            .map(|(_, text)| text.as_str())
+            .unwrap_or(&self.default)
+    }
+
+    /// The locales that have a section, without duplicates
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = vec![];
+        for (locale, _) in &self.sections {
+            if !locales.contains(&locale.as_str()) {
+                locales.push(locale);
+            }
+        }
+        locales
+    }
+}
+
+/// Replaces the description of each entity and its members with its text in
+/// `locale`, and lists the locales it has sections for in
+/// `meta.available_locales`.
+pub fn localize_descriptions(entities: &mut [Entity], locale: &str, marker: &str) {
+    for entity in entities.iter_mut() {
+        let text = LocalizedText::split(&entity.description, marker);
+
+        if !text.sections.is_empty() {
+            entity.meta["available_locales"] = text.locales().into();
+            entity.description = text.select(locale).to_owned();
+        }
+
+        localize_descriptions(&mut entity.members, locale, marker);
+    }
+}
+
+fn marker_locale<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.trim().strip_prefix(marker)?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    rest.split_whitespace().next()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, EntityBuilder};
+    use crate::Identity;
+    use serde_json::json;
+
+    const BILINGUAL: &str =
+        "Greets the user.\n@locale ja\nユーザーに挨拶します。\n@locale fr\nSalue l'utilisateur.";
+
+    #[test]
+    fn splits_sections_by_marker() {
+        let text = LocalizedText::split(BILINGUAL, "@locale");
+
+        assert_eq!(text.default, "Greets the user.");
+        assert_eq!(
+            text.sections,
+            [
+                ("ja".to_owned(), "ユーザーに挨拶します。".to_owned()),
+                ("fr".to_owned(), "Salue l'utilisateur.".to_owned())
+            ]
+        );
+
+        // The marker has to be followed by a locale
+        let text = LocalizedText::split("@localeja\n@locale\nText", "@locale");
+        assert!(text.sections.is_empty());
+    }
+
+    #[test]
+    fn selects_the_requested_locale() {
+        let mut entities = vec![entity("test", Identity::Anonymous)
+            .description(BILINGUAL)
+            .members(vec![
+                entity("test", Identity::Anonymous).description(BILINGUAL)
+            ])];
+        localize_descriptions(&mut entities, "ja", "@locale");
+
+        assert_eq!(entities[0].description, "ユーザーに挨拶します。");
+        assert_eq!(entities[0].members[0].description, "ユーザーに挨拶します。");
+    }
+
+    #[test]
+    fn falls_back_to_the_default_text() {
+        let mut entities = vec![entity("test", Identity::Anonymous).description(BILINGUAL)];
+        localize_descriptions(&mut entities, "de", "@locale");
+        assert_eq!(entities[0].description, "Greets the user.");
+
+        let text = LocalizedText::split("@locale en\nHello\n@locale ja\nこんにちは", "@locale");
+        assert_eq!(text.select("de"), "Hello");
+    }
+
+    #[test]
+    fn records_available_locales() {
+        let mut entities = vec![
+            entity("test", Identity::Anonymous).description(BILINGUAL),
+            entity("test", Identity::Anonymous).description("Not translated."),
+        ];
+        localize_descriptions(&mut entities, "ja", "@locale");
+
+        assert_eq!(entities[0].meta["available_locales"], json!(["ja", "fr"]));
+        assert_eq!(entities[1].description, "Not translated.");
+        assert!(entities[1].meta.get("available_locales").is_none());
+    }
+}
@@ -0,0 +1,181 @@
+//! What a member is to the entity it belongs to, see `Entity::member_context`.
+//!
+//! Every member context has a stable name it is serialized as. Parsers for
+//! the bundled languages only emit the named variants, which map to the
+//! constructs of each language like this:
+//!
+//! | Variant | Name | TypeScript | Python |
+//! |---|---|---|---|
+//! | `TypeParameter` | `type_parameter` | `T` in `<T>` | |
+//! | `Constraint` | `constraint` | `extends U` and `= U` of a type parameter | |
+//! | `Extends` | `extends` | Parents in `extends` clauses | |
+//! | `Base` | `base` | | Base classes |
+//! | `Implements` | `implements` | Interfaces in `implements` clauses | |
+//! | `Parameter` | `parameter` | Parameters | Parameters |
+//! | `ReturnType` | `return_type` | Return types | Return annotations |
+//! | `Type` | `type` | The type of a declaration, and the members of types | The annotation of a declaration |
+//! | `TypeArgument` | `type_argument` | `T` in `Promise<T>` | `T` in `list[T]` |
+//! | `Satisfies` | `satisfies` | `U` in `x satisfies U` | |
+//! | `Default` | `default` | `U` in `<T = U>` | |
+//! | `Property` | `property` | Properties of object types | |
+//! | `Method` | `method` | | Functions defined in a class |
+//! | `Member` | `member` | Other declarations, like methods and fields | Other declarations, like attributes |
+//! | `Inherited` | `inherited` | Members copied from parents | |
+//! | `Overload` | `overload` | Reserved for overload signatures | `@overload` signatures of a function |
+//!
+//! Parsers for other languages can use `Other` for anything that doesn't fit,
+//! which is serialized as the name it holds.
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MemberContext {
+    TypeParameter,
+    Constraint,
+    Extends,
+    Base,
+    Implements,
+    Parameter,
+    ReturnType,
+    Type,
+    TypeArgument,
+    Satisfies,
+    Default,
+    Property,
+    Method,
+    Member,
+    Inherited,
+    Overload,
+    Other(String),
+}
+
+impl MemberContext {
+    /// Every variant but `Other`
+    pub const NAMED: &'static [MemberContext] = &[
+        MemberContext::TypeParameter,
+        MemberContext::Constraint,
+        MemberContext::Extends,
+        MemberContext::Base,
+        MemberContext::Implements,
+        MemberContext::Parameter,
+        MemberContext::ReturnType,
+        MemberContext::Type,
+        MemberContext::TypeArgument,
+        MemberContext::Satisfies,
+        MemberContext::Default,
+        MemberContext::Property,
+        MemberContext::Method,
+        MemberContext::Member,
+        MemberContext::Inherited,
+        MemberContext::Overload,
+    ];
+
+    /// The name the context is serialized as
+    pub fn as_str(&self) -> &str {
+        match self {
+            MemberContext::TypeParameter => "type_parameter",
+            MemberContext::Constraint => "constraint",
+            MemberContext::Extends => "extends",
+            MemberContext::Base => "base",
+            MemberContext::Implements => "implements",
+            MemberContext::Parameter => "parameter",
+            MemberContext::ReturnType => "return_type",
+            MemberContext::Type => "type",
+            MemberContext::TypeArgument => "type_argument",
+            MemberContext::Satisfies => "satisfies",
+            MemberContext::Default => "default",
+            MemberContext::Property => "property",
+            MemberContext::Method => "method",
+            MemberContext::Member => "member",
+            MemberContext::Inherited => "inherited",
+            MemberContext::Overload => "overload",
+            MemberContext::Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for MemberContext {
+    /// The context with this name, or `Other` if none of them has it
+    fn from(name: &str) -> Self {
+        MemberContext::NAMED
+            .iter()
+            .find(|context| context.as_str() == name)
+            .cloned()
+            .unwrap_or_else(|| MemberContext::Other(name.to_owned()))
+    }
+}
+
+impl Display for MemberContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for MemberContext {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MemberContext {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(MemberContext::from(name.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_to_stable_names() {
+        let names = MemberContext::NAMED
+            .iter()
+            .map(|context| serde_json::to_value(context).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![
+                json!("type_parameter"),
+                json!("constraint"),
+                json!("extends"),
+                json!("base"),
+                json!("implements"),
+                json!("parameter"),
+                json!("return_type"),
+                json!("type"),
+                json!("type_argument"),
+                json!("satisfies"),
+                json!("default"),
+                json!("property"),
+                json!("method"),
+                json!("member"),
+                json!("inherited"),
+                json!("overload"),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(MemberContext::Other("decorator".to_owned())).unwrap(),
+            json!("decorator")
+        );
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        for context in MemberContext::NAMED
+            .iter()
+            .cloned()
+            .chain([MemberContext::Other("decorator".to_owned())])
+        {
+            let value = serde_json::to_value(&context).unwrap();
+            assert_eq!(
+                serde_json::from_value::<MemberContext>(value).unwrap(),
+                context
+            );
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! The order of the members of an entity, and what each of them is to it.
+//!
+//! Parsers add members in whatever order they come across them. Before
+//! returning their entities, they run `order_members` over them, which puts
+//! the members of declarations in this order:
+//!
+//! 1. Type parameters, with the `type_parameter` member context
+//! 2. What it extends or implements: `extends`, `base` and `implements`
+//! 3. Parameters, in the order they are declared
+//! 4. The return type
+//! 5. The type of the declaration itself: `type`, `satisfies` and `default`
+//! 6. Its own members, like properties, methods and attributes, in the order
+//!    they appear in the source
+//! 7. Members it inherits, in the order of the parents they come from
+//!
+//! The members of types, like the members of a union or the arguments of a
+//! generic, keep the order they were given in, since that order is part of
+//! the type.
+//!
+//! Members that parsers leave without a member context get one:
+//! `type_parameter` for type variables, `constraint` for their constraints,
+//! `member` for declarations and `type` for everything else.
+use crate::{Entity, MemberContext};
+
+/// Kinds of entities that declare something, and whose members are ordered
+const DECLARATIONS: &[&str] = &[
+    "attribute",
+    "class",
+    "enum_member",
+    "field",
+    "function",
+    "interface",
+    "method",
+    "namespace",
+    "parameter",
+    "property",
+    "reexport",
+    "type_alias",
+    "variable",
+];
+
+/// The member contexts in the order they come in, see the module
+/// documentation. Others, like `member`, `method` and `property`, are own
+/// members, which come after all of these.
+const ORDER: &[&[MemberContext]] = &[
+    &[MemberContext::TypeParameter],
+    &[
+        MemberContext::Extends,
+        MemberContext::Base,
+        MemberContext::Implements,
+    ],
+    &[MemberContext::Parameter],
+    &[MemberContext::ReturnType],
+    &[
+        MemberContext::Type,
+        MemberContext::Satisfies,
+        MemberContext::Default,
+    ],
+];
+
+/// Sets the member context of each member that doesn't have one, and orders
+/// the members of declarations, recursively
+pub fn order_members(entities: &mut [Entity]) {
+    for entity in entities.iter_mut() {
+        order(entity);
+    }
+}
+
+fn order(entity: &mut Entity) {
+    for member in entity.members.iter_mut() {
+        if member.member_context.is_none() {
+            member.member_context = Some(default_context(member));
+        }
+        order(member);
+    }
+
+    if DECLARATIONS.contains(&entity.kind.as_str()) {
+        // Stable, so inherited members stay in the order they were added in
+        entity.members.sort_by(|a, b| {
+            rank(a).cmp(&rank(b)).then_with(|| {
+                if is_inherited(a) {
+                    return std::cmp::Ordering::Equal;
+                }
+                a.source
+                    .file
+                    .cmp(&b.source.file)
+                    .then(a.source.start.byte_offset.cmp(&b.source.start.byte_offset))
+            })
+        });
+    }
+}
+
+fn default_context(member: &Entity) -> MemberContext {
+    match member.kind.as_str() {
+        "type_variable" => MemberContext::TypeParameter,
+        "type_constraint" => MemberContext::Constraint,
+        kind if DECLARATIONS.contains(&kind) => MemberContext::Member,
+        _ => MemberContext::Type,
+    }
+}
+
+fn rank(member: &Entity) -> usize {
+    if is_inherited(member) {
+        return ORDER.len() + 1;
+    }
+
+    let Some(context) = &member.member_context else {
+        return ORDER.len();
+    };
+    ORDER
+        .iter()
+        .position(|contexts| contexts.contains(context))
+        .unwrap_or(ORDER.len())
+}
+
+fn is_inherited(member: &Entity) -> bool {
+    member.member_context == Some(MemberContext::Inherited)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, EntityBuilder};
+    use crate::Identity;
+
+    fn members(entity: &Entity) -> Vec<(&str, &str)> {
+        entity
+            .members
+            .iter()
+            .map(|m| {
+                (
+                    m.title.as_deref().unwrap(),
+                    m.member_context.as_ref().unwrap().as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn orders_the_members_of_declarations() {
+        let mut entities = vec![entity("class", Identity::Anonymous)
+            .title("Repository")
+            .members(vec![
+                entity("method", Identity::Anonymous)
+                    .title("find")
+                    .span(90, 90),
+                entity("method", Identity::Anonymous)
+                    .title("toString")
+                    .span(10, 10)
+                    .context("inherited"),
+                entity("identifier", Identity::Anonymous)
+                    .title("Base")
+                    .span(30, 30)
+                    .context("extends"),
+                entity("field", Identity::Anonymous)
+                    .title("table")
+                    .span(60, 60),
+                entity("type_variable", Identity::Anonymous)
+                    .title("T")
+                    .span(20, 20),
+                entity("method", Identity::Anonymous)
+                    .title("clone")
+                    .span(5, 5)
+                    .context("inherited"),
+                entity("identifier", Identity::Anonymous)
+                    .title("Iterable")
+                    .span(40, 40)
+                    .context("implements"),
+            ])];
+
+        order_members(&mut entities);
+
+        assert_eq!(
+            members(&entities[0]),
+            vec![
+                ("T", "type_parameter"),
+                ("Base", "extends"),
+                ("Iterable", "implements"),
+                ("table", "member"),
+                ("find", "member"),
+                ("toString", "inherited"),
+                ("clone", "inherited"),
+            ]
+        );
+    }
+
+    #[test]
+    fn puts_parameters_before_the_return_type() {
+        let mut entities = vec![entity("function", Identity::Anonymous)
+            .title("find")
+            .members(vec![
+                entity("identifier", Identity::Anonymous)
+                    .title("Row")
+                    .span(50, 50)
+                    .context("return_type"),
+                entity("parameter", Identity::Anonymous)
+                    .title("id")
+                    .span(30, 30)
+                    .members(vec![entity("identifier", Identity::Anonymous)
+                        .title("Id")
+                        .span(35, 35)])
+                    .context("parameter"),
+                entity("parameter", Identity::Anonymous)
+                    .title("all")
+                    .span(10, 10)
+                    .context("parameter"),
+                entity("type_variable", Identity::Anonymous)
+                    .title("Id")
+                    .span(5, 5),
+            ])];
+
+        order_members(&mut entities);
+
+        assert_eq!(
+            members(&entities[0]),
+            vec![
+                ("Id", "type_parameter"),
+                ("all", "parameter"),
+                ("id", "parameter"),
+                ("Row", "return_type"),
+            ]
+        );
+        assert_eq!(members(&entities[0].members[2]), vec![("Id", "type")]);
+    }
+
+    #[test]
+    fn keeps_the_order_of_the_members_of_types() {
+        let mut entities = vec![entity("union", Identity::Anonymous).members(vec![
+            entity("identifier", Identity::Anonymous)
+                .title("B")
+                .span(20, 20),
+            entity("identifier", Identity::Anonymous)
+                .title("A")
+                .span(10, 10),
+        ])];
+
+        order_members(&mut entities);
+
+        assert_eq!(members(&entities[0]), vec![("B", "type"), ("A", "type")]);
+    }
+}
@@ -0,0 +1,85 @@
+//! Tree-sitter parsers kept for reuse across files.
+//!
+//! Creating a parser and loading its grammar for every file adds up over
+//! thousands of files. Instead, each thread keeps a parser per grammar, which
+//! it reuses for all the files it parses, so parsers never move between the
+//! threads of `parse_files`.
+//!
+//! ```
+//! # use dossier_core::{parser_pool, tree_sitter::Language};
+//! # fn example(language: Language, code: &str) {
+//! match parser_pool::parse("ts", language, code, None) {
+//!     Some(tree) => println!("{}", tree.root_node().to_sexp()),
+//!     None => println!("Timed out"),
+//! }
+//! # }
+//! ```
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use tree_sitter::{Language, Parser, Tree};
+
+use crate::Diagnostic;
+
+/// Reported for files that took longer than `Context::parse_timeout` to parse
+pub const PARSE_TIMEOUT: &str = "parse-timeout";
+
+thread_local! {
+    static PARSERS: RefCell<HashMap<&'static str, Parser>> = RefCell::new(HashMap::new());
+}
+
+/// Parses `code` with this thread's parser for `language`, whose name is
+/// `name`. Each name gets a parser of its own that loads the grammar once, so
+/// grammars that differ, like TypeScript's and TSX's, must have different
+/// names.
+///
+/// Returns `None` if parsing took longer than `timeout`.
+pub fn parse(
+    name: &'static str,
+    language: Language,
+    code: &str,
+    timeout: Option<Duration>,
+) -> Option<Tree> {
+    // Taken out while in use, in case parsing ever ends up here again
+    let mut parser = PARSERS
+        .with(|parsers| parsers.borrow_mut().remove(name))
+        .unwrap_or_else(|| {
+            let mut parser = Parser::new();
+            parser
+                .set_language(language)
+                .unwrap_or_else(|_| panic!("Error loading the {} grammar", name));
+            parser
+        });
+
+    // 0 means no timeout, so round shorter ones up
+    let micros = timeout.map_or(0, |timeout| {
+        u64::try_from(timeout.as_micros())
+            .unwrap_or(u64::MAX)
+            .max(1)
+    });
+    parser.set_timeout_micros(micros);
+
+    let tree = parser.parse(code, None);
+    if tree.is_none() {
+        // Otherwise the next parse would pick up where this one stopped
+        parser.reset();
+    }
+
+    PARSERS.with(|parsers| parsers.borrow_mut().insert(name, parser));
+    tree
+}
+
+/// The warning for `file`, which `parse` gave up on after `timeout`. Parsers
+/// leave such files out.
+pub fn parse_timeout_warning(file: &Path, timeout: Option<Duration>) -> Diagnostic {
+    Diagnostic::warning(
+        PARSE_TIMEOUT,
+        format!(
+            "Skipped file that took longer than {} ms to parse",
+            timeout.unwrap_or_default().as_millis()
+        ),
+        file,
+    )
+}
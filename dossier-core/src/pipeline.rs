@@ -0,0 +1,498 @@
+//! The parallel read-and-parse loop the language parsers share.
+use crate::{Diagnostic, DossierError, FileSource, Result};
+
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Lines shorter than this are never a sign of minification on their own,
+/// however much of the file they take up
+const MIN_LONG_LINE: usize = 1024;
+
+/// Reported for files that look minified
+pub const MINIFIED_FILE: &str = "minified-file";
+
+/// How much of a file to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileMode {
+    Full,
+    /// Only the names of top-level declarations, so that imports through the
+    /// file still resolve
+    NamesOnly,
+}
+
+/// What to do with files that look minified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MinifiedFiles {
+    /// Leave them out entirely
+    Skip,
+    /// Parse them in `FileMode::NamesOnly`
+    #[default]
+    NamesOnly,
+}
+
+/// Limits that decide whether a file looks minified, like a vendored bundle
+/// with the whole program on one line. Documenting those is not useful, and
+/// their huge lines make parsing them slow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileLimits {
+    /// Longest line, in bytes, a file can have
+    pub max_line_length: usize,
+    /// Largest share of a file's bytes that its longest line can take up.
+    /// Only applies to lines of at least 1024 bytes.
+    pub max_single_line_fraction: f64,
+    pub minified: MinifiedFiles,
+}
+
+impl Default for FileLimits {
+    fn default() -> Self {
+        Self {
+            max_line_length: 10_000,
+            max_single_line_fraction: 0.9,
+            minified: MinifiedFiles::default(),
+        }
+    }
+}
+
+impl FileLimits {
+    /// Checks `code` against the limits without walking every line of it.
+    /// Only the lines that start in the first `max_line_length` bytes are
+    /// held to `max_line_length`, which catches bundles behind a license
+    /// header. A line that takes up more than half of the file covers its
+    /// middle byte, so only that line is held to `max_single_line_fraction`,
+    /// unless the fraction is below a half.
+    pub fn looks_minified(&self, code: &str) -> bool {
+        let bytes = code.as_bytes();
+        if bytes.len() < MIN_LONG_LINE.min(self.max_line_length) {
+            return false;
+        }
+
+        let mut start = 0;
+        while start <= self.max_line_length && start < bytes.len() {
+            let rest = &bytes[start
+                ..bytes
+                    .len()
+                    .min(start.saturating_add(self.max_line_length).saturating_add(1))];
+            let length = rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len());
+            if length > self.max_line_length {
+                return true;
+            }
+            start += length + 1;
+        }
+
+        let longest = if self.max_single_line_fraction >= 0.5 {
+            line_length_at(bytes, bytes.len() / 2)
+        } else {
+            bytes
+                .split(|b| *b == b'\n')
+                .map(|line| line.len())
+                .max()
+                .unwrap_or(0)
+        };
+
+        longest >= MIN_LONG_LINE
+            && longest as f64 > bytes.len() as f64 * self.max_single_line_fraction
+    }
+}
+
+/// Length of the line that the byte at `index` is on
+fn line_length_at(bytes: &[u8], index: usize) -> usize {
+    let start = bytes[..index]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |i| i + 1);
+    let end = bytes[index..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(bytes.len(), |i| index + i);
+
+    end - start
+}
+
+/// Reads each of `paths` from `source` and runs `parse` on its contents,
+/// spread over `jobs` threads (one per CPU if `None`).
+///
+/// Files that look minified according to `limits` are skipped or parsed in
+/// `FileMode::NamesOnly`, with a warning added to `diagnostics`.
+///
+/// The results are in the same order as `paths`, regardless of which files
+/// finish first. Stops at the first file that can't be read or parsed.
+pub fn parse_files<T, F>(
+    paths: &[PathBuf],
+    source: &dyn FileSource,
+    jobs: Option<usize>,
+    limits: &FileLimits,
+    diagnostics: &mut Vec<Diagnostic>,
+    parse: F,
+) -> Result<Vec<T>>
+where
+    T: Send,
+    F: Fn(&Path, &str, FileMode) -> Result<T> + Sync,
+{
+    let results = thread_pool(jobs).install(|| {
+        paths
+            .par_iter()
+            .map(|path| {
+                let code = source
+                    .read_file(path)
+                    .map_err(|e| DossierError::IOError(path.to_owned(), e))?;
+
+                if !limits.looks_minified(&code) {
+                    return Ok((Some(parse(path, &code, FileMode::Full)?), None));
+                }
+
+                match limits.minified {
+                    MinifiedFiles::Skip => Ok((
+                        None,
+                        Some(Diagnostic::warning(
+                            MINIFIED_FILE,
+                            "Skipped file that looks minified",
+                            path,
+                        )),
+                    )),
+                    MinifiedFiles::NamesOnly => Ok((
+                        Some(parse(path, &code, FileMode::NamesOnly)?),
+                        Some(Diagnostic::warning(
+                            MINIFIED_FILE,
+                            "Only parsed top-level names of file that looks minified",
+                            path,
+                        )),
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    let mut out = Vec::with_capacity(results.len());
+    for (result, diagnostic) in results {
+        out.extend(result);
+        diagnostics.extend(diagnostic);
+    }
+
+    Ok(out)
+}
+
+/// The threads `parse_files` runs on with `jobs` threads. They are kept for
+/// as long as the process runs, so that later calls, like each run of
+/// `--watch`, reuse the parsers the threads keep in `parser_pool`.
+fn thread_pool(jobs: Option<usize>) -> Arc<ThreadPool> {
+    static POOLS: OnceLock<Mutex<HashMap<usize, Arc<ThreadPool>>>> = OnceLock::new();
+
+    let mut pools = POOLS.get_or_init(Default::default).lock().unwrap();
+    let pool = pools.entry(jobs.unwrap_or(0)).or_insert_with(|| {
+        // Some large union type are causing us to stack overflow, so let's
+        // bump the default stack size for the thread pool as a temporary
+        // measure
+        //
+        // The case that caused this was a union with 88 members. Because
+        // unions are modelled as a tree with a left and right side, this
+        // means we are doing a lot of recusion when this type is parsed.
+        let pool = rayon::ThreadPoolBuilder::new()
+            .stack_size(4 * 1024 * 1024)
+            .num_threads(jobs.unwrap_or(0))
+            .build()
+            .expect("Failed to start parser threads");
+
+        Arc::new(pool)
+    });
+
+    pool.clone()
+}
+
+/// What parsers made of each file, kept between runs so that parsing the same
+/// files again only re-parses the ones that changed. Parsers keep the results
+/// from before cross-file resolution, which always runs over all files again.
+#[derive(Debug)]
+pub struct ParseCache<T> {
+    files: Mutex<HashMap<PathBuf, CachedFile<T>>>,
+}
+
+#[derive(Debug)]
+struct CachedFile<T> {
+    code: String,
+    mode: FileMode,
+    module_name: Option<String>,
+    parsed: T,
+}
+
+impl<T> Default for ParseCache<T> {
+    fn default() -> Self {
+        Self {
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> ParseCache<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns what `parse` made of `code` the last time, if `path` had the
+    /// same contents, mode and module name then. Otherwise parses it and
+    /// remembers the result.
+    ///
+    /// The module name is what the file is named by in FQNs, see
+    /// `Context::module_names`. It depends on files around it, like a
+    /// `package.json`, so it can change when the file doesn't.
+    pub fn get_or_parse<F>(
+        &self,
+        path: &Path,
+        code: &str,
+        mode: FileMode,
+        module_name: Option<&str>,
+        parse: F,
+    ) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        if let Some(cached) = self.files.lock().unwrap().get(path) {
+            if cached.code == code
+                && cached.mode == mode
+                && cached.module_name.as_deref() == module_name
+            {
+                return Ok(cached.parsed.clone());
+            }
+        }
+
+        let parsed = parse()?;
+        self.files.lock().unwrap().insert(
+            path.to_owned(),
+            CachedFile {
+                code: code.to_owned(),
+                mode,
+                module_name: module_name.map(str::to_owned),
+                parsed: parsed.clone(),
+            },
+        );
+
+        Ok(parsed)
+    }
+
+    /// Forgets the files that aren't in `paths`, e.g. because they were
+    /// deleted
+    pub fn retain(&self, paths: &[PathBuf]) {
+        let paths = paths.iter().map(|p| p.as_path()).collect::<HashSet<_>>();
+
+        self.files
+            .lock()
+            .unwrap()
+            .retain(|path, _| paths.contains(path.as_path()));
+    }
+
+    /// The number of files in the cache
+    pub fn len(&self) -> usize {
+        self.files.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::InMemoryFileSystem;
+
+    #[test]
+    fn keeps_the_order_of_the_paths() {
+        let files = InMemoryFileSystem {
+            files: (0..50)
+                .map(|i| (PathBuf::from(format!("file_{}.ts", i)), "x".repeat(50 - i)))
+                .collect(),
+        };
+        let paths = files.files.keys().cloned().collect::<Vec<_>>();
+
+        let lengths = parse_files(
+            &paths,
+            &files,
+            Some(4),
+            &FileLimits::default(),
+            &mut vec![],
+            |_, code, _| Ok(code.len()),
+        )
+        .unwrap();
+
+        assert_eq!(lengths, (1..=50).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn later_calls_run_on_the_same_threads() {
+        let files = InMemoryFileSystem {
+            files: [(PathBuf::from("index.ts"), "x".to_owned())]
+                .into_iter()
+                .collect(),
+        };
+        let paths = files.files.keys().cloned().collect::<Vec<_>>();
+        let thread = || {
+            parse_files(
+                &paths,
+                &files,
+                Some(1),
+                &FileLimits::default(),
+                &mut vec![],
+                |_, _, _| Ok(std::thread::current().id()),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(thread(), thread());
+    }
+
+    #[test]
+    fn missing_files_are_errors() {
+        let paths = vec![PathBuf::from("missing.py")];
+
+        let err = parse_files(
+            &paths,
+            &InMemoryFileSystem {
+                files: Default::default(),
+            },
+            None,
+            &FileLimits::default(),
+            &mut vec![],
+            |_, _, _| Ok(()),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, DossierError::IOError(path, _) if path == Path::new("missing.py")));
+    }
+
+    #[test]
+    fn detects_minified_files() {
+        let limits = FileLimits::default();
+        let bundle = "var a=1;".repeat(2000);
+        let with_header = format!("/* license */\n{}\n", "x".repeat(5000));
+        let normal = "const a = 1;\n".repeat(2000);
+
+        assert!(limits.looks_minified(&bundle));
+        assert!(limits.looks_minified(&with_header));
+        assert!(!limits.looks_minified(&normal));
+        assert!(!limits.looks_minified("export const a = 1;"));
+    }
+
+    #[test]
+    fn finds_long_lines_past_the_start_of_a_file() {
+        let limits = FileLimits::default();
+        let header = "// license\n".repeat(2000);
+        let bundle = format!("{header}{}\n", "x".repeat(200_000));
+        let short_bundle = format!("{header}{}", "x".repeat(5000));
+
+        assert!(limits.looks_minified(&bundle));
+        assert!(!limits.looks_minified(&short_bundle));
+        assert!(FileLimits {
+            max_single_line_fraction: 0.15,
+            ..limits
+        }
+        .looks_minified(&short_bundle));
+    }
+
+    #[test]
+    fn skips_or_degrades_minified_files() {
+        let files = InMemoryFileSystem {
+            files: [
+                ("app.ts".into(), "const a = 1;\n".to_owned()),
+                ("vendor.ts".into(), "var a=1;".repeat(2000)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let paths = files.files.keys().cloned().collect::<Vec<_>>();
+
+        let run = |minified| {
+            let limits = FileLimits {
+                minified,
+                ..Default::default()
+            };
+            let mut diagnostics = vec![];
+            let modes = parse_files(
+                &paths,
+                &files,
+                None,
+                &limits,
+                &mut diagnostics,
+                |p, _, m| Ok((p.to_owned(), m)),
+            )
+            .unwrap();
+            (modes, diagnostics)
+        };
+
+        let (modes, diagnostics) = run(MinifiedFiles::Skip);
+        assert_eq!(modes, vec![("app.ts".into(), FileMode::Full)]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, MINIFIED_FILE);
+        assert_eq!(diagnostics[0].file, Path::new("vendor.ts"));
+
+        let (modes, diagnostics) = run(MinifiedFiles::NamesOnly);
+        assert_eq!(
+            modes,
+            vec![
+                ("app.ts".into(), FileMode::Full),
+                ("vendor.ts".into(), FileMode::NamesOnly)
+            ]
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn reparses_only_changed_files() {
+        let cache = ParseCache::new();
+        let parses = std::sync::atomic::AtomicUsize::new(0);
+        let parse = |path: &str, code: &str| {
+            cache
+                .get_or_parse(Path::new(path), code, FileMode::Full, None, || {
+                    parses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Ok(code.len())
+                })
+                .unwrap()
+        };
+
+        assert_eq!(parse("a.ts", "one"), 3);
+        assert_eq!(parse("b.ts", "two"), 3);
+        assert_eq!(parse("a.ts", "one"), 3);
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        assert_eq!(parse("a.ts", "three"), 5);
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        cache.retain(&[PathBuf::from("a.ts")]);
+        assert_eq!(cache.len(), 1);
+        parse("b.ts", "two");
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn reparses_files_whose_module_name_changed() {
+        let cache = ParseCache::new();
+        let parses = std::sync::atomic::AtomicUsize::new(0);
+        let parse = |module_name: Option<&str>| {
+            cache
+                .get_or_parse(
+                    Path::new("a.ts"),
+                    "one",
+                    FileMode::Full,
+                    module_name,
+                    || {
+                        parses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        Ok(module_name.map(str::to_owned))
+                    },
+                )
+                .unwrap()
+        };
+
+        assert_eq!(parse(None), None);
+        assert_eq!(parse(Some("client")), Some("client".to_owned()));
+        assert_eq!(parse(Some("client")), Some("client".to_owned()));
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        assert_eq!(
+            parse(Some("@myorg/client")),
+            Some("@myorg/client".to_owned())
+        );
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+}
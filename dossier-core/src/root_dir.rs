@@ -0,0 +1,109 @@
+//! Makes paths relative to a root directory, see `Context::root`.
+//!
+//! Dossier names files by the paths they were given as, so running it from
+//! the root of a monorepo gives FQNs like
+//! `packages/client/src/index.ts::createClient`. With a root directory of
+//! `packages/client/src`, the file is named `index.ts` instead, both in FQNs
+//! and in the `source.file` of its entities. Parsers still read it from the
+//! path it was given as.
+//!
+//! Paths are compared without looking at the file system, after making them
+//! absolute with the current directory, so a relative root works with
+//! absolute paths and the other way around. Files outside of the root keep
+//! the paths they were given as.
+use std::path::{Path, PathBuf};
+
+use crate::{normalize_path, Entity};
+
+/// `path` relative to `root`, or `path` as it is if it isn't in `root`
+pub fn relative_to(path: &Path, root: &Path) -> PathBuf {
+    match absolute(path).strip_prefix(absolute(root)) {
+        Ok(relative) => relative.to_owned(),
+        Err(_) => path.to_owned(),
+    }
+}
+
+/// Makes the `source.file` of each entity and all of its members relative to
+/// `root`
+pub fn relative_sources(entities: &mut [Entity], root: &Path) {
+    for entity in entities.iter_mut() {
+        entity.source.file = relative_to(&entity.source.file, root);
+        relative_sources(&mut entity.members, root);
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    let path = match std::env::current_dir() {
+        Ok(current) if !path.is_absolute() => current.join(path),
+        _ => path.to_owned(),
+    };
+
+    normalize_path(&path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, EntityBuilder};
+    use crate::Identity;
+
+    #[test]
+    fn strips_the_root() {
+        let relative = |path: &str, root: &str| {
+            crate::portable_path(&relative_to(Path::new(path), Path::new(root)))
+        };
+
+        assert_eq!(
+            relative(
+                "/repo/packages/client/src/index.ts",
+                "/repo/packages/client"
+            ),
+            "src/index.ts"
+        );
+        assert_eq!(relative("/repo/src/index.ts", "/repo/src/"), "index.ts");
+        assert_eq!(
+            relative("packages/client/index.ts", "./packages/client"),
+            "index.ts"
+        );
+        assert_eq!(
+            relative("/repo/src/../lib/index.ts", "/repo/lib"),
+            "index.ts"
+        );
+        assert_eq!(relative("/other/index.ts", "/repo"), "/other/index.ts");
+        assert_eq!(
+            relative("/repository/index.ts", "/repo"),
+            "/repository/index.ts"
+        );
+    }
+
+    #[test]
+    fn mixes_absolute_and_relative_paths() {
+        let current = std::env::current_dir().unwrap();
+
+        assert_eq!(
+            relative_to(&current.join("src/index.ts"), Path::new("src")),
+            Path::new("index.ts")
+        );
+        assert_eq!(
+            relative_to(Path::new("src/index.ts"), &current),
+            Path::new("src/index.ts")
+        );
+    }
+
+    #[test]
+    fn makes_the_sources_of_members_relative() {
+        let mut entities = vec![entity("test", Identity::Anonymous)
+            .file("/repo/src/index.ts")
+            .members(vec![
+                entity("test", Identity::Anonymous).file("/repo/src/index.ts")
+            ])];
+
+        relative_sources(&mut entities, Path::new("/repo"));
+
+        assert_eq!(entities[0].source.file, Path::new("src/index.ts"));
+        assert_eq!(
+            entities[0].members[0].source.file,
+            Path::new("src/index.ts")
+        );
+    }
+}
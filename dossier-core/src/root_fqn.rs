@@ -0,0 +1,68 @@
+//! Records which top-level entity each entity belongs to, see
+//! `Context::root_fqns`.
+//!
+//! The root FQN of an entity is the FQN of the top-level entity of its file
+//! that it is nested in, or its own FQN if it is top-level itself. Renderers
+//! put each top-level entity on a page of its own and give its members
+//! anchors on that page, so the root FQN names the page an entity is on and
+//! its own FQN the anchor, when both are turned into slugs the same way.
+use crate::{Entity, Identity};
+
+/// Sets `meta.root_fqn` on each entity and all of its members. Top-level
+/// entities without an FQN of their own, like references, have no page, so
+/// neither they nor their members get one.
+pub fn record_root_fqns(entities: &mut [Entity]) {
+    for entity in entities.iter_mut() {
+        if let Identity::FQN(fqn) = &entity.identity {
+            let root = fqn.clone();
+            record(entity, &root);
+        }
+    }
+}
+
+fn record(entity: &mut Entity, root: &str) {
+    entity.meta["root_fqn"] = root.into();
+
+    for member in entity.members.iter_mut() {
+        record(member, root);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, fqn, EntityBuilder};
+
+    #[test]
+    fn records_the_top_level_ancestor() {
+        let resize = entity("test", fqn("index.test::Shapes.Circle.resize"))
+            .members(vec![entity("test", Identity::Anonymous)]);
+        let circle = entity("test", fqn("index.test::Shapes.Circle")).members(vec![resize]);
+        let mut entities = vec![
+            entity("test", fqn("index.test::Shapes")).members(vec![circle]),
+            entity("test", fqn("index.test::area")),
+        ];
+        record_root_fqns(&mut entities);
+
+        let shapes = &entities[0];
+        let resize = &shapes.members[0].members[0];
+        assert_eq!(shapes.meta["root_fqn"], "index.test::Shapes");
+        assert_eq!(shapes.members[0].meta["root_fqn"], "index.test::Shapes");
+        assert_eq!(resize.meta["root_fqn"], "index.test::Shapes");
+        assert_eq!(resize.members[0].meta["root_fqn"], "index.test::Shapes");
+        assert_eq!(entities[1].meta["root_fqn"], "index.test::area");
+    }
+
+    #[test]
+    fn skips_top_level_entities_without_fqn() {
+        let mut entities =
+            vec![
+                entity("test", Identity::Reference("other.test::Shape".to_owned()))
+                    .members(vec![entity("test", fqn("index.test::Shape.area"))]),
+            ];
+        record_root_fqns(&mut entities);
+
+        assert!(entities[0].meta.get("root_fqn").is_none());
+        assert!(entities[0].members[0].meta.get("root_fqn").is_none());
+    }
+}
@@ -0,0 +1,157 @@
+//! URL-safe identifiers for entities, for renderers to use as file names and
+//! anchors.
+//!
+//! A slug is made from an FQN by these rules:
+//!
+//! 1. Letters are lowercased. Letters and digits are kept, including ones
+//!    outside of ASCII, so slugs never need percent-encoding to be read.
+//! 2. Every run of other characters, like the `/`, `\`, `.` and `::` in
+//!    `src\shapes.ts::Circle`, becomes a single `-`. Path separators are all
+//!    the same, so FQNs of the same file on different platforms get the same
+//!    slug.
+//! 3. Leading and trailing `-`s are dropped.
+//!
+//! Different FQNs can have the same slug, e.g. `User` and `user`, or
+//! `a.ts::x` and `a-ts::x`. `Slugs` makes them unique within a set of
+//! entities, by adding `-2`, `-3`, etc. in the order the FQNs are found.
+use std::collections::{HashMap, HashSet};
+
+use crate::{Entity, Identity};
+
+/// Used for FQNs without any letters or digits
+const FALLBACK: &str = "entity";
+
+/// Turns an FQN into a slug, see the module documentation. The same FQN
+/// always gets the same slug.
+pub fn slugify(fqn: &str) -> String {
+    let mut slug = String::with_capacity(fqn.len());
+
+    for c in fqn.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_end_matches('-').to_owned()
+}
+
+impl Entity {
+    /// The slug of the entity's FQN, if it has one. Not unique, see `Slugs`.
+    pub fn slug(&self) -> Option<String> {
+        match &self.identity {
+            Identity::FQN(fqn) => Some(slugify(fqn)),
+            _ => None,
+        }
+    }
+}
+
+/// Unique slugs for the FQNs of a set of entities
+#[derive(Debug, Clone, Default)]
+pub struct Slugs {
+    by_fqn: HashMap<String, String>,
+    taken: HashSet<String>,
+}
+
+impl Slugs {
+    /// Assigns slugs to the FQNs of the entities and all of their members,
+    /// depth first in order.
+    pub fn new(entities: &[Entity]) -> Self {
+        let mut slugs = Self::default();
+        for entity in entities {
+            slugs.collect(entity);
+        }
+        slugs
+    }
+
+    fn collect(&mut self, entity: &Entity) {
+        if let Identity::FQN(fqn) = &entity.identity {
+            self.insert(fqn);
+        }
+        for member in &entity.members {
+            self.collect(member);
+        }
+    }
+
+    /// The slug of `fqn`, assigning it one that isn't taken yet if it doesn't
+    /// have one.
+    pub fn insert(&mut self, fqn: &str) -> &str {
+        if !self.by_fqn.contains_key(fqn) {
+            let mut base = slugify(fqn);
+            if base.is_empty() {
+                base = FALLBACK.to_owned();
+            }
+
+            let mut slug = base.clone();
+            let mut suffix = 2;
+            while self.taken.contains(&slug) {
+                slug = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+
+            self.taken.insert(slug.clone());
+            self.by_fqn.insert(fqn.to_owned(), slug);
+        }
+
+        &self.by_fqn[fqn]
+    }
+
+    /// The slug of `fqn`, if it is the FQN of one of the entities
+    pub fn get(&self, fqn: &str) -> Option<&str> {
+        self.by_fqn.get(fqn).map(|s| s.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, fqn, EntityBuilder};
+
+    #[test]
+    fn slugifies_fqns() {
+        assert_eq!(
+            slugify("src/shapes.ts::Polygon::perimeter"),
+            "src-shapes-ts-polygon-perimeter"
+        );
+        assert_eq!(slugify("../a.py::Greeter"), "a-py-greeter");
+        assert_eq!(slugify("src/ユーザー.ts::Ä"), "src-ユーザー-ts-ä");
+    }
+
+    #[test]
+    fn windows_paths_get_the_same_slug() {
+        assert_eq!(
+            slugify(r"src\shapes\circle.ts::Circle"),
+            slugify("src/shapes/circle.ts::Circle")
+        );
+        assert_eq!(
+            entity("test", fqn(r"C:\src\a.ts::Foo")).slug().as_deref(),
+            Some("c-src-a-ts-foo")
+        );
+    }
+
+    #[test]
+    fn suffixes_colliding_slugs() {
+        let entities = vec![entity("test", fqn("a.ts::Config")).members(vec![
+            entity("test", fqn("a.ts::Config::user")),
+            entity("test", fqn("a.ts::Config::User")),
+            entity("test", fqn("a.ts::Config::USER")),
+            // Taken by the second one above
+            entity("test", fqn("a.ts::Config::user-2")),
+        ])];
+        let mut slugs = Slugs::new(&entities);
+
+        assert_eq!(slugs.get("a.ts::Config::user"), Some("a-ts-config-user"));
+        assert_eq!(slugs.get("a.ts::Config::User"), Some("a-ts-config-user-2"));
+        assert_eq!(slugs.get("a.ts::Config::USER"), Some("a-ts-config-user-3"));
+        assert_eq!(
+            slugs.get("a.ts::Config::user-2"),
+            Some("a-ts-config-user-2-2")
+        );
+
+        // The same FQN keeps its slug
+        assert_eq!(slugs.insert("a.ts::Config::User"), "a-ts-config-user-2");
+        assert_eq!(slugs.insert("::"), "entity");
+        assert_eq!(slugs.get("b.ts::Config"), None);
+    }
+}
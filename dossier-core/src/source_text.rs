@@ -0,0 +1,122 @@
+//! Embeds the source code of each entity in it, see
+//! `Context::include_source_text`.
+//!
+//! Entities only point at their source with byte offsets, so showing the
+//! declaration as written would otherwise mean reading the file again. This
+//! pass sets `meta.source_text` to the source between each entity's start and
+//! end. Text longer than the limit is cut at a character boundary and ends
+//! with `…`, and has `meta.source_text_truncated` set.
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{Entity, FileSource};
+
+/// What cut off source text ends with
+pub const ELLIPSIS: &str = "…";
+
+/// Sets `meta.source_text` on each entity and all of its members, reading
+/// their files from `files`. Text longer than `max_bytes` is truncated.
+/// Entities whose file can't be read again, or whose offsets don't fit in it,
+/// are left as they are.
+pub fn record_source_text(
+    entities: &mut [Entity],
+    files: &dyn FileSource,
+    max_bytes: Option<usize>,
+) {
+    let mut sources = HashMap::new();
+
+    for entity in entities.iter_mut() {
+        record(entity, files, max_bytes, &mut sources);
+    }
+}
+
+fn record(
+    entity: &mut Entity,
+    files: &dyn FileSource,
+    max_bytes: Option<usize>,
+    sources: &mut HashMap<PathBuf, Option<String>>,
+) {
+    let code = sources
+        .entry(entity.source.file.clone())
+        .or_insert_with(|| files.read_file(&entity.source.file).ok());
+
+    let text = code
+        .as_deref()
+        .and_then(|code| code.get(entity.source.start.byte_offset..entity.source.end.byte_offset));
+    if let Some(text) = text {
+        match max_bytes {
+            Some(max) if text.len() > max => {
+                entity.meta["source_text"] = format!("{}{}", truncate(text, max), ELLIPSIS).into();
+                entity.meta["source_text_truncated"] = true.into();
+            }
+            _ => entity.meta["source_text"] = text.into(),
+        }
+    }
+
+    for member in entity.members.iter_mut() {
+        record(member, files, max_bytes, sources);
+    }
+}
+
+/// The longest prefix of `text` that is at most `max` bytes long and doesn't
+/// split a character
+fn truncate(text: &str, max: usize) -> &str {
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, EntityBuilder};
+    use crate::{Identity, InMemoryFileSystem};
+
+    const CODE: &str = "class Point {\n  x: number;\n}\n";
+
+    /// The class in `CODE`, with its field
+    fn point() -> Vec<Entity> {
+        vec![entity("class", Identity::Anonymous)
+            .file("index.ts")
+            .span(0, 28)
+            .members(vec![entity("field", Identity::Anonymous)
+                .file("index.ts")
+                .span(16, 26)])]
+    }
+
+    fn files() -> InMemoryFileSystem {
+        let mut files = InMemoryFileSystem::new();
+        files.add_file("index.ts", CODE);
+        files
+    }
+
+    #[test]
+    fn records_the_source_of_each_entity() {
+        let mut entities = point();
+
+        record_source_text(&mut entities, &files(), None);
+
+        assert_eq!(entities[0].meta["source_text"], &CODE[0..28]);
+        assert_eq!(entities[0].members[0].meta["source_text"], "x: number;");
+        assert!(entities[0].meta.get("source_text_truncated").is_none());
+    }
+
+    #[test]
+    fn truncates_long_source() {
+        let mut entities = point();
+
+        record_source_text(&mut entities, &files(), Some(11));
+
+        assert_eq!(entities[0].meta["source_text"], "class Point…");
+        assert_eq!(entities[0].meta["source_text_truncated"], true);
+        assert_eq!(entities[0].members[0].meta["source_text"], "x: number;");
+    }
+
+    #[test]
+    fn truncates_at_character_boundaries() {
+        assert_eq!(truncate("naïve", 3), "na");
+        assert_eq!(truncate("naïve", 4), "naï");
+    }
+}
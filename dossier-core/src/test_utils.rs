@@ -0,0 +1,260 @@
+//! Helpers for the tests of parsers, so that each language crate doesn't
+//! have to set up its own. Enabled with the `test-utils` feature, which
+//! parser crates turn on for their tests only:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! dossier-core = { path = "../dossier-core", features = ["test-utils"] }
+//! ```
+//!
+//! A typical test parses some source, finds the node it is about, and checks
+//! what the parser makes of it:
+//!
+//! ```ignore
+//! let tree = test_utils::parse(source, tree_sitter_python::language());
+//! let node = test_utils::first_node(&tree, "class_definition");
+//! let symbol = Class::parse_symbol(node, &mut ctx).unwrap();
+//!
+//! dossier_core::assert_json_snapshot!("class.json", symbol.as_entity());
+//! ```
+//!
+//! Tests of code that works on entities rather than source can build them
+//! with `entity` and the setters of `EntityBuilder`.
+use crate::{Entity, Identity, MemberContext, Position, Source};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser, Tree};
+
+/// Set to write the actual output over snapshots that don't match
+const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// Parses `source` with the grammar `language`
+pub fn parse(source: &str, language: Language) -> Tree {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .expect("Error loading the grammar");
+
+    parser.parse(source, None).expect("Parsing was cancelled")
+}
+
+/// The first node of the kind `kind` in `tree`, in the order they appear in
+/// the source. Panics if there isn't one, since the test can't go on.
+pub fn first_node<'tree>(tree: &'tree Tree, kind: &str) -> Node<'tree> {
+    find_node(tree.root_node(), kind)
+        .unwrap_or_else(|| panic!("No `{}` node in {}", kind, tree.root_node().to_sexp()))
+}
+
+fn find_node<'tree>(node: Node<'tree>, kind: &str) -> Option<Node<'tree>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+
+    let mut cursor = node.walk();
+    let children = node.children(&mut cursor).collect::<Vec<_>>();
+    children
+        .into_iter()
+        .find_map(|child| find_node(child, kind))
+}
+
+/// The identity of an entity declared with the FQN `fqn`
+pub fn fqn(fqn: &str) -> Identity {
+    Identity::FQN(fqn.to_owned())
+}
+
+/// The identity of an entity that refers to the one declared with `fqn`
+pub fn reference(fqn: &str) -> Identity {
+    Identity::Reference(fqn.to_owned())
+}
+
+/// An entity of the kind `kind` in the file `index.test`, with no title,
+/// description, meta or members. Set the fields a test is about with the
+/// setters of `EntityBuilder`:
+///
+/// ```ignore
+/// let class = entity("class", fqn("index.ts::Shape"))
+///     .title("Shape")
+///     .visibility(Visibility::Exported)
+///     .members(vec![entity("method", Identity::Anonymous).title("area")]);
+/// ```
+pub fn entity(kind: &str, identity: Identity) -> Entity {
+    let position = Position {
+        row: 0,
+        column: 0,
+        byte_offset: 0,
+    };
+
+    Entity {
+        title: None,
+        description: String::new(),
+        kind: kind.to_owned(),
+        identity,
+        members: vec![],
+        member_context: None,
+        language: "test".to_owned(),
+        meta: serde_json::json!({}),
+        source: Source {
+            file: "index.test".into(),
+            start: position.clone(),
+            end: position,
+            repository: None,
+        },
+    }
+}
+
+/// How visible an entity is, recorded in its meta under the same keys as
+/// parsers use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// `meta.exported`
+    Exported,
+    /// `meta.private`
+    Private,
+    /// `meta.protected`
+    Protected,
+}
+
+/// Setters for the entities of tests, which each return the changed entity so
+/// they can be chained after `entity`
+pub trait EntityBuilder {
+    fn title(self, title: &str) -> Self;
+    fn description(self, description: &str) -> Self;
+    /// Replaces the whole meta
+    fn meta(self, meta: Value) -> Self;
+    fn context(self, context: impl Into<MemberContext>) -> Self;
+    fn members(self, members: Vec<Entity>) -> Self;
+    /// Sets the meta key of `visibility`, keeping the rest of the meta
+    fn visibility(self, visibility: Visibility) -> Self;
+    fn language(self, language: &str) -> Self;
+    fn file(self, file: &str) -> Self;
+    /// Sets the byte offsets the entity starts and ends at
+    fn span(self, start: usize, end: usize) -> Self;
+}
+
+impl EntityBuilder for Entity {
+    fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    fn description(mut self, description: &str) -> Self {
+        self.description = description.to_owned();
+        self
+    }
+
+    fn meta(mut self, meta: Value) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    fn context(mut self, context: impl Into<MemberContext>) -> Self {
+        self.member_context = Some(context.into());
+        self
+    }
+
+    fn members(mut self, members: Vec<Entity>) -> Self {
+        self.members = members;
+        self
+    }
+
+    fn visibility(mut self, visibility: Visibility) -> Self {
+        let key = match visibility {
+            Visibility::Exported => "exported",
+            Visibility::Private => "private",
+            Visibility::Protected => "protected",
+        };
+        self.meta[key] = true.into();
+        self
+    }
+
+    fn language(mut self, language: &str) -> Self {
+        self.language = language.to_owned();
+        self
+    }
+
+    fn file(mut self, file: &str) -> Self {
+        self.source.file = file.into();
+        self
+    }
+
+    fn span(mut self, start: usize, end: usize) -> Self {
+        self.source.start.byte_offset = start;
+        self.source.end.byte_offset = end;
+        self
+    }
+}
+
+/// Asserts that `value`, serialized as pretty-printed JSON, is what the file
+/// at `path` holds. Run the tests with `UPDATE_SNAPSHOTS=1` to write new or
+/// changed snapshots, and review them like any other change.
+///
+/// Usually called through `assert_json_snapshot!`.
+pub fn assert_json_snapshot<T: Serialize + ?Sized>(path: &Path, value: &T) {
+    let mut actual = serde_json::to_string_pretty(value).expect("Value can't be serialized");
+    actual.push('\n');
+
+    if std::env::var_os(UPDATE_SNAPSHOTS).is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(path, &actual).unwrap();
+    }
+
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|_| {
+        panic!(
+            "Snapshot {} doesn't exist. Run with {}=1 to create it.",
+            path.display(),
+            UPDATE_SNAPSHOTS
+        )
+    });
+    assert_eq!(
+        actual,
+        expected,
+        "Snapshot {} is out of date. Run with {}=1 to update it.",
+        path.display(),
+        UPDATE_SNAPSHOTS
+    );
+}
+
+/// `test_utils::assert_json_snapshot` with a snapshot in the `src/snapshots`
+/// directory of the crate the test is in
+#[macro_export]
+macro_rules! assert_json_snapshot {
+    ($name:expr, $value:expr) => {
+        $crate::test_utils::assert_json_snapshot(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("src/snapshots")
+                .join($name),
+            &$value,
+        )
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compares_values_with_snapshots() {
+        let dir = std::env::temp_dir().join(format!("dossier-snapshots-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("value.json");
+        std::fs::write(&path, "{\n  \"title\": \"Point\"\n}\n").unwrap();
+
+        assert_json_snapshot(&path, &serde_json::json!({ "title": "Point" }));
+
+        let mismatch = std::panic::catch_unwind(|| {
+            assert_json_snapshot(&path, &serde_json::json!({ "title": "Line" }))
+        });
+        let missing = std::panic::catch_unwind(|| {
+            assert_json_snapshot(&dir.join("missing.json"), &serde_json::json!({}))
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        if std::env::var_os(UPDATE_SNAPSHOTS).is_none() {
+            assert!(mismatch.is_err());
+            assert!(missing.is_err());
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Walking and transforming trees of entities and their members.
+//!
+//! ```
+//! # use dossier_core::{tree, Entity};
+//! # fn example(entities: &mut Vec<Entity>) {
+//! for node in tree::walk(entities) {
+//!     println!("{}{}", "  ".repeat(node.depth), node.entity.kind);
+//! }
+//!
+//! let removed = tree::Prune::matching(|e: &Entity| e.kind == "variable").run(entities);
+//! # }
+//! ```
+use std::collections::HashSet;
+
+use crate::{Entity, Identity};
+
+/// An entity found by `walk`, with where it is in the tree
+#[derive(Debug, Clone, Copy)]
+pub struct TreeNode<'a> {
+    pub entity: &'a Entity,
+    /// The entity it is a member of, or `None` at the top level
+    pub parent: Option<&'a Entity>,
+    /// How many ancestors it has, 0 at the top level
+    pub depth: usize,
+}
+
+/// Iterates over entities and all of their members, depth first, with each
+/// entity before its members. See `walk`.
+pub struct EntityTreeIter<'a> {
+    stack: Vec<TreeNode<'a>>,
+}
+
+impl<'a> EntityTreeIter<'a> {
+    pub fn new(entities: &'a [Entity]) -> Self {
+        let stack = entities
+            .iter()
+            .rev()
+            .map(|entity| TreeNode {
+                entity,
+                parent: None,
+                depth: 0,
+            })
+            .collect();
+
+        Self { stack }
+    }
+}
+
+impl<'a> Iterator for EntityTreeIter<'a> {
+    type Item = TreeNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+
+        self.stack
+            .extend(node.entity.members.iter().rev().map(|member| TreeNode {
+                entity: member,
+                parent: Some(node.entity),
+                depth: node.depth + 1,
+            }));
+
+        Some(node)
+    }
+}
+
+/// Every entity in `entities`, including members, in the order they appear
+/// in the output
+pub fn walk(entities: &[Entity]) -> EntityTreeIter<'_> {
+    EntityTreeIter::new(entities)
+}
+
+/// The entity declared with the FQN `fqn`. References to it don't count.
+pub fn find_by_fqn<'a>(entities: &'a [Entity], fqn: &str) -> Option<&'a Entity> {
+    walk(entities)
+        .map(|node| node.entity)
+        .find(|entity| matches!(&entity.identity, Identity::FQN(f) if f == fqn))
+}
+
+/// Calls `f` with every entity in `entities`, each before its members, so
+/// that it can change them in place. Members `f` adds are visited too.
+pub fn map<F>(entities: &mut [Entity], mut f: F)
+where
+    F: FnMut(&mut Entity),
+{
+    map_with(entities, &mut f);
+}
+
+fn map_with<F>(entities: &mut [Entity], f: &mut F)
+where
+    F: FnMut(&mut Entity),
+{
+    for entity in entities {
+        f(entity);
+        map_with(&mut entity.members, f);
+    }
+}
+
+/// Removes the entities matching a predicate, along with their members.
+///
+/// By default, references to the removed entities become anonymous, so that
+/// no FQN in what is left points at a missing entity.
+pub struct Prune<F, S = fn(&Entity) -> bool> {
+    predicate: F,
+    spare: S,
+    repair_references: bool,
+}
+
+impl<F> Prune<F>
+where
+    F: Fn(&Entity) -> bool,
+{
+    /// Prunes the entities `predicate` returns true for
+    pub fn matching(predicate: F) -> Self {
+        Self {
+            predicate,
+            spare: |_| false,
+            repair_references: true,
+        }
+    }
+}
+
+impl<F, S> Prune<F, S>
+where
+    F: Fn(&Entity) -> bool,
+    S: Fn(&Entity) -> bool,
+{
+    /// Keeps the entities `spare` returns true for, along with all of their
+    /// members, without checking them against the predicate
+    pub fn sparing<T>(self, spare: T) -> Prune<F, T>
+    where
+        T: Fn(&Entity) -> bool,
+    {
+        Prune {
+            predicate: self.predicate,
+            spare,
+            repair_references: self.repair_references,
+        }
+    }
+
+    /// Leaves references to removed entities as they are, e.g. when they
+    /// are documented elsewhere
+    pub fn keep_references(mut self) -> Self {
+        self.repair_references = false;
+        self
+    }
+
+    /// Prunes `entities`. Returns the FQNs of the removed entities and their
+    /// members, in the order they appeared in.
+    pub fn run(self, entities: &mut Vec<Entity>) -> Vec<String> {
+        let mut removed = vec![];
+        prune(entities, &self.predicate, &self.spare, &mut removed);
+
+        if self.repair_references && !removed.is_empty() {
+            let fqns = removed.iter().map(String::as_str).collect::<HashSet<_>>();
+            map(entities, |entity| {
+                if matches!(&entity.identity, Identity::Reference(fqn) if fqns.contains(fqn.as_str()))
+                {
+                    entity.identity = Identity::Anonymous;
+                }
+            });
+        }
+
+        removed
+    }
+}
+
+fn prune<F, S>(entities: &mut Vec<Entity>, predicate: &F, spare: &S, removed: &mut Vec<String>)
+where
+    F: Fn(&Entity) -> bool,
+    S: Fn(&Entity) -> bool,
+{
+    entities.retain_mut(|entity| {
+        if spare(entity) {
+            return true;
+        }
+
+        if predicate(entity) {
+            removed.extend(walk(std::slice::from_ref(entity)).filter_map(|node| {
+                match &node.entity.identity {
+                    Identity::FQN(fqn) => Some(fqn.clone()),
+                    _ => None,
+                }
+            }));
+            return false;
+        }
+
+        prune(&mut entity.members, predicate, spare, removed);
+        true
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{self, entity, fqn, reference, EntityBuilder};
+
+    /// ```text
+    /// Shape
+    ///   area
+    ///     number
+    ///   Point
+    ///     x
+    /// Circle
+    ///   Shape (reference)
+    ///   Point (reference)
+    /// ```
+    fn shapes() -> Vec<Entity> {
+        vec![
+            entity("namespace", fqn("index.test::Shape")).members(vec![
+                entity("function", fqn("index.test::Shape::area"))
+                    .members(vec![entity("predefined_type", Identity::Anonymous)]),
+                entity("interface", fqn("index.test::Shape::Point"))
+                    .members(vec![entity("field", fqn("index.test::Shape::Point::x"))]),
+            ]),
+            entity("class", fqn("index.test::Circle")).members(vec![
+                entity("identifier", reference("index.test::Shape")),
+                entity("identifier", reference("index.test::Shape::Point")),
+            ]),
+        ]
+    }
+
+    fn identities(entities: &[Entity]) -> Vec<Identity> {
+        entities.iter().map(|e| e.identity.clone()).collect()
+    }
+
+    #[test]
+    fn walks_depth_first_with_parents() {
+        let entities = shapes();
+
+        let walked = walk(&entities)
+            .map(|node| {
+                (
+                    node.entity.kind.as_str(),
+                    node.parent.map(|p| p.kind.as_str()),
+                    node.depth,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            walked,
+            [
+                ("namespace", None, 0),
+                ("function", Some("namespace"), 1),
+                ("predefined_type", Some("function"), 2),
+                ("interface", Some("namespace"), 1),
+                ("field", Some("interface"), 2),
+                ("class", None, 0),
+                ("identifier", Some("class"), 1),
+                ("identifier", Some("class"), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn walks_nothing() {
+        assert_eq!(walk(&[]).count(), 0);
+    }
+
+    #[test]
+    fn finds_nested_entities_by_fqn() {
+        let entities = shapes();
+
+        let x = find_by_fqn(&entities, "index.test::Shape::Point::x").unwrap();
+        assert_eq!(x.kind, "field");
+        assert_eq!(
+            find_by_fqn(&entities, "index.test::Circle").unwrap().kind,
+            "class"
+        );
+        assert!(find_by_fqn(&entities, "index.test::Missing").is_none());
+    }
+
+    #[test]
+    fn does_not_find_references() {
+        let entities = vec![entity("identifier", reference("index.test::Shape"))];
+
+        assert!(find_by_fqn(&entities, "index.test::Shape").is_none());
+    }
+
+    #[test]
+    fn maps_every_entity_in_place() {
+        let mut entities = shapes();
+
+        map(&mut entities, |entity| {
+            entity.kind = entity.kind.to_uppercase()
+        });
+
+        assert!(walk(&entities).all(|node| node.entity.kind == node.entity.kind.to_uppercase()));
+        assert_eq!(walk(&entities).count(), 8);
+    }
+
+    #[test]
+    fn maps_members_added_on_the_way() {
+        let mut entities = shapes();
+        let mut visited = 0;
+
+        map(&mut entities, |entity| {
+            visited += 1;
+            if entity.kind == "field" {
+                let number = test_utils::entity("predefined_type", Identity::Anonymous);
+                entity.members.push(number);
+            }
+        });
+
+        assert_eq!(visited, 9);
+    }
+
+    #[test]
+    fn prunes_subtrees_and_repairs_references() {
+        let mut entities = shapes();
+
+        let removed = Prune::matching(|e: &Entity| e.kind == "interface").run(&mut entities);
+
+        assert_eq!(
+            removed,
+            ["index.test::Shape::Point", "index.test::Shape::Point::x"]
+        );
+        assert_eq!(
+            identities(&entities[0].members),
+            [fqn("index.test::Shape::area")]
+        );
+        assert_eq!(
+            identities(&entities[1].members),
+            [reference("index.test::Shape"), Identity::Anonymous]
+        );
+    }
+
+    #[test]
+    fn prunes_top_level_entities() {
+        let mut entities = shapes();
+
+        let removed = Prune::matching(|e: &Entity| e.kind == "namespace").run(&mut entities);
+
+        assert_eq!(removed.len(), 4);
+        assert_eq!(identities(&entities), [fqn("index.test::Circle")]);
+        assert_eq!(
+            identities(&entities[0].members),
+            [Identity::Anonymous, Identity::Anonymous]
+        );
+    }
+
+    #[test]
+    fn prunes_without_repairing_references() {
+        let mut entities = shapes();
+
+        Prune::matching(|e: &Entity| e.kind == "namespace")
+            .keep_references()
+            .run(&mut entities);
+
+        assert_eq!(
+            identities(&entities[0].members),
+            [
+                reference("index.test::Shape"),
+                reference("index.test::Shape::Point")
+            ]
+        );
+    }
+
+    #[test]
+    fn spares_subtrees() {
+        let mut entities = shapes();
+
+        let removed = Prune::matching(|e: &Entity| e.kind != "namespace")
+            .sparing(|e: &Entity| e.kind == "interface")
+            .run(&mut entities);
+
+        assert_eq!(removed, ["index.test::Shape::area", "index.test::Circle"]);
+        assert_eq!(
+            identities(&entities[0].members),
+            [fqn("index.test::Shape::Point")]
+        );
+        assert_eq!(
+            identities(&entities[0].members[0].members),
+            [fqn("index.test::Shape::Point::x")]
+        );
+    }
+
+    #[test]
+    fn prunes_nothing() {
+        let mut entities = shapes();
+
+        let removed = Prune::matching(|_: &Entity| false).run(&mut entities);
+
+        assert!(removed.is_empty());
+        assert_eq!(entities, shapes());
+    }
+}
@@ -0,0 +1,196 @@
+//! Restricts entities to the public API, see `Context::public_only`.
+//!
+//! Dropping everything that isn't public would leave references dangling, e.g.
+//! when an exported function returns an interface that isn't exported itself.
+//! So entities that public entities refer to are kept too, along with whatever
+//! they refer to in turn.
+use std::collections::HashMap;
+
+use crate::{Entity, Identity};
+
+/// Removes the entities that aren't public, and aren't referred to from
+/// public ones.
+///
+/// `is_public` is called with each entity and its parent, if any. An entity is
+/// kept if it and all of its ancestors are public, or if a kept entity refers
+/// to it. Keeping a member keeps its ancestors, but not their other members.
+pub fn retain_public<F>(entities: &mut Vec<Entity>, is_public: F)
+where
+    F: Fn(&Entity, Option<&Entity>) -> bool,
+{
+    let mut nodes = vec![];
+    for entity in entities.iter() {
+        index(entity, None, &is_public, &mut nodes);
+    }
+
+    let by_fqn = nodes
+        .iter()
+        .enumerate()
+        .rev()
+        .filter_map(|(id, node)| node.fqn.map(|fqn| (fqn, id)))
+        .collect::<HashMap<_, _>>();
+
+    let mut kept = vec![false; nodes.len()];
+    let mut stack = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.parent.is_none() && node.public)
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+
+    while let Some(id) = stack.pop() {
+        if kept[id] {
+            continue;
+        }
+        kept[id] = true;
+
+        let mut ancestor = nodes[id].parent;
+        while let Some(parent) = ancestor {
+            kept[parent] = true;
+            ancestor = nodes[parent].parent;
+        }
+
+        let node = &nodes[id];
+        stack.extend(node.children.iter().filter(|c| nodes[**c].public));
+        if let Some(target) = node.reference.and_then(|fqn| by_fqn.get(fqn)) {
+            stack.push(*target);
+        }
+    }
+
+    let mut next_id = 0;
+    entities.retain_mut(|entity| prune(entity, &mut next_id, &kept));
+}
+
+/// An entity, identified by its position in a pre-order walk of the tree
+struct Node<'a> {
+    parent: Option<usize>,
+    children: Vec<usize>,
+    public: bool,
+    fqn: Option<&'a str>,
+    reference: Option<&'a str>,
+}
+
+fn index<'a, F>(
+    entity: &'a Entity,
+    parent: Option<(usize, &'a Entity)>,
+    is_public: &F,
+    nodes: &mut Vec<Node<'a>>,
+) where
+    F: Fn(&Entity, Option<&Entity>) -> bool,
+{
+    let id = nodes.len();
+    let (fqn, reference) = match &entity.identity {
+        Identity::FQN(fqn) => (Some(fqn.as_str()), None),
+        Identity::Reference(fqn) => (None, Some(fqn.as_str())),
+        Identity::Anonymous => (None, None),
+    };
+
+    nodes.push(Node {
+        parent: parent.map(|(id, _)| id),
+        children: vec![],
+        public: is_public(entity, parent.map(|(_, e)| e)),
+        fqn,
+        reference,
+    });
+    if let Some((parent_id, _)) = parent {
+        nodes[parent_id].children.push(id);
+    }
+
+    for member in &entity.members {
+        index(member, Some((id, entity)), is_public, nodes);
+    }
+}
+
+/// Removes the members that weren't kept. Visits the entities in the same
+/// order as `index`, so that the IDs match.
+fn prune(entity: &mut Entity, next_id: &mut usize, kept: &[bool]) -> bool {
+    let id = *next_id;
+    *next_id += 1;
+
+    entity
+        .members
+        .retain_mut(|member| prune(member, next_id, kept));
+
+    kept[id]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{entity, fqn, reference, EntityBuilder, Visibility};
+
+    fn titles(entities: &[Entity]) -> Vec<String> {
+        let mut out = vec![];
+        for e in entities {
+            out.push(e.title.clone().unwrap());
+            out.extend(titles(&e.members).into_iter().map(|t| format!("  {}", t)));
+        }
+        out
+    }
+
+    fn is_exported(entity: &Entity) -> bool {
+        entity.meta["exported"] == true
+    }
+
+    #[test]
+    fn keeps_public_entities_and_what_they_refer_to() {
+        let mut entities = vec![
+            entity("test", fqn("api"))
+                .title("api")
+                .visibility(Visibility::Exported)
+                .members(vec![
+                    entity("test", reference("Hidden"))
+                        .title("ret")
+                        .visibility(Visibility::Exported),
+                    entity("test", fqn("api::secret")).title("secret"),
+                ]),
+            entity("test", fqn("Hidden"))
+                .title("Hidden")
+                .members(vec![entity("test", fqn("Hidden::field"))
+                    .title("field")
+                    .visibility(Visibility::Exported)
+                    .members(vec![entity("test", reference("Inner::deep"))
+                        .title("type")
+                        .visibility(Visibility::Exported)])]),
+            entity("test", fqn("Inner")).title("Inner").members(vec![
+                entity("test", fqn("Inner::deep")).title("deep"),
+                entity("test", fqn("Inner::other"))
+                    .title("other")
+                    .visibility(Visibility::Exported),
+            ]),
+            entity("test", fqn("Unused")).title("Unused"),
+        ];
+
+        retain_public(&mut entities, |e, _| is_exported(e));
+
+        assert_eq!(
+            titles(&entities),
+            vec![
+                "api", "  ret", "Hidden", "  field", "    type",
+                // Kept to hold `deep`, but its other members aren't
+                "Inner", "  deep",
+            ]
+        );
+    }
+
+    #[test]
+    fn handles_reference_cycles() {
+        let mut entities = vec![
+            entity("test", fqn("A"))
+                .title("A")
+                .visibility(Visibility::Exported)
+                .members(vec![entity("test", reference("B"))
+                    .title("b")
+                    .visibility(Visibility::Exported)]),
+            entity("test", fqn("B"))
+                .title("B")
+                .members(vec![entity("test", reference("A"))
+                    .title("a")
+                    .visibility(Visibility::Exported)]),
+        ];
+
+        retain_public(&mut entities, |e, _| is_exported(e));
+
+        assert_eq!(titles(&entities), vec!["A", "  b", "B", "  a"]);
+    }
+}
@@ -0,0 +1,446 @@
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, MemberContext, Result};
+
+use crate::{
+    class::Flavor,
+    symbol::{Location, ParseSymbol, Symbol, SymbolContext, SymbolKind},
+    types::Type,
+    ParserContext,
+};
+
+/// An attribute of a class: an annotated assignment in the class body like
+/// `name: str = "x"`, or an assignment to `self` in `__init__` like
+/// `self.name = name`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Attribute {
+    pub title: String,
+    /// The comment on the lines right above the assignment, or at the end of
+    /// its line
+    pub documentation: Option<String>,
+    /// The type, if it is annotated
+    pub members: Vec<Symbol>,
+    /// The value assigned, as written. For the fields of dataclasses, the
+    /// `default` passed to `field()` if it is used.
+    pub default: Option<String>,
+    /// The `default_factory` passed to `field()` for a dataclass field
+    pub field_factory: Option<String>,
+    /// The parameter for the field in the `__init__` a dataclass generates,
+    /// like `y: float = 0.0`. Not set for other attributes, or fields left
+    /// out of `__init__` with `field(init=False)` or `ClassVar`.
+    pub init_parameter: Option<String>,
+}
+
+impl Attribute {
+    pub fn as_entity(
+        &self,
+        loc: &Location,
+        fqn: Option<&str>,
+        context: Option<&SymbolContext>,
+    ) -> Entity {
+        let mut meta = json!({});
+        if let Some(default) = &self.default {
+            meta["default"] = default.as_str().into();
+        }
+        if let Some(factory) = &self.field_factory {
+            meta["field_factory"] = factory.as_str().into();
+        }
+
+        Entity {
+            title: Some(self.title.to_owned()),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
+            kind: "attribute".to_owned(),
+            identity: dossier_core::Identity::FQN(fqn.expect("attribute without FQN").to_owned()),
+            members: self.members.iter().map(|s| s.as_entity()).collect(),
+            member_context: context.map(|c| MemberContext::from(*c)),
+            language: crate::LANGUAGE.to_owned(),
+            source: loc.as_source(),
+            meta,
+        }
+    }
+}
+
+/// Parses the attributes of a class with the body `body`: the annotated
+/// assignments in the body, and then the ones to `self` in `__init__` that
+/// aren't in the body already. The assignments in the body of enums are
+/// their members instead, see `enum_member`. Expects the FQN of the class to
+/// be pushed.
+pub(crate) fn parse_attributes(
+    body: &Node,
+    flavor: Flavor,
+    ctx: &mut ParserContext,
+) -> Result<Vec<Symbol>> {
+    let mut out = vec![];
+    let mut cursor = body.walk();
+
+    let class_level = body
+        .named_children(&mut cursor)
+        .filter(|_| flavor != Flavor::Enum)
+        .collect::<Vec<_>>();
+    for statement in class_level {
+        let Some(assignment) = assignment_of(&statement) else {
+            continue;
+        };
+        let target = assignment.child_by_field_name("left").unwrap();
+        if target.kind() != "identifier" || assignment.child_by_field_name("type").is_none() {
+            continue;
+        }
+
+        let title = text(&target, ctx).to_owned();
+        let mut attribute = parse_attribute(title, &statement, &assignment, ctx)?;
+        if flavor == Flavor::Dataclass {
+            as_dataclass_field(&mut attribute, &assignment, ctx);
+        }
+        out.push(attribute);
+    }
+
+    let mut cursor = body.walk();
+    let init = body
+        .named_children(&mut cursor)
+        .map(|statement| match statement.kind() {
+            "decorated_definition" => statement.child_by_field_name("definition"),
+            _ => Some(statement),
+        })
+        .find_map(|f| {
+            f.filter(|f| f.kind() == "function_definition").filter(|f| {
+                f.child_by_field_name("name").map(|n| text(&n, ctx)) == Some("__init__")
+            })
+        });
+
+    if let Some(init) = init {
+        let this = init
+            .child_by_field_name("parameters")
+            .and_then(|p| p.named_child(0))
+            .filter(|p| p.kind() == "identifier")
+            .map(|p| text(&p, ctx).to_owned());
+
+        if let (Some(this), Some(init_body)) = (this, init.child_by_field_name("body")) {
+            parse_init_attributes(&init_body, &this, ctx, &mut out)?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Adds an attribute for each `this.name = ...` in `block`, including the
+/// ones in nested blocks like `if` statements, but not in nested functions
+/// or classes
+fn parse_init_attributes(
+    block: &Node,
+    this: &str,
+    ctx: &mut ParserContext,
+    out: &mut Vec<Symbol>,
+) -> Result<()> {
+    let mut cursor = block.walk();
+
+    for statement in block.named_children(&mut cursor) {
+        match statement.kind() {
+            "function_definition" | "class_definition" | "decorated_definition" => continue,
+            "expression_statement" => {}
+            _ => {
+                let mut cursor = statement.walk();
+                for child in statement.named_children(&mut cursor) {
+                    parse_init_attributes(&child, this, ctx, out)?;
+                }
+                continue;
+            }
+        }
+
+        let Some(assignment) = assignment_of(&statement) else {
+            continue;
+        };
+        let target = assignment.child_by_field_name("left").unwrap();
+        if target.kind() != "attribute" {
+            continue;
+        }
+        let object = target.child_by_field_name("object").map(|o| text(&o, ctx));
+        let Some(name) = target.child_by_field_name("attribute") else {
+            continue;
+        };
+        let title = text(&name, ctx).to_owned();
+
+        let seen = out
+            .iter()
+            .any(|s| matches!(&s.kind, SymbolKind::Attribute(a) if a.title == title));
+        if object != Some(this) || seen {
+            continue;
+        }
+
+        out.push(parse_attribute(title, &statement, &assignment, ctx)?);
+    }
+
+    Ok(())
+}
+
+fn parse_attribute(
+    title: String,
+    statement: &Node,
+    assignment: &Node,
+    ctx: &mut ParserContext,
+) -> Result<Symbol> {
+    let mut members = vec![];
+    if let Some(the_type) = assignment.child_by_field_name("type") {
+        if Type::matches_node(the_type) {
+            ctx.push_fqn(&title);
+            members.push(Type::parse_symbol(the_type, ctx)?);
+            ctx.pop_fqn();
+        }
+    }
+
+    let default = assignment
+        .child_by_field_name("right")
+        .map(|value| text(&value, ctx).to_owned());
+
+    Ok(Symbol::in_context(
+        ctx,
+        SymbolKind::Attribute(Attribute {
+            title,
+            documentation: find_comment(statement, ctx),
+            members,
+            default,
+            field_factory: None,
+            init_parameter: None,
+        }),
+        Location::new(statement, ctx),
+    ))
+}
+
+/// Reads the default and the default factory of a dataclass field from its
+/// `field()` call, and works out its `__init__` parameter
+fn as_dataclass_field(symbol: &mut Symbol, assignment: &Node, ctx: &ParserContext) {
+    let SymbolKind::Attribute(attribute) = &mut symbol.kind else {
+        return;
+    };
+    let annotation = assignment
+        .child_by_field_name("type")
+        .map(|t| text(&t, ctx))
+        .unwrap_or_default();
+    let mut in_init = !is_class_var(annotation);
+
+    let call = assignment
+        .child_by_field_name("right")
+        .filter(|value| value.kind() == "call")
+        .filter(|call| {
+            let function = call.child_by_field_name("function").map(|f| text(&f, ctx));
+            matches!(function, Some("field" | "dataclasses.field"))
+        });
+    if let Some(arguments) = call.and_then(|c| c.child_by_field_name("arguments")) {
+        attribute.default = None;
+
+        let mut cursor = arguments.walk();
+        for argument in arguments.named_children(&mut cursor) {
+            if argument.kind() != "keyword_argument" {
+                continue;
+            }
+            let name = argument.child_by_field_name("name").map(|n| text(&n, ctx));
+            let value = argument
+                .child_by_field_name("value")
+                .map(|v| text(&v, ctx).to_owned());
+
+            match name {
+                Some("default") => attribute.default = value,
+                Some("default_factory") => attribute.field_factory = value,
+                Some("init") => in_init = value.as_deref() != Some("False"),
+                _ => {}
+            }
+        }
+    }
+
+    if !in_init {
+        return;
+    }
+    let default = match (&attribute.default, &attribute.field_factory) {
+        (Some(default), _) => format!(" = {}", default),
+        // What `inspect.signature` shows for them
+        (None, Some(_)) => " = <factory>".to_owned(),
+        (None, None) => String::new(),
+    };
+    attribute.init_parameter = Some(format!("{}: {}{}", attribute.title, annotation, default));
+}
+
+/// Whether an annotation is `ClassVar`, which makes an annotated assignment
+/// in a dataclass a class variable rather than a field
+fn is_class_var(annotation: &str) -> bool {
+    let name = annotation.split('[').next().unwrap_or_default();
+    name.rsplit('.').next() == Some("ClassVar")
+}
+
+/// The assignment of an `expression_statement` like `name: str = "x"`
+pub(crate) fn assignment_of<'a>(statement: &Node<'a>) -> Option<Node<'a>> {
+    if statement.kind() != "expression_statement" {
+        return None;
+    }
+
+    statement
+        .named_child(0)
+        .filter(|assignment| assignment.kind() == "assignment")
+}
+
+/// The `#` comments on the lines right above `statement`, or if there are
+/// none, the one at the end of its line. A `#:` marks a comment as
+/// documentation in Sphinx, so it is left out as well.
+pub(crate) fn find_comment(statement: &Node, ctx: &ParserContext) -> Option<String> {
+    let mut lines = vec![];
+    let mut row = statement.start_position().row;
+    let mut previous = statement.prev_sibling();
+
+    while let Some(comment) = previous.filter(|p| p.kind() == "comment") {
+        if comment.end_position().row + 1 != row {
+            break;
+        }
+        lines.push(comment_text(&comment, ctx));
+        row = comment.start_position().row;
+        previous = comment.prev_sibling();
+    }
+
+    if lines.is_empty() {
+        let trailing = statement
+            .next_sibling()
+            .filter(|n| n.kind() == "comment")
+            .filter(|n| n.start_position().row == statement.end_position().row)?;
+        return Some(comment_text(&trailing, ctx).to_owned());
+    }
+
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+fn comment_text<'a>(comment: &Node, ctx: &'a ParserContext) -> &'a str {
+    let text = text(comment, ctx).trim_start_matches('#');
+    let text = text.strip_prefix(':').unwrap_or(text);
+
+    text.trim()
+}
+
+fn text<'a>(node: &Node, ctx: &'a ParserContext) -> &'a str {
+    node.utf8_text(ctx.code().as_bytes()).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_file, ParserContext};
+    use dossier_core::serde_json::json;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn entities(source: &str) -> Vec<dossier_core::Entity> {
+        parse_file(ParserContext::new(Path::new("test.py"), source))
+            .unwrap()
+            .symbols
+            .iter()
+            .map(|s| s.as_entity())
+            .collect()
+    }
+
+    #[test]
+    fn parses_annotated_and_init_attributes() {
+        let source = indoc! {r#"
+        class User:
+            """A user."""
+
+            # The name people see.
+            # Doesn't have to be unique.
+            name: str
+            email: str | None = None  # Where to send notifications
+            MAX_LENGTH = 10
+
+            def __init__(self, name, tags: list[str]):
+                self.name = name
+                #: When the user last logged in
+                self.last_login = None
+                if tags:
+                    self.tags = tags
+                helper = 1
+
+                def nested(other):
+                    other.hidden = 1
+
+            def rename(self, name):
+                self.renamed = True
+        "#};
+        let entities = entities(source);
+
+        let attributes = entities[0]
+            .members
+            .iter()
+            .filter(|m| m.kind == "attribute")
+            .map(|m| {
+                (
+                    m.title.as_deref().unwrap(),
+                    m.description.as_str(),
+                    m.meta.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attributes,
+            vec![
+                (
+                    "name",
+                    "The name people see.\nDoesn't have to be unique.",
+                    json!({})
+                ),
+                (
+                    "email",
+                    "Where to send notifications",
+                    json!({ "default": "None" })
+                ),
+                (
+                    "last_login",
+                    "When the user last logged in",
+                    json!({ "default": "None" })
+                ),
+                ("tags", "", json!({ "default": "tags" })),
+            ]
+        );
+
+        let name = &entities[0].members[0];
+        assert_eq!(
+            name.identity,
+            dossier_core::Identity::FQN("test.py::User::name".to_owned())
+        );
+        assert_eq!(name.members[0].kind, "predefined_type");
+        assert_eq!(entities[0].members[1].members[0].kind, "union");
+    }
+
+    #[test]
+    fn parses_the_fields_of_dataclasses() {
+        let source = indoc! {r#"
+        from dataclasses import dataclass, field
+
+        @dataclass(frozen=True)
+        class Point:
+            x: int
+            y: int = 0
+            tags: list[str] = field(default_factory=list)
+
+        class Plain:
+            pass
+        "#};
+        let entities = entities(source);
+
+        let point = &entities[0];
+        assert_eq!(point.title.as_deref(), Some("Point"));
+        assert_eq!(point.meta["dataclass"], true);
+        assert_eq!(point.meta["signature"], "class Point");
+        let fields = point
+            .members
+            .iter()
+            .map(|m| {
+                (
+                    m.title.as_deref().unwrap(),
+                    m.meta["default"].as_str(),
+                    m.meta["field_factory"].as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("x", None, None),
+                ("y", Some("0"), None),
+                ("tags", None, Some("list")),
+            ]
+        );
+
+        assert!(entities[1].meta.get("dataclass").is_none());
+    }
+}
@@ -1,8 +1,12 @@
-use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Result};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, MemberContext, Result};
+
+use std::collections::HashMap;
 
 use crate::{
-    function::Function,
+    attribute, enum_member,
+    function::{self, Function},
     symbol::{Location, ParseSymbol, Symbol, SymbolContext, SymbolKind},
+    types::Type,
     ParserContext,
 };
 
@@ -10,7 +14,33 @@ use crate::{
 pub(crate) struct Class {
     pub title: String,
     pub documentation: Option<String>,
+    /// The base classes, followed by the attributes and the methods. Bases
+    /// are kept in the order they are listed in, since that decides the
+    /// method resolution order.
     pub members: Vec<Symbol>,
+    /// Whether the class body has nothing but methods, and no `__init__`
+    pub only_methods: bool,
+    /// Names of the bases that look like mixins. Filled in by `classify_bases`.
+    pub mixins: Vec<String>,
+    /// The declaration without its body, on one line, like `class
+    /// Greeter(Base)`. Not set for minified files.
+    pub signature: Option<String>,
+    pub flavor: Flavor,
+    /// The signature of the `__init__` a dataclass generates, like `def
+    /// __init__(self, x: float, y: float = 0.0)`. Not set when the class
+    /// defines its own, or is decorated with `@dataclass(init=False)`.
+    pub init_signature: Option<String>,
+}
+
+/// Kinds of classes that get special treatment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Flavor {
+    Plain,
+    /// Decorated with `@dataclass`. Its annotated attributes are its fields.
+    Dataclass,
+    /// A subclass of `Enum` or one of its variants like `IntEnum`. The
+    /// assignments in its body are its members.
+    Enum,
 }
 
 impl Class {
@@ -19,22 +49,55 @@ impl Class {
         self.members.iter().filter(|s| s.as_function().is_some())
     }
 
+    pub fn bases(&self) -> impl Iterator<Item = &Symbol> {
+        self.members
+            .iter()
+            .filter(|s| s.context == Some(SymbolContext::Base))
+    }
+
     pub fn as_entity(
         &self,
         loc: &Location,
         fqn: Option<&str>,
         context: Option<&SymbolContext>,
     ) -> Entity {
+        let mut meta = json!({});
+
+        let bases = self
+            .bases()
+            .filter_map(|b| match &b.kind {
+                SymbolKind::Type(t) => t.identifier(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !bases.is_empty() {
+            meta["bases"] = bases.into();
+        }
+        if !self.mixins.is_empty() {
+            meta["mixins"] = self.mixins.clone().into();
+        }
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        match self.flavor {
+            Flavor::Plain => {}
+            Flavor::Dataclass => meta["dataclass"] = true.into(),
+            Flavor::Enum => meta["enum"] = true.into(),
+        }
+        if let Some(init_signature) = &self.init_signature {
+            meta["init_signature"] = init_signature.as_str().into();
+        }
+
         Entity {
             title: Some(self.title.to_owned()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "class".to_owned(),
             identity: dossier_core::Identity::FQN(fqn.expect("class without FQN").to_owned()),
             members: self.members.iter().map(|s| s.as_entity()).collect(),
-            member_context: context.map(|c| c.to_string()),
+            member_context: context.map(|c| MemberContext::from(*c)),
             language: crate::LANGUAGE.to_owned(),
             source: loc.as_source(),
-            meta: json!({}),
+            meta,
         }
     }
 }
@@ -57,13 +120,37 @@ impl ParseSymbol for Class {
         let documentation = find_docs(&node, ctx);
 
         let mut members = vec![];
+        let mut only_methods = true;
+
+        if let Some(superclasses) = node.child_by_field_name("superclasses") {
+            parse_bases(&superclasses, ctx, &mut members);
+        }
+
+        let flavor = if enum_member::is_enum(base_names(&members)) {
+            Flavor::Enum
+        } else if dataclass_decorator(&node, ctx).is_some() {
+            Flavor::Dataclass
+        } else {
+            Flavor::Plain
+        };
+        let mut init_signature = None;
 
         if let Some(body) = node.child_by_field_name("body") {
-            ctx.push_context(SymbolContext::Method);
+            only_methods = has_only_methods(&body, ctx);
             ctx.push_fqn(&title);
+            if flavor == Flavor::Enum {
+                members.append(&mut enum_member::parse_members(&body, ctx)?);
+            }
+            let mut attributes = attribute::parse_attributes(&body, flavor, ctx)?;
+            if flavor == Flavor::Dataclass && generates_init(&node, &body, ctx) {
+                init_signature = Some(dataclass_init_signature(&attributes));
+            }
+            members.append(&mut attributes);
+            ctx.push_context(SymbolContext::Method);
             parse_methods(&body, ctx, &mut members)?;
-            ctx.pop_fqn();
+            function::merge_overloads(&mut members);
             ctx.pop_context();
+            ctx.pop_fqn();
         }
 
         Ok(Symbol::in_context(
@@ -72,12 +159,187 @@ impl ParseSymbol for Class {
                 title,
                 documentation,
                 members,
+                only_methods,
+                mixins: vec![],
+                signature: Some(dossier_core::helpers::signature(&node, ctx.code())),
+                flavor,
+                init_signature,
             }),
             Location::new(&node, ctx),
         ))
     }
 }
 
+/// The `@dataclass` decorator of the class definition `node`, with or
+/// without arguments, or as `@dataclasses.dataclass`
+pub(crate) fn dataclass_decorator<'a>(node: &Node<'a>, ctx: &ParserContext) -> Option<Node<'a>> {
+    let decorated = node
+        .parent()
+        .filter(|p| p.kind() == "decorated_definition")?;
+
+    let mut cursor = decorated.walk();
+    let result = decorated
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .filter_map(|decorator| decorator.named_child(0))
+        .find(|expression| {
+            let name = match expression.kind() {
+                "call" => expression.child_by_field_name("function"),
+                _ => Some(*expression),
+            };
+            let name = name.and_then(|n| n.utf8_text(ctx.code().as_bytes()).ok());
+
+            matches!(name, Some("dataclass" | "dataclasses.dataclass"))
+        });
+
+    result
+}
+
+/// Whether a dataclass gets a generated `__init__`: when it doesn't define
+/// one, and isn't decorated with `@dataclass(init=False)`
+fn generates_init(node: &Node, body: &Node, ctx: &ParserContext) -> bool {
+    let text = |n: Node| {
+        n.utf8_text(ctx.code().as_bytes())
+            .unwrap_or_default()
+            .to_owned()
+    };
+
+    let mut cursor = body.walk();
+    let defines_init = body.named_children(&mut cursor).any(|statement| {
+        statement.kind() == "function_definition"
+            && statement.child_by_field_name("name").map(text).as_deref() == Some("__init__")
+    });
+
+    let disabled = dataclass_decorator(node, ctx)
+        .and_then(|d| d.child_by_field_name("arguments"))
+        .is_some_and(|arguments| {
+            let mut cursor = arguments.walk();
+            let result = arguments.named_children(&mut cursor).any(|argument| {
+                argument.kind() == "keyword_argument"
+                    && argument.child_by_field_name("name").map(text).as_deref() == Some("init")
+                    && argument.child_by_field_name("value").map(text).as_deref() == Some("False")
+            });
+            result
+        });
+
+    !defines_init && !disabled
+}
+
+/// The `__init__` a dataclass with the attributes `attributes` generates
+fn dataclass_init_signature(attributes: &[Symbol]) -> String {
+    let mut out = "def __init__(self".to_owned();
+
+    for symbol in attributes {
+        if let SymbolKind::Attribute(attribute::Attribute {
+            init_parameter: Some(parameter),
+            ..
+        }) = &symbol.kind
+        {
+            out.push_str(", ");
+            out.push_str(parameter);
+        }
+    }
+
+    out.push(')');
+    out
+}
+
+/// The names of the bases in `members`
+fn base_names(members: &[Symbol]) -> impl Iterator<Item = &str> {
+    members
+        .iter()
+        .filter(|s| s.context == Some(SymbolContext::Base))
+        .filter_map(|s| match &s.kind {
+            SymbolKind::Type(t) => t.identifier(),
+            _ => None,
+        })
+}
+
+/// Adds the positional arguments of a class definition, like `Base` and
+/// `abc.ABC` in `class Foo(Base, abc.ABC, metaclass=Meta)`, as bases.
+fn parse_bases(node: &Node, ctx: &ParserContext, members: &mut Vec<Symbol>) {
+    let mut cursor = node.walk();
+
+    for base in node.named_children(&mut cursor) {
+        if !matches!(base.kind(), "identifier" | "attribute" | "subscript") {
+            continue;
+        }
+
+        let name = base.utf8_text(ctx.code().as_bytes()).unwrap().to_owned();
+        let mut symbol = Symbol::in_context(
+            ctx,
+            SymbolKind::Type(Type::Identifier(name, None)),
+            Location::new(&base, ctx),
+        );
+        // Bases only refer to the class they are resolved to
+        symbol.fqn = None;
+        symbol.context = Some(SymbolContext::Base);
+        members.push(symbol);
+    }
+}
+
+/// Whether a class body has only methods, and no attributes or `__init__` that
+/// would give its instances state. Docstrings and `pass` don't count.
+fn has_only_methods(body: &Node, ctx: &ParserContext) -> bool {
+    let mut cursor = body.walk();
+    let result = body.named_children(&mut cursor).all(|statement| {
+        let function = match statement.kind() {
+            "decorated_definition" => statement.child_by_field_name("definition"),
+            _ => Some(statement),
+        };
+
+        match function.map(|f| (f.kind(), f)) {
+            Some(("function_definition", f)) => {
+                f.child_by_field_name("name")
+                    .and_then(|n| n.utf8_text(ctx.code().as_bytes()).ok())
+                    != Some("__init__")
+            }
+            Some(("pass_statement", _)) | Some(("comment", _)) => true,
+            Some(("expression_statement", s)) => s
+                .named_child(0)
+                .is_some_and(|e| matches!(e.kind(), "string" | "ellipsis")),
+            _ => false,
+        }
+    });
+
+    result
+}
+
+/// Records which of the bases of the classes in `symbols` look like mixins.
+///
+/// A base is a mixin if its name ends in `Mixin`, or it is a class in the
+/// same file that has nothing but methods.
+pub(crate) fn classify_bases(symbols: &mut [Symbol]) {
+    let classes = symbols
+        .iter()
+        .filter_map(|s| match &s.kind {
+            SymbolKind::Class(c) => Some((c.title.clone(), c.only_methods)),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+
+    for symbol in symbols.iter_mut() {
+        let SymbolKind::Class(class) = &mut symbol.kind else {
+            continue;
+        };
+
+        for base in class.members.iter() {
+            if base.context != Some(SymbolContext::Base) {
+                continue;
+            }
+            let SymbolKind::Type(Type::Identifier(name, _)) = &base.kind else {
+                continue;
+            };
+
+            let unqualified = name.rsplit('.').next().unwrap_or(name);
+
+            if unqualified.ends_with("Mixin") || classes.get(name).is_some_and(|m| *m) {
+                class.mixins.push(name.clone());
+            }
+        }
+    }
+}
+
 fn parse_methods(node: &Node, ctx: &mut ParserContext, members: &mut Vec<Symbol>) -> Result<()> {
     let mut cursor = node.walk();
     cursor.goto_first_child();
@@ -121,6 +383,8 @@ fn find_docs(node: &Node, ctx: &ParserContext) -> Option<String> {
 mod test {
     use super::*;
     use crate::symbol::SymbolContext;
+    use dossier_core::test_utils;
+    use dossier_core::Identity;
     use indoc::indoc;
     use std::path::Path;
 
@@ -134,13 +398,12 @@ mod test {
         "#};
 
         let mut ctx = ParserContext::new(Path::new("test.py"), source);
-        let tree = crate::init_parser().parse(source, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        cursor.goto_first_child();
+        let tree = test_utils::parse(source, tree_sitter_python::language());
+        let node = test_utils::first_node(&tree, "class_definition");
 
-        assert!(Class::matches_node(cursor.node()));
+        assert!(Class::matches_node(node));
 
-        let symbol = Class::parse_symbol(cursor.node(), &mut ctx).unwrap();
+        let symbol = Class::parse_symbol(node, &mut ctx).unwrap();
         assert_eq!(symbol.fqn.as_deref(), Some("test.py::PyClass"));
         let class = symbol.as_class().unwrap();
 
@@ -156,4 +419,129 @@ mod test {
 
         assert_eq!(method_symbol.context, Some(SymbolContext::Method));
     }
+
+    #[test]
+    fn keeps_base_order_and_detects_mixins() {
+        let source = indoc! {r#"
+        class Serializable:
+            """Adds JSON support."""
+
+            def to_json(self):
+                pass
+
+        class Model:
+            table = None
+
+            def save(self):
+                pass
+
+        class User(Serializable, Model, auth.PermissionsMixin):
+            pass
+
+        class Admin(Model, Serializable, metaclass=Meta):
+            pass
+        "#};
+
+        let symbols = crate::parse_file(ParserContext::new(Path::new("test.py"), source))
+            .unwrap()
+            .symbols;
+        let entities = symbols.iter().map(|s| s.as_entity()).collect::<Vec<_>>();
+
+        let user = &entities[2];
+        assert_eq!(
+            user.meta["bases"],
+            json!(["Serializable", "Model", "auth.PermissionsMixin"])
+        );
+        assert_eq!(
+            user.meta["mixins"],
+            json!(["Serializable", "auth.PermissionsMixin"])
+        );
+        assert_eq!(
+            entities[3].meta["signature"],
+            "class Admin(Model, Serializable, metaclass=Meta)"
+        );
+
+        let base_members = user
+            .members
+            .iter()
+            .map(|m| {
+                (
+                    m.member_context.as_ref().map(|c| c.as_str()),
+                    m.identity.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            base_members,
+            vec![
+                (
+                    Some("base"),
+                    Identity::Reference("test.py::Serializable".to_owned())
+                ),
+                (
+                    Some("base"),
+                    Identity::Reference("test.py::Model".to_owned())
+                ),
+                (Some("base"), Identity::Anonymous),
+            ]
+        );
+
+        let admin = &entities[3];
+        assert_eq!(admin.meta["bases"], json!(["Model", "Serializable"]));
+        assert_eq!(admin.meta["mixins"], json!(["Serializable"]));
+
+        assert!(entities[0].meta.get("bases").is_none());
+    }
+
+    #[test]
+    fn describes_the_fields_and_init_of_dataclasses() {
+        let source = include_str!("../../fixtures/python/points.py");
+        let entities = crate::parse_file(ParserContext::new(Path::new("points.py"), source))
+            .unwrap()
+            .symbols
+            .iter()
+            .map(|s| s.as_entity())
+            .collect::<Vec<_>>();
+
+        let point = &entities[0];
+        assert_eq!(point.meta["dataclass"], true);
+        assert_eq!(
+            point.meta["init_signature"],
+            "def __init__(self, x: float, y: float = 0.0, label: str = \"origin\", tags: list[str] = <factory>)"
+        );
+
+        let field = |name: &str| {
+            point
+                .members
+                .iter()
+                .find(|m| m.title.as_deref() == Some(name))
+                .unwrap()
+        };
+        assert_eq!(field("x").description, "Distance from the y axis");
+        assert_eq!(field("label").meta["default"], "\"origin\"");
+        assert_eq!(field("tags").meta["field_factory"], "list");
+        assert!(field("tags").meta.get("default").is_none());
+        assert_eq!(field("dimensions").meta["default"], "2");
+
+        let source = indoc! {r#"
+        @dataclass(init=False)
+        class Lazy:
+            x: int
+
+        @dataclass
+        class Custom:
+            x: int
+
+            def __init__(self):
+                self.x = 0
+        "#};
+        let entities = crate::parse_file(ParserContext::new(Path::new("test.py"), source))
+            .unwrap()
+            .symbols
+            .iter()
+            .map(|s| s.as_entity())
+            .collect::<Vec<_>>();
+        assert!(entities[0].meta.get("init_signature").is_none());
+        assert!(entities[1].meta.get("init_signature").is_none());
+    }
 }
@@ -0,0 +1,163 @@
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, MemberContext, Result};
+
+use crate::{
+    attribute,
+    symbol::{Location, Symbol, SymbolContext, SymbolKind},
+    ParserContext,
+};
+
+/// A member of an `Enum` subclass, like `RED = "red"`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EnumMember {
+    pub title: String,
+    /// The comment on the lines right above the member, or at the end of its
+    /// line
+    pub documentation: Option<String>,
+    /// The value, as written, like `"red"` or `auto()`
+    pub value: String,
+}
+
+impl EnumMember {
+    pub fn as_entity(
+        &self,
+        loc: &Location,
+        fqn: Option<&str>,
+        context: Option<&SymbolContext>,
+    ) -> Entity {
+        Entity {
+            title: Some(self.title.to_owned()),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
+            kind: "enum_member".to_owned(),
+            identity: dossier_core::Identity::FQN(fqn.expect("enum member without FQN").to_owned()),
+            members: vec![],
+            member_context: context.map(|c| MemberContext::from(*c)),
+            language: crate::LANGUAGE.to_owned(),
+            source: loc.as_source(),
+            meta: json!({ "value": self.value }),
+        }
+    }
+}
+
+/// The bases that make a class an enum, without their module
+const ENUM_BASES: &[&str] = &["Enum", "IntEnum", "StrEnum", "Flag", "IntFlag", "ReprEnum"];
+
+/// Whether a class with the bases named `bases` is an enum. Only looks at
+/// the names, so subclasses of enums defined elsewhere aren't recognized.
+pub(crate) fn is_enum<'a>(mut bases: impl Iterator<Item = &'a str>) -> bool {
+    bases.any(|base| {
+        let unqualified = base.rsplit('.').next().unwrap_or(base);
+        ENUM_BASES.contains(&unqualified)
+    })
+}
+
+/// Parses the assignments in the body of an enum into its members. Names
+/// starting with an underscore, like `_ignore_`, are not members. Expects
+/// the FQN of the class to be pushed.
+pub(crate) fn parse_members(body: &Node, ctx: &ParserContext) -> Result<Vec<Symbol>> {
+    let mut out = vec![];
+    let mut cursor = body.walk();
+
+    for statement in body.named_children(&mut cursor) {
+        let Some(assignment) = attribute::assignment_of(&statement) else {
+            continue;
+        };
+        let target = assignment.child_by_field_name("left").unwrap();
+        let Some(value) = assignment.child_by_field_name("right") else {
+            continue;
+        };
+        let title = target.utf8_text(ctx.code().as_bytes()).unwrap();
+        if target.kind() != "identifier" || title.starts_with('_') {
+            continue;
+        }
+
+        out.push(Symbol::in_context(
+            ctx,
+            SymbolKind::EnumMember(EnumMember {
+                title: title.to_owned(),
+                documentation: attribute::find_comment(&statement, ctx),
+                value: value.utf8_text(ctx.code().as_bytes()).unwrap().to_owned(),
+            }),
+            Location::new(&statement, ctx),
+        ));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_file, ParserContext};
+    use std::path::Path;
+
+    #[test]
+    fn parses_enum_members() {
+        let source = include_str!("../../fixtures/python/enums.py");
+        let entities = parse_file(ParserContext::new(Path::new("enums.py"), source))
+            .unwrap()
+            .symbols
+            .iter()
+            .map(|s| s.as_entity())
+            .collect::<Vec<_>>();
+
+        let members = |index: usize| {
+            entities[index]
+                .members
+                .iter()
+                .map(|m| {
+                    (
+                        m.kind.as_str(),
+                        m.title.as_deref().unwrap(),
+                        m.meta["value"].as_str().unwrap_or_default(),
+                        m.description.as_str(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let color = &entities[0];
+        assert_eq!(color.meta["enum"], true);
+        assert_eq!(
+            members(0),
+            vec![
+                ("identifier", "Enum", "", ""),
+                ("enum_member", "RED", "\"red\"", ""),
+                (
+                    "enum_member",
+                    "GREEN",
+                    "\"green\"",
+                    "Also used for the default theme"
+                ),
+                ("enum_member", "BLUE", "auto()", "Picked by `auto`"),
+                ("function", "describe", "", ""),
+            ]
+        );
+        assert_eq!(
+            color.members[1].identity,
+            dossier_core::Identity::FQN("enums.py::Color::RED".to_owned())
+        );
+
+        assert_eq!(entities[1].meta["enum"], true);
+        let priorities = members(1)
+            .iter()
+            .filter(|m| m.0 == "enum_member")
+            .map(|m| (m.1, m.2))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            priorities,
+            vec![("LOW", "1"), ("NORMAL", "2"), ("HIGH", "3")]
+        );
+    }
+
+    #[test]
+    fn serializes_enums() {
+        let source = include_str!("../../fixtures/python/enums.py");
+        let entities = parse_file(ParserContext::new(Path::new("enums.py"), source))
+            .unwrap()
+            .symbols
+            .iter()
+            .map(|s| s.as_entity())
+            .collect::<Vec<_>>();
+
+        dossier_core::assert_json_snapshot!("enums.json", entities);
+    }
+}
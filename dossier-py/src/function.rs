@@ -1,4 +1,4 @@
-use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Result};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, MemberContext, Result};
 
 use crate::{
     parameter::Parameter,
@@ -12,6 +12,11 @@ pub(crate) struct Function {
     pub title: String,
     pub documentation: Option<String>,
     pub members: Vec<Symbol>,
+    /// The declaration without its body, on one line, like `async def
+    /// fetch(url: str) -> bytes`. Not set for minified files.
+    pub signature: Option<String>,
+    /// Decorated with `@overload`, see `merge_overloads`
+    pub overload: bool,
 }
 
 impl Function {
@@ -21,18 +26,30 @@ impl Function {
         fqn: Option<&str>,
         context: Option<&SymbolContext>,
     ) -> Entity {
+        let mut meta = json!({});
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        if self.overload {
+            meta["overload"] = true.into();
+        } else if self.members.iter().any(is_overload_signature) {
+            meta["implementation"] = true.into();
+        }
+
         Entity {
             title: Some(self.title.to_owned()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "function".to_owned(),
-            identity: dossier_core::Identity::FQN(
-                fqn.expect("function or method without FQN").to_owned(),
-            ),
+            // Overload signatures are anonymous, see `merge_overloads`
+            identity: match fqn {
+                Some(fqn) => dossier_core::Identity::FQN(fqn.to_owned()),
+                None => dossier_core::Identity::Anonymous,
+            },
             members: self.members.iter().map(|s| s.as_entity()).collect(),
-            member_context: context.map(|sc| sc.to_string()),
+            member_context: context.map(|c| MemberContext::from(*c)),
             language: crate::LANGUAGE.to_owned(),
             source: loc.as_source(),
-            meta: json!({}),
+            meta,
         }
     }
 
@@ -93,12 +110,115 @@ impl ParseSymbol for Function {
                 title,
                 documentation,
                 members,
+                signature: Some(dossier_core::helpers::signature(&node, ctx.code())),
+                overload: is_overload(&node, ctx),
             }),
             Location::new(&node, ctx),
         ))
     }
 }
 
+/// Whether the function definition `node` is decorated with `@overload`, or
+/// `@typing.overload`
+fn is_overload(node: &Node, ctx: &ParserContext) -> bool {
+    let Some(decorated) = node.parent().filter(|p| p.kind() == "decorated_definition") else {
+        return false;
+    };
+
+    let mut cursor = decorated.walk();
+    let result = decorated
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .filter_map(|decorator| decorator.named_child(0))
+        .any(|expression| {
+            matches!(
+                expression.utf8_text(ctx.code().as_bytes()),
+                Ok("overload" | "typing.overload" | "typing_extensions.overload")
+            )
+        });
+
+    result
+}
+
+fn is_overload_signature(symbol: &Symbol) -> bool {
+    symbol.context == Some(SymbolContext::Overload)
+}
+
+/// Merges each run of `@overload` signatures in `symbols` into the function
+/// of the same name that follows them, their implementation. The signatures
+/// become members of the implementation, with the `overload` member context.
+/// Signatures without an implementation, like in stub files, are merged into
+/// the first of them.
+///
+/// The signatures declare the same function as the one they are merged into,
+/// so they and their parameters are anonymous.
+pub(crate) fn merge_overloads(symbols: &mut Vec<Symbol>) {
+    let mut merged = Vec::with_capacity(symbols.len());
+    let mut signatures: Vec<Symbol> = vec![];
+
+    for mut symbol in symbols.drain(..) {
+        let (title, overload) = match &symbol.kind {
+            SymbolKind::Function(f) => (Some(f.title.as_str()), f.overload),
+            _ => (None, false),
+        };
+        let continues = title.is_some() && title == signatures.first().and_then(function_title);
+
+        if !continues {
+            flush_signatures(&mut signatures, &mut merged);
+        }
+
+        if overload {
+            signatures.push(symbol);
+            continue;
+        }
+
+        if continues {
+            attach_signatures(&mut symbol, signatures.drain(..));
+        }
+        merged.push(symbol);
+    }
+    flush_signatures(&mut signatures, &mut merged);
+
+    *symbols = merged;
+}
+
+fn function_title(symbol: &Symbol) -> Option<&str> {
+    match &symbol.kind {
+        SymbolKind::Function(f) => Some(f.title.as_str()),
+        _ => None,
+    }
+}
+
+/// Merges signatures that have no implementation into the first of them
+fn flush_signatures(signatures: &mut Vec<Symbol>, merged: &mut Vec<Symbol>) {
+    if signatures.is_empty() {
+        return;
+    }
+
+    let mut first = signatures.remove(0);
+    attach_signatures(&mut first, signatures.drain(..));
+    merged.push(first);
+}
+
+fn attach_signatures(function: &mut Symbol, signatures: impl Iterator<Item = Symbol>) {
+    let SymbolKind::Function(f) = &mut function.kind else {
+        return;
+    };
+
+    for mut signature in signatures {
+        forget_fqns(&mut signature);
+        signature.context = Some(SymbolContext::Overload);
+        f.members.push(signature);
+    }
+}
+
+fn forget_fqns(symbol: &mut Symbol) {
+    symbol.fqn = None;
+    for child in symbol.kind.children_mut() {
+        forget_fqns(child);
+    }
+}
+
 fn parse_parameters(node: &Node, out: &mut Vec<Symbol>, ctx: &mut ParserContext) -> Result<()> {
     let mut cursor = node.walk();
     cursor.goto_first_child();
@@ -144,9 +264,35 @@ mod test {
 
     use super::*;
     use crate::symbol::SymbolContext;
+    use dossier_core::test_utils;
     use indoc::indoc;
     use std::path::Path;
 
+    #[test]
+    fn records_the_signature() {
+        let source = indoc! {r#"
+            async def fetch(
+                url: str,
+                retries: int = 3,
+            ) -> bytes:
+                """Fetch the contents of a URL."""
+        "#};
+
+        let mut ctx = ParserContext::new(Path::new("test.py"), source);
+        let tree = test_utils::parse(source, tree_sitter_python::language());
+        let node = test_utils::first_node(&tree, "function_definition");
+
+        let symbol = Function::parse_symbol(node, &mut ctx).unwrap();
+        assert_eq!(
+            symbol.as_function().unwrap().signature.as_deref(),
+            Some("async def fetch(url: str, retries: int = 3) -> bytes")
+        );
+        assert_eq!(
+            symbol.as_entity().meta["signature"],
+            "async def fetch(url: str, retries: int = 3) -> bytes"
+        );
+    }
+
     #[test]
     fn parse_function_params() {
         let source = indoc! {r#"
@@ -155,11 +301,10 @@ mod test {
         "#};
 
         let mut ctx = ParserContext::new(Path::new("test.py"), source);
-        let tree = crate::init_parser().parse(source, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        cursor.goto_first_child();
+        let tree = test_utils::parse(source, tree_sitter_python::language());
+        let node = test_utils::first_node(&tree, "function_definition");
 
-        let symbol = Function::parse_symbol(cursor.node(), &mut ctx).unwrap();
+        let symbol = Function::parse_symbol(node, &mut ctx).unwrap();
         assert_eq!(symbol.fqn.as_deref(), Some("test.py::foo"));
 
         let function = symbol.as_function().unwrap();
@@ -181,14 +326,14 @@ mod test {
         let the_type = param.the_type();
         assert_eq!(
             the_type.unwrap().as_type().unwrap(),
-            &Type::BuiltIn("int".to_owned())
+            &Type::Predefined("int".to_owned())
         );
 
         let return_type = function.return_type().unwrap();
         assert_eq!(return_type.context, Some(SymbolContext::ReturnType));
         assert_eq!(
             return_type.as_type().unwrap(),
-            &Type::BuiltIn("bool".to_owned())
+            &Type::Predefined("bool".to_owned())
         );
     }
 }
@@ -0,0 +1,160 @@
+use dossier_core::tree_sitter::Node;
+
+use crate::ParserContext;
+
+/// A name an import statement binds in the importing file.
+///
+/// An `import` or `from ... import ...` statement binding several names is
+/// split into one `Import` per name.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Import {
+    /// The module imported, or imported from, as written: `app.models`, or
+    /// `..models` relative to the package of the importing file
+    pub module: String,
+    /// The name imported from the module, like `User` in `from app.models
+    /// import User`, or `*` for a wildcard import. `None` when the module
+    /// itself is imported.
+    pub name: Option<String>,
+    /// The name bound in the importing file: the alias if there is one, the
+    /// imported name, or the whole module path for `import app.models`,
+    /// since that is how the file refers to it
+    pub alias: String,
+}
+
+impl Import {
+    /// Whether this is a `from module import *`
+    pub fn is_wildcard(&self) -> bool {
+        self.name.as_deref() == Some("*")
+    }
+
+    /// The module a name imported with `from module import name` would be,
+    /// if it is a submodule rather than a name defined in the module
+    pub fn submodule(&self) -> Option<String> {
+        let name = self.name.as_ref()?;
+
+        if self.module.ends_with('.') {
+            Some(format!("{}{}", self.module, name))
+        } else {
+            Some(format!("{}.{}", self.module, name))
+        }
+    }
+}
+
+/// Whether `node` is an import statement `parse` handles. `from __future__`
+/// imports are not, since they don't bind names.
+pub(crate) fn matches_node(node: Node) -> bool {
+    matches!(node.kind(), "import_statement" | "import_from_statement")
+}
+
+/// The names an import statement binds
+pub(crate) fn parse(node: Node, ctx: &ParserContext) -> Vec<Import> {
+    let text = |node: Node| node.utf8_text(ctx.code().as_bytes()).unwrap().to_owned();
+    let mut out = vec![];
+    let mut cursor = node.walk();
+
+    if node.kind() == "import_statement" {
+        for name in node.children_by_field_name("name", &mut cursor) {
+            let (module, alias) = name_and_alias(name, &text);
+            out.push(Import {
+                alias: alias.unwrap_or_else(|| module.clone()),
+                module,
+                name: None,
+            });
+        }
+
+        return out;
+    }
+
+    let Some(module) = node.child_by_field_name("module_name").map(text) else {
+        return out;
+    };
+
+    for name in node.children_by_field_name("name", &mut cursor) {
+        let (name, alias) = name_and_alias(name, &text);
+        out.push(Import {
+            module: module.clone(),
+            alias: alias.unwrap_or_else(|| name.clone()),
+            name: Some(name),
+        });
+    }
+
+    let mut cursor = node.walk();
+    let wildcard = node
+        .named_children(&mut cursor)
+        .any(|child| child.kind() == "wildcard_import");
+    if wildcard {
+        out.push(Import {
+            module,
+            name: Some("*".to_owned()),
+            alias: "*".to_owned(),
+        });
+    }
+
+    out
+}
+
+/// The name of a `dotted_name` or `aliased_import`, and its alias
+fn name_and_alias(node: Node, text: &impl Fn(Node) -> String) -> (String, Option<String>) {
+    match node.kind() {
+        "aliased_import" => (
+            node.child_by_field_name("name")
+                .map(text)
+                .unwrap_or_default(),
+            node.child_by_field_name("alias").map(text),
+        ),
+        _ => (text(node), None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn imports(source: &str) -> Vec<(String, Option<String>, String)> {
+        let ctx = ParserContext::new(Path::new("main.py"), source);
+        let tree = dossier_core::test_utils::parse(source, tree_sitter_python::language());
+        let mut cursor = tree.root_node().walk();
+
+        tree.root_node()
+            .named_children(&mut cursor)
+            .filter(|node| matches_node(*node))
+            .flat_map(|node| parse(node, &ctx))
+            .map(|i| (i.module, i.name, i.alias))
+            .collect()
+    }
+
+    fn import(module: &str, name: Option<&str>, alias: &str) -> (String, Option<String>, String) {
+        (
+            module.to_owned(),
+            name.map(|n| n.to_owned()),
+            alias.to_owned(),
+        )
+    }
+
+    #[test]
+    fn parses_imports() {
+        let source = indoc! {r#"
+        from __future__ import annotations
+        import os, app.models
+        import app.models.user as user_models
+        from .models import User, Admin as Root
+        from .. import config
+        from app.types import *
+        "#};
+
+        assert_eq!(
+            imports(source),
+            vec![
+                import("os", None, "os"),
+                import("app.models", None, "app.models"),
+                import("app.models.user", None, "user_models"),
+                import(".models", Some("User"), "User"),
+                import(".models", Some("Admin"), "Root"),
+                import("..", Some("config"), "config"),
+                import("app.types", Some("*"), "*"),
+            ]
+        );
+    }
+}
@@ -1,96 +1,275 @@
+mod attribute;
 mod class;
+mod enum_member;
 mod function;
+mod import;
+mod module_name;
 mod parameter;
 mod symbol;
+mod symbol_table;
 mod types;
 
 use dossier_core::tree_sitter::Node;
-use dossier_core::Result;
+use dossier_core::{parser_pool, FileMode, ParseCache, Repository, Result};
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use class::Class;
 use function::Function;
-use symbol::{ParseSymbol, Symbol, SymbolContext};
+use symbol::{Location, ParseSymbol, Symbol, SymbolContext, SymbolKind};
+use symbol_table::SymbolTable;
 
-#[derive(Debug, Clone, PartialEq, Default)]
-pub struct PythonParser {}
+#[derive(Debug, Default)]
+pub struct PythonParser {
+    /// See `with_cache`
+    cache: Option<ParseCache<SymbolTable>>,
+}
 
 impl PythonParser {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// A parser that keeps the files it parses, so that parsing them again
+    /// only re-parses the ones that changed
+    pub fn with_cache() -> Self {
+        Self {
+            cache: Some(ParseCache::new()),
+        }
+    }
 }
 
 pub const LANGUAGE: &str = "py";
 
+/// The extensions of the files the parser parses
+pub const FILE_EXTENSIONS: &[&str] = &["py"];
+
+/// Starts the section of a docstring written for another locale, like
+/// `.. locale:: ja`. See `dossier_core::locale`.
+const LOCALE_MARKER: &str = ".. locale::";
+
 impl dossier_core::DocsParser for PythonParser {
-    fn parse<'a, P: Into<&'a Path>, T: IntoIterator<Item = P>>(
+    fn parse(
         &self,
-        paths: T,
-        _ctx: &mut dossier_core::Context,
+        paths: &[&Path],
+        ctx: &mut dossier_core::Context,
     ) -> Result<Vec<dossier_core::Entity>> {
-        let mut symbols = vec![];
+        let paths: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
 
-        let paths: Vec<PathBuf> = paths
-            .into_iter()
-            .map(|p| p.into().to_owned())
-            .collect::<Vec<_>>();
+        if let Some(cache) = &self.cache {
+            cache.retain(&paths);
+        }
 
-        paths.iter().for_each(|path| {
-            let code = std::fs::read_to_string(path).unwrap();
-            let ctx = ParserContext::new(path, &code);
+        let module_names = match ctx.module_names {
+            true => module_name::module_names(&paths, ctx.file_source.as_ref()),
+            false => HashMap::new(),
+        };
+
+        let mut tables = dossier_core::parse_files(
+            &paths,
+            ctx.file_source.as_ref(),
+            ctx.jobs,
+            &ctx.file_limits,
+            &mut ctx.diagnostics,
+            |path, code, mode| {
+                let parse = || {
+                    parse_file(
+                        ParserContext::new(path, code)
+                            .with_repository(ctx.repository.as_ref())
+                            .with_root(ctx.root.as_deref())
+                            .with_module_name(module_names.get(path))
+                            .with_mode(mode)
+                            .with_timeout(ctx.parse_timeout),
+                    )
+                };
+
+                let start = Instant::now();
+                let parsed = match &self.cache {
+                    Some(cache) => {
+                        let module_name = module_names.get(path).map(String::as_str);
+                        cache.get_or_parse(path, code, mode, module_name, parse)
+                    }
+                    None => parse(),
+                };
+                if let Some(instrumentation) = &ctx.instrumentation {
+                    instrumentation.file_parsed(path, LANGUAGE, start.elapsed());
+                }
 
-            // TODO(Nik): Handle error
-            let mut results = parse_file(ctx).unwrap();
+                parsed
+            },
+        )?;
 
-            symbols.append(&mut results);
-        });
+        symbol_table::resolve_imported_types(&mut tables);
 
         let mut entities = vec![];
-        for symbol in symbols {
-            let entity = symbol.as_entity();
-            entities.push(entity);
+        for table in tables {
+            ctx.diagnostics.extend(table.diagnostics);
+            for symbol in table.symbols {
+                entities.push(symbol.as_entity());
+            }
+        }
+
+        if ctx.public_only {
+            dossier_core::retain_public(&mut entities, is_public);
         }
 
+        if let Some(locale) = &ctx.doc_locale {
+            dossier_core::locale::localize_descriptions(&mut entities, locale, LOCALE_MARKER);
+        }
+
+        if ctx.root_fqns {
+            dossier_core::root_fqn::record_root_fqns(&mut entities);
+        }
+
+        if ctx.include_source_text {
+            dossier_core::source_text::record_source_text(
+                &mut entities,
+                ctx.file_source.as_ref(),
+                ctx.max_source_bytes,
+            );
+        }
+
+        // After the source text, which is read from the files where they are
+        if let Some(root) = &ctx.root {
+            dossier_core::root_dir::relative_sources(&mut entities, root);
+        }
+
+        dossier_core::member_order::order_members(&mut entities);
+
         Ok(entities)
     }
+
+    fn language(&self) -> &'static str {
+        LANGUAGE
+    }
+
+    fn file_extensions(&self) -> &'static [&'static str] {
+        FILE_EXTENSIONS
+    }
+
+    fn is_public(
+        &self,
+        entity: &dossier_core::Entity,
+        parent: Option<&dossier_core::Entity>,
+    ) -> bool {
+        is_public(entity, parent)
+    }
 }
 
-fn init_parser() -> dossier_core::tree_sitter::Parser {
-    let mut parser = tree_sitter::Parser::new();
-    parser
-        .set_language(tree_sitter_python::language())
-        .expect("Error loading Python language");
+/// Python has no exports, so classes, functions and attributes are public
+/// unless their name starts with an underscore. Dunder methods like `__init__` are public.
+pub fn is_public(entity: &dossier_core::Entity, _parent: Option<&dossier_core::Entity>) -> bool {
+    if !matches!(entity.kind.as_str(), "class" | "function" | "attribute") {
+        return true;
+    }
 
-    parser
+    match entity.title.as_deref() {
+        Some(name) => !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__")),
+        None => true,
+    }
 }
 
-fn parse_file(mut ctx: ParserContext) -> Result<Vec<Symbol>> {
-    let mut parser = init_parser();
-    let tree = parser.parse(ctx.code, None).unwrap();
+fn parse_file(mut ctx: ParserContext) -> Result<SymbolTable> {
+    let Some(tree) = parser_pool::parse(
+        LANGUAGE,
+        tree_sitter_python::language(),
+        ctx.code,
+        ctx.timeout,
+    ) else {
+        ctx.symbol_table
+            .diagnostics
+            .push(parser_pool::parse_timeout_warning(ctx.file, ctx.timeout));
+        return Ok(ctx.symbol_table);
+    };
+    ctx.symbol_table
+        .diagnostics
+        .extend(dossier_core::helpers::syntax_error(
+            &tree.root_node(),
+            ctx.code,
+            ctx.file,
+        ));
 
     let mut cursor = tree.root_node().walk();
     assert_eq!(cursor.node().kind(), "module");
     cursor.goto_first_child();
     let mut out = vec![];
 
+    let handle_node = match ctx.mode {
+        FileMode::Full => handle_node,
+        FileMode::NamesOnly => handle_node_names_only,
+    };
+
     loop {
-        handle_node(cursor.node(), &mut out, &mut ctx)?;
+        handle_statement(cursor.node(), &mut out, &mut ctx, handle_node)?;
 
         if !cursor.goto_next_sibling() {
             break;
         }
     }
 
-    Ok(out)
+    function::merge_overloads(&mut out);
+    class::classify_bases(&mut out);
+
+    let mut table = ctx.symbol_table;
+    table.symbols = out;
+    table.resolve_types();
+
+    Ok(table)
+}
+
+/// Handles a top-level statement, adding its imports to the symbol table and
+/// the symbols it declares to `out`.
+///
+/// The body of an `if TYPE_CHECKING:` block is handled like top-level code,
+/// since it often imports or declares the classes only annotations refer to.
+fn handle_statement(
+    node: Node,
+    out: &mut Vec<Symbol>,
+    ctx: &mut ParserContext,
+    handle_node: fn(Node, &mut Vec<Symbol>, &mut ParserContext) -> Result<()>,
+) -> Result<()> {
+    if let Some(block) = type_checking_block(node, ctx) {
+        let mut cursor = block.walk();
+        for statement in block.named_children(&mut cursor) {
+            handle_statement(statement, out, ctx, handle_node)?;
+        }
+        return Ok(());
+    }
+
+    if import::matches_node(node) {
+        let imports = import::parse(node, ctx);
+        ctx.symbol_table.imports.extend(imports);
+        return Ok(());
+    }
+
+    handle_node(node, out, ctx)
+}
+
+/// The body of `node`, if it is an `if TYPE_CHECKING:` block
+fn type_checking_block<'a>(node: Node<'a>, ctx: &ParserContext) -> Option<Node<'a>> {
+    if node.kind() != "if_statement" {
+        return None;
+    }
+
+    let condition = node
+        .child_by_field_name("condition")
+        .and_then(|c| c.utf8_text(ctx.code.as_bytes()).ok());
+    if !matches!(condition, Some("TYPE_CHECKING" | "typing.TYPE_CHECKING")) {
+        return None;
+    }
+
+    node.child_by_field_name("consequence")
 }
 
 fn handle_node(node: Node, out: &mut Vec<Symbol>, ctx: &mut ParserContext) -> Result<()> {
+    let node = undecorated(node);
+
     if Class::matches_node(node) {
-        out.push(Class::parse_symbol(node, ctx).unwrap());
+        out.push(Class::parse_symbol(node, ctx)?);
     } else if Function::matches_node(node) {
-        out.push(Function::parse_symbol(node, ctx).unwrap());
+        out.push(Function::parse_symbol(node, ctx)?);
     } else {
         // println!("Unhandled node: {}", node.kind());
     }
@@ -98,12 +277,70 @@ fn handle_node(node: Node, out: &mut Vec<Symbol>, ctx: &mut ParserContext) -> Re
     Ok(())
 }
 
+/// The class or function a decorated definition like `@dataclass class
+/// Point: ...` defines, or `node` itself
+fn undecorated(node: Node) -> Node {
+    match node.kind() {
+        "decorated_definition" => node.child_by_field_name("definition").unwrap_or(node),
+        _ => node,
+    }
+}
+
+/// Adds a symbol with only a name for each top-level class and function,
+/// leaving out their members and documentation.
+fn handle_node_names_only(
+    node: Node,
+    out: &mut Vec<Symbol>,
+    ctx: &mut ParserContext,
+) -> Result<()> {
+    let node = undecorated(node);
+    let title = match node.child_by_field_name("name") {
+        Some(name) => name.utf8_text(ctx.code.as_bytes()).unwrap().to_owned(),
+        None => return Ok(()),
+    };
+
+    let kind = if Class::matches_node(node) {
+        SymbolKind::Class(Class {
+            title,
+            documentation: None,
+            members: vec![],
+            only_methods: false,
+            mixins: vec![],
+            signature: None,
+            flavor: match class::dataclass_decorator(&node, ctx) {
+                Some(_) => class::Flavor::Dataclass,
+                None => class::Flavor::Plain,
+            },
+            init_signature: None,
+        })
+    } else if Function::matches_node(node) {
+        SymbolKind::Function(Function {
+            title,
+            documentation: None,
+            members: vec![],
+            signature: None,
+            overload: false,
+        })
+    } else {
+        return Ok(());
+    };
+
+    out.push(Symbol::in_context(ctx, kind, Location::new(&node, ctx)));
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub(crate) struct ParserContext<'a> {
     pub file: &'a Path,
     pub code: &'a str,
     symbol_context: Vec<SymbolContext>,
-    fqn_parts: Vec<String>,
+    symbol_table: SymbolTable,
+    /// Link to the file in its repository, if we know where it lives
+    file_url: Option<String>,
+    mode: FileMode,
+    /// See `Context::parse_timeout`
+    timeout: Option<Duration>,
 }
 
 impl<'a> ParserContext<'a> {
@@ -112,14 +349,52 @@ impl<'a> ParserContext<'a> {
             file,
             code,
             symbol_context: vec![],
-            fqn_parts: vec![],
+            symbol_table: SymbolTable::new(file),
+            file_url: None,
+            mode: FileMode::Full,
+            timeout: None,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: FileMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_repository(mut self, repository: Option<&Repository>) -> Self {
+        self.file_url = repository.and_then(|r| r.file_url(self.file));
+        self
+    }
+
+    pub fn with_root(mut self, root: Option<&Path>) -> Self {
+        if let Some(root) = root {
+            let relative = dossier_core::root_dir::relative_to(self.file, root);
+            self.symbol_table
+                .name_file(dossier_core::portable_path(&relative));
+        }
+        self
+    }
+
+    pub fn with_module_name(mut self, module_name: Option<&String>) -> Self {
+        if let Some(module_name) = module_name {
+            self.symbol_table.name_file(module_name.clone());
         }
+        self
     }
 
     pub fn file(&self) -> &Path {
         self.file
     }
 
+    pub fn file_url(&self) -> Option<&str> {
+        self.file_url.as_deref()
+    }
+
     pub fn code(&self) -> &str {
         self.code
     }
@@ -137,31 +412,28 @@ impl<'a> ParserContext<'a> {
     }
 
     pub fn construct_fqn(&self, identifier: &str) -> String {
-        let mut out = vec![];
-        let file_part = format!("{}", self.file.display());
-        out.push(file_part.as_str());
-        for part in &self.fqn_parts {
-            out.push(part)
-        }
-        out.push(identifier);
-
-        out.join("::")
+        self.symbol_table.construct_fqn(identifier)
     }
 
     fn push_fqn(&mut self, identifier: &str) {
-        self.fqn_parts.push(identifier.to_owned());
+        self.symbol_table.push_fqn(identifier);
     }
 
     fn pop_fqn(&mut self) -> Option<String> {
-        self.fqn_parts.pop()
+        self.symbol_table.pop_fqn()
     }
 }
 
 mod helpers {
+    use dossier_core::helpers::{dedent, normalize_line_endings};
+
+    /// The text of a docstring, without the quotes, and with the lines after
+    /// the first dedented by the indentation they have in common
     pub(crate) fn process_docs(possible_docs: &str) -> Option<String> {
         if !possible_docs.starts_with("\"\"\"") {
             return None;
         }
+        let possible_docs = normalize_line_endings(possible_docs);
 
         // Remove the triple quotes from the start and end of the docstring
         let trimmed_docstring = possible_docs
@@ -169,31 +441,13 @@ mod helpers {
             .trim_end_matches("\"\"\"")
             .trim();
 
-        // Split the trimmed docstring into lines
-        let lines: Vec<&str> = trimmed_docstring.lines().collect();
+        // The first line starts right after the quotes, so it isn't indented
+        let mut lines = trimmed_docstring.lines();
+        let first = lines.next().unwrap_or_default();
+        let rest = lines.collect::<Vec<_>>();
 
-        // Find the minimum indentation starting from the second line
-        let min_indent = lines
-            .iter()
-            .skip(1)
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| line.len() - line.trim_start().len())
-            .min()
-            .unwrap_or(0);
-
-        // Process each line, removing the minimum indentation from lines other than the first
-        let parsed = lines
-            .iter()
-            .enumerate()
-            .map(|(i, line)| {
-                if i == 0 {
-                    *line
-                } else if line.len() > min_indent {
-                    &line[min_indent..]
-                } else {
-                    line.trim()
-                }
-            })
+        let parsed = std::iter::once(first)
+            .chain(dedent(&rest))
             .collect::<Vec<&str>>()
             .join("\n");
 
@@ -218,7 +472,7 @@ mod test {
         "#};
 
         let ctx = ParserContext::new(Path::new("main.py"), source);
-        let symbols = parse_file(ctx).unwrap();
+        let symbols = parse_file(ctx).unwrap().symbols;
 
         let class = symbols.first().unwrap().as_class().unwrap();
         assert_eq!(class.title, "PyClass");
@@ -228,6 +482,263 @@ mod test {
         );
     }
 
+    #[test]
+    fn parses_docstrings_with_crlf_line_endings() {
+        let source = "class PyClass:\r\n    \"\"\"Documentation.\r\n\r\n    More details.\r\n        Indented.\r\n    \"\"\"\r\n";
+
+        let ctx = ParserContext::new(Path::new("main.py"), source);
+        let symbols = parse_file(ctx).unwrap().symbols;
+
+        let class = symbols.first().unwrap().as_class().unwrap();
+        assert_eq!(
+            class.documentation.as_deref(),
+            Some("Documentation.\n\nMore details.\n    Indented.")
+        );
+    }
+
+    #[test]
+    fn dedents_tab_indented_docstrings() {
+        assert_eq!(
+            helpers::process_docs("\"\"\"Summary.\n\n\tDetails.\n\t\tcode()\n\t\"\"\"").as_deref(),
+            Some("Summary.\n\nDetails.\n\tcode()")
+        );
+        assert_eq!(
+            helpers::process_docs("\"\"\"Summary.\n\t\tDetails.\n\t\t  More.\"\"\"").as_deref(),
+            Some("Summary.\nDetails.\n  More.")
+        );
+    }
+
+    #[test]
+    fn public_only_skips_underscored_classes_and_functions() {
+        let source = indoc! {r#"
+        class Greeter:
+            def __init__(self, _name):
+                self._name = _name
+                self.greeting = "Hello"
+
+            def _helper(self):
+                pass
+
+            def greet(self):
+                pass
+
+        class _Private:
+            pass
+
+        def _util():
+            pass
+        "#};
+
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file("main.py", source);
+        let mut ctx = dossier_core::Context::new().with_file_source(files);
+        ctx.public_only = true;
+
+        let entities = dossier_core::DocsParser::parse(
+            &PythonParser::new(),
+            &[Path::new("main.py")],
+            &mut ctx,
+        )
+        .unwrap();
+
+        assert_eq!(entities.len(), 1);
+        let methods = entities[0]
+            .members
+            .iter()
+            .map(|m| m.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        // In source order, so attributes set in `__init__` come after it
+        assert_eq!(methods, vec!["__init__", "greeting", "greet"]);
+        // Parameters aren't filtered, even with a leading underscore
+        assert_eq!(
+            entities[0].members[0].members[1].title.as_deref(),
+            Some("_name")
+        );
+    }
+
+    #[test]
+    fn resolves_imports_in_type_checking_blocks() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file("models.py", "class User:\n    pass\n");
+        files.add_file(
+            "app.py",
+            indoc! {r#"
+            from typing import TYPE_CHECKING
+
+            if TYPE_CHECKING:
+                from models import User
+
+            def current() -> "User":
+                pass
+            "#},
+        );
+        let mut ctx = dossier_core::Context::new().with_file_source(files);
+
+        let entities = dossier_core::DocsParser::parse(
+            &PythonParser::new(),
+            &[Path::new("models.py"), Path::new("app.py")],
+            &mut ctx,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entities[1].members[0].identity,
+            dossier_core::Identity::Reference("models.py::User".to_owned())
+        );
+    }
+
+    #[test]
+    fn parses_type_checking_blocks_like_top_level_code() {
+        let source = indoc! {r#"
+        from typing import TYPE_CHECKING
+
+        if TYPE_CHECKING:
+            class Session:
+                pass
+
+        def connect() -> "Session":
+            pass
+        "#};
+
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file("app.py", source);
+        let mut ctx = dossier_core::Context::new().with_file_source(files);
+
+        let entities =
+            dossier_core::DocsParser::parse(&PythonParser::new(), &[Path::new("app.py")], &mut ctx)
+                .unwrap();
+
+        assert_eq!(
+            entities[0].identity,
+            dossier_core::Identity::FQN("app.py::Session".to_owned())
+        );
+        assert_eq!(
+            entities[1].members[0].identity,
+            dossier_core::Identity::Reference("app.py::Session".to_owned())
+        );
+    }
+
+    #[test]
+    fn merges_overloads_into_their_implementation() {
+        let source = indoc! {r#"
+        from typing import overload
+
+        @overload
+        def parse(value: str) -> int: ...
+        @overload
+        def parse(value: bytes) -> int: ...
+        def parse(value):
+            """Parses a number."""
+            return int(value)
+        "#};
+
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file("main.py", source);
+        let mut ctx = dossier_core::Context::new().with_file_source(files);
+
+        let entities = dossier_core::DocsParser::parse(
+            &PythonParser::new(),
+            &[Path::new("main.py")],
+            &mut ctx,
+        )
+        .unwrap();
+
+        assert_eq!(entities.len(), 1);
+        let parse = &entities[0];
+        assert_eq!(
+            parse.identity,
+            dossier_core::Identity::FQN("main.py::parse".to_owned())
+        );
+        assert_eq!(parse.description, "Parses a number.");
+        assert_eq!(parse.meta["implementation"], true);
+
+        let overloads = parse
+            .members
+            .iter()
+            .filter(|m| m.member_context == Some(dossier_core::MemberContext::Overload))
+            .collect::<Vec<_>>();
+        assert_eq!(overloads.len(), 2);
+        for overload in overloads {
+            assert_eq!(overload.identity, dossier_core::Identity::Anonymous);
+            assert_eq!(overload.meta["overload"], true);
+            assert_eq!(overload.members[0].title.as_deref(), Some("value"));
+            assert_eq!(
+                overload.members[0].identity,
+                dossier_core::Identity::Anonymous
+            );
+        }
+    }
+
+    #[test]
+    fn names_files_relative_to_the_root() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file("/repo/src/models.py", "class User:\n    pass\n");
+        files.add_file(
+            "/repo/src/app.py",
+            "from models import User\n\ndef current() -> User:\n    pass\n",
+        );
+        let mut ctx = dossier_core::Context::new().with_file_source(files);
+        ctx.root = Some(PathBuf::from("/repo/src"));
+
+        let entities = dossier_core::DocsParser::parse(
+            &PythonParser::new(),
+            &[
+                Path::new("/repo/src/models.py"),
+                Path::new("/repo/src/app.py"),
+            ],
+            &mut ctx,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entities[0].identity,
+            dossier_core::Identity::FQN("models.py::User".to_owned())
+        );
+        assert_eq!(entities[0].source.file, Path::new("models.py"));
+        assert_eq!(
+            entities[1].members[0].identity,
+            dossier_core::Identity::Reference("models.py::User".to_owned())
+        );
+        assert_eq!(entities[1].members[0].source.file, Path::new("app.py"));
+    }
+
+    #[test]
+    fn names_files_by_their_module() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file("src/mypkg/__init__.py", "");
+        files.add_file("src/mypkg/models.py", "class User:\n    pass\n");
+        files.add_file(
+            "src/mypkg/app.py",
+            "from mypkg.models import User\n\ndef current() -> User:\n    pass\n",
+        );
+        let mut ctx = dossier_core::Context::new().with_file_source(files);
+        ctx.module_names = true;
+
+        let entities = dossier_core::DocsParser::parse(
+            &PythonParser::new(),
+            &[
+                Path::new("src/mypkg/models.py"),
+                Path::new("src/mypkg/app.py"),
+            ],
+            &mut ctx,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entities[0].identity,
+            dossier_core::Identity::FQN("mypkg.models::User".to_owned())
+        );
+        assert_eq!(entities[0].source.file, Path::new("src/mypkg/models.py"));
+        assert_eq!(
+            entities[1].identity,
+            dossier_core::Identity::FQN("mypkg.app::current".to_owned())
+        );
+        assert_eq!(
+            entities[1].members[0].identity,
+            dossier_core::Identity::Reference("mypkg.models::User".to_owned())
+        );
+    }
+
     #[test]
     fn parses_a_function() {
         let source = indoc! {r#"
@@ -240,7 +751,7 @@ mod test {
         "#};
 
         let ctx = ParserContext::new(Path::new("main.py"), source);
-        let symbols = parse_file(ctx).unwrap();
+        let symbols = parse_file(ctx).unwrap().symbols;
 
         let function = symbols.first().unwrap().as_function().unwrap();
         assert_eq!(function.title, "complex");
@@ -249,4 +760,53 @@ mod test {
             Some("Form a complex number.")
         );
     }
+
+    #[test]
+    fn serializes_source_positions() {
+        let source = indoc! {r#"
+        import os
+
+
+        def foo(bar: int) -> str:
+            return str(bar)
+        "#};
+
+        let ctx = ParserContext::new(Path::new("main.py"), source);
+        let symbols = parse_file(ctx).unwrap().symbols;
+
+        let json = dossier_core::serde_json::to_value(symbols[0].as_entity()).unwrap();
+        let start = &json["source"]["start"];
+        let end = &json["source"]["end"];
+
+        assert_eq!(start["row"], 3);
+        assert_eq!(start["column"], 0);
+        assert_eq!(start["byte_offset"], 12);
+        assert_eq!(end["row"], 4);
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let source = indoc! {r#"
+        def ok():
+            pass
+
+        def broken(:
+            pass
+        "#};
+
+        let parsed = parse_file(ParserContext::new(Path::new("main.py"), source)).unwrap();
+        assert_eq!(parsed.diagnostics.len(), 1);
+        assert_eq!(parsed.diagnostics[0].code, dossier_core::SYNTAX_ERROR);
+        assert!(parsed.diagnostics[0].message.contains("line 4"));
+        assert!(parsed
+            .symbols
+            .iter()
+            .any(|s| s.as_entity().title.as_deref() == Some("ok")));
+
+        let parsed = parse_file(ParserContext::new(
+            Path::new("main.py"),
+            "def ok():\n    pass\n",
+        ));
+        assert!(parsed.unwrap().diagnostics.is_empty());
+    }
 }
@@ -0,0 +1,78 @@
+//! Names files by the modules they are imported as, for
+//! `Context::module_names`.
+//!
+//! A file is named by its module: its name without `.py`, after the names of
+//! the directories it is in that are packages, i.e. have an `__init__.py`,
+//! joined with `.`. So `src/mypkg/sub/module.py` is `mypkg.sub.module` if
+//! `mypkg` and `sub` have an `__init__.py` and `src` doesn't, and
+//! `src/mypkg/__init__.py` is `mypkg`.
+use dossier_core::FileSource;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const INIT: &str = "__init__";
+
+/// The module name of each of `paths`
+pub(crate) fn module_names(paths: &[PathBuf], files: &dyn FileSource) -> HashMap<PathBuf, String> {
+    let mut packages = HashMap::new();
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let mut parts = vec![];
+            let stem = path.file_stem()?.to_str()?;
+            if stem != INIT {
+                parts.push(stem);
+            }
+
+            let mut dir = path.parent();
+            while let Some(package) = dir.filter(|d| is_package(d, files, &mut packages)) {
+                let Some(name) = package.file_name().and_then(|n| n.to_str()) else {
+                    break;
+                };
+                parts.push(name);
+                dir = package.parent();
+            }
+
+            if parts.is_empty() {
+                return None;
+            }
+            parts.reverse();
+            Some((path.clone(), parts.join(".")))
+        })
+        .collect()
+}
+
+/// Whether `dir` has an `__init__.py`
+fn is_package(dir: &Path, files: &dyn FileSource, packages: &mut HashMap<PathBuf, bool>) -> bool {
+    *packages
+        .entry(dir.to_owned())
+        .or_insert_with(|| files.read_file(&dir.join(format!("{}.py", INIT))).is_ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::InMemoryFileSystem;
+
+    #[test]
+    fn names_files_by_their_module() {
+        let mut files = InMemoryFileSystem::new();
+        files.add_file("src/mypkg/__init__.py", "");
+        files.add_file("src/mypkg/sub/__init__.py", "");
+
+        let paths = [
+            "src/mypkg/sub/module.py",
+            "src/mypkg/__init__.py",
+            "src/mypkg/plain/tool.py",
+            "src/main.py",
+        ]
+        .map(PathBuf::from);
+        let names = module_names(&paths, &files);
+
+        assert_eq!(
+            paths.iter().map(|p| names[p].as_str()).collect::<Vec<_>>(),
+            ["mypkg.sub.module", "mypkg", "tool", "main"]
+        );
+    }
+}
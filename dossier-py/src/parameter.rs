@@ -1,4 +1,4 @@
-use dossier_core::{serde_json::json, Entity, Result};
+use dossier_core::{serde_json::json, Entity, MemberContext, Result};
 
 use crate::{
     symbol::{Location, ParseSymbol, Symbol, SymbolContext, SymbolKind},
@@ -24,9 +24,13 @@ impl Parameter {
             title: Some(self.title.to_owned()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "parameter".to_owned(),
-            identity: dossier_core::Identity::FQN(fqn.expect("parameter without FQN").to_owned()),
+            // Parameters of overload signatures are anonymous
+            identity: match fqn {
+                Some(fqn) => dossier_core::Identity::FQN(fqn.to_owned()),
+                None => dossier_core::Identity::Anonymous,
+            },
             members: self.members.iter().map(|s| s.as_entity()).collect(),
-            member_context: context.map(|sc| sc.to_string()),
+            member_context: context.map(|c| MemberContext::from(*c)),
             language: crate::LANGUAGE.to_owned(),
             source: loc.as_source(),
             meta: json!({}),
@@ -1,6 +1,6 @@
 use crate::ParserContext;
 
-use dossier_core::{Entity, Position, Result, Source};
+use dossier_core::{Entity, Position, Repository, Result, Source};
 use tree_sitter::Node;
 
 use std::path::PathBuf;
@@ -15,22 +15,29 @@ pub(crate) struct Location {
     file: PathBuf,
     start: Position,
     end: Position,
+    repository: Option<String>,
 }
 
 impl Location {
     pub fn new(node: &Node, ctx: &ParserContext) -> Self {
+        let start = Position {
+            row: node.start_position().row,
+            column: node.start_position().column,
+            byte_offset: node.start_byte(),
+        };
+        let end = Position {
+            row: node.end_position().row,
+            column: node.end_position().column,
+            byte_offset: node.end_byte(),
+        };
+
         Location {
             file: ctx.file().to_path_buf(),
-            start: Position {
-                row: node.start_position().row,
-                column: node.start_position().column,
-                byte_offset: node.start_byte(),
-            },
-            end: Position {
-                row: node.end_position().row,
-                column: node.end_position().column,
-                byte_offset: node.end_byte(),
-            },
+            repository: ctx
+                .file_url()
+                .map(|url| Repository::source_url(url, &start, &end)),
+            start,
+            end,
         }
     }
 
@@ -39,7 +46,7 @@ impl Location {
             file: self.file.to_path_buf(),
             start: self.start.clone(),
             end: self.end.clone(),
-            repository: None,
+            repository: self.repository.clone(),
         }
     }
 }
@@ -76,7 +83,11 @@ impl Symbol {
             SymbolKind::Parameter(s) => {
                 s.as_entity(&self.loc, self.fqn.as_deref(), self.context.as_ref())
             }
-            SymbolKind::Type(s) => {
+            SymbolKind::Type(s) => s.as_entity(&self.loc, self.context.as_ref()),
+            SymbolKind::Attribute(s) => {
+                s.as_entity(&self.loc, self.fqn.as_deref(), self.context.as_ref())
+            }
+            SymbolKind::EnumMember(s) => {
                 s.as_entity(&self.loc, self.fqn.as_deref(), self.context.as_ref())
             }
         }
@@ -121,6 +132,8 @@ pub(crate) enum SymbolKind {
     Function(crate::function::Function),
     Parameter(crate::parameter::Parameter),
     Type(crate::types::Type),
+    Attribute(crate::attribute::Attribute),
+    EnumMember(crate::enum_member::EnumMember),
 }
 
 impl SymbolKind {
@@ -132,6 +145,35 @@ impl SymbolKind {
             Function(crate::function::Function { title, .. }) => Some(title),
             Parameter(crate::parameter::Parameter { title, .. }) => Some(title),
             Type(t) => t.identifier(),
+            Attribute(crate::attribute::Attribute { title, .. }) => Some(title),
+            EnumMember(crate::enum_member::EnumMember { title, .. }) => Some(title),
+        }
+    }
+
+    /// The symbols nested in this one, like the parameters of a function
+    pub fn children(&self) -> &[Symbol] {
+        use SymbolKind::*;
+
+        match &self {
+            Class(c) => &c.members,
+            Function(f) => &f.members,
+            Parameter(p) => &p.members,
+            Type(t) => t.children(),
+            Attribute(a) => &a.members,
+            EnumMember(_) => &[],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> &mut [Symbol] {
+        use SymbolKind::*;
+
+        match self {
+            Class(c) => &mut c.members,
+            Function(f) => &mut f.members,
+            Parameter(p) => &mut p.members,
+            Type(t) => t.children_mut(),
+            Attribute(a) => &mut a.members,
+            EnumMember(_) => &mut [],
         }
     }
 }
@@ -141,15 +183,24 @@ pub(crate) enum SymbolContext {
     Method,
     Parameter,
     ReturnType,
+    Base,
+    /// An argument of a generic, `User` in `list[User]`
+    TypeArgument,
+    /// An `@overload` signature of a function
+    Overload,
 }
 
-impl std::fmt::Display for SymbolContext {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use SymbolContext::*;
-        match self {
-            Method => write!(f, "method"),
-            Parameter => write!(f, "parameter"),
-            ReturnType => write!(f, "return_type"),
+impl From<SymbolContext> for dossier_core::MemberContext {
+    fn from(context: SymbolContext) -> Self {
+        use dossier_core::MemberContext;
+
+        match context {
+            SymbolContext::Method => MemberContext::Method,
+            SymbolContext::Parameter => MemberContext::Parameter,
+            SymbolContext::ReturnType => MemberContext::ReturnType,
+            SymbolContext::Base => MemberContext::Base,
+            SymbolContext::TypeArgument => MemberContext::TypeArgument,
+            SymbolContext::Overload => MemberContext::Overload,
         }
     }
 }
@@ -0,0 +1,574 @@
+//! The symbols of each file, the names it imports, and resolving the names
+//! of classes in annotations and bases to the classes they refer to. That
+//! includes the annotations of parameters, return types and class attributes,
+//! and the arguments of generics like `list[Item]` in them.
+//!
+//! Names resolve like Python looks them up at the top level of a module:
+//!
+//! - A class defined in the file wins over an import of the same name.
+//! - `from module import Name` resolves `Name` to what `module` binds under
+//!   that name: a class defined there, or a name it imports in turn.
+//! - `import app.models` and `import app.models as m` resolve dotted names
+//!   like `app.models.User` and `m.User` through the module.
+//! - Names not found otherwise are looked up in the modules of wildcard
+//!   imports, latest import first.
+//!
+//! Modules map to files like they do on `sys.path`: `app.models` is
+//! `app/models.py` or `app/models/__init__.py`, looked for in the directory of
+//! the importing file and then in each directory above it. Relative modules
+//! like `..models` are found from the package of the importing file.
+use dossier_core::Diagnostic;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::import::Import;
+use crate::symbol::{Symbol, SymbolKind};
+use crate::types::Type;
+
+/// How many imports are followed to find where a name is defined, so that
+/// modules importing each other don't loop forever
+const MAX_IMPORT_DEPTH: usize = 16;
+
+/// The symbols of one file
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolTable {
+    pub file: PathBuf,
+    pub symbols: Vec<Symbol>,
+    /// In the order they appear in the file
+    pub imports: Vec<Import>,
+    /// The problems found parsing the file
+    pub diagnostics: Vec<Diagnostic>,
+    fqn_parts: Vec<String>,
+    /// How the file is named in FQNs
+    fqn_file: String,
+}
+
+impl SymbolTable {
+    pub fn new<P: Into<PathBuf>>(file: P) -> Self {
+        let file = file.into();
+
+        Self {
+            fqn_file: dossier_core::portable_path(&file),
+            file,
+            symbols: vec![],
+            imports: vec![],
+            diagnostics: vec![],
+            fqn_parts: vec![],
+        }
+    }
+
+    /// Names the file `name` in FQNs, rather than by the path it was read
+    /// from
+    pub(crate) fn name_file(&mut self, name: String) {
+        self.fqn_file = name;
+    }
+
+    pub fn construct_fqn(&self, identifier: &str) -> String {
+        let mut out = vec![];
+        out.push(self.fqn_file.as_str());
+        for part in &self.fqn_parts {
+            out.push(part)
+        }
+        out.push(identifier);
+
+        out.join("::")
+    }
+
+    pub(crate) fn push_fqn(&mut self, identifier: &str) {
+        self.fqn_parts.push(identifier.to_owned());
+    }
+
+    pub(crate) fn pop_fqn(&mut self) -> Option<String> {
+        self.fqn_parts.pop()
+    }
+
+    /// The class called `name` at the top level of the file
+    pub fn lookup(&self, name: &str) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .find(|s| matches!(&s.kind, SymbolKind::Class(c) if c.title == name))
+    }
+
+    /// Resolves the class names in the file to the classes defined in it
+    pub fn resolve_types(&mut self) {
+        let classes = self
+            .symbols
+            .iter()
+            .filter_map(|s| match &s.kind {
+                SymbolKind::Class(c) => Some((c.title.clone(), s.fqn.clone()?)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        self.apply_resolutions(&classes);
+    }
+
+    /// Resolves the names left unresolved by `resolve_types` through the
+    /// imports of the file
+    fn imported_resolutions(&self, index: &TableIndex) -> HashMap<String, String> {
+        let mut resolutions = HashMap::new();
+
+        for_each_type(&self.symbols, &mut |t| {
+            if let Type::Identifier(_, None) = t {
+                let name = t.referenced_name().unwrap();
+                if let Some(fqn) = self.resolve_imported(name, index, 0) {
+                    resolutions.insert(name.to_owned(), fqn);
+                }
+            }
+        });
+
+        resolutions
+    }
+
+    fn apply_resolutions(&mut self, resolutions: &HashMap<String, String>) {
+        for_each_type_mut(&mut self.symbols, &mut |t| {
+            let Some(fqn) = t.referenced_name().and_then(|name| resolutions.get(name)) else {
+                return;
+            };
+            if let Type::Identifier(_, resolved @ None) = t {
+                *resolved = Some(fqn.clone());
+            }
+        });
+    }
+
+    /// The FQN of the class `name` refers to in the file, where `name` is a
+    /// class of the file or a name it imports
+    fn resolve(&self, name: &str, index: &TableIndex, depth: usize) -> Option<String> {
+        match self.lookup(name) {
+            Some(symbol) => symbol.fqn.clone(),
+            None => self.resolve_imported(name, index, depth),
+        }
+    }
+
+    fn resolve_imported(&self, name: &str, index: &TableIndex, depth: usize) -> Option<String> {
+        if depth > MAX_IMPORT_DEPTH {
+            return None;
+        }
+        let depth = depth + 1;
+
+        for import in self.imports.iter().rev().filter(|i| !i.is_wildcard()) {
+            if import.alias == name {
+                let Some(imported) = &import.name else {
+                    // A module, not a class
+                    continue;
+                };
+
+                return index
+                    .module(&self.file, &import.module)?
+                    .resolve(imported, index, depth);
+            }
+
+            let Some(rest) = name
+                .strip_prefix(import.alias.as_str())
+                .and_then(|rest| rest.strip_prefix('.'))
+            else {
+                continue;
+            };
+
+            let module = match import.name {
+                None => import.module.clone(),
+                // `from app import models` followed by `models.User`
+                Some(_) => import.submodule()?,
+            };
+            return index.resolve_in_module(&self.file, &module, rest, depth);
+        }
+
+        self.imports
+            .iter()
+            .rev()
+            .filter(|i| i.is_wildcard())
+            .filter_map(|i| index.module(&self.file, &i.module))
+            .find_map(|module| module.resolve(name, index, depth))
+    }
+}
+
+/// Resolves the names each of `tables` imports from the others
+pub(crate) fn resolve_imported_types(tables: &mut [SymbolTable]) {
+    let resolutions = {
+        let index = TableIndex::new(tables.iter());
+
+        tables
+            .iter()
+            .map(|table| table.imported_resolutions(&index))
+            .collect::<Vec<_>>()
+    };
+
+    for (table, resolutions) in tables.iter_mut().zip(resolutions) {
+        table.apply_resolutions(&resolutions);
+    }
+}
+
+/// The tables of the files being parsed, by their path
+pub(crate) struct TableIndex<'a> {
+    by_path: HashMap<PathBuf, &'a SymbolTable>,
+}
+
+impl<'a> TableIndex<'a> {
+    pub fn new<T: IntoIterator<Item = &'a SymbolTable>>(tables: T) -> Self {
+        Self {
+            by_path: tables
+                .into_iter()
+                .map(|table| (dossier_core::normalize_path(&table.file), table))
+                .collect(),
+        }
+    }
+
+    /// The table of the file `module` refers to, when imported from `from`
+    pub fn module(&self, from: &Path, module: &str) -> Option<&'a SymbolTable> {
+        module_paths(from, module)
+            .iter()
+            .find_map(|path| self.by_path.get(&dossier_core::normalize_path(path)))
+            .copied()
+    }
+
+    /// Resolves a `name` that may be dotted in `module`, where a dotted name
+    /// like `user.User` may also be the class `User` of the submodule `user`
+    fn resolve_in_module(
+        &self,
+        from: &Path,
+        module: &str,
+        name: &str,
+        depth: usize,
+    ) -> Option<String> {
+        if let Some(fqn) = self
+            .module(from, module)
+            .and_then(|table| table.resolve(name, self, depth))
+        {
+            return Some(fqn);
+        }
+
+        let (submodule, rest) = name.split_once('.')?;
+        let submodule = if module.ends_with('.') {
+            format!("{}{}", module, submodule)
+        } else {
+            format!("{}.{}", module, submodule)
+        };
+
+        self.resolve_in_module(from, &submodule, rest, depth)
+    }
+}
+
+/// The paths the file of `module` may have when imported from the file
+/// `from`, in the order they are tried
+fn module_paths(from: &Path, module: &str) -> Vec<PathBuf> {
+    let dots = module.len() - module.trim_start_matches('.').len();
+    let parts = module[dots..]
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+
+    let package = from.parent().unwrap_or_else(|| Path::new(""));
+    let roots = if dots > 0 {
+        // One dot is the package of the file, and each further one its parent
+        let mut root = package.to_path_buf();
+        for _ in 1..dots {
+            root.push("..");
+        }
+        vec![root]
+    } else {
+        package.ancestors().map(|dir| dir.to_path_buf()).collect()
+    };
+
+    let mut paths = vec![];
+    for root in roots {
+        let mut path = root;
+        path.extend(&parts);
+
+        if !parts.is_empty() {
+            paths.push(path.with_extension("py"));
+        }
+        paths.push(path.join("__init__.py"));
+    }
+
+    paths
+}
+
+/// Calls `f` with each type in `symbols` and their members, like the
+/// annotations of the attributes of classes, including the ones nested in
+/// other types
+fn for_each_type(symbols: &[Symbol], f: &mut impl FnMut(&Type)) {
+    for symbol in symbols {
+        if let SymbolKind::Type(t) = &symbol.kind {
+            f(t);
+        }
+        for_each_type(symbol.kind.children(), f);
+    }
+}
+
+/// Mutable counterpart of `for_each_type`
+fn for_each_type_mut(symbols: &mut [Symbol], f: &mut impl FnMut(&mut Type)) {
+    for symbol in symbols {
+        if let SymbolKind::Type(t) = &mut symbol.kind {
+            f(t);
+        }
+        for_each_type_mut(symbol.kind.children_mut(), f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_file, ParserContext};
+    use dossier_core::test_utils::reference;
+    use dossier_core::{Entity, Identity, MemberContext};
+    use indoc::indoc;
+
+    /// Parses the files and resolves the names they import, returning the
+    /// top-level entities of all of them
+    fn resolve(sources: &[(&str, &str)]) -> Vec<Entity> {
+        let mut tables = sources
+            .iter()
+            .map(|(path, code)| parse_file(ParserContext::new(Path::new(path), code)).unwrap())
+            .collect::<Vec<_>>();
+
+        resolve_imported_types(&mut tables);
+
+        tables
+            .iter()
+            .flat_map(|table| table.symbols.iter().map(|s| s.as_entity()))
+            .collect()
+    }
+
+    /// What the return type of the function called `name` refers to
+    fn return_type<'a>(entities: &'a [Entity], name: &str) -> &'a Identity {
+        let function = entities
+            .iter()
+            .find(|e| e.kind == "function" && e.title.as_deref() == Some(name))
+            .unwrap();
+
+        &function
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::ReturnType))
+            .unwrap()
+            .identity
+    }
+
+    #[test]
+    fn resolves_classes_in_the_same_file() {
+        let source = indoc! {r#"
+        class User:
+            def clone(self) -> "User":
+                pass
+
+        def find(name: str) -> User:
+            pass
+
+        def missing() -> Group:
+            pass
+
+        def maybe() -> Optional[list[User]]:
+            pass
+        "#};
+        let entities = resolve(&[("app.py", source)]);
+
+        let user = reference("app.py::User");
+        assert_eq!(return_type(&entities, "find"), &user);
+        assert_eq!(
+            entities[0].members[0].members[1].identity, user,
+            "forward references resolve too"
+        );
+        assert_eq!(return_type(&entities, "missing"), &Identity::Anonymous);
+
+        let maybe = entities
+            .iter()
+            .find(|e| e.title.as_deref() == Some("maybe"))
+            .unwrap();
+        let list = &maybe.members[0].members[0];
+        assert_eq!(list.members[0].identity, user, "nested types resolve too");
+    }
+
+    #[test]
+    fn resolves_classes_imported_from_other_files() {
+        let user = indoc! {r#"
+        class User:
+            pass
+        "#};
+        let init = indoc! {r#"
+        from .user import User as Member
+        "#};
+        let app = indoc! {r#"
+        from models.user import User
+        from .models import Member
+        from . import models
+        import models.user
+        import models.user as user_models
+
+        class Admin(models.user.User):
+            pass
+
+        def direct() -> User:
+            pass
+
+        def reexported() -> Member:
+            pass
+
+        def by_module() -> models.Member:
+            pass
+
+        def by_submodule() -> models.user.User:
+            pass
+
+        def by_alias() -> user_models.User:
+            pass
+        "#};
+        let entities = resolve(&[
+            ("src/models/user.py", user),
+            ("src/models/__init__.py", init),
+            ("src/app.py", app),
+        ]);
+
+        let user = reference("src/models/user.py::User");
+        for name in [
+            "direct",
+            "reexported",
+            "by_module",
+            "by_submodule",
+            "by_alias",
+        ] {
+            assert_eq!(return_type(&entities, name), &user, "{}", name);
+        }
+        let admin = entities
+            .iter()
+            .find(|e| e.title.as_deref() == Some("Admin"))
+            .unwrap();
+        assert_eq!(admin.members[0].identity, user);
+    }
+
+    #[test]
+    fn prefers_classes_defined_in_the_file_and_follows_wildcard_imports() {
+        let models = indoc! {r#"
+        class User:
+            pass
+
+        class Group:
+            pass
+        "#};
+        let app = indoc! {r#"
+        from models import *
+        from models import User
+
+        class User:
+            pass
+
+        def local() -> User:
+            pass
+
+        def wildcard() -> Group:
+            pass
+        "#};
+        let cycle = indoc! {r#"
+        from cycle import Loop
+
+        def looping() -> Loop:
+            pass
+        "#};
+        let entities = resolve(&[("models.py", models), ("app.py", app), ("cycle.py", cycle)]);
+
+        assert_eq!(return_type(&entities, "local"), &reference("app.py::User"));
+        assert_eq!(
+            return_type(&entities, "wildcard"),
+            &reference("models.py::Group")
+        );
+        assert_eq!(return_type(&entities, "looping"), &Identity::Anonymous);
+    }
+
+    #[test]
+    fn resolves_the_annotations_of_attributes() {
+        let accounts = indoc! {r#"
+        class Account:
+            pass
+        "#};
+        let app = indoc! {r#"
+        from dataclasses import dataclass
+        from accounts import Account
+
+        class Item:
+            pass
+
+        class Order:
+            owner: User
+            items: list[Item]
+            account: Account
+            group: Group
+            reviewer: "User"
+
+        @dataclass
+        class Invoice:
+            owner: User
+            items: list[Item]
+            account: Account
+            group: Group
+            reviewer: "User"
+
+        class User:
+            pass
+        "#};
+        let entities = resolve(&[("accounts.py", accounts), ("app.py", app)]);
+
+        for class in ["Order", "Invoice"] {
+            let annotation = |attribute: &str| {
+                let class = entities
+                    .iter()
+                    .find(|e| e.title.as_deref() == Some(class))
+                    .unwrap();
+                let attribute = class
+                    .members
+                    .iter()
+                    .find(|m| m.kind == "attribute" && m.title.as_deref() == Some(attribute))
+                    .unwrap();
+                attribute.members[0].clone()
+            };
+
+            let user = reference("app.py::User");
+            assert_eq!(annotation("owner").identity, user, "{}", class);
+            assert_eq!(
+                annotation("items").members[0].identity,
+                reference("app.py::Item"),
+                "{}",
+                class
+            );
+            assert_eq!(
+                annotation("account").identity,
+                reference("accounts.py::Account"),
+                "{}",
+                class
+            );
+            assert_eq!(
+                annotation("group").identity,
+                Identity::Anonymous,
+                "{}",
+                class
+            );
+            assert_eq!(annotation("reviewer").identity, user, "{}", class);
+        }
+    }
+
+    #[test]
+    fn maps_modules_to_paths() {
+        let paths = |from: &str, module: &str| {
+            module_paths(Path::new(from), module)
+                .iter()
+                .map(|p| dossier_core::portable_path(&dossier_core::normalize_path(p)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            paths("src/app/main.py", "models"),
+            [
+                "src/app/models.py",
+                "src/app/models/__init__.py",
+                "src/models.py",
+                "src/models/__init__.py",
+                "models.py",
+                "models/__init__.py",
+            ]
+        );
+        assert_eq!(
+            paths("src/app/main.py", ".models.user"),
+            ["src/app/models/user.py", "src/app/models/user/__init__.py"]
+        );
+        assert_eq!(paths("src/app/main.py", ".."), ["src/__init__.py"]);
+    }
+}
@@ -1,4 +1,4 @@
-use dossier_core::{serde_json::json, Entity, Result};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, MemberContext, Result};
 
 use crate::{
     symbol::{Location, ParseSymbol, Symbol, SymbolContext, SymbolKind},
@@ -7,27 +7,51 @@ use crate::{
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Type {
-    BuiltIn(String),
-    Identifier(String),
+    /// Built-in types like `int` and `str`, and `None`
+    Predefined(String),
+    /// A class name, and the FQN of the class it was resolved to
+    Identifier(String, Option<String>),
+    /// A subscripted generic like `list[User]` or `Dict[str, int]`, with the
+    /// type arguments as members
+    Generic {
+        identifier: String,
+        members: Vec<Symbol>,
+    },
+    /// `X | Y` and `Union[X, Y]`. `Optional[X]` is a union of `X` and `None`.
+    Union { members: Vec<Symbol> },
+    /// Anything else in an annotation, as written, like the `"a"` in
+    /// `Literal["a"]` or the `...` in `tuple[int, ...]`
+    Literal(String),
 }
 
 impl Type {
-    pub fn as_entity(
-        &self,
-        loc: &Location,
-        fqn: Option<&str>,
-        context: Option<&SymbolContext>,
-    ) -> Entity {
+    pub fn as_entity(&self, loc: &Location, context: Option<&SymbolContext>) -> Entity {
+        // Uses of types, not declarations, so they have no FQN of their own
+        let (kind, identity) = match self {
+            Type::Predefined(_) => ("predefined_type", dossier_core::Identity::Anonymous),
+            // Point at the class the name was resolved to
+            Type::Identifier(_, Some(resolved)) => (
+                "identifier",
+                dossier_core::Identity::Reference(resolved.to_owned()),
+            ),
+            Type::Identifier(_, None) => ("identifier", dossier_core::Identity::Anonymous),
+            Type::Generic { .. } => ("generic_type", dossier_core::Identity::Anonymous),
+            Type::Union { .. } => ("union", dossier_core::Identity::Anonymous),
+            Type::Literal(_) => ("literal", dossier_core::Identity::Anonymous),
+        };
+
+        let title = match self {
+            Type::Literal(text) => Some(text.to_owned()),
+            _ => self.identifier().map(|i| i.to_owned()),
+        };
+
         Entity {
-            title: self.identifier().map(|i| i.to_owned()),
+            title,
             description: String::new(),
-            kind: "type".to_owned(),
-            identity: match fqn {
-                Some(f) => dossier_core::Identity::FQN(f.to_owned()),
-                None => dossier_core::Identity::Anonymous,
-            },
-            members: vec![],
-            member_context: context.map(|c| c.to_string()),
+            kind: kind.to_owned(),
+            identity,
+            members: self.children().iter().map(|s| s.as_entity()).collect(),
+            member_context: context.map(|c| MemberContext::from(*c)),
             language: crate::LANGUAGE.to_owned(),
             source: loc.as_source(),
             meta: json!({}),
@@ -36,8 +60,34 @@ impl Type {
 
     pub fn identifier(&self) -> Option<&str> {
         match self {
-            Type::BuiltIn(s) => Some(s),
-            Type::Identifier(s) => Some(s),
+            Type::Predefined(s) => Some(s),
+            Type::Identifier(s, _) => Some(s),
+            Type::Generic { identifier, .. } => Some(identifier),
+            Type::Union { .. } | Type::Literal(_) => None,
+        }
+    }
+
+    /// The name of the class an identifier refers to, without the quotes of
+    /// a forward reference like `"User"`
+    pub fn referenced_name(&self) -> Option<&str> {
+        match self {
+            Type::Identifier(s, _) => Some(s.trim_matches(|c| c == '"' || c == '\'')),
+            _ => None,
+        }
+    }
+
+    /// The type arguments of a generic, or the members of a union
+    pub fn children(&self) -> &[Symbol] {
+        match self {
+            Type::Generic { members, .. } | Type::Union { members } => members,
+            _ => &[],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> &mut [Symbol] {
+        match self {
+            Type::Generic { members, .. } | Type::Union { members } => members,
+            _ => &mut [],
         }
     }
 }
@@ -50,32 +100,264 @@ impl ParseSymbol for Type {
     fn parse_symbol(node: tree_sitter::Node, ctx: &mut ParserContext) -> Result<Symbol> {
         assert_eq!(node.kind(), "type", "Expected type");
 
+        Ok(parse(node, ctx, false))
+    }
+}
+
+/// Parses an annotation, or an expression in one. In the arguments of
+/// `Literal`, which are values rather than types, `in_literal` is set.
+fn parse(node: Node, ctx: &ParserContext, in_literal: bool) -> Symbol {
+    let text = node.utf8_text(ctx.code().as_bytes()).unwrap().to_owned();
+
+    let kind = match node.kind() {
+        "type" => match node.named_child(0) {
+            Some(inner) => return in_place_of(parse(inner, ctx, in_literal), node, ctx),
+            None => Type::Literal(text),
+        },
+        "none" => Type::Predefined(text),
+        "identifier" | "attribute" if is_built_in(&text) => Type::Predefined(text),
+        // Strings outside of `Literal` are forward references to classes
+        "identifier" | "attribute" | "string" if !in_literal => Type::Identifier(text, None),
+        "union_type" => Type::Union {
+            members: union_members(node, ctx),
+        },
+        "binary_operator" if operator(node, ctx) == Some("|") => Type::Union {
+            members: union_members(node, ctx),
+        },
+        "generic_type" | "subscript" => return parse_generic(node, ctx),
+        _ => Type::Literal(text),
+    };
+
+    Symbol::in_context(ctx, SymbolKind::Type(kind), Location::new(&node, ctx))
+}
+
+/// `X[A, B]`, where `Optional` and `Union` make a union of their arguments
+fn parse_generic(node: Node, ctx: &ParserContext) -> Symbol {
+    let (identifier, arguments) = if node.kind() == "generic_type" {
+        let identifier = node.named_child(0).unwrap();
+        let arguments = node
+            .named_child(1)
+            .map(|parameters| {
+                let mut cursor = parameters.walk();
+                parameters.named_children(&mut cursor).collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        (identifier, arguments)
+    } else {
+        let identifier = node.child_by_field_name("value").unwrap();
         let mut cursor = node.walk();
-        cursor.goto_first_child();
+        let arguments = node
+            .children_by_field_name("subscript", &mut cursor)
+            .collect::<Vec<_>>();
+        (identifier, arguments)
+    };
 
-        let title = cursor
-            .node()
-            .utf8_text(ctx.code().as_bytes())
-            .unwrap()
-            .to_owned();
+    let identifier = identifier
+        .utf8_text(ctx.code().as_bytes())
+        .unwrap()
+        .to_owned();
+    let unqualified = identifier.rsplit('.').next().unwrap_or(&identifier);
 
-        let out = if is_built_in(&title) {
-            Type::BuiltIn(title)
-        } else {
-            Type::Identifier(title)
+    let mut members = arguments
+        .iter()
+        .map(|argument| member(parse(*argument, ctx, unqualified == "Literal")))
+        .collect::<Vec<_>>();
+
+    let kind = match unqualified {
+        "Optional" if members.len() == 1 => {
+            let none = Symbol::in_context(
+                ctx,
+                SymbolKind::Type(Type::Predefined("None".to_owned())),
+                Location::new(&node, ctx),
+            );
+            members.push(member(none));
+            Type::Union { members }
+        }
+        "Union" => Type::Union { members },
+        _ => Type::Generic {
+            identifier,
+            members: members
+                .into_iter()
+                .map(|mut argument| {
+                    argument.context = Some(SymbolContext::TypeArgument);
+                    argument
+                })
+                .collect(),
+        },
+    };
+
+    Symbol::in_context(ctx, SymbolKind::Type(kind), Location::new(&node, ctx))
+}
+
+/// The members of `X | Y | Z`, which is parsed as `(X | Y) | Z`
+fn union_members(node: Node, ctx: &ParserContext) -> Vec<Symbol> {
+    let mut members = vec![];
+    let mut cursor = node.walk();
+
+    for child in node.named_children(&mut cursor) {
+        let inner = match child.kind() {
+            "type" => child.named_child(0).unwrap_or(child),
+            _ => child,
+        };
+        let nested = match inner.kind() {
+            "union_type" => true,
+            "binary_operator" => operator(inner, ctx) == Some("|"),
+            _ => false,
         };
 
-        Ok(Symbol::in_context(
-            ctx,
-            SymbolKind::Type(out),
-            Location::new(&node, ctx),
-        ))
+        if nested {
+            members.extend(union_members(inner, ctx));
+        } else {
+            members.push(member(parse(child, ctx, false)));
+        }
     }
+
+    members
+}
+
+fn operator<'a>(node: Node, ctx: &'a ParserContext) -> Option<&'a str> {
+    node.child_by_field_name("operator")
+        .and_then(|o| o.utf8_text(ctx.code().as_bytes()).ok())
+}
+
+/// A type nested in another one only gets its context from the outer one
+fn member(mut symbol: Symbol) -> Symbol {
+    symbol.context = None;
+    symbol
+}
+
+/// `symbol`, parsed from inside the `type` node `node`, with the location and
+/// context of the whole annotation
+fn in_place_of(mut symbol: Symbol, node: Node, ctx: &ParserContext) -> Symbol {
+    symbol.loc = Location::new(&node, ctx);
+    symbol.context = ctx.symbol_context();
+    symbol
 }
 
 fn is_built_in(title: &str) -> bool {
     matches!(
         title,
-        "int" | "string" | "bool" | "float" | "double" | "void"
+        "int"
+            | "float"
+            | "complex"
+            | "str"
+            | "bytes"
+            | "bool"
+            | "object"
+            | "list"
+            | "dict"
+            | "set"
+            | "frozenset"
+            | "tuple"
+            | "type"
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::symbol_table::SymbolTable;
+    use indoc::indoc;
+    use std::path::Path;
+
+    /// The text of a type as it would be written, with unions in brackets
+    fn written(symbol: &Symbol) -> String {
+        let SymbolKind::Type(t) = &symbol.kind else {
+            panic!("Expected a type, got {:?}", symbol.kind);
+        };
+        let members = t.children().iter().map(written).collect::<Vec<_>>();
+
+        match t {
+            Type::Predefined(name) => format!("builtin {}", name),
+            Type::Identifier(name, _) => name.to_owned(),
+            Type::Generic { identifier, .. } => format!("{}[{}]", identifier, members.join(", ")),
+            Type::Union { .. } => format!("({})", members.join(" | ")),
+            Type::Literal(text) => format!("literal {}", text),
+        }
+    }
+
+    fn parameter_types(source: &str) -> Vec<Symbol> {
+        let table: SymbolTable =
+            crate::parse_file(ParserContext::new(Path::new("test.py"), source)).unwrap();
+
+        table.symbols[0]
+            .kind
+            .children()
+            .iter()
+            .map(|s| match &s.kind {
+                SymbolKind::Parameter(p) => p.members[0].clone(),
+                _ => s.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_nested_generics() {
+        let source = indoc! {r#"
+        def f(
+            a: list[dict[str, User]],
+            b: typing.Dict[str, typing.List["User"]],
+            c: Literal["a", 1],
+            d: tuple[int, ...],
+            e: None,
+        ) -> Callable[[int], str]:
+            pass
+        "#};
+        let types = parameter_types(source);
+
+        let written = types.iter().map(written).collect::<Vec<_>>();
+        assert_eq!(
+            written,
+            vec![
+                "list[dict[builtin str, User]]",
+                "typing.Dict[builtin str, typing.List[\"User\"]]",
+                "Literal[literal \"a\", literal 1]",
+                "tuple[builtin int, literal ...]",
+                "builtin None",
+                "Callable[literal [int], builtin str]",
+            ]
+        );
+
+        let a = &types[0];
+        assert_eq!(a.context, Some(SymbolContext::Parameter));
+        assert_eq!(a.as_entity().source.start.row, 1);
+        assert_eq!(a.as_entity().source.start.column, 7);
+        let arguments = a.kind.children()[0].kind.children();
+        assert_eq!(arguments[1].context, Some(SymbolContext::TypeArgument));
+        assert_eq!(arguments[1].fqn.as_deref(), Some("test.py::f::User"));
+    }
+
+    #[test]
+    fn parses_unions() {
+        let source = indoc! {r#"
+        def f(
+            a: int | str | None,
+            b: Optional[User],
+            c: Union[int, list[str | bytes]],
+        ) -> "User" | None:
+            pass
+        "#};
+        let types = parameter_types(source);
+
+        let written = types.iter().map(written).collect::<Vec<_>>();
+        assert_eq!(
+            written,
+            vec![
+                "(builtin int | builtin str | builtin None)",
+                "(User | builtin None)",
+                "(builtin int | list[(builtin str | builtin bytes)])",
+                "(\"User\" | builtin None)",
+            ]
+        );
+
+        let entity = types[0].as_entity();
+        assert_eq!(entity.kind, "union");
+        assert_eq!(entity.member_context, Some(MemberContext::Parameter));
+        assert_eq!(entity.members[0].kind, "predefined_type");
+        assert_eq!(
+            entity.members[0].identity,
+            dossier_core::Identity::Anonymous
+        );
+        assert_eq!(entity.members[0].member_context, None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Follows references through chains of type aliases.
+//!
+//! A reference to `type ID = UserId` correctly points at `ID`, but consumers
+//! often want to know what the alias ultimately stands for. This pass records
+//! the end of the chain in `meta.resolves_to`, next to the immediate reference.
+use dossier_core::{Entity, Identity};
+use std::collections::{HashMap, HashSet};
+
+/// How many aliases we are willing to follow before giving up.
+const MAX_DEPTH: usize = 32;
+
+pub(crate) fn resolve_alias_chains(entities: &mut [Entity]) {
+    let mut aliases = HashMap::new();
+    for entity in entities.iter() {
+        collect_alias_targets(entity, &mut aliases);
+    }
+
+    for entity in entities.iter_mut() {
+        annotate(entity, &aliases);
+    }
+}
+
+/// Maps the FQN of every alias whose sole child is a reference to another
+/// entity to the FQN it refers to.
+fn collect_alias_targets(entity: &Entity, aliases: &mut HashMap<String, String>) {
+    if entity.kind == "type_alias" {
+        if let (Identity::FQN(fqn), [member]) = (&entity.identity, entity.members.as_slice()) {
+            if let Identity::Reference(target) = &member.identity {
+                aliases.insert(fqn.clone(), target.clone());
+            }
+        }
+    }
+
+    for member in &entity.members {
+        collect_alias_targets(member, aliases);
+    }
+}
+
+fn annotate(entity: &mut Entity, aliases: &HashMap<String, String>) {
+    if let Identity::Reference(target) = &entity.identity {
+        if let Some(resolved) = follow(target, aliases) {
+            if !entity.meta.is_object() {
+                entity.meta = dossier_core::serde_json::json!({});
+            }
+            entity.meta["resolves_to"] = resolved.into();
+        }
+    }
+
+    for member in entity.members.iter_mut() {
+        annotate(member, aliases);
+    }
+}
+
+/// Returns the final FQN at the end of an alias chain, if it differs from
+/// the starting point. Cycles and chains longer than `MAX_DEPTH` resolve to
+/// nothing, since there is no meaningful end to point to.
+fn follow(start: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    let mut seen = HashSet::from([start]);
+    let mut current = start;
+
+    for _ in 0..MAX_DEPTH {
+        match aliases.get(current) {
+            Some(next) => {
+                if !seen.insert(next.as_str()) {
+                    return None;
+                }
+                current = next;
+            }
+            None => {
+                return if current == start {
+                    None
+                } else {
+                    Some(current.to_owned())
+                };
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,173 @@
+//! Parsing shared by functions and methods, which only differ in how they
+//! are named and where they can be declared.
+use crate::{
+    helpers::*,
+    parameter,
+    symbol::{Symbol, SymbolContext},
+    tsdoc::{self, DocTags},
+    type_variable, types, ParserContext,
+};
+
+use dossier_core::{tree_sitter::Node, Result};
+
+/// The type parameters, parameters, return type, and documentation of a
+/// function or method
+pub(crate) struct Signature {
+    pub children: Vec<Symbol>,
+    pub documentation: Option<String>,
+    pub doc_tags: DocTags,
+}
+
+/// Parses the signature of the function or method declared by `main_node`,
+/// in scopes of its own under `identifier`.
+pub(crate) fn parse_signature(
+    identifier: &str,
+    main_node: &Node,
+    type_parameters: Option<Node>,
+    parameters: Option<Node>,
+    return_type: Option<Node>,
+    ctx: &mut ParserContext,
+) -> Result<Signature> {
+    let mut children = vec![];
+
+    ctx.push_scope();
+    ctx.push_fqn(identifier);
+
+    if let Some(type_parameters) = type_parameters {
+        parse_type_parameters(&type_parameters, &mut children, ctx)?;
+        ctx.push_scope();
+    }
+
+    if let Some(parameters) = parameters {
+        parse_parameters(&parameters, &mut children, ctx)?;
+    }
+
+    if let Some(return_type) = return_type {
+        parse_return_type(&return_type, &mut children, ctx)?;
+    }
+
+    let (documentation, mut doc_tags) = tsdoc::parse(find_docs(main_node, ctx.code));
+    doc_tags.attach_to_parameters(&mut children, main_node, ctx);
+
+    if type_parameters.is_some() {
+        ctx.pop_scope();
+    }
+    ctx.pop_scope();
+    ctx.pop_fqn();
+
+    Ok(Signature {
+        children,
+        documentation,
+        doc_tags,
+    })
+}
+
+/// Whether the function or method declared by `main_node` is `async`. Only
+/// looks at the children before `name_node`, so a method called `async`
+/// isn't mistaken for one.
+pub(crate) fn is_async(main_node: &Node, name_node: &Node) -> bool {
+    let mut cursor = main_node.walk();
+    let is_async = main_node
+        .children(&mut cursor)
+        .take_while(|child| child.start_byte() < name_node.start_byte())
+        .any(|child| child.kind() == "async");
+    is_async
+}
+
+/// `T` in the `Promise<T>` return type of an `async` function or method, as
+/// written, which is what awaiting it results in
+pub(crate) fn awaited_type(return_type: Option<Node>, code: &str) -> Option<String> {
+    let the_type = return_type?.named_child(0)?;
+    if the_type.kind() != "generic_type" {
+        return None;
+    }
+
+    let name = the_type.child_by_field_name("name")?;
+    if name.utf8_text(code.as_bytes()).ok()? != "Promise" {
+        return None;
+    }
+
+    let arguments = the_type.child_by_field_name("type_arguments")?;
+    if arguments.named_child_count() != 1 {
+        return None;
+    }
+
+    arguments
+        .named_child(0)?
+        .utf8_text(code.as_bytes())
+        .ok()
+        .map(dossier_core::helpers::join_lines)
+}
+
+/// Parses the `type_annotation` of a return type
+pub(crate) fn parse_return_type(
+    node: &Node,
+    children: &mut Vec<Symbol>,
+    ctx: &mut ParserContext,
+) -> Result<()> {
+    let mut type_node_cursor = node.walk();
+    type_node_cursor.goto_first_child();
+
+    while !type_node_cursor.node().is_named() {
+        type_node_cursor.goto_next_sibling();
+    }
+
+    let mut the_type = types::parse(&type_node_cursor.node(), ctx)?;
+    the_type.context = Some(SymbolContext::ReturnType);
+    children.push(the_type);
+
+    Ok(())
+}
+
+/// Parses `formal_parameters`, giving each parameter the `Parameter` context
+pub(crate) fn parse_parameters(
+    parameters: &Node,
+    children: &mut Vec<Symbol>,
+    ctx: &mut ParserContext,
+) -> Result<()> {
+    assert_eq!(parameters.kind(), "formal_parameters");
+
+    let mut cursor = parameters.walk();
+    cursor.goto_first_child();
+
+    loop {
+        if parameter::NODE_KINDS.contains(&cursor.node().kind()) {
+            let mut parameter = parameter::parse(&cursor.node(), ctx)?;
+            parameter.context = Some(SymbolContext::Parameter);
+            children.push(parameter);
+        }
+
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses `type_parameters`, giving each type variable the `TypeParameter`
+/// context
+pub(crate) fn parse_type_parameters(
+    type_parameters: &Node,
+    children: &mut Vec<Symbol>,
+    ctx: &mut ParserContext,
+) -> Result<()> {
+    assert_eq!(type_parameters.kind(), "type_parameters");
+
+    let mut cursor = type_parameters.walk();
+    cursor.goto_first_child();
+
+    loop {
+        if cursor.node().kind() == "type_parameter" {
+            let mut type_variable = type_variable::parse(&cursor.node(), ctx)?;
+            type_variable.context = Some(SymbolContext::TypeParameter);
+            children.push(type_variable);
+        }
+
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+
+    Ok(())
+}
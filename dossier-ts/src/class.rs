@@ -1,24 +1,32 @@
 use crate::{
+    decorator::{self, Decorator},
     field,
     helpers::*,
     method,
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    tsdoc::{self, DocTags},
+    types::{self, Type},
     ParserContext,
 };
-use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 pub(crate) const NODE_KIND: &str = "class_declaration";
 pub(crate) const ABSTRACT_NODE_KIND: &str = "abstract_class_declaration";
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Class {
+pub struct Class {
     pub identifier: String,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
     /// Interfaces are actually just a single object type.
     /// We forward a bunch of methods to this child object.
     pub children: Vec<Symbol>,
     pub exported: bool,
     pub is_abstract: bool,
+    pub decorators: Vec<Decorator>,
+    /// The declaration without its body, on one line, see
+    /// `dossier_core::helpers::signature`. Not set for minified files.
+    pub signature: Option<String>,
 }
 
 impl Class {
@@ -32,13 +40,21 @@ impl Class {
         if self.exported {
             meta["exported"] = true.into();
         }
+        if self.is_abstract {
+            meta["abstract"] = true.into();
+        }
+        decorator::add_to_meta(&self.decorators, &mut meta);
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "class".to_owned(),
             identity: Identity::FQN(fqn.expect("Class did not have FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: "ts".to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -49,14 +65,15 @@ impl Class {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
-    pub(crate) fn fields(&self) -> impl Iterator<Item = &Symbol> {
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Class {
+    pub fn fields(&self) -> impl Iterator<Item = &Symbol> {
         self.children.iter().filter(|s| s.kind.as_field().is_some())
     }
 
-    #[cfg(test)]
-    pub(crate) fn methods(&self) -> impl Iterator<Item = &Symbol> {
+    pub fn methods(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_method().is_some())
@@ -87,9 +104,19 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         .unwrap()
         .to_owned();
 
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
+
     ctx.push_scope();
     ctx.push_fqn(&identifier);
 
+    let mut cursor = node.walk();
+    if let Some(heritage) = node
+        .children(&mut cursor)
+        .find(|n| n.kind() == "class_heritage")
+    {
+        parse_class_heritage(&heritage, ctx, &mut children)?;
+    }
+
     parse_class_body(
         &node.child_by_field_name("body").unwrap(),
         ctx,
@@ -103,15 +130,62 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         ctx,
         SymbolKind::Class(Class {
             identifier,
-            documentation: find_docs(node, ctx.code).map(process_comment),
+            documentation,
+            doc_tags,
             children,
             exported: is_exported(node),
             is_abstract,
+            decorators: decorator::of(node, ctx.code),
+            signature: Some(dossier_core::helpers::signature(node, ctx.code)),
         }),
         Source::for_node(node, ctx),
     ))
 }
 
+/// `extends Base implements Comparable, Serializable`
+fn parse_class_heritage(
+    node: &Node,
+    ctx: &mut ParserContext,
+    children: &mut Vec<Symbol>,
+) -> Result<()> {
+    let mut cursor = node.walk();
+
+    for clause in node.named_children(&mut cursor) {
+        match clause.kind() {
+            // Classes extend values, not types, e.g. `extends mixin(Base)`. We
+            // keep the expression as written, which resolves if it's a name.
+            "extends_clause" => {
+                for value in clause.children_by_field_name("value", &mut clause.walk()) {
+                    let name = value
+                        .utf8_text(ctx.code.as_bytes())
+                        .unwrap()
+                        .chars()
+                        .filter(|c| !c.is_whitespace())
+                        .collect::<String>();
+
+                    let mut extends = Symbol::in_context(
+                        ctx,
+                        SymbolKind::Type(Type::Identifier(name, None)),
+                        Source::for_node(&value, ctx),
+                    );
+                    extends.context = Some(SymbolContext::Extends);
+                    children.push(extends);
+                }
+            }
+            "implements_clause" => {
+                for the_type in clause.named_children(&mut clause.walk()) {
+                    let mut implements = types::parse(&the_type, ctx)?;
+                    implements.context = Some(SymbolContext::Implements);
+                    children.push(implements);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 fn parse_class_body(
     node: &Node,
     ctx: &mut ParserContext,
@@ -122,13 +196,21 @@ fn parse_class_body(
     cursor.goto_first_child();
 
     loop {
-        if cursor.node().kind() == "method_definition" {
-            children.push(method::parse(&cursor.node(), ctx)?);
-        }
-        if cursor.node().kind() == "abstract_method_signature" {
+        if crate::has_syntax_error(&cursor.node()) {
+            crate::skip_member(&cursor.node(), ctx);
+        } else if cursor.node().kind() == "method_definition" {
+            let method = method::parse(&cursor.node(), ctx)?;
+            if method.kind.as_method().unwrap().identifier.as_str() == "constructor" {
+                children.extend(field::parse_parameter_properties(
+                    &cursor.node(),
+                    &method,
+                    ctx,
+                )?);
+            }
+            children.push(method);
+        } else if cursor.node().kind() == "abstract_method_signature" {
             children.push(method::parse(&cursor.node(), ctx)?);
-        }
-        if cursor.node().kind() == field::NODE_KIND {
+        } else if cursor.node().kind() == field::NODE_KIND {
             children.push(field::parse(&cursor.node(), ctx)?);
         }
 
@@ -140,24 +222,6 @@ fn parse_class_body(
     Ok(())
 }
 
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    let parent = node.parent().unwrap();
-
-    if parent.kind() == "export_statement" {
-        if let Some(maybe_comment) = parent.prev_sibling() {
-            if maybe_comment.kind() == "comment" {
-                return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-            }
-        }
-    } else if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
-
-    None
-}
-
 fn is_exported(node: &Node) -> bool {
     if let Some(parent) = node.parent() {
         if parent.kind() == "export_statement" {
@@ -171,6 +235,7 @@ fn is_exported(node: &Node) -> bool {
 mod test {
     use dossier_core::tree_sitter::Parser;
     use dossier_core::tree_sitter::TreeCursor;
+    use dossier_core::{test_utils, Identity};
     use indoc::indoc;
     use std::path::Path;
 
@@ -319,4 +384,279 @@ mod test {
             &crate::method::Identifier::Computed("SOME_IDENTIFIER".into())
         );
     }
+
+    #[test]
+    fn method_parameters_have_the_parameter_context() {
+        let code = indoc! {r#"
+        class Example {
+          resize(width: number, height?: number, ...rest: number[]): void {}
+        }
+        "#};
+
+        let tree = init_parser().parse(code, None).unwrap();
+        let mut cursor = tree.root_node().walk();
+        walk_tree_to_class(&mut cursor);
+
+        let symbol = parse(
+            &cursor.node(),
+            &mut ParserContext::new(Path::new("index.ts"), code),
+        )
+        .unwrap();
+
+        let method = symbol
+            .kind
+            .as_class()
+            .unwrap()
+            .children
+            .iter()
+            .find(|s| s.kind.as_method().is_some())
+            .unwrap();
+
+        let parameters = method
+            .kind
+            .as_method()
+            .unwrap()
+            .parameters()
+            .collect::<Vec<_>>();
+        assert_eq!(parameters.len(), 3);
+        assert!(parameters
+            .iter()
+            .all(|p| p.context == Some(SymbolContext::Parameter)));
+
+        let return_type = method.kind.as_method().unwrap().return_type().unwrap();
+        assert_eq!(return_type.context, Some(SymbolContext::ReturnType));
+    }
+
+    #[test]
+    fn heritage() {
+        let code = indoc! { r#"
+            class Circle extends Shapes.Base implements Comparable<Circle>, Serializable {}
+        "#};
+
+        let tree = init_parser().parse(code, None).unwrap();
+        let mut cursor = tree.root_node().walk();
+        walk_tree_to_class(&mut cursor);
+
+        let symbol = parse(
+            &cursor.node(),
+            &mut ParserContext::new(Path::new("index.ts"), code),
+        )
+        .unwrap();
+
+        let heritage = symbol
+            .kind
+            .as_class()
+            .unwrap()
+            .children
+            .iter()
+            .map(|s| (s.context.unwrap(), s.as_entity().title.unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            heritage,
+            [
+                (SymbolContext::Extends, "Shapes.Base".to_owned()),
+                (SymbolContext::Implements, "Comparable".to_owned()),
+                (SymbolContext::Implements, "Serializable".to_owned()),
+            ]
+        );
+    }
+
+    /// The entities of the methods of the first class in `code`
+    fn method_entities(code: &str) -> Vec<Entity> {
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "class_declaration");
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
+
+        symbol
+            .as_entity()
+            .members
+            .into_iter()
+            .filter(|m| m.kind == "method")
+            .collect()
+    }
+
+    #[test]
+    fn method_modifiers() {
+        let code = indoc! {r#"
+        class Repository extends Base {
+          protected static async connect(): Promise<void> {}
+          override find() {}
+          private remove() {}
+          get size(): number { return 0; }
+          set size(value: number) {}
+          get() {}
+        }
+        "#};
+
+        let methods = method_entities(code);
+        let meta = |index: usize| &methods[index].meta;
+
+        assert_eq!(methods[0].title.as_deref(), Some("connect"));
+        assert_eq!(meta(0)["protected"], true);
+        assert_eq!(meta(0)["static"], true);
+        assert_eq!(meta(0)["async"], true);
+        assert!(meta(0).get("override").is_none());
+
+        assert_eq!(methods[1].title.as_deref(), Some("find"));
+        assert_eq!(meta(1)["override"], true);
+        assert!(meta(1).get("static").is_none());
+
+        assert_eq!(meta(2)["private"], true);
+
+        assert_eq!(methods[3].title.as_deref(), Some("size"));
+        assert_eq!(meta(3)["accessor"], "get");
+        assert_eq!(methods[4].title.as_deref(), Some("size"));
+        assert_eq!(meta(4)["accessor"], "set");
+
+        // A method called `get` isn't a getter
+        assert_eq!(methods[5].title.as_deref(), Some("get"));
+        assert!(meta(5).get("accessor").is_none());
+    }
+
+    #[test]
+    fn async_and_generator_methods() {
+        let code = indoc! {r#"
+        class Repository {
+          async load(): Promise<Row[]> {}
+          *rows(): Generator<Row> {}
+          async *stream() {}
+          async() {}
+        }
+        "#};
+
+        let methods = method_entities(code);
+        let meta = |index: usize| &methods[index].meta;
+
+        assert_eq!(meta(0)["async"], true);
+        assert!(meta(0).get("generator").is_none());
+        assert_eq!(meta(0)["resolved_return_type"], "Row[]");
+
+        assert!(meta(1).get("async").is_none());
+        assert_eq!(meta(1)["generator"], true);
+        assert!(meta(1).get("resolved_return_type").is_none());
+
+        assert_eq!(meta(2)["async"], true);
+        assert_eq!(meta(2)["generator"], true);
+
+        // A method called `async` isn't async
+        assert_eq!(methods[3].title.as_deref(), Some("async"));
+        assert!(meta(3).get("async").is_none());
+    }
+
+    #[test]
+    fn decorators() {
+        let code = indoc! {r#"
+        /** The users in the database */
+        @Component({ selector: "app-users" })
+        @sealed
+        export class Users {
+          /** The user's name */
+          @Column({ nullable: true })
+          private name: string;
+
+          /** Finds a user */
+          @Get(":id")
+          @core.Trace()
+          find(@Param("id") id: string) {}
+
+          undecorated() {}
+        }
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "class_declaration");
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
+        let entity = symbol.as_entity();
+
+        assert_eq!(entity.description, "The users in the database");
+        assert_eq!(
+            entity.meta["decorators"],
+            json!([
+                { "name": "Component", "arguments": "{ selector: \"app-users\" }" },
+                { "name": "sealed" }
+            ])
+        );
+
+        let name = &entity.members[0];
+        assert_eq!(name.description, "The user's name");
+        assert_eq!(name.meta["private"], true);
+        assert_eq!(
+            name.meta["decorators"],
+            json!([{ "name": "Column", "arguments": "{ nullable: true }" }])
+        );
+
+        let find = &entity.members[1];
+        assert_eq!(find.description, "Finds a user");
+        assert_eq!(
+            find.meta["decorators"],
+            json!([
+                { "name": "Get", "arguments": "\":id\"" },
+                { "name": "core.Trace", "arguments": "" }
+            ])
+        );
+        assert_eq!(
+            find.members[0].meta["decorators"],
+            json!([{ "name": "Param", "arguments": "\"id\"" }])
+        );
+
+        assert!(entity.members[2].meta.get("decorators").is_none());
+    }
+
+    #[test]
+    fn parameter_properties() {
+        let code = indoc! {r#"
+        class Repository {
+          /**
+           * @param db The connection to query
+           */
+          constructor(private readonly db: Database, public name = "rows", limit: number) {}
+        }
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "class_declaration");
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
+        let entity = symbol.as_entity();
+
+        let fields = entity
+            .members
+            .iter()
+            .filter(|m| m.kind == "field")
+            .collect::<Vec<_>>();
+        assert_eq!(fields.len(), 2);
+
+        let db = fields[0];
+        assert_eq!(db.title.as_deref(), Some("db"));
+        assert_eq!(
+            db.identity,
+            Identity::FQN("index.ts::Repository::db".to_owned())
+        );
+        assert_eq!(db.description, "The connection to query");
+        assert_eq!(db.meta["private"], true);
+        assert_eq!(db.meta["readonly"], true);
+        assert_eq!(db.meta["parameter_property"], true);
+        assert_eq!(db.members[0].title.as_deref(), Some("Database"));
+
+        let name = fields[1];
+        assert_eq!(name.title.as_deref(), Some("name"));
+        assert_eq!(name.meta["value"], "\"rows\"");
+        assert!(name.meta.get("private").is_none());
+
+        let constructor = method_entities(code).remove(0);
+        let parameters = constructor
+            .members
+            .iter()
+            .filter(|m| m.kind == "parameter")
+            .collect::<Vec<_>>();
+        assert_eq!(parameters[0].title.as_deref(), Some("db"));
+        assert_eq!(
+            parameters[0].identity,
+            Identity::FQN("index.ts::Repository::constructor::db".to_owned())
+        );
+        assert_eq!(parameters[0].meta["parameter_property"], true);
+        assert_eq!(parameters[1].title.as_deref(), Some("name"));
+        assert_eq!(parameters[2].title.as_deref(), Some("limit"));
+        assert!(parameters[2].meta.get("parameter_property").is_none());
+    }
 }
@@ -0,0 +1,113 @@
+//! Decorators, like `@Injectable()` or `@Column({ nullable: true })`, on
+//! classes, their members, and parameters.
+//!
+//! Where the decorator nodes are depends on what they decorate: those of
+//! classes, fields and parameters are children of their declaration, or of
+//! the `export_statement` around it when written before `export`, while those
+//! of methods come before the method in the class body.
+use dossier_core::{
+    serde_json::{json, Value},
+    tree_sitter::Node,
+};
+
+pub(crate) const NODE_KIND: &str = "decorator";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decorator {
+    /// What the decorator is, as written, like `Column` or `core.Input`
+    pub name: String,
+    /// The arguments as written, without the parentheses, or `None` for
+    /// decorators that aren't called, like `@sealed`
+    pub arguments: Option<String>,
+}
+
+impl Decorator {
+    fn parse(node: &Node, code: &str) -> Self {
+        assert_eq!(node.kind(), NODE_KIND);
+        let text = |node: Node| node.utf8_text(code.as_bytes()).unwrap().to_owned();
+
+        match node.named_child(0) {
+            Some(call) if call.kind() == "call_expression" => Decorator {
+                name: call
+                    .child_by_field_name("function")
+                    .map(text)
+                    .unwrap_or_default(),
+                arguments: call.child_by_field_name("arguments").map(|arguments| {
+                    let arguments = text(arguments);
+                    arguments
+                        .strip_prefix('(')
+                        .and_then(|a| a.strip_suffix(')'))
+                        .unwrap_or(&arguments)
+                        .trim()
+                        .to_owned()
+                }),
+            },
+            Some(expression) => Decorator {
+                name: text(expression),
+                arguments: None,
+            },
+            None => Decorator {
+                name: text(*node).trim_start_matches('@').to_owned(),
+                arguments: None,
+            },
+        }
+    }
+
+    fn as_json(&self) -> Value {
+        let mut out = json!({ "name": self.name });
+        if let Some(arguments) = &self.arguments {
+            out["arguments"] = arguments.as_str().into();
+        }
+        out
+    }
+}
+
+/// The decorators of the declaration `node`, in source order
+pub(crate) fn of(node: &Node, code: &str) -> Vec<Decorator> {
+    let mut nodes = vec![];
+
+    if let Some(parent) = node.parent().filter(|p| p.kind() == "export_statement") {
+        let mut cursor = parent.walk();
+        nodes.extend(
+            parent
+                .children(&mut cursor)
+                .filter(|child| child.kind() == NODE_KIND),
+        );
+    }
+
+    let mut before = vec![];
+    let mut sibling = node.prev_sibling();
+    while let Some(decorator) = sibling.filter(|s| s.kind() == NODE_KIND) {
+        before.push(decorator);
+        sibling = decorator.prev_sibling();
+    }
+    nodes.extend(before.into_iter().rev());
+
+    let mut cursor = node.walk();
+    nodes.extend(
+        node.children(&mut cursor)
+            .filter(|child| child.kind() == NODE_KIND),
+    );
+
+    nodes
+        .iter()
+        .map(|decorator| Decorator::parse(decorator, code))
+        .collect()
+}
+
+/// The first of the decorators before `node`, or `node` itself when there
+/// are none, which is where the doc comment of a decorated method ends
+pub(crate) fn first_before<'a>(node: &Node<'a>) -> Node<'a> {
+    let mut first = *node;
+    while let Some(decorator) = first.prev_sibling().filter(|s| s.kind() == NODE_KIND) {
+        first = decorator;
+    }
+    first
+}
+
+/// Adds the decorators as `meta.decorators`, if there are any
+pub(crate) fn add_to_meta(decorators: &[Decorator], meta: &mut Value) {
+    if !decorators.is_empty() {
+        meta["decorators"] = decorators.iter().map(Decorator::as_json).collect();
+    }
+}
@@ -0,0 +1,218 @@
+//! Turns `{@link Target}` references in documentation comments into Markdown
+//! links to the entities they refer to, see `dossier_core::fqn_link`.
+//!
+//! Targets are resolved like type names: from the scope of the documented
+//! declaration in its own file first, and then through the file's imports.
+//! `{@link Target | label}` and `{@link Target label}` are labeled, and
+//! `Class#member` is the same as `Class.member`. Links to URLs become plain
+//! Markdown links. Targets that don't resolve are left as text and reported.
+use dossier_core::{fqn_link, Diagnostic};
+
+use crate::resolver::{self, NameResolver};
+use crate::symbol::{self, Symbol, SymbolID, SymbolIterator};
+use crate::symbol_table::{SymbolTable, TableIndex};
+
+/// Reported for `{@link}` references to names that don't resolve
+pub(crate) const UNRESOLVED_LINK: &str = "unresolved-link";
+
+/// Replaces the `{@link}` references in the documentation of the symbols in
+/// all of the tables. Runs after types are resolved.
+pub(crate) fn link_references(tables: &mut [SymbolTable]) {
+    let linked = {
+        let index = TableIndex::new(tables.iter());
+        tables
+            .iter()
+            .map(|table| linked_documentation(table, &index, resolver::DEFAULT))
+            .collect::<Vec<_>>()
+    };
+
+    for (table, (documentation, mut diagnostics)) in tables.iter_mut().zip(linked) {
+        for (id, linked) in documentation {
+            if let Some(documentation) = table.symbol_mut(id).and_then(|s| s.documentation_mut()) {
+                *documentation = Some(linked);
+            }
+        }
+        table.diagnostics.append(&mut diagnostics);
+    }
+}
+
+/// The documentation of each symbol in `table` that has links in it, with
+/// the links replaced, and the warnings about the ones that didn't resolve.
+fn linked_documentation(
+    table: &SymbolTable,
+    index: &TableIndex,
+    resolver: &dyn NameResolver,
+) -> (Vec<(SymbolID, String)>, Vec<Diagnostic>) {
+    let mut documentation = vec![];
+    let mut diagnostics = vec![];
+
+    for top_level in table.all_symbols() {
+        for symbol in SymbolIterator::new(std::slice::from_ref(top_level)) {
+            let Some(text) = symbol.documentation().filter(|d| d.contains("{@link")) else {
+                continue;
+            };
+
+            let (linked, unresolved) =
+                replace_links(text, |path| resolve(table, index, resolver, symbol, path));
+
+            for target in unresolved {
+                let name = symbol.fqn.as_deref().or(symbol.identifier()).unwrap_or("?");
+                diagnostics.push(
+                    Diagnostic::warning(
+                        UNRESOLVED_LINK,
+                        format!(
+                            "`{{@link {}}}` in the documentation of `{}` doesn't refer to anything",
+                            target, name
+                        ),
+                        &table.file,
+                    )
+                    .with_range(symbol.source.start.byte_offset..symbol.source.end.byte_offset),
+                );
+            }
+
+            documentation.push((symbol.id, linked));
+        }
+    }
+
+    (documentation, diagnostics)
+}
+
+/// The FQN of what the dotted `path` refers to, as seen from `symbol`
+fn resolve(
+    table: &SymbolTable,
+    index: &TableIndex,
+    resolver: &dyn NameResolver,
+    symbol: &Symbol,
+    path: &str,
+) -> Option<String> {
+    // A declaration may link to itself
+    if let Some(fqn) =
+        resolver.resolve_local(table, path, symbol.scope_id, symbol::UNUSED_SYMBOL_ID)
+    {
+        return Some(fqn);
+    }
+
+    let imported_name = path.split('.').next().unwrap_or_default();
+    let import = table.lookup_import(imported_name, symbol.scope_id)?;
+    let path = import.imported_path(path);
+
+    resolver
+        .resolve_import(table, import, &path, index)
+        .or_else(|| table.external_fqn(index, import, &path))
+}
+
+/// Replaces the `{@link}`, `{@linkcode}` and `{@linkplain}` references in
+/// `documentation`, resolving their targets with `resolve`. Returns the new
+/// documentation and the targets that didn't resolve.
+fn replace_links(
+    documentation: &str,
+    mut resolve: impl FnMut(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let mut linked = String::with_capacity(documentation.len());
+    let mut unresolved = vec![];
+    let mut rest = documentation;
+
+    while let Some(start) = rest.find("{@link") {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let end = start + length + 1;
+
+        // Between `{@` and `}`, e.g. `link Client | the client`
+        let tag = &rest[start + 2..end - 1];
+        let (name, content) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+
+        if matches!(name, "link" | "linkcode" | "linkplain") {
+            linked.push_str(&rest[..start]);
+            linked.push_str(&replace_link(
+                content,
+                name == "linkcode",
+                &mut resolve,
+                &mut unresolved,
+            ));
+        } else {
+            linked.push_str(&rest[..end]);
+        }
+
+        rest = &rest[end..];
+    }
+    linked.push_str(rest);
+
+    (linked, unresolved)
+}
+
+/// The Markdown for a single link, given what follows its tag
+fn replace_link(
+    content: &str,
+    code: bool,
+    resolve: &mut impl FnMut(&str) -> Option<String>,
+    unresolved: &mut Vec<String>,
+) -> String {
+    let content = content.trim();
+    let (target, label) = match content.split_once('|') {
+        Some((target, label)) => (target.trim(), label.trim()),
+        None => content
+            .split_once(char::is_whitespace)
+            .map(|(target, label)| (target, label.trim()))
+            .unwrap_or((content, "")),
+    };
+
+    let label = if label.is_empty() { target } else { label };
+    let label = match code {
+        true => format!("`{}`", label),
+        false => label.to_owned(),
+    };
+
+    if target.is_empty() {
+        return label;
+    }
+
+    if target.contains("://") {
+        return format!("[{}]({})", label, target);
+    }
+
+    let path = target.trim_end_matches("()").replace('#', ".");
+    match resolve(&path) {
+        Some(fqn) => fqn_link::link(&label, &fqn),
+        None => {
+            unresolved.push(target.to_owned());
+            label
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn replace(documentation: &str) -> (String, Vec<String>) {
+        replace_links(documentation, |path| match path {
+            "Client" | "Client.connect" => Some(format!("index.ts::{}", path.replace('.', "::"))),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn replaces_each_form_of_link() {
+        let (linked, unresolved) = replace(
+            "Use {@link Client}, {@linkcode Client#connect() | connect} or \
+             {@link https://example.com the docs}. Not {@literal this}.",
+        );
+
+        assert_eq!(
+            linked,
+            "Use [Client](#fqn:index.ts::Client), \
+             [`connect`](#fqn:index.ts::Client::connect) or \
+             [the docs](https://example.com). Not {@literal this}."
+        );
+        assert!(unresolved.is_empty());
+    }
+
+    #[test]
+    fn leaves_unresolved_links_as_text() {
+        let (linked, unresolved) = replace("See {@link Missing | the missing one}.");
+
+        assert_eq!(linked, "See the missing one.");
+        assert_eq!(unresolved, vec!["Missing".to_owned()]);
+    }
+}
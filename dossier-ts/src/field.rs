@@ -1,13 +1,16 @@
 use crate::{
+    decorator::{self, Decorator},
     helpers::*,
+    parameter::{self, ParameterProperty},
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    tsdoc::{self, DocTags},
     types, ParserContext,
 };
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Field {
+pub struct Field {
     pub identifier: String,
     /// Technically will ever only have max one child, the value itself, but other
     /// parts of the program will expect a slice of children so this is simpler.
@@ -15,7 +18,9 @@ pub(crate) struct Field {
     pub readonly: bool,
     pub private: bool,
     pub protected: bool,
+    pub is_static: bool,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
 
     /// For now, we're going to just parse a value as a string literal.
     /// This is because it's essentially arbitrary code, and we don't want to
@@ -24,6 +29,10 @@ pub(crate) struct Field {
     /// We may want to parse out the simple cases like string and number
     /// constants in the future, but for now we'll just leave it as a string.
     pub value: Option<String>,
+    /// Declared by a constructor parameter, like
+    /// `constructor(private readonly db: Database)`
+    pub parameter_property: bool,
+    pub decorators: Vec<Decorator>,
 }
 
 impl Field {
@@ -43,16 +52,24 @@ impl Field {
         if self.private {
             meta["private"] = true.into();
         }
+        if self.is_static {
+            meta["static"] = true.into();
+        }
         if let Some(value) = &self.value {
             meta["value"] = json!(value);
         }
+        if self.parameter_property {
+            meta["parameter_property"] = true.into();
+        }
+        decorator::add_to_meta(&self.decorators, &mut meta);
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "field".to_owned(),
             identity: Identity::FQN(fqn.expect("Field did not have FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: "ts".to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -63,8 +80,10 @@ impl Field {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Field {
     pub fn the_type(&self) -> Option<&Symbol> {
         self.children.iter().find(|s| s.kind.as_type().is_some())
     }
@@ -84,7 +103,7 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
 
     cursor.goto_first_child();
 
-    while !cursor.node().is_named() {
+    while !cursor.node().is_named() || cursor.node().kind() == decorator::NODE_KIND {
         cursor.goto_next_sibling();
     }
 
@@ -128,29 +147,104 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         );
     }
 
-    let documentation = find_docs(node, ctx.code).map(process_comment);
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
 
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::Field(Field {
             identifier,
             children,
-            readonly: is_readonly(node),
+            readonly: has_modifier(node, "readonly"),
             documentation,
+            doc_tags,
             private,
             protected,
+            is_static: has_modifier(node, "static"),
             value,
+            parameter_property: false,
+            decorators: decorator::of(node, ctx.code),
         }),
         Source::for_node(node, ctx),
     ))
 }
 
-fn is_readonly(field_node: &Node) -> bool {
+/// The fields declared by the parameters of `constructor`, a method
+/// definition, like `db` in `constructor(private readonly db: Database)`.
+/// They have the `@param` documentation of their parameter in `method`, the
+/// constructor parsed. Expects the FQN of the class to be pushed.
+pub(crate) fn parse_parameter_properties(
+    constructor: &Node,
+    method: &Symbol,
+    ctx: &mut ParserContext,
+) -> Result<Vec<Symbol>> {
+    let mut out = vec![];
+    let Some(parameters) = constructor.child_by_field_name("parameters") else {
+        return Ok(out);
+    };
+
+    let mut cursor = parameters.walk();
+    for node in parameters.named_children(&mut cursor) {
+        if !parameter::NODE_KINDS.contains(&node.kind()) {
+            continue;
+        }
+        let Some(property) = ParameterProperty::of(&node, ctx.code) else {
+            continue;
+        };
+
+        let identifier = node
+            .child_by_field_name("pattern")
+            .unwrap()
+            .utf8_text(ctx.code.as_bytes())
+            .unwrap()
+            .to_owned();
+
+        let mut children = vec![];
+        if let Some(type_node) = node.child_by_field_name("type") {
+            let mut tmp = type_node.walk();
+            tmp.goto_first_child();
+            tmp.goto_next_sibling();
+
+            children.push(types::parse(&tmp.node(), ctx)?);
+        }
+
+        let documentation = method.kind.as_method().and_then(|m| {
+            m.parameters()
+                .filter_map(|p| p.kind.as_parameter())
+                .find(|p| p.identifier == identifier)
+                .and_then(|p| p.documentation.clone())
+        });
+
+        out.push(Symbol::in_context(
+            ctx,
+            SymbolKind::Field(Field {
+                identifier,
+                children,
+                readonly: property.readonly,
+                private: property.private,
+                protected: property.protected,
+                is_static: false,
+                documentation,
+                doc_tags: Default::default(),
+                value: node
+                    .child_by_field_name("value")
+                    .map(|value| value.utf8_text(ctx.code.as_bytes()).unwrap().to_owned()),
+                parameter_property: true,
+                decorators: decorator::of(&node, ctx.code),
+            }),
+            Source::for_node(&node, ctx),
+        ));
+    }
+
+    Ok(out)
+}
+
+/// Whether the field is declared with the keyword `modifier`, like `static`
+fn has_modifier(field_node: &Node, modifier: &str) -> bool {
     let mut cursor = field_node.walk();
 
     cursor.goto_first_child();
     loop {
-        if cursor.node().kind() == "readonly" {
+        if cursor.node().kind() == modifier {
             return true;
         }
         if !cursor.goto_next_sibling() {
@@ -159,16 +253,6 @@ fn is_readonly(field_node: &Node) -> bool {
     }
 }
 
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
-
-    None
-}
-
 #[cfg(test)]
 mod test {
     /// NOTE ABOUT THESE TESTS
@@ -1,37 +1,60 @@
 use dossier_core::serde_json::json;
 use dossier_core::tree_sitter::{Node, Query, QueryCursor};
-use dossier_core::{helpers::*, Entity, Identity, Result};
+use dossier_core::{helpers::*, Entity, Identity, MemberContext, Result};
 use indoc::indoc;
 use lazy_static::lazy_static;
 
-use crate::{helpers::*, parameter, type_variable};
+use crate::callable::{self, Signature};
+use crate::tsdoc::DocTags;
 use crate::{
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
     types, ParserContext,
 };
 
 const QUERY_STRING: &str = indoc! {"
-    (function_declaration 
-          name: (identifier) @function_name
-          type_parameters: (type_parameters) ? @function_type_parameters
-          parameters: (formal_parameters) @function_parameters
-          return_type: (type_annotation) ? @function_return_type
-    ) @function
+    [
+        (function_declaration 
+              name: (identifier) @function_name
+              type_parameters: (type_parameters) ? @function_type_parameters
+              parameters: (formal_parameters) @function_parameters
+              return_type: (type_annotation) ? @function_return_type
+        ) @function
+        (generator_function_declaration 
+              name: (identifier) @function_name
+              type_parameters: (type_parameters) ? @function_type_parameters
+              parameters: (formal_parameters) @function_parameters
+              return_type: (type_annotation) ? @function_return_type
+        ) @function
+    ]
     "};
 
 lazy_static! {
     static ref QUERY: Query =
         Query::new(tree_sitter_typescript::language_typescript(), QUERY_STRING).unwrap();
+    static ref TSX_QUERY: Query =
+        Query::new(tree_sitter_typescript::language_tsx(), QUERY_STRING).unwrap();
 }
 
 pub(crate) const NODE_KIND: &str = "function_declaration";
+/// `function* gen() {}`
+pub(crate) const GENERATOR_NODE_KIND: &str = "generator_function_declaration";
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Function {
+pub struct Function {
     pub identifier: String,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
     pub is_exported: bool,
+    pub is_async: bool,
+    /// Declared with `function*`
+    pub is_generator: bool,
     pub children: Vec<Symbol>,
+    /// What an `async` function's `Promise<T>` return type resolves to, `T`,
+    /// see `callable::awaited_type`
+    pub awaited_type: Option<String>,
+    /// The declaration without its body, on one line, see
+    /// `dossier_core::helpers::signature`. Not set for minified files.
+    pub signature: Option<String>,
 }
 
 impl Function {
@@ -45,13 +68,29 @@ impl Function {
         if self.is_exported {
             meta["exported"] = true.into();
         }
+        if self.is_async {
+            meta["async"] = true.into();
+        }
+        if self.is_generator {
+            meta["generator"] = true.into();
+        }
+        if let Some(awaited_type) = &self.awaited_type {
+            meta["resolved_return_type"] = awaited_type.as_str().into();
+        }
+        if types::returns_this(self.return_type()) {
+            meta["chainable"] = true.into();
+        }
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "function".to_owned(),
             identity: Identity::FQN(fqn.expect("Function did not have FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: "ts".to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -62,22 +101,22 @@ impl Function {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Function {
     pub fn parameters(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_parameter().is_some())
     }
 
-    #[cfg(test)]
     pub fn type_variables(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_type_variable().is_some())
     }
 
-    #[cfg(test)]
     pub fn return_type(&self) -> Option<&Symbol> {
         self.children
             .iter()
@@ -86,143 +125,56 @@ impl Function {
 }
 
 pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
-    assert_eq!(node.kind(), NODE_KIND);
-
-    let mut children = vec![];
+    assert!(matches!(node.kind(), NODE_KIND | GENERATOR_NODE_KIND));
 
+    let query = crate::helpers::query_for(node, &QUERY, &TSX_QUERY);
     let mut cursor = QueryCursor::new();
     let function = cursor
-        .matches(&QUERY, *node, ctx.code.as_bytes())
+        .matches(query, *node, ctx.code.as_bytes())
         .next()
         .unwrap();
 
-    let main_node = node_for_capture("function", function.captures, &QUERY).unwrap();
-    let name_node = node_for_capture("function_name", function.captures, &QUERY).unwrap();
-    let type_param_node = node_for_capture("function_type_parameters", function.captures, &QUERY);
-    let parameters_node = node_for_capture("function_parameters", function.captures, &QUERY);
-    let return_type_node = node_for_capture("function_return_type", function.captures, &QUERY);
+    let main_node = node_for_capture("function", function.captures, query).unwrap();
+    let name_node = node_for_capture("function_name", function.captures, query).unwrap();
+    let type_param_node = node_for_capture("function_type_parameters", function.captures, query);
+    let parameters_node = node_for_capture("function_parameters", function.captures, query);
+    let return_type_node = node_for_capture("function_return_type", function.captures, query);
 
     let identifier = name_node.utf8_text(ctx.code.as_bytes()).unwrap().to_owned();
-
-    ctx.push_scope();
-    ctx.push_fqn(&identifier);
-
-    if let Some(type_parameters) = type_param_node {
-        parse_type_parameters(&type_parameters, &mut children, ctx);
-        ctx.push_scope();
-    }
-
-    if let Some(parameter_nodes) = parameters_node {
-        parse_parameters(&parameter_nodes, &mut children, ctx)?;
-    }
-
-    if let Some(type_node) = return_type_node {
-        parse_return_type(&type_node, &mut children, ctx)?;
-    }
-
-    let docs = find_docs(&main_node, ctx.code);
-
-    if type_param_node.is_some() {
-        ctx.pop_scope();
-    }
-    ctx.pop_scope();
-    ctx.pop_fqn();
+    let is_async = callable::is_async(&main_node, &name_node);
+
+    let Signature {
+        children,
+        documentation,
+        doc_tags,
+    } = callable::parse_signature(
+        &identifier,
+        &main_node,
+        type_param_node,
+        parameters_node,
+        return_type_node,
+        ctx,
+    )?;
 
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::Function(Function {
             identifier,
-            documentation: docs.map(process_comment),
+            documentation,
+            doc_tags,
             is_exported: is_exported(&main_node),
+            is_async,
+            is_generator: main_node.kind() == GENERATOR_NODE_KIND,
             children,
+            awaited_type: is_async
+                .then(|| callable::awaited_type(return_type_node, ctx.code))
+                .flatten(),
+            signature: Some(signature(&main_node, ctx.code)),
         }),
         Source::for_node(&main_node, ctx),
     ))
 }
 
-pub(crate) fn parse_return_type(
-    node: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) -> Result<()> {
-    let mut type_node_cursor = node.walk();
-    type_node_cursor.goto_first_child();
-    while !type_node_cursor.node().is_named() {
-        type_node_cursor.goto_next_sibling();
-    }
-    let mut the_type = types::parse(&type_node_cursor.node(), ctx).unwrap();
-    the_type.context = Some(SymbolContext::ReturnType);
-    children.push(the_type);
-    Ok(())
-}
-
-pub(crate) fn parse_parameters(
-    parameters: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) -> Result<()> {
-    assert_eq!(parameters.kind(), "formal_parameters");
-
-    let mut cursor = parameters.walk();
-    cursor.goto_first_child();
-
-    loop {
-        if cursor.node().kind() == "required_parameter"
-            || cursor.node().kind() == "optional_parameter"
-        {
-            let mut parameter = parameter::parse(&cursor.node(), ctx)?;
-            parameter.context = Some(SymbolContext::Parameter);
-            children.push(parameter);
-        }
-
-        if !cursor.goto_next_sibling() {
-            break;
-        }
-    }
-
-    Ok(())
-}
-
-pub(crate) fn parse_type_parameters(
-    type_parameters: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) {
-    assert_eq!(type_parameters.kind(), "type_parameters");
-
-    let mut cursor = type_parameters.walk();
-    cursor.goto_first_child();
-
-    loop {
-        if cursor.node().kind() == "type_parameter" {
-            let type_variable = type_variable::parse(&cursor.node(), ctx).unwrap();
-            children.push(type_variable);
-        }
-
-        if !cursor.goto_next_sibling() {
-            break;
-        }
-    }
-}
-
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    let parent = node.parent().unwrap();
-
-    if parent.kind() == "export_statement" {
-        if let Some(maybe_comment) = parent.prev_sibling() {
-            if maybe_comment.kind() == "comment" {
-                return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-            }
-        }
-    } else if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
-
-    None
-}
-
 fn is_exported(node: &Node) -> bool {
     if let Some(parent) = node.parent() {
         if parent.kind() == "export_statement" {
@@ -237,40 +189,19 @@ mod test {
     use crate::types::Type;
 
     use super::*;
-    use dossier_core::tree_sitter::Parser;
-    use dossier_core::tree_sitter::TreeCursor;
+    use dossier_core::test_utils;
     use std::path::Path;
 
-    fn init_parser() -> Parser {
-        let mut parser = Parser::new();
-        parser
-            .set_language(tree_sitter_typescript::language_typescript())
-            .expect("Error loading TypeScript grammar");
-
-        parser
-    }
-
-    fn walk_tree_to_function(cursor: &mut TreeCursor) {
-        assert_eq!(cursor.node().kind(), "program");
-        cursor.goto_first_child();
-        assert_eq!(cursor.node().kind(), "function_declaration");
-    }
-
     #[test]
     fn fqns() {
         let code = indoc! {r#"
         function foo<Bar extends Baz>() {}
         "#};
 
-        let tree = init_parser().parse(code, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        walk_tree_to_function(&mut cursor);
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
 
-        let symbol = parse(
-            &cursor.node(),
-            &mut ParserContext::new(Path::new("index.ts"), code),
-        )
-        .unwrap();
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
 
         assert_eq!(symbol.fqn.unwrap(), "index.ts::foo");
 
@@ -290,15 +221,10 @@ mod test {
         function foo(bar: string, baz, fizz?) {}
         "#};
 
-        let tree = init_parser().parse(code, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        walk_tree_to_function(&mut cursor);
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
 
-        let symbol = parse(
-            &cursor.node(),
-            &mut ParserContext::new(Path::new("index.ts"), code),
-        )
-        .unwrap();
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
 
         let function = symbol.kind.as_function().unwrap();
 
@@ -328,21 +254,62 @@ mod test {
         assert_eq!(fizz.parameter_type(), None);
     }
 
+    #[test]
+    fn default_and_rest_parameters() {
+        let code = indoc! {r#"
+        function foo(bar: string, limit: number = 10, label = "x", ...rest: string[]) {}
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
+
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
+
+        let function = symbol.kind.as_function().unwrap();
+        let params = function
+            .parameters()
+            .map(|p| p.kind.as_parameter().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(params.len(), 4);
+
+        assert_eq!(params[0].default, None);
+        assert!(!params[0].rest);
+
+        assert_eq!(params[1].identifier, "limit");
+        assert_eq!(params[1].default.as_deref(), Some("10"));
+        assert_eq!(
+            params[1].parameter_type().unwrap().kind.as_type().unwrap(),
+            &Type::Predefined("number".to_owned())
+        );
+
+        assert_eq!(params[2].default.as_deref(), Some("\"x\""));
+        assert_eq!(params[2].parameter_type(), None);
+
+        assert_eq!(params[3].identifier, "rest");
+        assert!(params[3].rest);
+        assert_eq!(params[3].default, None);
+
+        let entity = symbol.as_entity();
+        let meta = entity
+            .members
+            .iter()
+            .map(|m| m.meta.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(meta[1]["default"], "10");
+        assert_eq!(meta[3]["rest"], true);
+        assert!(meta[0].get("rest").is_none());
+    }
+
     #[test]
     fn readonly_parameter() {
         let code = indoc! {r#"
         function foo(bar: readonly string) {}
         "#};
 
-        let tree = init_parser().parse(code, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        walk_tree_to_function(&mut cursor);
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
 
-        let symbol = parse(
-            &cursor.node(),
-            &mut ParserContext::new(Path::new("index.ts"), code),
-        )
-        .unwrap();
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
 
         let function = symbol.kind.as_function().unwrap();
 
@@ -365,15 +332,10 @@ mod test {
         function identity<Type>(arg: Type): Type {}
         "#};
 
-        let tree = init_parser().parse(code, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        walk_tree_to_function(&mut cursor);
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
 
-        let symbol = parse(
-            &cursor.node(),
-            &mut ParserContext::new(Path::new("index.ts"), code),
-        )
-        .unwrap();
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
 
         let function = symbol.kind.as_function().unwrap();
 
@@ -402,15 +364,10 @@ mod test {
         function identity<Type extends SomeOtherType>(arg: Type): Type {}
         "#};
 
-        let tree = init_parser().parse(code, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        walk_tree_to_function(&mut cursor);
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
 
-        let symbol = parse(
-            &cursor.node(),
-            &mut ParserContext::new(Path::new("index.ts"), code),
-        )
-        .unwrap();
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
 
         let function = symbol.kind.as_function().unwrap();
         assert_eq!(function.identifier, "identity");
@@ -458,15 +415,10 @@ mod test {
         function example<A extends keyof B>() {}
         "#};
 
-        let tree = init_parser().parse(code, None).unwrap();
-        let mut cursor = tree.root_node().walk();
-        walk_tree_to_function(&mut cursor);
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
 
-        let symbol = parse(
-            &cursor.node(),
-            &mut ParserContext::new(Path::new("index.ts"), code),
-        )
-        .unwrap();
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
 
         let function = symbol.kind.as_function().unwrap();
         assert_eq!(function.identifier, "example");
@@ -490,4 +442,198 @@ mod test {
         let type_kind = constraint_kind.the_type().kind.as_type().unwrap();
         assert!(matches!(type_kind, &Type::KeyOf(_)));
     }
+
+    #[test]
+    fn tsdoc_tags() {
+        let code = indoc! {r#"
+        /**
+         * Finds a user.
+         *
+         * @param id The ID of the user
+         * @param {boolean} [includeDeleted] - Whether to look at deleted users
+         * @returns The user, if found
+         * @deprecated Use `findUsers` instead
+         * @example
+         * const user = findUser("123");
+         * @internal
+         */
+        function findUser(id: string, includeDeleted?: boolean): User | undefined {}
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
+
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
+
+        let entity = symbol.as_entity();
+
+        assert_eq!(entity.description, "Finds a user.");
+        assert_eq!(entity.meta["returns"], "The user, if found");
+        assert_eq!(entity.meta["deprecated"], true);
+        assert_eq!(
+            entity.meta["deprecation_message"],
+            "Use `findUsers` instead"
+        );
+        assert_eq!(
+            entity.meta["examples"],
+            json!(["const user = findUser(\"123\");"])
+        );
+        assert_eq!(
+            entity.meta["tags"],
+            json!([{ "tag": "internal", "text": "" }])
+        );
+
+        let parameters = entity
+            .members
+            .iter()
+            .filter(|m| m.kind == "parameter")
+            .map(|m| (m.title.as_deref().unwrap(), m.description.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            parameters,
+            vec![
+                ("id", "The ID of the user"),
+                ("includeDeleted", "Whether to look at deleted users")
+            ]
+        );
+    }
+
+    fn parameter_fields(parameter: &Entity) -> Vec<(&str, &str)> {
+        parameter.members[0]
+            .members
+            .iter()
+            .map(|m| (m.title.as_deref().unwrap(), m.description.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn dotted_param_tags_document_inline_object_fields() {
+        let code = indoc! {r#"
+        /**
+         * @param options How to fetch
+         * @param options.retries How many times to retry
+         * @param options.timeout Milliseconds to wait
+         * @param options.cache Not a field
+         */
+        function fetch(options: { retries: number; timeout?: number }) {}
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
+
+        let mut ctx = ParserContext::new(Path::new("index.ts"), code);
+        let entity = parse(&node, &mut ctx).unwrap().as_entity();
+
+        let options = &entity.members[0];
+        assert_eq!(options.description, "How to fetch");
+        assert_eq!(
+            parameter_fields(options),
+            vec![
+                ("retries", "How many times to retry"),
+                ("timeout", "Milliseconds to wait")
+            ]
+        );
+
+        assert_eq!(
+            entity.meta["tags"],
+            json!([{ "tag": "param", "text": "options.cache Not a field" }])
+        );
+
+        let diagnostics = ctx.take_symbol_table().diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, crate::tsdoc::UNMATCHED_PARAM_TAG);
+        assert_eq!(
+            diagnostics[0].message,
+            "`@param options.cache` does not match a parameter field"
+        );
+    }
+
+    #[test]
+    fn dotted_param_tags_document_destructured_fields() {
+        let code = indoc! {r#"
+        /**
+         * @param options How to connect
+         * @param options.host The host name
+         * @param options.port The port, 80 by default
+         * @param verbose Whether to log
+         */
+        function connect({ host, port = 80, ...rest }, verbose) {}
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
+
+        let mut ctx = ParserContext::new(Path::new("index.ts"), code);
+        let entity = parse(&node, &mut ctx).unwrap().as_entity();
+
+        let options = &entity.members[0];
+        assert_eq!(options.description, "How to connect");
+        assert_eq!(
+            parameter_fields(options),
+            vec![
+                ("host", "The host name"),
+                ("port", "The port, 80 by default")
+            ]
+        );
+        assert_eq!(options.members[0].members[1].meta["optional"], true);
+        assert_eq!(entity.members[1].description, "Whether to log");
+
+        assert!(ctx.take_symbol_table().diagnostics.is_empty());
+    }
+
+    #[test]
+    fn async_and_generator_functions() {
+        let code = indoc! {r#"
+        async function load(): Promise<Map<string, number>> {}
+        function* ids(): Generator<number> {}
+        async function* pages() {}
+        function plain(): Promise<void> {}
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let mut cursor = tree.root_node().walk();
+        let entities = tree
+            .root_node()
+            .children(&mut cursor)
+            .map(|node| {
+                parse(&node, &mut ParserContext::new(Path::new("index.ts"), code))
+                    .unwrap()
+                    .as_entity()
+            })
+            .collect::<Vec<_>>();
+        let meta = |index: usize| &entities[index].meta;
+
+        assert_eq!(entities[0].title.as_deref(), Some("load"));
+        assert_eq!(meta(0)["async"], true);
+        assert!(meta(0).get("generator").is_none());
+        assert_eq!(meta(0)["resolved_return_type"], "Map<string, number>");
+
+        assert_eq!(entities[1].title.as_deref(), Some("ids"));
+        assert!(meta(1).get("async").is_none());
+        assert_eq!(meta(1)["generator"], true);
+
+        assert_eq!(entities[2].title.as_deref(), Some("pages"));
+        assert_eq!(meta(2)["async"], true);
+        assert_eq!(meta(2)["generator"], true);
+        assert!(meta(2).get("resolved_return_type").is_none());
+
+        // Only async functions get their `Promise` unwrapped
+        assert!(meta(3).get("async").is_none());
+        assert!(meta(3).get("resolved_return_type").is_none());
+    }
+
+    #[test]
+    fn serializes_functions() {
+        let code = indoc! {r#"
+        /** Returns what it is given. */
+        function identity<Type extends object>(arg: Type, ...rest: string[]): Type {}
+        "#};
+
+        let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+        let node = test_utils::first_node(&tree, "function_declaration");
+        let symbol = parse(&node, &mut ParserContext::new(Path::new("index.ts"), code)).unwrap();
+
+        dossier_core::assert_json_snapshot!("function.json", symbol.as_entity());
+    }
 }
@@ -1,13 +1,344 @@
-/// Parses the comment and returns it without the comment delimiters.
+use dossier_core::{
+    helpers::{dedent, normalize_line_endings},
+    tree_sitter::{Node, Query},
+};
+
+use crate::decorator;
+
+/// Line comments that configure tools rather than document anything, like
+/// `// eslint-disable-next-line`. They are left out of blocks of line comments.
+const DIRECTIVES: &[&str] = &["eslint-", "prettier-ignore", "@ts-", "tslint:", "istanbul "];
+
+/// `query` for nodes of TypeScript files, `tsx_query` for nodes of TSX files.
+/// The two grammars number their node kinds differently, so a query only
+/// matches nodes of the grammar it was made with.
+pub(crate) fn query_for<'q>(node: &Node, query: &'q Query, tsx_query: &'q Query) -> &'q Query {
+    if node.language() == tree_sitter_typescript::language_tsx() {
+        tsx_query
+    } else {
+        query
+    }
+}
+
+/// Parses the comment and returns it without the comment delimiters, or the
+/// `*` at the start of each line. Lines after the first are dedented by the
+/// indentation they have in common, so that code in the comment keeps its
+/// indentation relative to the text around it.
 pub(crate) fn process_comment(comment: &str) -> String {
-    let mut tmp = comment.trim().to_owned();
-    tmp = tmp.trim_start_matches("/**").to_owned();
-    tmp = tmp.trim_end_matches("*/").to_owned();
+    let comment = normalize_line_endings(comment.trim());
+    let body = comment.trim_start_matches("/**").trim_end_matches("*/");
+
+    let mut lines = body.lines();
+    let first = lines.next().unwrap_or_default().trim();
 
-    tmp.lines()
-        .map(|l| l.trim().trim_start_matches("* ").trim_start_matches('*'))
+    let mut in_fence = false;
+    let rest = lines
+        .map(|line| {
+            let line = match line.trim_start().strip_prefix('*') {
+                // A tab after the `*` is indentation in fenced code blocks
+                Some(after) if in_fence => after.strip_prefix(' ').unwrap_or(after),
+                Some(after) => after.strip_prefix([' ', '\t']).unwrap_or(after),
+                None => line,
+            };
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+            }
+            line.trim_end()
+        })
+        .collect::<Vec<_>>();
+
+    std::iter::once(first)
+        .chain(dedent(&rest))
         .collect::<Vec<_>>()
         .join("\n")
-        .trim()
+        .trim_matches('\n')
         .to_owned()
 }
+
+/// The documentation of the declaration `node`, from the comments right
+/// before it, without the comment delimiters.
+///
+/// The closest `/** */` comment is the documentation, skipping other
+/// comments in between, like `// eslint-disable-next-line`, so that license
+/// headers and the like before it are never picked. Without one, the `//` or
+/// `///` comments on the lines right above the declaration are. Comments
+/// before another statement or member never document this one.
+///
+/// Declarations in an `export` statement are documented before the `export`,
+/// and decorated ones before their decorators.
+pub(crate) fn find_docs(node: &Node, code: &str) -> Option<String> {
+    let commented = match node.parent() {
+        // Namespaces that aren't exported are wrapped in an expression statement
+        Some(parent) if matches!(parent.kind(), "export_statement" | "expression_statement") => {
+            parent
+        }
+        _ => *node,
+    };
+    let commented = decorator::first_before(&commented);
+
+    let mut line_comments = vec![];
+    let mut adjacent = true;
+    let mut next_row = commented.start_position().row;
+    let mut sibling = commented.prev_sibling();
+
+    while let Some(comment) = sibling.filter(|s| s.kind() == "comment") {
+        let text = comment.utf8_text(code.as_bytes()).unwrap();
+        if text.starts_with("/**") {
+            return Some(process_comment(text));
+        }
+
+        adjacent = adjacent
+            && text.starts_with("//")
+            && comment.end_position().row + 1 == next_row
+            && !is_trailing(&comment);
+        if adjacent {
+            line_comments.push(text);
+            next_row = comment.start_position().row;
+        }
+
+        sibling = comment.prev_sibling();
+    }
+
+    let lines = line_comments
+        .iter()
+        .rev()
+        .map(|line| {
+            let line = line.trim_start_matches('/');
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .filter(|line| !DIRECTIVES.iter().any(|d| line.starts_with(d)))
+        .collect::<Vec<_>>();
+
+    (!lines.is_empty()).then(|| lines.join("\n").trim().to_owned())
+}
+
+/// Whether `comment` is on the same line as the end of the code before it,
+/// like the comment in `a: string; // the name`, which is about that code
+fn is_trailing(comment: &Node) -> bool {
+    comment.prev_sibling().is_some_and(|previous| {
+        previous.kind() != "comment" && previous.end_position().row == comment.start_position().row
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::test_utils;
+    use indoc::indoc;
+
+    #[test]
+    fn processes_comments_with_crlf_line_endings() {
+        assert_eq!(
+            process_comment("/**\r\n * Greets.\r\n *\r\n * @param name Who\r\n */"),
+            "Greets.\n\n@param name Who"
+        );
+    }
+
+    #[test]
+    fn keeps_fenced_code_as_written() {
+        let comment = indoc! {"
+        /**
+         * Greets.
+         *
+         * ```ts
+         * if (ready) {
+         *\tgreet(\"world\");
+         *   * 2;
+         * }
+         * ```
+         */
+        "};
+
+        assert_eq!(
+            process_comment(comment),
+            "Greets.\n\n```ts\nif (ready) {\n\tgreet(\"world\");\n  * 2;\n}\n```"
+        );
+    }
+
+    #[test]
+    fn dedents_comments_without_stars() {
+        let comment = "/** Greets.\n\n    Example:\n\n        greet();\n */";
+
+        assert_eq!(
+            process_comment(comment),
+            "Greets.\n\nExample:\n\n    greet();"
+        );
+    }
+
+    #[test]
+    fn finds_docs_in_tricky_layouts() {
+        let cases: &[(&str, &str, &str, Option<&str>)] = &[
+            (
+                "a doc comment",
+                indoc! {"
+                /** Greets */
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "a license header, a blank line, then the doc comment",
+                indoc! {"
+                /* Copyright (c) Example, MIT licensed */
+
+                /** Greets */
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "a doc comment separated by a blank line",
+                indoc! {"
+                /** Greets */
+
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "a doc comment, then a directive",
+                indoc! {"
+                /** Greets */
+                // eslint-disable-next-line no-console
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "the closest of two doc comments",
+                indoc! {"
+                /** @license MIT */
+                /** Greets */
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "a block of line comments",
+                indoc! {"
+                // Greets
+                // everyone
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets\neveryone"),
+            ),
+            (
+                "a block of triple-slash comments",
+                indoc! {"
+                /// Greets
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "only the line comments right above",
+                indoc! {"
+                // Not about greet
+
+                // Greets
+                function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "line comments separated by a blank line",
+                indoc! {"
+                // Not about greet
+
+                function greet() {}
+                "},
+                "function_declaration",
+                None,
+            ),
+            (
+                "only a directive",
+                indoc! {"
+                // eslint-disable-next-line
+                function greet() {}
+                "},
+                "function_declaration",
+                None,
+            ),
+            (
+                "a plain block comment",
+                indoc! {"
+                /* Not documentation */
+                function greet() {}
+                "},
+                "function_declaration",
+                None,
+            ),
+            (
+                "a doc comment before another statement",
+                indoc! {"
+                /** About x */
+                const x = 1;
+                function greet() {}
+                "},
+                "function_declaration",
+                None,
+            ),
+            (
+                "a doc comment before export",
+                indoc! {"
+                /** Greets */
+                export function greet() {}
+                "},
+                "function_declaration",
+                Some("Greets"),
+            ),
+            (
+                "a trailing comment of the member before",
+                indoc! {"
+                interface Person {
+                  name: string; // The name
+                  age: number;
+                }
+                "},
+                "property_signature",
+                None,
+            ),
+            (
+                "a doc comment above decorators",
+                indoc! {"
+                class Users {
+                  /** Finds a user */
+                  @Get()
+                  find() {}
+                }
+                "},
+                "method_definition",
+                Some("Finds a user"),
+            ),
+        ];
+
+        for (description, code, kind, expected) in cases {
+            let tree = test_utils::parse(code, tree_sitter_typescript::language_typescript());
+            // The last node of the kind, e.g. the property after the one with a
+            // trailing comment
+            let mut node = test_utils::first_node(&tree, kind);
+            let mut sibling = node.next_sibling();
+            while let Some(next) = sibling {
+                if next.kind() == *kind {
+                    node = next;
+                }
+                sibling = next.next_sibling();
+            }
+
+            assert_eq!(
+                find_docs(&node, code).as_deref(),
+                *expected,
+                "Docs of {}",
+                description
+            );
+        }
+    }
+}
@@ -7,57 +7,144 @@ pub(crate) const NODE_KIND: &str = "import_statement";
 ///
 /// Can be created by parsing an ES6 module import, or a CommonJS require.
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Import {
-    pub names: Vec<String>,
+pub struct Import {
+    pub names: Vec<ImportedName>,
     pub source: String,
+    /// Imported with `import type`, so only usable as types
+    pub type_only: bool,
 }
 
-pub(crate) fn parse(node: &Node, ctx: &ParserContext) -> Result<Import> {
-    let mut cursor = node.walk();
-    assert!(cursor.node().kind() == NODE_KIND);
+impl Import {
+    /// Whether the import brings `local` into scope
+    pub fn imports(&self, local: &str) -> bool {
+        self.names.iter().any(|name| name.local == local)
+    }
 
-    cursor.goto_first_child();
+    /// What `path`, which starts with a name the import brings into scope,
+    /// is called in the module it is imported from, e.g. `Foo.Inner` for
+    /// `Bar.Inner` with `import { Foo as Bar }`
+    pub fn imported_path(&self, path: &str) -> String {
+        let (head, rest) = match path.split_once('.') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (path, None),
+        };
 
-    // Pop the unnamed import node
-    //
-    // import { Foo, Bar } from 'baz';
-    // ^^^^^^
-    cursor.goto_next_sibling();
+        let imported = self
+            .names
+            .iter()
+            .find(|name| name.local == head)
+            .map_or(head, |name| name.imported.as_str());
+
+        match rest {
+            Some(rest) => format!("{}.{}", imported, rest),
+            None => imported.to_owned(),
+        }
+    }
+}
+
+/// A name an import brings into scope, e.g. `Bar` in
+/// `import { Foo as Bar } from "./foo";`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedName {
+    /// The name in the importing file, `Bar`
+    pub local: String,
+    /// The name the module it is imported from exports it as, `Foo`
+    pub imported: String,
+    /// Imported with `import type`, or as `import { type Foo }`
+    pub type_only: bool,
+}
+
+impl ImportedName {
+    /// A name imported as itself, like `Foo` in `import { Foo } from "./foo";`
+    pub fn unaliased(name: &str) -> Self {
+        Self {
+            local: name.to_owned(),
+            imported: name.to_owned(),
+            type_only: false,
+        }
+    }
+}
+
+pub(crate) fn parse(node: &Node, ctx: &ParserContext) -> Result<Import> {
+    assert_eq!(node.kind(), NODE_KIND);
+    let text = |node: Node| node.utf8_text(ctx.code.as_bytes()).unwrap();
+
+    // import type { Foo } from './baz';
+    //        ^^^^
+    let mut cursor = node.walk();
+    let type_only = node.children(&mut cursor).any(|n| n.kind() == "type");
 
     // Parse the import names.
     //
-    // import { Foo, Bar } from 'baz';
+    // import { Foo, Bar } from './baz';
     //        ^^^^^^^^^^^^
-    let mut import_cursor = cursor.node().walk();
     let mut names = vec![];
-    // named_imports
-    import_cursor.goto_first_child();
-    // first import_specifier
-    import_cursor.goto_first_child();
-
-    while import_cursor.goto_next_sibling() {
-        if !import_cursor.node().is_named() {
-            continue;
+    if let Some(clause) = node
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "import_clause")
+    {
+        let mut clause_cursor = clause.walk();
+        for child in clause.named_children(&mut clause_cursor) {
+            match child.kind() {
+                "named_imports" => {
+                    let mut specifiers = child.walk();
+                    for specifier in child.named_children(&mut specifiers) {
+                        if specifier.kind() == "import_specifier" {
+                            names.push(imported_name(&specifier, type_only, ctx));
+                        }
+                    }
+                }
+                // import * as Baz from './baz';
+                "namespace_import" => {
+                    if let Some(identifier) = child.named_child(0) {
+                        let mut name = ImportedName::unaliased(text(identifier));
+                        name.type_only = type_only;
+                        names.push(name);
+                    }
+                }
+                // Default imports aren't resolved
+                _ => {}
+            }
         }
-        let name = import_cursor.node().utf8_text(ctx.code.as_bytes()).unwrap();
-        names.push(name.to_owned());
     }
 
-    // Pop "from"
-    cursor.goto_next_sibling();
-
     // Parse the source
     //
     // import { Foo, Bar } from './baz';
-    cursor.goto_next_sibling();
-    // Pop quote
-    cursor.goto_first_child();
-    cursor.goto_next_sibling();
-    let source = cursor
-        .node()
-        .utf8_text(ctx.code.as_bytes())
-        .unwrap()
-        .to_owned();
-
-    Ok(Import { names, source })
+    //                          ^^^^^^^
+    let source = node
+        .child_by_field_name("source")
+        .map(|s| text(s).trim_matches(|c| c == '"' || c == '\'').to_owned())
+        .unwrap_or_default();
+
+    Ok(Import {
+        names,
+        source,
+        type_only,
+    })
+}
+
+/// The name an `import_specifier` like `Foo as Bar` brings into scope.
+/// `type_only` is set for the whole statement, as in `import type { Foo }`,
+/// or for the specifier, as in `import { type Foo }`.
+fn imported_name(specifier: &Node, type_only: bool, ctx: &ParserContext) -> ImportedName {
+    let text = |node: Node| node.utf8_text(ctx.code.as_bytes()).unwrap().to_owned();
+
+    let name = specifier
+        .child_by_field_name("name")
+        .map(text)
+        .unwrap_or_else(|| text(*specifier));
+    let type_only = type_only
+        || specifier
+            .child(0)
+            .is_some_and(|n| !n.is_named() && n.kind() == "type");
+
+    ImportedName {
+        local: specifier
+            .child_by_field_name("alias")
+            .map(text)
+            .unwrap_or_else(|| name.clone()),
+        imported: name,
+        type_only,
+    }
 }
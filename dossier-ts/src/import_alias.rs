@@ -0,0 +1,174 @@
+use crate::{
+    helpers::*,
+    symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    tsdoc::{self, DocTags},
+    ParserContext,
+};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, MemberContext, Result};
+
+/// `import Alias = Some.Other.Thing;`
+pub(crate) const NODE_KIND: &str = "import_alias";
+
+/// An alias for a qualified name, usually re-exporting a member of another
+/// namespace under a shorter name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportAlias {
+    pub identifier: String,
+    pub documentation: Option<String>,
+    pub doc_tags: DocTags,
+    /// The dotted path being aliased, e.g. `Some.Other.Thing`
+    pub target: String,
+    /// The FQN of the target, if we could resolve it
+    pub resolved: Option<String>,
+    pub exported: bool,
+}
+
+impl ImportAlias {
+    pub fn as_entity(
+        &self,
+        source: &Source,
+        fqn: Option<&str>,
+        symbol_context: Option<SymbolContext>,
+    ) -> Entity {
+        let mut meta = json!({});
+        if self.exported {
+            meta["exported"] = true.into();
+        }
+        if self.resolved.is_none() {
+            meta["target"] = self.target.as_str().into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
+
+        let identity = match &self.resolved {
+            Some(target) => Identity::Reference(target.clone()),
+            None => Identity::FQN(fqn.expect("Import alias without FQN").to_owned()),
+        };
+
+        Entity {
+            title: Some(self.identifier.clone()),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
+            kind: "alias".to_owned(),
+            identity,
+            member_context: symbol_context.map(MemberContext::from),
+            language: crate::LANGUAGE.to_owned(),
+            source: source.as_entity_source(),
+            meta,
+            members: vec![],
+        }
+    }
+}
+
+pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
+    assert_eq!(node.kind(), NODE_KIND);
+
+    let mut cursor = node.walk();
+    let mut names = node
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() != "comment")
+        .map(|n| n.utf8_text(ctx.code.as_bytes()).unwrap());
+
+    let identifier = names.next().unwrap().to_owned();
+    // Qualified names may be split over lines, e.g. `Foo\n  .Bar`
+    let target = names
+        .next()
+        .unwrap()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
+
+    Ok(Symbol::in_context(
+        ctx,
+        SymbolKind::ImportAlias(ImportAlias {
+            identifier,
+            documentation,
+            doc_tags,
+            target,
+            resolved: None,
+            exported: is_exported(node),
+        }),
+        Source::for_node(node, ctx),
+    ))
+}
+
+fn is_exported(node: &Node) -> bool {
+    node.parent()
+        .is_some_and(|p| p.kind() == "export_statement")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_file, ParserContext};
+    use dossier_core::Identity;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn aliases_in(source: &str) -> Vec<dossier_core::Entity> {
+        let mut table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        table.resolve_types();
+
+        let namespace = table.all_symbols().last().unwrap().as_entity();
+        namespace
+            .members
+            .into_iter()
+            .filter(|m| m.kind == "alias")
+            .collect()
+    }
+
+    #[test]
+    fn resolves_aliases_of_nested_namespace_members() {
+        let source = indoc! { r#"
+        namespace Shapes {
+            export namespace Polygons {
+                export interface Triangle {}
+            }
+
+            /** The most popular polygon */
+            export import Triangle = Polygons.Triangle;
+            import Tri = Shapes.Polygons
+                .Triangle;
+        }
+        "#};
+
+        let aliases = aliases_in(source);
+        assert_eq!(aliases.len(), 2);
+
+        let triangle = &aliases[0];
+        assert_eq!(triangle.title.as_deref(), Some("Triangle"));
+        assert_eq!(triangle.description, "The most popular polygon");
+        assert_eq!(
+            triangle.identity,
+            Identity::Reference("index.ts::Shapes::Polygons::Triangle".to_owned())
+        );
+        assert_eq!(triangle.meta["exported"], true);
+        assert!(triangle.meta.get("target").is_none());
+
+        let tri = &aliases[1];
+        assert_eq!(
+            tri.identity,
+            Identity::Reference("index.ts::Shapes::Polygons::Triangle".to_owned())
+        );
+        assert!(tri.meta.get("exported").is_none());
+    }
+
+    #[test]
+    fn keeps_the_path_of_unresolvable_aliases() {
+        let source = indoc! { r#"
+        namespace Messages {
+            export import Timestamp = google.protobuf.Timestamp;
+        }
+        "#};
+
+        let aliases = aliases_in(source);
+        assert_eq!(aliases.len(), 1);
+
+        let timestamp = &aliases[0];
+        assert_eq!(
+            timestamp.identity,
+            Identity::FQN("index.ts::Messages::Timestamp".to_owned())
+        );
+        assert_eq!(timestamp.meta["target"], "google.protobuf.Timestamp");
+        assert_eq!(timestamp.meta["exported"], true);
+    }
+}
@@ -0,0 +1,159 @@
+//! Records what classes and interfaces inherit from.
+//!
+//! The `extends` and `implements` clauses are already members of the entity,
+//! but a renderer that wants to show "Inherited from" sections would have to
+//! know which members those are and how references work. This pass lists them
+//! in `meta.extends` and `meta.implements`, as FQNs where they were resolved
+//! and as written otherwise.
+//!
+//! With `Context::include_inherited`, the members a class or interface
+//! inherits are then copied into it as well.
+use dossier_core::{serde_json::json, Entity, Identity, MemberContext};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+pub(crate) fn record_inheritance(entities: &mut [Entity]) {
+    for entity in entities.iter_mut() {
+        if matches!(entity.kind.as_str(), "class" | "interface") {
+            for context in [MemberContext::Extends, MemberContext::Implements] {
+                let parents = parents(entity, &context);
+                if !parents.is_empty() {
+                    entity.meta[context.as_str()] = json!(parents);
+                }
+            }
+        }
+
+        record_inheritance(&mut entity.members);
+    }
+}
+
+fn parents(entity: &Entity, context: &MemberContext) -> Vec<String> {
+    entity
+        .members
+        .iter()
+        .filter(|m| m.member_context.as_ref() == Some(context))
+        .filter_map(|m| match &m.identity {
+            Identity::Reference(fqn) => Some(fqn.clone()),
+            _ => m.title.clone(),
+        })
+        .collect()
+}
+
+/// What we need to know about a class or interface to copy its members into
+/// the ones that extend it
+struct Parent {
+    extends: Vec<String>,
+    members: Vec<Entity>,
+}
+
+/// Copies inherited members into classes and interfaces, after their own
+/// members. Needs `record_inheritance` to have run first.
+///
+/// Copies have the `inherited` member context, refer to the original member,
+/// and name the class or interface that declares it in `meta.inherited_from`.
+/// Members the entity declares itself shadow inherited ones, as do members of
+/// closer ancestors. Each member is copied once, even if it is inherited
+/// along several paths.
+pub(crate) fn include_inherited_members(entities: &mut [Entity]) {
+    let mut parents = HashMap::new();
+    for entity in entities.iter() {
+        collect_parents(entity, &mut parents);
+    }
+
+    for entity in entities.iter_mut() {
+        add_inherited_members(entity, &parents);
+    }
+}
+
+fn collect_parents(entity: &Entity, parents: &mut HashMap<String, Parent>) {
+    if let (true, Identity::FQN(fqn)) = (is_inheritable(entity), &entity.identity) {
+        parents.insert(
+            fqn.clone(),
+            Parent {
+                extends: extends(entity),
+                members: own_members(entity).cloned().collect(),
+            },
+        );
+    }
+
+    for member in &entity.members {
+        collect_parents(member, parents);
+    }
+}
+
+fn add_inherited_members(entity: &mut Entity, parents: &HashMap<String, Parent>) {
+    if is_inheritable(entity) {
+        let mut seen = own_members(entity)
+            .filter_map(|m| m.title.clone())
+            .collect::<HashSet<_>>();
+        let mut visited = HashSet::new();
+        // Breadth first, so that closer ancestors shadow further ones
+        let mut queue = extends(entity).into_iter().collect::<VecDeque<_>>();
+        let mut inherited = vec![];
+
+        while let Some(fqn) = queue.pop_front() {
+            let Some(parent) = parents.get(&fqn) else {
+                continue;
+            };
+            if !visited.insert(fqn.clone()) {
+                continue;
+            }
+
+            for member in &parent.members {
+                let Some(title) = &member.title else {
+                    continue;
+                };
+                if seen.insert(title.clone()) {
+                    inherited.push(inherited_copy(member, &fqn));
+                }
+            }
+
+            queue.extend(parent.extends.iter().cloned());
+        }
+
+        entity.members.extend(inherited);
+    }
+
+    for member in entity.members.iter_mut() {
+        add_inherited_members(member, parents);
+    }
+}
+
+fn inherited_copy(member: &Entity, declared_in: &str) -> Entity {
+    let mut copy = member.clone();
+    // The member and its own members, like the parameters of a method, are
+    // declared in the parent, so the copies only refer to them
+    dossier_core::tree::map(std::slice::from_mut(&mut copy), |entity| {
+        if let Identity::FQN(fqn) = &entity.identity {
+            entity.identity = Identity::Reference(fqn.clone());
+        }
+    });
+    copy.member_context = Some(MemberContext::Inherited);
+    copy.meta["inherited_from"] = json!(declared_in);
+    copy
+}
+
+fn is_inheritable(entity: &Entity) -> bool {
+    matches!(entity.kind.as_str(), "class" | "interface")
+}
+
+fn extends(entity: &Entity) -> Vec<String> {
+    entity.meta["extends"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|fqn| fqn.as_str().map(|s| s.to_owned()))
+        .collect()
+}
+
+/// The properties and methods an entity declares itself. Those of interfaces
+/// are members of their object type.
+fn own_members(entity: &Entity) -> impl Iterator<Item = &Entity> {
+    let object = entity.members.iter().find(|m| m.kind == "object");
+
+    entity
+        .members
+        .iter()
+        .chain(object.into_iter().flat_map(|o| o.members.iter()))
+        .filter(|m| m.member_context != Some(MemberContext::Inherited))
+        .filter(|m| matches!(m.kind.as_str(), "property" | "field" | "method"))
+}
@@ -1,20 +1,26 @@
 use crate::{
+    callable,
     helpers::*,
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
-    type_variable, types, ParserContext,
+    tsdoc::{self, DocTags},
+    types, ParserContext,
 };
-use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 pub(crate) const NODE_KIND: &str = "interface_declaration";
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Interface {
+pub struct Interface {
     pub identifier: String,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
     /// Interfaces are actually just a single object type.
     /// We forward a bunch of methods to this child object.
     pub children: Vec<Symbol>,
     pub exported: bool,
+    /// The declaration without its body, on one line, see
+    /// `dossier_core::helpers::signature`. Not set for minified files.
+    pub signature: Option<String>,
 }
 
 impl Interface {
@@ -28,13 +34,17 @@ impl Interface {
         if self.exported {
             meta["exported"] = true.into();
         }
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "interface".to_owned(),
             identity: Identity::FQN(fqn.expect("Interface without FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: "ts".to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -45,45 +55,42 @@ impl Interface {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Interface {
     pub fn type_variables(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_type_variable().is_some())
     }
 
-    #[cfg(test)]
     /// Not actually the properties of the interface, but the properties of the
     /// object type that the interface is forwarding to.
     pub fn properties(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .find(|s| s.kind.as_type().is_some())
-            .unwrap()
-            .children()
-            .iter()
+            .into_iter()
+            .flat_map(|s| s.children())
             .filter(|s| s.kind.as_property().is_some())
     }
 
-    #[cfg(test)]
     /// Not actually the properties of the interface, but the properties of the
     /// object type that the interface is forwarding to.
     pub fn methods(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .find(|s| s.kind.as_type().is_some())
-            .unwrap()
-            .children()
-            .iter()
+            .into_iter()
+            .flat_map(|s| s.children())
             .filter(|s| s.kind.as_method().is_some())
     }
 
-    #[cfg(test)]
-    pub fn extends(&self) -> Option<&Symbol> {
+    pub fn extends(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
-            .find(|s| s.context == Some(SymbolContext::Extends))
+            .filter(|s| s.context == Some(SymbolContext::Extends))
     }
 }
 
@@ -109,21 +116,19 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
     cursor.goto_next_sibling();
 
     if cursor.node().kind() == "type_parameters" {
-        parse_type_parameters(&cursor.node(), &mut children, ctx);
+        callable::parse_type_parameters(&cursor.node(), &mut children, ctx)?;
         ctx.push_scope();
         has_generics = true;
+        cursor.goto_next_sibling();
     }
 
-    cursor.goto_next_sibling();
-
     if cursor.node().kind() == "extends_type_clause" {
-        let mut tmp = cursor.node().walk();
-        tmp.goto_first_child();
-        tmp.goto_next_sibling();
-
-        let mut extends = types::parse(&tmp.node(), ctx)?;
-        extends.context = Some(SymbolContext::Extends);
-        children.push(extends);
+        let clause = cursor.node();
+        for the_type in clause.children_by_field_name("type", &mut clause.walk()) {
+            let mut extends = types::parse(&the_type, ctx)?;
+            extends.context = Some(SymbolContext::Extends);
+            children.push(extends);
+        }
 
         cursor.goto_next_sibling();
     }
@@ -138,36 +143,22 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         ctx.pop_scope();
     }
 
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
+
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::Interface(Interface {
             identifier,
-            documentation: find_docs(node, ctx.code).map(process_comment),
+            documentation,
+            doc_tags,
             children,
             exported: is_exported(node),
+            signature: Some(dossier_core::helpers::signature(node, ctx.code)),
         }),
         Source::for_node(node, ctx),
     ))
 }
 
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    let parent = node.parent().unwrap();
-
-    if parent.kind() == "export_statement" {
-        if let Some(maybe_comment) = parent.prev_sibling() {
-            if maybe_comment.kind() == "comment" {
-                return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-            }
-        }
-    } else if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
-
-    None
-}
-
 fn is_exported(node: &Node) -> bool {
     if let Some(parent) = node.parent() {
         if parent.kind() == "export_statement" {
@@ -177,28 +168,6 @@ fn is_exported(node: &Node) -> bool {
     false
 }
 
-fn parse_type_parameters(
-    type_parameters: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) {
-    assert_eq!(type_parameters.kind(), "type_parameters");
-
-    let mut cursor = type_parameters.walk();
-    cursor.goto_first_child();
-
-    loop {
-        if cursor.node().kind() == "type_parameter" {
-            let type_variable = type_variable::parse(&cursor.node(), ctx).unwrap();
-            children.push(type_variable);
-        }
-
-        if !cursor.goto_next_sibling() {
-            break;
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -376,7 +345,7 @@ mod test {
     #[test]
     fn extends_syntax() {
         let code = indoc! {r#"
-        export interface Expression<T> extends OperationNodeSource {
+        export interface Expression<T> extends OperationNodeSource, Nodes.Visitable {
         }
         "#};
 
@@ -392,10 +361,16 @@ mod test {
 
         let interface = symbol.kind.as_interface().unwrap();
 
-        let extends = interface.extends().unwrap().kind.as_type().unwrap();
+        let extends = interface
+            .extends()
+            .map(|s| s.kind.as_type().unwrap())
+            .collect::<Vec<_>>();
         assert_eq!(
             extends,
-            &Type::Identifier("OperationNodeSource".to_owned(), None)
+            [
+                &Type::Identifier("OperationNodeSource".to_owned(), None),
+                &Type::Identifier("Nodes.Visitable".to_owned(), None)
+            ]
         );
     }
 }
@@ -1,127 +1,344 @@
+mod alias_chain;
+mod callable;
 mod class;
+mod decorator;
+mod doc_links;
 mod export_clause;
 mod field;
 mod function;
 mod helpers;
 mod import;
+mod import_alias;
+mod inheritance;
 mod interface;
 mod method;
+mod module_name;
+mod namespace;
 mod parameter;
 mod property;
+mod reexport;
+mod resolver;
 mod symbol;
 mod symbol_table;
+mod tsconfig;
+mod tsdoc;
 mod type_alias;
 mod type_constraint;
 mod type_variable;
 mod types;
+mod variable;
 
-use dossier_core::tree_sitter::{Node, Parser};
-use dossier_core::Result;
+#[cfg(feature = "unstable-symbols")]
+pub mod unstable;
 
-use rayon::prelude::*;
+use dossier_core::parser_pool::{self, parse_timeout_warning};
+use dossier_core::tree_sitter::{self, Node};
+use dossier_core::{Diagnostic, FileMode, ParseCache, Repository, Result};
 
+use symbol::{Source, Symbol, SymbolID, SymbolKind};
 use symbol_table::{ScopeID, SymbolTable};
 
+use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, PartialEq, Default)]
-pub struct TypeScriptParser {}
+#[derive(Debug, Default)]
+pub struct TypeScriptParser {
+    /// See `with_cache`
+    cache: Option<ParseCache<SymbolTable>>,
+}
 
 impl TypeScriptParser {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// A parser that keeps the files it parses, so that parsing them again
+    /// only re-parses the ones that changed
+    pub fn with_cache() -> Self {
+        Self {
+            cache: Some(ParseCache::new()),
+        }
+    }
 }
 
-const LANGUAGE: &str = "ts";
+pub const LANGUAGE: &str = "ts";
+
+/// The extensions of the files the parser parses
+pub const FILE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts"];
+
+/// Reported for types the parser doesn't understand, which are kept as
+/// written
+pub const UNSUPPORTED_TYPE: &str = "unsupported-type";
 
 impl dossier_core::DocsParser for TypeScriptParser {
-    fn parse<'a, P: Into<&'a Path>, T: IntoIterator<Item = P>>(
+    fn parse(
         &self,
-        paths: T,
-        _ctx: &mut dossier_core::Context,
+        paths: &[&Path],
+        ctx: &mut dossier_core::Context,
     ) -> Result<Vec<dossier_core::Entity>> {
-        let out = Mutex::new(Vec::new());
+        let symbols = parse_tables(paths, self.cache.as_ref(), ctx)?;
 
-        let paths: Vec<PathBuf> = paths
-            .into_iter()
-            .map(|p| p.into().to_owned())
-            .collect::<Vec<_>>();
+        Ok(into_entities(symbols, ctx))
+    }
 
-        // Some large union type are causing us to stack overflow, so let's
-        // bump the default stack size for the thread pool as a temporary
-        // measure
-        //
-        // The case that caused this was a union with 88 members. Because
-        // unions are modelled as a tree with a left and right side, this
-        // means we are doing a lot of recusion when this type is parsed.
-        rayon::ThreadPoolBuilder::new()
-            .stack_size(4 * 1024 * 1024)
-            .build_global()
-            .unwrap();
+    fn language(&self) -> &'static str {
+        LANGUAGE
+    }
+
+    fn file_extensions(&self) -> &'static [&'static str] {
+        FILE_EXTENSIONS
+    }
 
-        paths.as_slice().par_iter().for_each(|path| {
-            let code = std::fs::read_to_string(path).unwrap();
-            let ctx = ParserContext::new(path, &code);
+    fn is_public(
+        &self,
+        entity: &dossier_core::Entity,
+        parent: Option<&dossier_core::Entity>,
+    ) -> bool {
+        is_public(entity, parent)
+    }
+}
 
-            // TODO(Nik): Handle error
-            let symbol_table = parse_file(ctx).unwrap();
+#[cfg(feature = "unstable-symbols")]
+impl TypeScriptParser {
+    /// Parses the files into symbol tables, with types resolved across all of
+    /// them, but doesn't convert them into entities.
+    ///
+    /// The tables are returned in the order of `paths`, less any files that
+    /// were skipped. See the `unstable` module for the caveats.
+    pub fn parse_to_tables<'a, P: Into<&'a Path>, T: IntoIterator<Item = P>>(
+        &self,
+        paths: T,
+        ctx: &mut dossier_core::Context,
+    ) -> Result<Vec<SymbolTable>> {
+        let paths = paths.into_iter().map(|p| p.into()).collect::<Vec<_>>();
+        parse_tables(&paths, self.cache.as_ref(), ctx)
+    }
+}
 
-            out.lock().unwrap().push(symbol_table);
-        });
+/// Reads and parses the files, and resolves types across all their symbol
+/// tables.
+fn parse_tables(
+    paths: &[&Path],
+    cache: Option<&ParseCache<SymbolTable>>,
+    ctx: &mut dossier_core::Context,
+) -> Result<Vec<SymbolTable>> {
+    let paths: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
+
+    if let Some(cache) = cache {
+        cache.retain(&paths);
+    }
 
-        let mut symbols = out.into_inner().unwrap();
+    let module_names = match ctx.module_names {
+        true => module_name::module_names(&paths, ctx.file_source.as_ref()),
+        false => HashMap::new(),
+    };
+
+    let mut symbols = dossier_core::parse_files(
+        &paths,
+        ctx.file_source.as_ref(),
+        ctx.jobs,
+        &ctx.file_limits,
+        &mut ctx.diagnostics,
+        |path, code, mode| {
+            let parse = || {
+                parse_file(
+                    ParserContext::new(path, code)
+                        .with_repository(ctx.repository.as_ref())
+                        .with_root(ctx.root.as_deref())
+                        .with_module_name(module_names.get(path))
+                        .with_mode(mode)
+                        .with_timeout(ctx.parse_timeout),
+                )
+            };
+
+            let start = Instant::now();
+            let parsed = match cache {
+                Some(cache) => {
+                    let module_name = module_names.get(path).map(String::as_str);
+                    cache.get_or_parse(path, code, mode, module_name, parse)
+                }
+                None => parse(),
+            };
+            if let Some(instrumentation) = &ctx.instrumentation {
+                instrumentation.file_parsed(path, LANGUAGE, start.elapsed());
+            }
 
-        for table in symbols.iter_mut() {
-            table.resolve_types();
-        }
+            parsed
+        },
+    )?;
 
-        let mut window = vec![];
+    let mut configs = tsconfig::TsConfigs::new(ctx.file_source.as_ref(), ctx.tsconfig.as_deref())?;
+    for table in symbols.iter_mut() {
+        table.tsconfig = configs.for_file(&table.file);
+    }
+    ctx.diagnostics.append(&mut configs.diagnostics);
 
-        while let Some(mut table) = symbols.pop() {
-            table.resolve_imported_types(symbols.iter().chain(window.iter()));
-            window.push(table);
-        }
+    for table in symbols.iter_mut() {
+        table.resolve_types();
+    }
+    symbol_table::resolve_imported_types_to_fixpoint(&mut symbols);
+    doc_links::link_references(&mut symbols);
 
-        let mut entities = vec![];
-        for table in window {
-            for symbol in table.all_symbols() {
-                let entity = symbol.as_entity();
-                entities.push(entity);
-            }
+    for table in symbols.iter_mut() {
+        ctx.diagnostics.append(&mut table.diagnostics);
+    }
+
+    Ok(symbols)
+}
+
+/// Converts resolved symbol tables into entities.
+fn into_entities(
+    symbols: Vec<SymbolTable>,
+    ctx: &mut dossier_core::Context,
+) -> Vec<dossier_core::Entity> {
+    let mut entities = vec![];
+    for table in symbols {
+        for symbol in table.all_symbols() {
+            let entity = symbol.as_entity();
+            entities.push(entity);
         }
+    }
+
+    // First, so that nothing else sees them
+    if ctx.strip_internal {
+        dossier_core::internal::strip_internal(&mut entities, dossier_core::internal::is_internal);
+    }
+
+    inheritance::record_inheritance(&mut entities);
+    if ctx.include_inherited {
+        inheritance::include_inherited_members(&mut entities);
+    }
+
+    // Keep the output stable regardless of the order the files were parsed in
+    entities.sort_by(|a, b| {
+        a.source
+            .file
+            .cmp(&b.source.file)
+            .then(a.source.start.byte_offset.cmp(&b.source.start.byte_offset))
+    });
+
+    if ctx.public_only {
+        dossier_core::retain_public(&mut entities, is_public);
+    }
+
+    if let Some(locale) = &ctx.doc_locale {
+        dossier_core::locale::localize_descriptions(&mut entities, locale, tsdoc::LOCALE_MARKER);
+    }
+
+    if ctx.resolve_alias_chains {
+        alias_chain::resolve_alias_chains(&mut entities);
+    }
+
+    if ctx.root_fqns {
+        dossier_core::root_fqn::record_root_fqns(&mut entities);
+    }
+
+    if ctx.include_source_text {
+        dossier_core::source_text::record_source_text(
+            &mut entities,
+            ctx.file_source.as_ref(),
+            ctx.max_source_bytes,
+        );
+    }
+
+    // After the source text, which is read from the files where they are
+    if let Some(root) = &ctx.root {
+        dossier_core::root_dir::relative_sources(&mut entities, root);
+    }
+
+    dossier_core::member_order::order_members(&mut entities);
+
+    entities
+}
+
+/// Top-level declarations and the members of namespaces are public if they
+/// are exported. Class and object members are public unless marked `private`.
+pub fn is_public(entity: &dossier_core::Entity, parent: Option<&dossier_core::Entity>) -> bool {
+    let marked = |key: &str| entity.meta.get(key) == Some(&true.into());
+
+    match parent {
+        None => marked("exported"),
+        Some(parent) if parent.kind == "namespace" => marked("exported"),
+        Some(_) => !marked("private"),
+    }
+}
 
-        Ok(entities)
+/// The name `parser_pool` knows the grammar of `path` by, and the grammar.
+/// `.tsx` files have one of their own, where `<T>x` is JSX rather than a
+/// type assertion.
+fn grammar(path: &Path) -> (&'static str, tree_sitter::Language) {
+    if path.extension() == Some(OsStr::new("tsx")) {
+        ("tsx", tree_sitter_typescript::language_tsx())
+    } else {
+        (LANGUAGE, tree_sitter_typescript::language_typescript())
     }
 }
 
 fn parse_file(mut ctx: ParserContext) -> Result<SymbolTable> {
-    let mut parser = Parser::new();
+    let (name, language) = grammar(ctx.file);
+    let Some(tree) = parser_pool::parse(name, language, ctx.code, ctx.timeout) else {
+        ctx.symbol_table
+            .diagnostics
+            .push(parse_timeout_warning(ctx.file, ctx.timeout));
+        return Ok(ctx.take_symbol_table());
+    };
+    if tree.root_node().kind() != "program" {
+        // Not even the statements could be told apart
+        report_skipped(&tree.root_node(), "whole file", &mut ctx);
+        return Ok(ctx.take_symbol_table());
+    }
 
-    parser
-        .set_language(tree_sitter_typescript::language_typescript())
-        .expect("Error loading TypeScript grammar");
+    parse_statements(&tree.root_node(), &mut ctx)?;
+
+    Ok(ctx.take_symbol_table())
+}
 
-    let tree = parser.parse(ctx.code, None).unwrap();
+/// Parses the statements in a file, or in the body of a namespace.
+/// Statements with syntax errors are skipped with a warning, since what
+/// tree-sitter makes of them can't be trusted.
+fn parse_statements(node: &Node, ctx: &mut ParserContext) -> Result<()> {
+    let mut cursor = node.walk();
+    if !cursor.goto_first_child() {
+        return Ok(());
+    }
 
-    let mut cursor = tree.root_node().walk();
-    assert_eq!(cursor.node().kind(), "program");
-    cursor.goto_first_child();
+    let handle_node = match ctx.mode {
+        FileMode::Full => handle_node,
+        FileMode::NamesOnly => handle_node_names_only,
+    };
 
     loop {
+        if has_syntax_error(&cursor.node()) {
+            skip_statement(&cursor.node(), ctx);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+            continue;
+        }
+
         match cursor.node().kind() {
             "comment" => {
                 // Skip comments
             }
+            "export_statement" if cursor.node().child_by_field_name("source").is_some() => {
+                for symbol in reexport::parse(&cursor.node(), ctx)? {
+                    ctx.symbol_table.add_symbol(symbol);
+                }
+            }
             "export_statement" => {
                 let mut tmp = cursor.node().walk();
                 tmp.goto_first_child();
                 tmp.goto_next_sibling();
-                handle_node(&tmp.node(), &mut ctx)?;
+                // `export type { Foo };`
+                if tmp.node().kind() == "type" {
+                    tmp.goto_next_sibling();
+                }
+                handle_node(&tmp.node(), ctx)?;
             }
             _ => {
-                handle_node(&cursor.node(), &mut ctx)?;
+                handle_node(&cursor.node(), ctx)?;
             }
         }
 
@@ -130,7 +347,57 @@ fn parse_file(mut ctx: ParserContext) -> Result<SymbolTable> {
         }
     }
 
-    Ok(ctx.take_symbol_table())
+    Ok(())
+}
+
+/// Whether a statement or member has a syntax error. Errors in the body of a
+/// namespace, class or interface don't count, since its statements and
+/// members are checked one by one.
+pub(crate) fn has_syntax_error(node: &Node) -> bool {
+    if node.is_error() || node.is_missing() {
+        return true;
+    }
+    if !node.has_error() {
+        return false;
+    }
+
+    match node.kind() {
+        "export_statement"
+        | "expression_statement"
+        | namespace::NODE_KIND
+        | namespace::MODULE_NODE_KIND
+        | class::NODE_KIND
+        | class::ABSTRACT_NODE_KIND
+        | interface::NODE_KIND => {
+            let body = node.child_by_field_name("body");
+            let mut cursor = node.walk();
+            let has_error = node
+                .children(&mut cursor)
+                .any(|child| Some(child) != body && has_syntax_error(&child));
+            has_error
+        }
+        _ => true,
+    }
+}
+
+/// Warns about the first syntax error in a statement that is skipped
+fn skip_statement(node: &Node, ctx: &mut ParserContext) {
+    report_skipped(node, "statement around it", ctx);
+}
+
+/// Warns about the first syntax error in a member of a class or interface
+/// that is skipped
+pub(crate) fn skip_member(node: &Node, ctx: &mut ParserContext) {
+    report_skipped(node, "member around it", ctx);
+}
+
+fn report_skipped(node: &Node, what: &str, ctx: &mut ParserContext) {
+    if let Some(mut diagnostic) = dossier_core::helpers::syntax_error(node, ctx.code, ctx.file) {
+        diagnostic
+            .message
+            .push_str(&format!(", so the {} was skipped", what));
+        ctx.symbol_table.diagnostics.push(diagnostic);
+    }
 }
 
 fn handle_node(node: &Node, ctx: &mut ParserContext) -> Result<()> {
@@ -147,7 +414,7 @@ fn handle_node(node: &Node, ctx: &mut ParserContext) -> Result<()> {
             let symbol = class::parse(node, ctx)?;
             ctx.symbol_table.add_symbol(symbol);
         }
-        function::NODE_KIND => {
+        function::NODE_KIND | function::GENERATOR_NODE_KIND => {
             let symbol = function::parse(node, ctx)?;
             ctx.symbol_table.add_symbol(symbol);
         }
@@ -159,6 +426,27 @@ fn handle_node(node: &Node, ctx: &mut ParserContext) -> Result<()> {
             let symbol = interface::parse(node, ctx)?;
             ctx.symbol_table.add_symbol(symbol);
         }
+        namespace::NODE_KIND | namespace::MODULE_NODE_KIND => {
+            let symbol = namespace::parse(node, ctx)?;
+            ctx.symbol_table.add_symbol(symbol);
+        }
+        // Namespaces that aren't exported are parsed as expressions
+        "expression_statement" => {
+            if let Some(child) = node.named_child(0) {
+                if child.kind() == namespace::NODE_KIND {
+                    handle_node(&child, ctx)?;
+                }
+            }
+        }
+        import_alias::NODE_KIND => {
+            let symbol = import_alias::parse(node, ctx)?;
+            ctx.symbol_table.add_symbol(symbol);
+        }
+        variable::NODE_KIND | variable::VAR_NODE_KIND | variable::AMBIENT_NODE_KIND => {
+            for symbol in variable::parse(node, ctx)? {
+                ctx.symbol_table.add_symbol(symbol);
+            }
+        }
         export_clause::NODE_KIND => {
             let exported_identifiers = export_clause::parse_exports(node, ctx)?;
 
@@ -174,11 +462,78 @@ fn handle_node(node: &Node, ctx: &mut ParserContext) -> Result<()> {
     Ok(())
 }
 
+/// Adds a symbol with only a name for each top-level declaration, leaving out
+/// their members, types, and documentation. Imports and exports are handled
+/// as usual, so that other files can still import from this one.
+fn handle_node_names_only(node: &Node, ctx: &mut ParserContext) -> Result<()> {
+    let Some(name_node) = node.child_by_field_name("name") else {
+        return handle_node(node, ctx);
+    };
+
+    let identifier = name_node.utf8_text(ctx.code.as_bytes()).unwrap().to_owned();
+    let exported = node
+        .parent()
+        .is_some_and(|p| p.kind() == "export_statement");
+
+    let kind = match node.kind() {
+        class::NODE_KIND | class::ABSTRACT_NODE_KIND => SymbolKind::Class(class::Class {
+            identifier,
+            documentation: None,
+            doc_tags: Default::default(),
+            children: vec![],
+            exported,
+            is_abstract: node.kind() == class::ABSTRACT_NODE_KIND,
+            decorators: vec![],
+            signature: None,
+        }),
+        function::NODE_KIND | function::GENERATOR_NODE_KIND => {
+            SymbolKind::Function(function::Function {
+                identifier,
+                documentation: None,
+                doc_tags: Default::default(),
+                is_exported: exported,
+                is_async: false,
+                is_generator: node.kind() == function::GENERATOR_NODE_KIND,
+                children: vec![],
+                awaited_type: None,
+                signature: None,
+            })
+        }
+        type_alias::NODE_KIND => SymbolKind::TypeAlias(type_alias::TypeAlias {
+            identifier,
+            documentation: None,
+            doc_tags: Default::default(),
+            children: vec![],
+            exported,
+            signature: None,
+        }),
+        interface::NODE_KIND => SymbolKind::Interface(interface::Interface {
+            identifier,
+            documentation: None,
+            doc_tags: Default::default(),
+            children: vec![],
+            exported,
+            signature: None,
+        }),
+        _ => return handle_node(node, ctx),
+    };
+
+    let symbol = Symbol::in_context(ctx, kind, Source::for_node(node, ctx));
+    ctx.symbol_table.add_symbol(symbol);
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct ParserContext<'a> {
     file: &'a Path,
     code: &'a str,
     symbol_table: SymbolTable,
+    /// Link to the file in its repository, if we know where it lives
+    file_url: Option<String>,
+    mode: FileMode,
+    /// See `Context::parse_timeout`
+    timeout: Option<Duration>,
 }
 
 impl<'a> ParserContext<'a> {
@@ -187,7 +542,41 @@ impl<'a> ParserContext<'a> {
             file: path,
             code,
             symbol_table: SymbolTable::new(path),
+            file_url: None,
+            mode: FileMode::Full,
+            timeout: None,
+        }
+    }
+
+    fn with_mode(mut self, mode: FileMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn with_repository(mut self, repository: Option<&Repository>) -> Self {
+        self.file_url = repository.and_then(|r| r.file_url(self.file));
+        self
+    }
+
+    fn with_root(mut self, root: Option<&Path>) -> Self {
+        if let Some(root) = root {
+            let relative = dossier_core::root_dir::relative_to(self.file, root);
+            self.symbol_table
+                .name_file(dossier_core::portable_path(&relative));
+        }
+        self
+    }
+
+    fn with_module_name(mut self, module_name: Option<&String>) -> Self {
+        if let Some(module_name) = module_name {
+            self.symbol_table.name_file(module_name.clone());
         }
+        self
     }
 
     fn take_symbol_table(self) -> SymbolTable {
@@ -214,16 +603,29 @@ impl<'a> ParserContext<'a> {
         self.symbol_table.pop_scope();
     }
 
+    pub fn next_symbol_id(&self) -> SymbolID {
+        self.symbol_table.next_symbol_id()
+    }
+
     pub fn current_scope(&self) -> ScopeID {
         self.symbol_table.current_scope().id
     }
+
+    pub fn warn(&mut self, code: &str, message: impl Into<String>, node: &Node) {
+        let diagnostic =
+            Diagnostic::warning(code, message, self.file).with_range(node.byte_range());
+        self.symbol_table.diagnostics.push(diagnostic);
+    }
 }
 
 #[cfg(test)]
 mod test {
     use indoc::indoc;
+    use std::collections::HashSet;
 
+    use crate::import::Import;
     use crate::types::Type;
+    use dossier_core::{DocsParser, Entity, Identity, MemberContext};
 
     use super::*;
 
@@ -288,7 +690,10 @@ mod test {
         let imports = table.all_imports().collect::<Vec<_>>();
         assert_eq!(imports.len(), 1);
 
-        assert_eq!(imports[0].names, vec!["Foo"]);
+        assert_eq!(
+            imports[0].names,
+            vec![crate::import::ImportedName::unaliased("Foo")]
+        );
         assert_eq!(imports[0].source, "./foo.ts");
     }
 
@@ -446,7 +851,7 @@ mod test {
             .as_type()
             .unwrap()
         {
-            Type::Object { properties, .. } => {
+            Type::Object { properties } => {
                 let resolved_type = properties[0].kind.as_property().unwrap().children[0]
                     .kind
                     .as_type()
@@ -461,6 +866,10 @@ mod test {
         }
     }
 
+    fn foo_property_type(entities: &[Entity]) -> &Entity {
+        &find_property(find_entity(entities, "Bar"), "foo").members[0]
+    }
+
     #[test]
     fn resolves_type_aliases_across_files() {
         let foo_file = indoc! { r#"
@@ -475,224 +884,2536 @@ mod test {
         }
         "#};
 
-        let mut foo_table = parse_file(ParserContext::new(Path::new("foo.ts"), foo_file)).unwrap();
-        let mut index_table =
-            parse_file(ParserContext::new(Path::new("index.ts"), index_file)).unwrap();
-
-        foo_table.resolve_types();
-        index_table.resolve_types();
-
-        let all_tables = vec![&foo_table];
-
-        index_table.resolve_imported_types(all_tables);
+        let entities = parse_sources(
+            &[("foo.ts", foo_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
 
-        let symbols = index_table.all_symbols().collect::<Vec<_>>();
-        assert_eq!(symbols.len(), 1);
-        let function = symbols[0].kind.as_function().unwrap();
+        let return_type = find_entity(&entities, "makeFoo")
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::ReturnType))
+            .unwrap();
 
         assert_eq!(
-            function.return_type().as_ref().unwrap().kind.as_type(),
-            Some(&Type::Identifier(
-                "Foo".to_owned(),
-                Some("foo.ts::Foo".to_owned())
-            ))
+            return_type.identity,
+            Identity::Reference("foo.ts::Foo".to_owned())
         );
     }
 
     #[test]
-    fn resolves_type_aliases_in_nested_symbols_across_files() {
+    fn resolves_aliased_imports_to_the_name_they_import() {
         let foo_file = indoc! { r#"
-        export type Foo = string;
+        export interface Foo {}
+        export interface Bar {}
         "#};
 
         let index_file = indoc! { r#"
-        import { Foo } from "./foo.ts";
+        import { Foo as Bar, Bar as Baz } from "./foo";
 
-        type Bar = {
-            foo: Foo;
-        }
+        export function make(): Bar {}
+        export type Other = Baz;
         "#};
 
-        let mut foo_table = parse_file(ParserContext::new(Path::new("foo.ts"), foo_file)).unwrap();
-        let mut index_table =
-            parse_file(ParserContext::new(Path::new("index.ts"), index_file)).unwrap();
-
-        foo_table.resolve_types();
-        index_table.resolve_types();
-
-        let all_tables = vec![&foo_table];
+        let entities = parse_sources(
+            &[("foo.ts", foo_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
 
-        index_table.resolve_imported_types(all_tables);
+        let return_type = find_entity(&entities, "make")
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::ReturnType))
+            .unwrap();
+        assert_eq!(
+            return_type.identity,
+            Identity::Reference("foo.ts::Foo".to_owned())
+        );
+        assert_eq!(
+            find_entity(&entities, "Other").members[0].identity,
+            Identity::Reference("foo.ts::Bar".to_owned())
+        );
+    }
 
-        let symbols = index_table.all_symbols().collect::<Vec<_>>();
-        assert_eq!(symbols.len(), 1);
+    #[test]
+    fn parses_type_only_imports() {
+        let source = indoc! { r#"
+        import type { User, Role as UserRole } from "./models";
+        import { type Token, verify } from './auth';
+        import type * as Shapes from "./shapes";
+        "#};
 
-        match symbols[0]
-            .kind
-            .as_type_alias()
-            .unwrap()
-            .the_type()
-            .kind
-            .as_type()
-            .unwrap()
-        {
-            Type::Object { properties, .. } => {
-                let resolved_type = properties[0].kind.as_property().unwrap().children[0]
-                    .kind
-                    .as_type()
-                    .unwrap();
+        let table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        let imports = table.all_imports().collect::<Vec<_>>();
 
-                assert_eq!(
-                    resolved_type,
-                    &Type::Identifier("Foo".to_owned(), Some("foo.ts::Foo".to_owned()))
-                );
-            }
-            _ => panic!("Expected an object type"),
+        fn names(import: &Import) -> Vec<(&str, &str, bool)> {
+            import
+                .names
+                .iter()
+                .map(|n| (n.local.as_str(), n.imported.as_str(), n.type_only))
+                .collect()
         }
+        assert_eq!(
+            imports
+                .iter()
+                .map(|i| (i.source.as_str(), i.type_only))
+                .collect::<Vec<_>>(),
+            [("./models", true), ("./auth", false), ("./shapes", true)]
+        );
+        assert_eq!(
+            names(imports[0]),
+            [("User", "User", true), ("UserRole", "Role", true)]
+        );
+        assert_eq!(
+            names(imports[1]),
+            [("Token", "Token", true), ("verify", "verify", false)]
+        );
+        assert_eq!(names(imports[2]), [("Shapes", "Shapes", true)]);
     }
 
     #[test]
-    fn does_not_resolves_type_aliases_in_nested_symbols_across_files_if_the_referenced_type_is_not_exported(
-    ) {
-        let foo_file = indoc! { r#"
-        type Foo = string;
+    fn resolves_type_only_imports_and_exports_across_files() {
+        let models_file = indoc! { r#"
+        interface User {}
+        interface Role {}
+        export type { User };
+        export { type Role };
         "#};
 
         let index_file = indoc! { r#"
-        import { Foo } from "./foo.ts";
+        import type { User } from "./models";
+        import { type Role } from "./models";
 
-        type Bar = {
-            foo: Foo;
-        }
+        export function find(role: Role): User {}
         "#};
 
-        let mut foo_table = parse_file(ParserContext::new(Path::new("foo.ts"), foo_file)).unwrap();
-        let mut index_table =
-            parse_file(ParserContext::new(Path::new("index.ts"), index_file)).unwrap();
+        let entities = parse_sources(
+            &[("models.ts", models_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
 
-        foo_table.resolve_types();
-        index_table.resolve_types();
+        let find = find_entity(&entities, "find");
+        let identities = find
+            .members
+            .iter()
+            .map(|m| match m.kind.as_str() {
+                "parameter" => &m.members[0].identity,
+                _ => &m.identity,
+            })
+            .collect::<Vec<_>>();
 
-        let all_tables = vec![&foo_table];
+        assert_eq!(
+            identities,
+            [
+                &Identity::Reference("models.ts::Role".to_owned()),
+                &Identity::Reference("models.ts::User".to_owned()),
+            ]
+        );
+        assert!(entities
+            .iter()
+            .filter(|e| e.source.file == Path::new("models.ts"))
+            .all(|e| e.meta["exported"] == true));
+    }
 
-        index_table.resolve_imported_types(all_tables);
+    #[test]
+    fn resolves_imports_from_files_in_other_directories() {
+        let index_file = indoc! { r#"
+        import { Id } from "../shared";
+        import { User } from "./models";
 
-        let symbols = index_table.all_symbols().collect::<Vec<_>>();
-        assert_eq!(symbols.len(), 1);
+        export function find(id: Id): User {}
+        "#};
 
-        match symbols[0]
-            .kind
-            .as_type_alias()
-            .unwrap()
-            .the_type()
-            .kind
-            .as_type()
-            .unwrap()
-        {
-            Type::Object { properties, .. } => {
-                let resolved_type = properties[0].kind.as_property().unwrap().children[0]
-                    .kind
-                    .as_type()
-                    .unwrap();
+        let entities = parse_sources(
+            &[
+                ("src/app/models/index.ts", "export interface User {}"),
+                ("src/shared.ts", "export type Id = string;"),
+                ("src/app/index.ts", index_file),
+            ],
+            &mut dossier_core::Context::new(),
+        );
 
-                assert_eq!(
-                    resolved_type,
-                    &Type::Identifier("Foo".to_owned(), None),
-                    "The type should not be resolved because it is not exported"
-                );
+        let find = find_entity(&entities, "find");
+        let identities = find
+            .members
+            .iter()
+            .map(|m| match m.kind.as_str() {
+                "parameter" => &m.members[0].identity,
+                _ => &m.identity,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            identities,
+            [
+                &Identity::Reference("src/shared.ts::Id".to_owned()),
+                &Identity::Reference("src/app/models/index.ts::User".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_tsx_and_module_files() {
+        let component = indoc! { r#"
+        import { Props } from "./props.mts";
+
+        /** Greets the user */
+        export function Greeting(props: Props): JSX.Element {
+            return <h1>Hello, {props.name}</h1>;
+        }
+
+        export class Page {
+            render(): JSX.Element {
+                return <Greeting name="world" />;
             }
-            _ => panic!("Expected an object type"),
         }
+        "#};
+
+        let entities = parse_sources(
+            &[
+                ("greeting.tsx", component),
+                ("props.mts", "export interface Props { name: string }"),
+                ("legacy.cts", "export type Legacy = number;"),
+            ],
+            &mut dossier_core::Context::new(),
+        );
+
+        let greeting = find_entity(&entities, "Greeting");
+        assert_eq!(greeting.description, "Greets the user");
+        assert_eq!(
+            greeting.members[0].members[0].identity,
+            Identity::Reference("props.mts::Props".to_owned())
+        );
+        let page = find_entity(&entities, "Page");
+        assert_eq!(page.members[0].title.as_deref(), Some("render"));
+        find_entity(&entities, "Legacy");
     }
 
     #[test]
-    fn resolves_type_aliases_in_nested_symbols_across_files_if_the_referenced_type_is_exported_later_in_the_file(
-    ) {
-        let foo_file = indoc! { r#"
-        type Foo = string;
+    fn resolves_imports_without_an_extension() {
+        let index_file = indoc! { r#"
+        import { Foo } from "./types";
 
-        export { Foo };
+        export type Alias = Foo;
         "#};
 
-        let index_file = indoc! { r#"
-        import { Foo } from "./foo.ts";
+        let entities = parse_sources(
+            &[
+                ("index.ts", index_file),
+                ("types.d.ts", "export type Foo = number;"),
+                ("types.ts", "export type Foo = string;"),
+            ],
+            &mut dossier_core::Context::new(),
+        );
 
-        type Bar = {
-            foo: Foo;
+        let alias = find_entity(&entities, "Alias");
+        assert_eq!(
+            alias.members[0].identity,
+            Identity::Reference("types.ts::Foo".to_owned())
+        );
+    }
+
+    #[test]
+    fn resolves_imports_with_tsconfig_paths() {
+        let tsconfig = indoc! { r#"
+        {
+            "compilerOptions": {
+                "baseUrl": "src",
+                "paths": { "@app/*": ["app/*", "vendor/*"] }
+            }
         }
         "#};
+        let index_file = indoc! { r#"
+        import { User } from "@app/models/user";
+        import { Theme } from "@app/theme";
+        import { Id } from "shared";
+        import { Kysely } from "kysely";
 
-        let mut foo_table = parse_file(ParserContext::new(Path::new("foo.ts"), foo_file)).unwrap();
-        let mut index_table =
-            parse_file(ParserContext::new(Path::new("index.ts"), index_file)).unwrap();
+        export function find(id: Id, theme: Theme, db: Kysely): User {}
+        "#};
 
-        foo_table.resolve_types();
-        index_table.resolve_types();
+        let entities = parse_sources_and_configs(
+            &[
+                ("src/app/models/user.ts", "export interface User {}"),
+                ("src/vendor/theme/index.ts", "export type Theme = string;"),
+                ("src/shared.ts", "export type Id = string;"),
+                ("src/index.ts", index_file),
+            ],
+            &[("tsconfig.json", tsconfig)],
+            &mut dossier_core::Context::new(),
+        );
 
-        let all_tables = vec![&foo_table];
+        let find = find_entity(&entities, "find");
+        let identities = find
+            .members
+            .iter()
+            .map(|m| match m.kind.as_str() {
+                "parameter" => &m.members[0].identity,
+                _ => &m.identity,
+            })
+            .collect::<Vec<_>>();
 
-        index_table.resolve_imported_types(all_tables);
+        assert_eq!(
+            identities[..2],
+            [
+                &Identity::Reference("src/shared.ts::Id".to_owned()),
+                &Identity::Reference("src/vendor/theme/index.ts::Theme".to_owned()),
+            ]
+        );
+        assert_eq!(
+            identities[2],
+            &Identity::Reference("external:kysely::Kysely".to_owned())
+        );
+        assert_eq!(
+            identities[3],
+            &Identity::Reference("src/app/models/user.ts::User".to_owned())
+        );
+    }
 
-        let symbols = index_table.all_symbols().collect::<Vec<_>>();
-        assert_eq!(symbols.len(), 1);
+    #[test]
+    fn refers_to_names_imported_from_packages_as_external() {
+        let index_file = indoc! { r#"
+        import { Client } from "@org/pkg/client";
+        import { Stats } from "node:fs";
+        import { Missing } from "./local";
+        import { z } from "zod";
+        export { Kysely } from "kysely";
 
-        match symbols[0]
+        export function open(stats: Stats, missing: Missing, schema: z.ZodType): Client {}
+        "#};
+
+        let entities = parse_sources(
+            &[("index.ts", index_file), ("local.ts", "")],
+            &mut dossier_core::Context::new(),
+        );
+
+        let open = find_entity(&entities, "open");
+        let types = open
+            .members
+            .iter()
+            .map(|m| match m.kind.as_str() {
+                "parameter" => &m.members[0],
+                _ => m,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            types[0].identity,
+            Identity::Reference("external:node:fs::Stats".to_owned())
+        );
+        assert_eq!(types[0].meta["external_package"], "node:fs");
+        // Relative imports never refer to packages
+        assert_eq!(types[1].identity, Identity::Anonymous);
+        assert_eq!(
+            types[2].identity,
+            Identity::Reference("external:zod::z::ZodType".to_owned())
+        );
+        assert_eq!(
+            types[3].identity,
+            Identity::Reference("external:@org/pkg::Client".to_owned())
+        );
+        assert_eq!(types[3].meta["external_package"], "@org/pkg");
+
+        let reexport = find_entity(&entities, "Kysely");
+        assert_eq!(
+            reexport.identity,
+            Identity::Reference("external:kysely::Kysely".to_owned())
+        );
+    }
+
+    #[test]
+    fn records_signatures_without_bodies() {
+        let source = indoc! { r#"
+        export function identity<Type>(arg: Type): Type {
+            return arg;
+        }
+
+        @sealed
+        abstract class Box<T>
+            extends Base
+            implements Container<T> {
+            static async open(
+                path: string,
+                mode?: "r" | "w",
+            ): Promise<Box<string>> {}
+            abstract close(): void;
+        }
+
+        interface Named extends Base { name: string }
+
+        type Pair<A, B> =
+            [A, B];
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+        let signature = |entity: &Entity| entity.meta["signature"].as_str().unwrap().to_owned();
+
+        assert_eq!(
+            signature(find_entity(&entities, "identity")),
+            "function identity<Type>(arg: Type): Type"
+        );
+
+        let class = find_entity(&entities, "Box");
+        assert_eq!(
+            signature(class),
+            "abstract class Box<T> extends Base implements Container<T>"
+        );
+        assert_eq!(
+            signature(find_entity(&class.members, "open")),
+            r#"static async open(path: string, mode?: "r" | "w"): Promise<Box<string>>"#
+        );
+        assert_eq!(
+            signature(find_entity(&class.members, "close")),
+            "abstract close(): void"
+        );
+
+        assert_eq!(
+            signature(find_entity(&entities, "Named")),
+            "interface Named extends Base"
+        );
+        assert_eq!(
+            signature(find_entity(&entities, "Pair")),
+            "type Pair<A, B> = [A, B]"
+        );
+    }
+
+    #[test]
+    fn resolves_imports_from_several_modules_in_one_file() {
+        let index_file = indoc! { r#"
+        import { Foo } from "./foo.ts";
+        import { Bar } from "./bar.ts";
+
+        export function make(foo: Foo): Bar {
+            return foo.bar;
+        }
+        "#};
+
+        let entities = parse_sources(
+            &[
+                ("foo.ts", "export type Foo = { bar: Bar };"),
+                ("bar.ts", "export type Bar = string;"),
+                ("index.ts", index_file),
+            ],
+            &mut dossier_core::Context::new(),
+        );
+
+        let make = find_entity(&entities, "make");
+        let parameter = make.members.iter().find(|m| m.kind == "parameter").unwrap();
+        let return_type = make
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::ReturnType))
+            .unwrap();
+
+        assert_eq!(
+            parameter.members[0].identity,
+            Identity::Reference("foo.ts::Foo".to_owned())
+        );
+        assert_eq!(
+            return_type.identity,
+            Identity::Reference("bar.ts::Bar".to_owned())
+        );
+    }
+
+    #[test]
+    fn resolves_many_imports_regardless_of_file_order() {
+        let modules = (0..10)
+            .map(|i| {
+                (
+                    format!("m{}.ts", i),
+                    format!("export type T{} = string;", i),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut index_file = String::new();
+        for i in 0..10 {
+            index_file.push_str(&format!("import {{ T{} }} from \"./m{}.ts\";\n", i, i));
+        }
+        index_file.push_str("export type All = {\n");
+        for i in 0..10 {
+            index_file.push_str(&format!("    p{}: T{};\n", i, i));
+        }
+        index_file.push_str("};\n");
+
+        let mut sources = modules
+            .iter()
+            .map(|(path, code)| (path.as_str(), code.as_str()))
+            .collect::<Vec<_>>();
+        sources.push(("index.ts", &index_file));
+
+        for _ in 0..2 {
+            let entities = parse_sources(&sources, &mut dossier_core::Context::new());
+            let all = find_entity(&entities, "All");
+
+            for i in 0..10 {
+                let property = find_entity(&all.members[0].members, &format!("p{}", i));
+                assert_eq!(
+                    property.members[0].identity,
+                    Identity::Reference(format!("m{}.ts::T{}", i, i))
+                );
+            }
+
+            sources.reverse();
+        }
+    }
+
+    #[test]
+    fn resolves_imported_types_in_type_parameter_defaults() {
+        let index_file = indoc! { r#"
+        import { Row } from "./row.ts";
+
+        export interface Query<R = Row, T extends string = string> {}
+        "#};
+
+        let entities = parse_sources(
+            &[
+                ("row.ts", "export type Row = string[];"),
+                ("index.ts", index_file),
+            ],
+            &mut dossier_core::Context::new(),
+        );
+
+        let query = find_entity(&entities, "Query");
+        let r = &query.members[0];
+        assert_eq!(r.title.as_deref(), Some("R"));
+        assert_eq!(r.members[0].member_context, Some(MemberContext::Default));
+        assert_eq!(
+            r.members[0].identity,
+            Identity::Reference("row.ts::Row".to_owned())
+        );
+
+        let t = &query.members[1];
+        assert_eq!(t.members[0].kind, "type_constraint");
+        assert_eq!(t.members[1].member_context, Some(MemberContext::Default));
+        assert_eq!(t.members[1].title.as_deref(), Some("string"));
+    }
+
+    #[test]
+    fn picks_the_first_file_an_ambiguous_import_could_refer_to() {
+        let index_file = indoc! { r#"
+        import { Config } from "./config";
+
+        export function load(): Config {}
+        "#};
+
+        let run = |sources: &[(&str, &str)]| {
+            let mut ctx = dossier_core::Context::new();
+            let entities = parse_sources(sources, &mut ctx);
+            let return_type = find_entity(&entities, "load").members[0].identity.clone();
+
+            (return_type, ctx.diagnostics)
+        };
+
+        let file = ("config.ts", "export type Config = string;");
+        let index = ("config/index.ts", "export type Config = number;");
+
+        let (return_type, diagnostics) = run(&[index, file, ("index.ts", index_file)]);
+        assert_eq!(
+            return_type,
+            Identity::Reference("config.ts::Config".to_owned())
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, symbol_table::AMBIGUOUS_IMPORT);
+        assert!(diagnostics[0]
+            .message
+            .contains("config.ts, config/index.ts"));
+
+        let (return_type, _) = run(&[("index.ts", index_file), file, index]);
+        assert_eq!(
+            return_type,
+            Identity::Reference("config.ts::Config".to_owned())
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_and_unresolved_imports() {
+        let index = indoc! { r#"
+        import { Config } from "./missing";
+        import { Kysely } from "kysely";
+
+        export function load(config: Config, db: Kysely) {}
+
+        export interface Broken {
+            name: string
+            age number;
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", index)], &mut ctx);
+        assert!(entities.iter().any(|e| e.title.as_deref() == Some("load")));
+
+        let codes = ctx.diagnostics.iter().map(|d| d.code.as_str());
+        assert_eq!(
+            codes.collect::<Vec<_>>(),
+            [dossier_core::SYNTAX_ERROR, symbol_table::UNRESOLVED_IMPORT]
+        );
+        assert!(ctx.diagnostics[0].message.contains("line 8"));
+        assert!(ctx.diagnostics[1].message.contains("`./missing`"));
+
+        let mut ctx = dossier_core::Context::new();
+        parse_sources(&[("index.ts", "export type Id = string;")], &mut ctx);
+        assert!(ctx.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn skips_files_that_take_too_long_to_parse() {
+        let slow = (0..20_000)
+            .map(|i| format!("export interface Shape{} {{ area(): number }}\n", i))
+            .collect::<String>();
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.parse_timeout = Some(std::time::Duration::from_micros(1));
+        let entities = parse_sources(&[("slow.ts", slow.as_str())], &mut ctx);
+
+        assert!(entities.is_empty());
+        assert_eq!(ctx.diagnostics.len(), 1);
+        assert_eq!(ctx.diagnostics[0].code, parser_pool::PARSE_TIMEOUT);
+        assert_eq!(ctx.diagnostics[0].file, Path::new("slow.ts"));
+    }
+
+    #[test]
+    fn reuses_parsers_after_a_timeout() {
+        let language = tree_sitter_typescript::language_typescript();
+        let slow = "export type Id = string;\n".repeat(20_000);
+        let timeout = Some(std::time::Duration::from_micros(1));
+
+        assert!(parser_pool::parse(LANGUAGE, language, &slow, timeout).is_none());
+
+        // Doesn't resume the parse that timed out
+        let tree = parser_pool::parse(LANGUAGE, language, "let a = 1;", None).unwrap();
+        assert_eq!(
+            tree.root_node()
+                .to_sexp()
+                .matches("lexical_declaration")
+                .count(),
+            1
+        );
+        assert!(!tree.root_node().has_error());
+    }
+
+    /// Run with `cargo test --release -p dossier-ts -- --ignored --nocapture
+    /// parser_reuse` to compare parsing many small files with a new parser
+    /// for each, and with the parsers of `parser_pool`
+    #[test]
+    #[ignore]
+    fn parser_reuse_benchmark() {
+        use dossier_core::tree_sitter::Parser;
+        use std::time::Instant;
+
+        let code = indoc! { r#"
+        /** A shape */
+        export interface Shape {
+            area(): number;
+        }
+        "#};
+        let files = 5_000;
+
+        let start = Instant::now();
+        for _ in 0..files {
+            let mut parser = Parser::new();
+            parser
+                .set_language(tree_sitter_typescript::language_typescript())
+                .unwrap();
+            parser.parse(code, None).unwrap();
+        }
+        let fresh = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..files {
+            parser_pool::parse(
+                LANGUAGE,
+                tree_sitter_typescript::language_typescript(),
+                code,
+                None,
+            )
+            .unwrap();
+        }
+        let pooled = start.elapsed();
+
+        println!(
+            "{} files: {:?} with a new parser each, {:?} reusing parsers",
+            files, fresh, pooled
+        );
+    }
+
+    #[test]
+    fn links_references_in_documentation() {
+        let client = indoc! { r#"
+        export class Client {}
+        "#};
+        let index = indoc! { r#"
+        import { Client } from "./client";
+
+        /**
+         * Connects a {@link Client} with the {@link Options | given options}.
+         *
+         * Call {@link Pool#acquire} instead, or {@link Missing}.
+         */
+        export function connect(options: Options) {}
+
+        export interface Options {}
+
+        class Pool {
+            acquire() {}
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("client.ts", client), ("index.ts", index)], &mut ctx);
+
+        assert_eq!(
+            find_entity(&entities, "connect").description,
+            "Connects a [Client](#fqn:client.ts::Client) with the \
+             [given options](#fqn:index.ts::Options).\n\n\
+             Call [Pool#acquire](#fqn:index.ts::Pool::acquire) instead, or Missing."
+        );
+
+        assert_eq!(ctx.diagnostics.len(), 1);
+        assert_eq!(ctx.diagnostics[0].code, doc_links::UNRESOLVED_LINK);
+        assert_eq!(ctx.diagnostics[0].file, Path::new("index.ts"));
+        assert!(ctx.diagnostics[0].message.contains("Missing"));
+    }
+
+    #[test]
+    fn skips_declarations_with_syntax_errors() {
+        let source = indoc! { r#"
+        export function broken(a string) {
+            return a;
+        }
+
+        export interface User {
+            name: string;
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].title.as_deref(), Some("User"));
+        assert_eq!(entities[0].kind, "interface");
+
+        assert_eq!(ctx.diagnostics.len(), 1);
+        let diagnostic = &ctx.diagnostics[0];
+        assert_eq!(diagnostic.code, dossier_core::SYNTAX_ERROR);
+        assert!(diagnostic.message.contains("skipped"));
+        let range = diagnostic.range.clone().unwrap();
+        assert!(range.end <= source.find("export interface").unwrap());
+    }
+
+    #[test]
+    fn keeps_the_valid_statements_of_namespaces_with_syntax_errors() {
+        let source = indoc! { r#"
+        export namespace Shapes {
+            export interface Circle {
+                radius: number;
+            }
+
+            export type Broken = ;
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let shapes = find_entity(&entities, "Shapes");
+        let titles = shapes
+            .members
+            .iter()
+            .map(|m| m.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, ["Circle"]);
+        assert_eq!(ctx.diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn skips_files_that_do_not_parse_at_all() {
+        let source = indoc! { r#"
+        export const x = <div className="x">{value}</div>;
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        assert!(entities.is_empty());
+        assert_eq!(ctx.diagnostics.len(), 1);
+        assert_eq!(ctx.diagnostics[0].code, dossier_core::SYNTAX_ERROR);
+    }
+
+    #[test]
+    fn keeps_the_valid_members_of_classes_and_interfaces_with_syntax_errors() {
+        let source = indoc! { r#"
+        export class Api {
+            broken(a string) {
+                return a;
+            }
+
+            fetch(id: string): string {
+                return id;
+            }
+        }
+
+        export interface User {
+            rename(a string): void;
+            email: string;
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let api = find_entity(&entities, "Api");
+        let methods = api
+            .members
+            .iter()
+            .filter(|m| m.kind == "method")
+            .map(|m| m.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(methods, ["fetch"]);
+
+        let user = find_entity(&entities, "User");
+        let properties = user
+            .members
+            .iter()
+            .find(|m| m.kind == "object")
+            .unwrap()
+            .members
+            .iter()
+            .map(|m| m.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(properties, ["email"]);
+
+        assert_eq!(ctx.diagnostics.len(), 2);
+        assert!(ctx
+            .diagnostics
+            .iter()
+            .all(|d| d.code == dossier_core::SYNTAX_ERROR && d.message.contains("member")));
+    }
+
+    #[test]
+    fn resolves_type_aliases_in_nested_symbols_across_files() {
+        let foo_file = indoc! { r#"
+        export type Foo = string;
+        "#};
+
+        let index_file = indoc! { r#"
+        import { Foo } from "./foo.ts";
+
+        type Bar = {
+            foo: Foo;
+        }
+        "#};
+
+        let entities = parse_sources(
+            &[("foo.ts", foo_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
+
+        assert_eq!(
+            foo_property_type(&entities).identity,
+            Identity::Reference("foo.ts::Foo".to_owned())
+        );
+    }
+
+    #[test]
+    fn does_not_resolves_type_aliases_in_nested_symbols_across_files_if_the_referenced_type_is_not_exported(
+    ) {
+        let foo_file = indoc! { r#"
+        type Foo = string;
+        "#};
+
+        let index_file = indoc! { r#"
+        import { Foo } from "./foo.ts";
+
+        type Bar = {
+            foo: Foo;
+        }
+        "#};
+
+        let entities = parse_sources(
+            &[("foo.ts", foo_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
+
+        assert!(
+            !matches!(
+                foo_property_type(&entities).identity,
+                Identity::Reference(_)
+            ),
+            "The type should not be resolved because it is not exported"
+        );
+    }
+
+    #[test]
+    fn resolves_type_aliases_in_nested_symbols_across_files_if_the_referenced_type_is_exported_later_in_the_file(
+    ) {
+        let foo_file = indoc! { r#"
+        type Foo = string;
+
+        export { Foo };
+        "#};
+
+        let index_file = indoc! { r#"
+        import { Foo } from "./foo.ts";
+
+        type Bar = {
+            foo: Foo;
+        }
+        "#};
+
+        let entities = parse_sources(
+            &[("foo.ts", foo_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
+
+        assert_eq!(
+            foo_property_type(&entities).identity,
+            Identity::Reference("foo.ts::Foo".to_owned())
+        );
+    }
+
+    #[test]
+    fn records_inheritance_across_files() {
+        let c_file = indoc! { r#"
+        export interface C {}
+        "#};
+
+        let b_file = indoc! { r#"
+        import { C } from "./c.ts";
+
+        export interface B extends C {}
+        "#};
+
+        let a_file = indoc! { r#"
+        import { B } from "./b.ts";
+
+        export interface A extends B {}
+
+        export class Impl extends Base implements A {}
+        "#};
+
+        let entities = parse_sources(
+            &[("a.ts", a_file), ("b.ts", b_file), ("c.ts", c_file)],
+            &mut dossier_core::Context::new(),
+        );
+
+        let a = find_entity(&entities, "A");
+        assert_eq!(
+            a.meta["extends"],
+            dossier_core::serde_json::json!(["b.ts::B"])
+        );
+
+        let b = find_entity(&entities, "B");
+        assert_eq!(
+            b.meta["extends"],
+            dossier_core::serde_json::json!(["c.ts::C"])
+        );
+
+        let c = find_entity(&entities, "C");
+        assert!(c.meta.get("extends").is_none());
+
+        // `Base` isn't declared anywhere, so it stays as written
+        let class = find_entity(&entities, "Impl");
+        assert_eq!(
+            class.meta["extends"],
+            dossier_core::serde_json::json!(["Base"])
+        );
+        assert_eq!(
+            class.meta["implements"],
+            dossier_core::serde_json::json!(["a.ts::A"])
+        );
+    }
+
+    #[test]
+    fn records_every_parent_of_interfaces() {
+        let b_file = indoc! { r#"
+        export interface Named {}
+        "#};
+
+        let a_file = indoc! { r#"
+        import { Named } from "./b.ts";
+
+        interface Shape {}
+
+        interface Container<T> {}
+
+        export interface Circle extends Shape, Container<number>, Named {}
+        "#};
+
+        let entities = parse_sources(
+            &[("a.ts", a_file), ("b.ts", b_file)],
+            &mut dossier_core::Context::new(),
+        );
+
+        let circle = find_entity(&entities, "Circle");
+        assert_eq!(
+            circle.meta["extends"],
+            dossier_core::serde_json::json!(["a.ts::Shape", "a.ts::Container", "b.ts::Named"])
+        );
+
+        let parents = circle
+            .members
+            .iter()
+            .filter(|m| m.member_context == Some(MemberContext::Extends))
+            .collect::<Vec<_>>();
+        assert_eq!(parents.len(), 3);
+        assert_eq!(
+            parents[0].identity,
+            Identity::Reference("a.ts::Shape".to_owned())
+        );
+        assert_eq!(parents[1].kind, "generic_type");
+        assert_eq!(
+            parents[1].identity,
+            Identity::Reference("a.ts::Container".to_owned())
+        );
+        assert_eq!(parents[1].members[0].title.as_deref(), Some("number"));
+        assert_eq!(
+            parents[2].identity,
+            Identity::Reference("b.ts::Named".to_owned())
+        );
+    }
+
+    #[test]
+    fn gives_the_members_of_function_types_the_contexts_of_callables() {
+        let source = indoc! { r#"
+        interface Handlers {
+            onChange: <T>(value: T, previous: T) => void;
+            create: new (name: string) => Handlers;
+        }
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        let contexts = |property: &str| {
+            let the_type = &find_property(find_entity(&entities, "Handlers"), property).members[0];
+            let value = dossier_core::serde_json::to_value(the_type).unwrap();
+            value["members"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|m| m["member_context"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            contexts("onChange"),
+            ["type_parameter", "parameter", "parameter", "return_type"]
+        );
+        assert_eq!(contexts("create"), ["parameter", "return_type"]);
+    }
+
+    #[test]
+    fn includes_inherited_members() {
+        let base_file = indoc! { r#"
+        export interface Named {
+            name: string;
+        }
+
+        export interface Base extends Named {
+            id: number;
+            describe(): string;
+        }
+        "#};
+
+        let index_file = indoc! { r#"
+        import { Base, Named } from "./base.ts";
+
+        interface Left extends Base {}
+
+        interface Right extends Base {
+            describe(): string;
+        }
+
+        // Inherits `Base` along two paths, and `describe` from `Right`
+        export interface Both extends Left, Right, Named {
+            name: "both";
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.include_inherited = true;
+        let entities = parse_sources(
+            &[("base.ts", base_file), ("index.ts", index_file)],
+            &mut ctx,
+        );
+
+        let inherited = |title: &str| {
+            find_entity(&entities, title)
+                .members
+                .iter()
+                .filter(|m| m.member_context == Some(MemberContext::Inherited))
+                .map(|m| {
+                    (
+                        m.title.clone().unwrap(),
+                        m.meta["inherited_from"].as_str().unwrap().to_owned(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            inherited("Both"),
+            [
+                ("describe".to_owned(), "index.ts::Right".to_owned()),
+                ("id".to_owned(), "base.ts::Base".to_owned()),
+            ]
+        );
+        assert_eq!(
+            inherited("Left"),
+            [
+                ("id".to_owned(), "base.ts::Base".to_owned()),
+                ("describe".to_owned(), "base.ts::Base".to_owned()),
+                ("name".to_owned(), "base.ts::Named".to_owned()),
+            ]
+        );
+
+        let id = find_entity(&entities, "Left")
+            .members
+            .iter()
+            .find(|m| m.title.as_deref() == Some("id"))
+            .unwrap();
+        assert_eq!(
+            id.identity,
+            Identity::Reference("base.ts::Base::id".to_owned())
+        );
+        assert_eq!(id.members[0].title.as_deref(), Some("number"));
+    }
+
+    #[test]
+    fn declares_each_fqn_once_with_inherited_members() {
+        let source = indoc! { r#"
+        export class Base {
+            find<T>(id: string, fallback?: T): T | undefined {
+                return fallback;
+            }
+        }
+
+        export class Child extends Base {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.include_inherited = true;
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let find = find_entity(&entities, "Child")
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::Inherited))
+            .unwrap();
+        assert_eq!(find.title.as_deref(), Some("find"));
+        assert!(find.members.iter().any(|m| m.kind == "parameter"));
+
+        let mut declared = HashSet::new();
+        for node in dossier_core::tree::walk(&entities) {
+            if let Identity::FQN(fqn) = &node.entity.identity {
+                assert!(declared.insert(fqn.clone()), "{} declared twice", fqn);
+            }
+        }
+    }
+
+    #[test]
+    fn does_not_include_inherited_members_by_default() {
+        let source = indoc! { r#"
+        interface Base { id: number; }
+        interface Child extends Base {}
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        assert!(find_entity(&entities, "Child")
+            .members
+            .iter()
+            .all(|m| m.member_context != Some(MemberContext::Inherited)));
+    }
+
+    #[test]
+    fn describes_the_branches_of_conditional_types() {
+        let animals_file = indoc! { r#"
+        export interface Animal {}
+        export interface Dog {}
+        "#};
+
+        let index_file = indoc! { r#"
+        import { Animal, Dog } from "./animals.ts";
+
+        export type Pets<T> = T extends Animal
+            ? T extends Dog ? Dog[] : T[]
+            : never;
+        "#};
+
+        let entities = parse_sources(
+            &[("animals.ts", animals_file), ("index.ts", index_file)],
+            &mut dossier_core::Context::new(),
+        );
+
+        // After the type parameter `T`
+        let conditional = &find_entity(&entities, "Pets").members[1];
+        assert_eq!(conditional.kind, "conditional_type");
+        assert_eq!(
+            conditional.meta,
+            dossier_core::serde_json::json!({
+                "checked": { "short": "T", "reference": "index.ts::Pets::T" },
+                "extends": { "short": "Animal", "reference": "animals.ts::Animal" },
+                "true_branch": { "short": "T extends Dog ? Dog[] : T[]" },
+                "false_branch": { "short": "never" },
+            })
+        );
+
+        let nested = &conditional.members[2];
+        assert_eq!(nested.kind, "conditional_type");
+        assert_eq!(nested.meta, dossier_core::serde_json::json!({}));
+    }
+
+    #[test]
+    fn type_variables_shadow_outer_declarations() {
+        let source = indoc! { r#"
+        type T = string;
+
+        export interface Box<T> {
+            value: T;
+            map<T>(fn: (value: T) => T): T;
+        }
+
+        export type Alias = T;
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        let reference = |entity: &Entity| match &entity.identity {
+            Identity::Reference(fqn) => fqn.clone(),
+            other => panic!("Expected a reference, got {:?}", other),
+        };
+
+        let the_box = find_entity(&entities, "Box");
+        let value = find_property(the_box, "value");
+        assert_eq!(reference(&value.members[0]), "index.ts::Box::T");
+
+        let object = the_box.members.iter().find(|m| m.kind == "object").unwrap();
+        let map = find_entity(&object.members, "map");
+        let return_type = map
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::ReturnType))
+            .unwrap();
+        assert_eq!(reference(return_type), "index.ts::Box::map::T");
+
+        let alias = find_entity(&entities, "Alias");
+        assert_eq!(reference(&alias.members[0]), "index.ts::T");
+    }
+
+    #[test]
+    fn resolves_type_aliases_to_nearest_symbol() {
+        let source = indoc! { r#"
+        type Foo = string;
+
+        function identity<Foo>(arg: Foo): Foo {
+            return arg;
+        }
+        "#};
+
+        let mut table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+
+        table.resolve_types();
+
+        let symbols = table.all_symbols().collect::<Vec<_>>();
+        assert_eq!(symbols.len(), 2);
+
+        // Find the return type and make sure it has resolved to the FQN of the
+        // type variable `Foo`, and not the symbol `Foo` that is a type alias, and
+        // in a lower scope
+        let return_type = symbols[1]
             .kind
-            .as_type_alias()
+            .as_function()
+            .unwrap()
+            .return_type()
+            .unwrap()
+            .kind
+            .as_type()
+            .unwrap();
+
+        assert_eq!(
+            return_type,
+            &Type::Identifier("Foo".to_owned(), Some("index.ts::identity::Foo".to_owned()))
+        );
+    }
+
+    #[test]
+    fn resolves_type_variables_of_type_aliases_in_their_own_scope() {
+        let source = indoc! { r#"
+        type T = string;
+
+        type Box<T> = { value: T };
+
+        type List<T> = T[];
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        let value = find_property(find_entity(&entities, "Box"), "value");
+
+        assert_eq!(
+            value.members[0].identity,
+            Identity::Reference("index.ts::Box::T".to_owned())
+        );
+
+        let list = find_entity(&entities, "List");
+        assert_eq!(
+            list.members[1].members[0].identity,
+            Identity::Reference("index.ts::List::T".to_owned())
+        );
+    }
+
+    #[test]
+    fn orders_the_members_of_classes() {
+        let source = indoc! { r#"
+        export class Repository extends Base implements Iterable<Row> {
+            find<Id extends number>(id: Id): Row {}
+
+            table: string;
+
+            constructor(table: string) {}
+        }
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        let summary = |entity: &Entity| {
+            entity
+                .members
+                .iter()
+                .map(|m| {
+                    (
+                        m.member_context.as_ref().unwrap().to_string(),
+                        m.title.clone().unwrap_or_default(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let class = find_entity(&entities, "Repository");
+        assert_eq!(
+            summary(class),
+            vec![
+                ("extends".to_owned(), "Base".to_owned()),
+                ("implements".to_owned(), "Iterable".to_owned()),
+                ("member".to_owned(), "find".to_owned()),
+                ("member".to_owned(), "table".to_owned()),
+                ("member".to_owned(), "constructor".to_owned()),
+            ]
+        );
+        assert_eq!(
+            summary(&class.members[2]),
+            vec![
+                ("type_parameter".to_owned(), "Id".to_owned()),
+                ("parameter".to_owned(), "id".to_owned()),
+                ("return_type".to_owned(), "Row".to_owned()),
+            ]
+        );
+    }
+
+    /// Runs the parser over in-memory files
+    fn parse_sources(sources: &[(&str, &str)], ctx: &mut dossier_core::Context) -> Vec<Entity> {
+        parse_sources_and_configs(sources, &[], ctx)
+    }
+
+    /// Like `parse_sources`, with other files the parser reads but doesn't
+    /// parse as sources, like `tsconfig.json`
+    fn parse_sources_and_configs(
+        sources: &[(&str, &str)],
+        configs: &[(&str, &str)],
+        ctx: &mut dossier_core::Context,
+    ) -> Vec<Entity> {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        for (path, code) in sources.iter().chain(configs) {
+            files.add_file(*path, *code);
+        }
+        ctx.file_source = std::sync::Arc::new(files);
+
+        TypeScriptParser::new()
+            .parse(
+                &sources
+                    .iter()
+                    .map(|(path, _)| Path::new(path))
+                    .collect::<Vec<_>>(),
+                ctx,
+            )
+            .unwrap()
+    }
+
+    fn find_entity<'a>(entities: &'a [Entity], title: &str) -> &'a Entity {
+        entities
+            .iter()
+            .find(|e| e.title.as_deref() == Some(title))
+            .unwrap()
+    }
+
+    fn find_property<'a>(interface: &'a Entity, title: &str) -> &'a Entity {
+        let object = interface
+            .members
+            .iter()
+            .find(|m| m.kind == "object")
+            .unwrap();
+
+        find_entity(&object.members, title)
+    }
+
+    #[test]
+    fn pairs_getters_and_setters_in_object_types() {
+        let source = indoc! { r#"
+        export interface Cursor {
+            /** The number of rows */
+            get length(): number;
+            /** Where the cursor is */
+            get position(): number;
+            set position(value: number);
+            set label(value: string);
+        }
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+        let cursor = find_entity(&entities, "Cursor");
+
+        let length = find_property(cursor, "length");
+        assert_eq!(length.kind, "method");
+        assert_eq!(length.meta["accessor"], "get");
+
+        let position = find_property(cursor, "position");
+        assert_eq!(position.kind, "property");
+        assert_eq!(position.description, "Where the cursor is");
+        assert_eq!(
+            position.identity,
+            Identity::FQN("index.ts::Cursor::position".to_owned())
+        );
+        assert_eq!(position.meta["getter"], true);
+        assert_eq!(position.meta["setter"], true);
+        assert_eq!(position.members.len(), 1);
+        assert_eq!(position.members[0].kind, "predefined_type");
+        assert_eq!(position.members[0].title.as_deref(), Some("number"));
+
+        let label = find_property(cursor, "label");
+        assert_eq!(label.kind, "method");
+        assert_eq!(label.meta["accessor"], "set");
+
+        let object = cursor.members.iter().find(|m| m.kind == "object").unwrap();
+        assert_eq!(object.members.len(), 3);
+    }
+
+    #[test]
+    fn public_only_keeps_unexported_types_that_public_signatures_use() {
+        let source = indoc! { r#"
+        interface Options {
+            verbose: boolean;
+        }
+
+        interface Unused {
+            x: number;
+        }
+
+        function helper(): void {}
+
+        export function configure(): Options {
+            return { verbose: true };
+        }
+
+        export class Service {
+            private cache: string;
+            protected retries: number;
+            name: string;
+        }
+
+        interface Listed {}
+
+        export { Listed };
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.public_only = true;
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let titles = entities
+            .iter()
+            .map(|e| e.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Options", "configure", "Service", "Listed"]);
+
+        // The referenced interface is kept in full, so the reference resolves
+        let options = find_entity(&entities, "Options");
+        assert_eq!(
+            find_property(options, "verbose").title.as_deref(),
+            Some("verbose")
+        );
+        let return_type = &find_entity(&entities, "configure").members[0];
+        assert_eq!(
+            return_type.identity,
+            Identity::Reference("index.ts::Options".to_owned())
+        );
+
+        let fields = find_entity(&entities, "Service")
+            .members
+            .iter()
+            .map(|m| m.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["retries", "name"]);
+    }
+
+    #[test]
+    fn strips_internal_declarations_and_references_to_them() {
+        let source = indoc! { r#"
+        /** @internal */
+        export interface Handle {
+            fd: number;
+        }
+
+        export class Service {
+            /** @hidden */
+            debug(): void {}
+
+            name: string;
+        }
+
+        /** Opens a file */
+        export function open(): Handle {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.strip_internal = true;
+        ctx.public_only = true;
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let titles = entities
+            .iter()
+            .map(|e| e.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Service", "open"]);
+
+        let members = find_entity(&entities, "Service")
+            .members
+            .iter()
+            .map(|m| m.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(members, vec!["name"]);
+
+        // Not kept for `--public-only`, and not pointing at a missing entity
+        let return_type = &find_entity(&entities, "open").members[0];
+        assert_eq!(return_type.title.as_deref(), Some("Handle"));
+        assert_eq!(return_type.identity, Identity::Anonymous);
+    }
+
+    #[test]
+    fn lists_type_and_value_reexports_of_a_package_entry() {
+        let models = indoc! { r#"
+        export interface User {
+            name: string;
+        }
+
+        export type Role = "admin" | "guest";
+        "#};
+
+        let factory = indoc! { r#"
+        import { User } from "./models";
+
+        export function createUser(name: string): User {
+            return { name };
+        }
+
+        export function internalHelper(): void {}
+        "#};
+
+        let index = indoc! { r#"
+        interface Token {
+            value: string;
+        }
+
+        export type { User, Role as UserRole } from "./internal/models";
+        export { createUser } from "./internal/factory";
+        export type { Token };
+        "#};
+
+        let app = indoc! { r#"
+        import { User } from "./index";
+
+        export type Admin = User;
+        "#};
+
+        let sources = [
+            ("internal/models.ts", models),
+            ("internal/factory.ts", factory),
+            ("index.ts", index),
+            ("app.ts", app),
+        ];
+        let entities = parse_sources(&sources, &mut dossier_core::Context::new());
+
+        let reexports = entities
+            .iter()
+            .filter(|e| e.kind == "reexport")
+            .map(|e| {
+                (
+                    e.title.as_deref().unwrap(),
+                    e.identity.clone(),
+                    e.meta.get("type_only").is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reexports,
+            [
+                (
+                    "User",
+                    Identity::Reference("internal/models.ts::User".to_owned()),
+                    true
+                ),
+                (
+                    "UserRole",
+                    Identity::Reference("internal/models.ts::Role".to_owned()),
+                    true
+                ),
+                (
+                    "createUser",
+                    Identity::Reference("internal/factory.ts::createUser".to_owned()),
+                    false
+                ),
+            ]
+        );
+        assert_eq!(find_entity(&entities, "UserRole").meta["original"], "Role");
+
+        // Importing from the entry follows the re-export to the declaration
+        let admin = find_entity(&entities, "Admin");
+        assert_eq!(
+            admin.members[0].identity,
+            Identity::Reference("internal/models.ts::User".to_owned())
+        );
+
+        // Names only exported as types are public too
+        let mut ctx = dossier_core::Context::new();
+        ctx.public_only = true;
+        let entities = parse_sources(&sources, &mut ctx);
+        let titles = entities
+            .iter()
+            .map(|e| e.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert!(titles.contains(&"Token"));
+    }
+
+    #[test]
+    fn resolves_two_hop_alias_chains_across_files() {
+        let models = indoc! { r#"
+        export interface User {
+            name: string;
+        }
+
+        export type UserId = User;
+        "#};
+
+        let index = indoc! { r#"
+        import { UserId } from "./models.ts";
+
+        type ID = UserId;
+
+        interface Admin extends ID {
+            id: ID;
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.resolve_alias_chains = true;
+        let entities = parse_sources(&[("models.ts", models), ("index.ts", index)], &mut ctx);
+
+        let admin = find_entity(&entities, "Admin");
+
+        let extends = admin
+            .members
+            .iter()
+            .find(|m| m.member_context == Some(MemberContext::Extends))
+            .unwrap();
+        assert_eq!(
+            extends.identity,
+            Identity::Reference("index.ts::ID".to_owned())
+        );
+        assert_eq!(extends.meta["resolves_to"], "models.ts::User");
+
+        let id_type = &find_property(admin, "id").members[0];
+        assert_eq!(
+            id_type.identity,
+            Identity::Reference("index.ts::ID".to_owned())
+        );
+        assert_eq!(id_type.meta["resolves_to"], "models.ts::User");
+
+        // A single hop does not need enriching, the reference already points at the end
+        let alias = find_entity(&entities, "UserId");
+        assert_eq!(
+            alias.members[0].identity,
+            Identity::Reference("models.ts::User".to_owned())
+        );
+        assert!(alias.members[0].meta.get("resolves_to").is_none());
+    }
+
+    #[test]
+    fn stops_following_recursive_aliases() {
+        let source = indoc! { r#"
+        type A = B;
+        type B = A;
+
+        interface Foo {
+            bar: A;
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.resolve_alias_chains = true;
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let bar_type = &find_property(find_entity(&entities, "Foo"), "bar").members[0];
+        assert_eq!(
+            bar_type.identity,
+            Identity::Reference("index.ts::A".to_owned())
+        );
+        assert!(bar_type.meta.get("resolves_to").is_none());
+    }
+
+    #[test]
+    fn does_not_follow_alias_chains_by_default() {
+        let source = indoc! { r#"
+        interface User {}
+        type UserId = User;
+        type ID = UserId;
+        type Key = ID;
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        let key = find_entity(&entities, "Key");
+        assert!(key.members[0].meta.get("resolves_to").is_none());
+    }
+
+    #[test]
+    fn uses_forward_slashes_for_windows_paths() {
+        let entities = parse_sources(
+            &[(r"src\shapes.ts", "export class Circle {}")],
+            &mut dossier_core::Context::new(),
+        );
+
+        let circle = find_entity(&entities, "Circle");
+        assert_eq!(
+            circle.identity,
+            Identity::FQN("src/shapes.ts::Circle".to_owned())
+        );
+        assert_eq!(
+            dossier_core::serde_json::to_value(&circle.source).unwrap()["file"],
+            "src/shapes.ts"
+        );
+        assert_eq!(circle.slug().as_deref(), Some("src-shapes-ts-circle"));
+    }
+
+    #[test]
+    fn records_root_fqns_of_nested_members() {
+        let source = indoc! { r#"
+        export namespace Shapes {
+            export class Circle {
+                resize(radius: number): void {}
+            }
+        }
+        export function area(): number {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.root_fqns = true;
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let shapes = find_entity(&entities, "Shapes");
+        let circle = &shapes.members[0];
+        let resize = &circle.members[0];
+        let radius = &resize.members[0];
+        assert_eq!(radius.title.as_deref(), Some("radius"));
+        for entity in [shapes, circle, resize, radius] {
+            assert_eq!(entity.meta["root_fqn"], "index.ts::Shapes");
+        }
+
+        let area = find_entity(&entities, "area");
+        assert_eq!(area.meta["root_fqn"], "index.ts::area");
+        assert_eq!(area.members[0].meta["root_fqn"], "index.ts::area");
+    }
+
+    #[test]
+    fn resolves_names_in_namespaces_from_other_files() {
+        let validation = indoc! { r#"
+        export namespace Validation {
+            export interface StringValidator {
+                isAcceptable(s: string): boolean;
+            }
+        }
+
+        export namespace Rules.Strings {
+            export type Rule = string;
+            type Internal = string;
+        }
+        "#};
+        let form = indoc! { r#"
+        import { Validation, Rules } from "./validation";
+
+        export function validate(
+            validator: Validation.StringValidator,
+            rule: Rules.Strings.Rule,
+            internal: Rules.Strings.Internal,
+        ) {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(
+            &[("validation.ts", validation), ("form.ts", form)],
+            &mut ctx,
+        );
+
+        let parameters = &find_entity(&entities, "validate").members;
+        assert_eq!(
+            parameters[0].members[0].identity,
+            Identity::Reference("validation.ts::Validation::StringValidator".to_owned())
+        );
+        assert_eq!(
+            parameters[1].members[0].identity,
+            Identity::Reference("validation.ts::Rules::Strings::Rule".to_owned())
+        );
+        // Only what namespaces export can be reached from other files
+        assert!(!matches!(
+            &parameters[2].members[0].identity,
+            Identity::Reference(fqn) if fqn.starts_with("validation.ts")
+        ));
+    }
+
+    #[test]
+    fn resolves_aliases_of_imported_names_across_files() {
+        let models = indoc! { r#"
+        export namespace Models {
+            export interface User {}
+        }
+        "#};
+        let api = indoc! { r#"
+        import { Models } from "./models";
+
+        export namespace Api {
+            export import User = Models.User;
+        }
+        "#};
+        let admin = indoc! { r#"
+        import { Api } from "./api";
+
+        export type Admin = Api.User;
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(
+            &[("admin.ts", admin), ("api.ts", api), ("models.ts", models)],
+            &mut ctx,
+        );
+
+        let alias = &find_entity(&entities, "Api").members[0];
+        assert_eq!(
+            alias.identity,
+            Identity::Reference("models.ts::Models::User".to_owned())
+        );
+        // Resolved a pass after the alias itself
+        assert_eq!(
+            find_entity(&entities, "Admin").members[0].identity,
+            Identity::Reference("models.ts::Models::User".to_owned())
+        );
+    }
+
+    #[test]
+    fn records_the_source_text_of_entities() {
+        let source = indoc! { r#"
+        /** A point */
+        export class Point {
+            constructor(public x: number) {}
+
+            norm(): number {
+                return Math.abs(this.x);
+            }
+        }
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.include_source_text = true;
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let class = find_entity(&entities, "Point");
+        let (start, end) = (class.source.start.byte_offset, class.source.end.byte_offset);
+        assert_eq!(class.meta["source_text"], &source[start..end]);
+        assert!(class.meta["source_text"]
+            .as_str()
             .unwrap()
-            .the_type()
-            .kind
-            .as_type()
+            .starts_with("class Point {"));
+
+        let norm = class
+            .members
+            .iter()
+            .find(|m| m.kind == "method" && m.title.as_deref() == Some("norm"))
+            .unwrap();
+        assert_eq!(
+            norm.meta["source_text"],
+            "norm(): number {\n        return Math.abs(this.x);\n    }"
+        );
+
+        ctx.max_source_bytes = Some(11);
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let class = find_entity(&entities, "Point");
+        assert_eq!(class.meta["source_text"], "class Point…");
+        assert_eq!(class.meta["source_text_truncated"], true);
+    }
+
+    #[test]
+    fn names_meta_keys_the_same_for_every_kind() {
+        let source = indoc! { r#"
+        export async function load(id: Id, retries?: number): Promise<User> {}
+
+        export abstract class Repository {
+            static count = 0;
+            protected readonly label: string;
+
+            abstract find(key: Id): User;
+
+            private async save(user: User): Promise<void> {}
+        }
+
+        export interface User {
+            readonly id: Id;
+            name?: string;
+        }
+
+        export type Id = string;
+
+        export namespace Storage {}
+
+        export const MAX = 3;
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let kinds = [
+            "function",
+            "parameter",
+            "class",
+            "field",
+            "method",
+            "interface",
+            "property",
+            "type_alias",
+            "namespace",
+            "variable",
+        ];
+        let keys = dossier_core::tree::walk(&entities)
+            .map(|node| node.entity)
+            .filter(|e| kinds.contains(&e.kind.as_str()))
+            .map(|e| {
+                let mut keys = e
+                    .meta
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                keys.sort();
+                (format!("{} {}", e.kind, e.title.as_deref().unwrap()), keys)
+            })
+            .collect::<std::collections::BTreeMap<_, _>>();
+
+        let expected: [(&str, Vec<&str>); 16] = [
+            (
+                "function load",
+                vec!["async", "exported", "resolved_return_type", "signature"],
+            ),
+            ("parameter id", vec![]),
+            ("parameter key", vec![]),
+            ("parameter retries", vec!["optional"]),
+            ("parameter user", vec![]),
+            (
+                "class Repository",
+                vec!["abstract", "exported", "signature"],
+            ),
+            ("field count", vec!["static", "value"]),
+            ("field label", vec!["protected", "readonly"]),
+            ("method find", vec!["abstract", "signature"]),
+            (
+                "method save",
+                vec!["async", "private", "resolved_return_type", "signature"],
+            ),
+            ("interface User", vec!["exported", "signature"]),
+            ("property id", vec!["readonly"]),
+            ("property name", vec!["optional"]),
+            ("type_alias Id", vec!["exported", "signature"]),
+            ("namespace Storage", vec!["exported"]),
+            (
+                "variable MAX",
+                vec!["const", "exported", "inferred_type", "value"],
+            ),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(entity, keys)| {
+                let keys = keys.into_iter().map(String::from).collect::<Vec<_>>();
+                (entity.to_owned(), keys)
+            })
+            .collect::<std::collections::BTreeMap<_, _>>();
+
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn leaves_the_text_of_object_types_out() {
+        let source = indoc! { r#"
+        export function configure(options: { retries: number; timeout: number }) {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let configure = find_entity(&entities, "configure");
+        let object = &configure.members[0].members[0];
+        assert_eq!(object.kind, "object");
+        assert_eq!(object.title.as_deref(), Some("object"));
+        assert_eq!(object.meta, dossier_core::serde_json::json!({}));
+        assert_eq!(object.members.len(), 2);
+
+        // The function's signature has the text, but the object type doesn't
+        let json = dossier_core::serde_json::to_string(object).unwrap();
+        assert!(!json.contains("retries: number"));
+
+        ctx.include_source_text = true;
+        ctx.max_source_bytes = Some(10);
+        let entities = parse_sources(&[("index.ts", source)], &mut ctx);
+
+        let object = &find_entity(&entities, "configure").members[0].members[0];
+        assert_eq!(object.meta["source_text"], "{ retries:…");
+        assert_eq!(object.meta["source_text_truncated"], true);
+    }
+
+    #[test]
+    fn leaves_the_text_of_object_types_out_of_the_json_of_a_fixture() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../fixtures/golden/typescript/glob/index.d.ts");
+        let code = std::fs::read_to_string(&fixture).unwrap();
+
+        let parse = |include_source_text: bool| {
+            let mut ctx = dossier_core::Context::new();
+            ctx.include_source_text = include_source_text;
+            TypeScriptParser::new()
+                .parse(&[fixture.as_path()], &mut ctx)
+                .unwrap()
+        };
+
+        let entities = parse(false);
+        let objects = dossier_core::tree::walk(&entities)
+            .map(|item| item.entity)
+            .filter(|entity| entity.kind == "object")
+            .collect::<Vec<_>>();
+        assert!(!objects.is_empty());
+
+        let mut object_bytes = 0;
+        for object in &objects {
+            let text = &code[object.source.start.byte_offset..object.source.end.byte_offset];
+            object_bytes += text.len();
+
+            let json = dossier_core::serde_json::to_string(object).unwrap();
+            assert!(!json.contains(text), "{}", text);
+        }
+
+        // Before, with the text of every entity, and after, by default
+        let before = dossier_core::serde_json::to_string(&parse(true)).unwrap();
+        let after = dossier_core::serde_json::to_string(&entities).unwrap();
+        assert!(after.len() + object_bytes <= before.len());
+    }
+
+    #[test]
+    fn names_files_relative_to_the_root() {
+        let user = indoc! { r#"
+        export interface User {}
+        "#};
+
+        let index = indoc! { r#"
+        import { User } from "./models/user";
+
+        export function createClient(user: User) {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.root = Some(PathBuf::from("/repo/packages/client/src"));
+        ctx.include_source_text = true;
+        let entities = parse_sources(
+            &[
+                ("/repo/packages/client/src/index.ts", index),
+                ("/repo/packages/client/src/models/user.ts", user),
+            ],
+            &mut ctx,
+        );
+
+        let create = find_entity(&entities, "createClient");
+        assert_eq!(
+            create.identity,
+            Identity::FQN("index.ts::createClient".to_owned())
+        );
+        assert_eq!(create.source.file, Path::new("index.ts"));
+        assert_eq!(
+            create.members[0].members[0].identity,
+            Identity::Reference("models/user.ts::User".to_owned())
+        );
+        assert_eq!(create.members[0].source.file, Path::new("index.ts"));
+        // Still read from where the file is
+        assert!(create.meta["source_text"]
+            .as_str()
             .unwrap()
-        {
-            Type::Object { properties, .. } => {
-                let resolved_type = properties[0].kind.as_property().unwrap().children[0]
-                    .kind
-                    .as_type()
-                    .unwrap();
+            .starts_with("function createClient"));
 
-                assert_eq!(
-                    resolved_type,
-                    &Type::Identifier("Foo".to_owned(), Some("foo.ts::Foo".to_owned()))
-                );
+        let user = find_entity(&entities, "User");
+        assert_eq!(
+            user.identity,
+            Identity::FQN("models/user.ts::User".to_owned())
+        );
+        let value = dossier_core::serde_json::to_value(user).unwrap();
+        assert_eq!(value["source"]["file"], "models/user.ts");
+    }
+
+    #[test]
+    fn names_files_by_their_package() {
+        let user = indoc! { r#"
+        export interface User {}
+        "#};
+
+        let index = indoc! { r#"
+        import { User } from "./models/user";
+
+        export function createClient(user: User) {}
+        "#};
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.module_names = true;
+        let entities = parse_sources_and_configs(
+            &[
+                ("packages/client/src/index.ts", index),
+                ("packages/client/src/models/user.ts", user),
+            ],
+            &[(
+                "packages/client/package.json",
+                r#"{ "name": "@myorg/client" }"#,
+            )],
+            &mut ctx,
+        );
+
+        let create = find_entity(&entities, "createClient");
+        assert_eq!(
+            create.identity,
+            Identity::FQN("@myorg/client/src::createClient".to_owned())
+        );
+        assert_eq!(
+            create.members[0].members[0].identity,
+            Identity::Reference("@myorg/client/src/models/user::User".to_owned())
+        );
+        assert_eq!(
+            create.source.file,
+            Path::new("packages/client/src/index.ts")
+        );
+    }
+
+    #[test]
+    fn gives_types_no_fqns_of_their_own() {
+        let source = indoc! { r#"
+        export type Event =
+            | { kind: "click"; position: { x: number; y: number } }
+            | { kind: "key"; key: string | number };
+
+        export interface Options {
+            mode: "fast" | "slow" | `custom-${string}`;
+            nested: { deep: { value: number | null } };
+            callback: (event: Event, retries: number) => Missing;
+        }
+
+        export function handle(event: Event, options?: Options): string | number | undefined {}
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+
+        fn check(entity: &Entity) {
+            let mut siblings = HashSet::new();
+            for member in &entity.members {
+                if let Identity::FQN(fqn) = &member.identity {
+                    assert!(
+                        siblings.insert(fqn),
+                        "{} is shared by members of {:?}",
+                        fqn,
+                        entity.title
+                    );
+                }
+                check(member);
+            }
+
+            if matches!(
+                entity.kind.as_str(),
+                "predefined_type" | "literal" | "template_literal_type" | "identifier" | "union"
+            ) {
+                assert!(!matches!(entity.identity, Identity::FQN(_)), "{:?}", entity);
             }
-            _ => panic!("Expected an object type"),
         }
+        for entity in &entities {
+            check(entity);
+        }
+
+        let callback = find_entity(&entities, "Options").members[0].members[2].members[0].clone();
+        assert_eq!(
+            callback.members[0].members[0].identity,
+            Identity::Reference("index.ts::Event".to_owned())
+        );
+        // Not declared anywhere
+        assert_eq!(callback.members[2].identity, Identity::Anonymous);
     }
 
     #[test]
-    fn resolves_type_aliases_to_nearest_symbol() {
+    fn links_entities_to_their_repository() {
         let source = indoc! { r#"
-        type Foo = string;
+        interface Foo {
+            bar: string;
+        }
+        "#};
 
-        function identity<Foo>(arg: Foo): Foo {
-            return arg;
+        let repository = Repository {
+            url: "https://github.com/Doctave/dossier".to_owned(),
+            revision: "main".to_owned(),
+            root: std::env::current_dir().unwrap(),
+        };
+
+        let ctx = ParserContext::new(Path::new("src/index.ts"), source)
+            .with_repository(Some(&repository));
+        let table = parse_file(ctx).unwrap();
+        let entity = table.all_symbols().next().unwrap().as_entity();
+
+        assert_eq!(
+            entity.source.repository.as_deref(),
+            Some("https://github.com/Doctave/dossier/blob/main/src/index.ts#L1-L3")
+        );
+
+        let property = &entity.members[0].members[0];
+        assert_eq!(
+            property.source.repository.as_deref(),
+            Some("https://github.com/Doctave/dossier/blob/main/src/index.ts#L2-L2")
+        );
+    }
+
+    #[test]
+    fn serializes_source_positions() {
+        let source = indoc! { r#"
+        import { Foo } from "./foo.ts";
+
+        export function makeFoo(): Foo {
+            return new Foo();
         }
         "#};
 
-        let mut table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        let table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        let entity = table.all_symbols().next().unwrap().as_entity();
+
+        let json = dossier_core::serde_json::to_value(entity).unwrap();
+        let start = &json["source"]["start"];
+        let end = &json["source"]["end"];
+
+        // Declared on line 3, and rows start from zero
+        assert_eq!(start["row"], 2);
+        assert_eq!(start["column"], 7);
+        assert_eq!(start["byte_offset"], 40);
+        assert_eq!(end["row"], 4);
+        assert_eq!(end["column"], 1);
+    }
+
+    #[test]
+    fn resolves_constrained_infer_types_in_consequence() {
+        let source = indoc! { r#"
+        type Unwrap<T> = T extends infer U extends string ? U : never;
+        "#};
 
+        let mut table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
         table.resolve_types();
 
         let symbols = table.all_symbols().collect::<Vec<_>>();
-        assert_eq!(symbols.len(), 2);
+        let conditional = symbols[0].kind.as_type_alias().unwrap().the_type();
 
-        // Find the return type and make sure it has resolved to the FQN of the
-        // type variable `Foo`, and not the symbol `Foo` that is a type alias, and
-        // in a lower scope
-        let return_type = symbols[1]
+        let infer = conditional
             .kind
-            .as_function()
-            .unwrap()
-            .return_type()
+            .as_type()
             .unwrap()
+            .conditional_right()
+            .unwrap();
+        let variable = infer.children()[0].kind.as_type_variable().unwrap();
+        assert_eq!(variable.identifier, "U");
+
+        let constraint = variable.constraints().next().unwrap();
+        assert_eq!(
+            constraint
+                .kind
+                .as_type_constraint()
+                .unwrap()
+                .the_type()
+                .kind
+                .as_type(),
+            Some(&Type::Predefined("string".to_owned()))
+        );
+
+        let consequence = conditional
             .kind
             .as_type()
+            .unwrap()
+            .conditional_consequence()
             .unwrap();
+        assert_eq!(
+            consequence.kind.as_type(),
+            Some(&Type::Identifier(
+                "U".to_owned(),
+                Some(infer.children()[0].fqn.clone().unwrap())
+            ))
+        );
+
+        let entity = infer.as_entity();
+        assert_eq!(entity.kind, "infer_type");
+        assert_eq!(entity.members[0].title.as_deref(), Some("U"));
+        assert_eq!(entity.members[0].members[0].kind, "type_constraint");
+        assert_eq!(entity.members[0].members[0].meta["extends"], true);
+    }
+
+    #[test]
+    fn resolves_the_type_arguments_of_generics() {
+        let source = indoc! { r#"
+        interface Base<X> {}
+
+        interface QueryResult<T> extends Promise<T>, Base<T> {}
+
+        type Rows<R> = Array<Map<string, R>>;
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
 
+        let extends = find_entity(&entities, "QueryResult")
+            .members
+            .iter()
+            .filter(|m| m.member_context == Some(MemberContext::Extends))
+            .collect::<Vec<_>>();
+        assert_eq!(extends[0].title.as_deref(), Some("Promise"));
+        assert_eq!(extends[0].identity, Identity::Anonymous);
         assert_eq!(
-            return_type,
-            &Type::Identifier("Foo".to_owned(), Some("index.ts::identity::Foo".to_owned()))
+            extends[0].members[0].identity,
+            Identity::Reference("index.ts::QueryResult::T".to_owned())
+        );
+        assert_eq!(
+            extends[1].identity,
+            Identity::Reference("index.ts::Base".to_owned())
+        );
+
+        let map = &find_entity(&entities, "Rows")
+            .members
+            .iter()
+            .find(|m| m.kind == "generic_type")
+            .unwrap()
+            .members[0];
+        assert_eq!(map.title.as_deref(), Some("Map"));
+        assert_eq!(map.members.len(), 2);
+        assert_eq!(
+            dossier_core::serde_json::to_value(&map.members[1]).unwrap()["member_context"],
+            "type_argument"
+        );
+        assert_eq!(
+            map.members[1].identity,
+            Identity::Reference("index.ts::Rows::R".to_owned())
+        );
+
+        // Using a generic doesn't declare anything under its name
+        fn fqns(entity: &Entity, out: &mut Vec<String>) {
+            if let Identity::FQN(fqn) = &entity.identity {
+                out.push(fqn.clone());
+            }
+            for member in &entity.members {
+                fqns(member, out);
+            }
+        }
+        let mut all = vec![];
+        for entity in &entities {
+            fqns(entity, &mut all);
+        }
+        for name in ["Promise", "Array", "Map"] {
+            assert!(
+                all.iter().all(|fqn| !fqn.contains(&format!("::{}", name))),
+                "{:?}",
+                all
+            );
+        }
+    }
+
+    #[test]
+    fn marks_methods_that_return_this_as_chainable() {
+        let source = indoc! { r#"
+        export class QueryBuilder {
+            where(clause: string): this { return this; }
+            limit(count: number): this | undefined { return this; }
+            offset(count: number): (this) { return this; }
+            build(): string { return ""; }
+        }
+        "#};
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+        let builder = find_entity(&entities, "QueryBuilder");
+
+        let chainable = builder
+            .members
+            .iter()
+            .filter(|m| m.kind == "method")
+            .map(|m| {
+                (
+                    m.title.as_deref().unwrap(),
+                    m.meta.get("chainable") == Some(&true.into()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            chainable,
+            [
+                ("where", true),
+                ("limit", true),
+                ("offset", true),
+                ("build", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn serializes_a_distinct_kind_for_every_type() {
+        let source = indoc! { r#"
+        interface Point { x: number }
+
+        export type Everything<T> = {
+            self: this;
+            rest: [string, ...number[]];
+            infer: T extends Promise<infer U extends string> ? U : never;
+            lookup: Point["x"];
+            template: `id-${string}`;
+            readonly: readonly boolean[];
+            keyof: keyof Point;
+            ctor: new (x: number) => Point;
+            paren: (string | number);
+            literal: "a";
+            typeof: typeof origin;
+            fn: (x: number) => void;
+            both: Point & { y: number };
+        };
+        "#};
+
+        fn collect_kinds(entity: &Entity, kinds: &mut std::collections::BTreeSet<String>) {
+            kinds.insert(entity.kind.clone());
+            for member in &entity.members {
+                collect_kinds(member, kinds);
+            }
+        }
+
+        let entities = parse_sources(&[("index.ts", source)], &mut dossier_core::Context::new());
+        let mut kinds = std::collections::BTreeSet::new();
+        for entity in &entities {
+            collect_kinds(entity, &mut kinds);
+        }
+
+        assert_eq!(
+            kinds.into_iter().collect::<Vec<_>>(),
+            [
+                "array_type",
+                "conditional_type",
+                "constructor_type",
+                "function_type",
+                "generic_type",
+                "identifier",
+                "infer_type",
+                "interface",
+                "intersection",
+                "keyof",
+                "literal",
+                "lookup_type",
+                "object",
+                "parameter",
+                "parenthesized_type",
+                "predefined_type",
+                "property",
+                "rest_type",
+                "template_literal_type",
+                "this_type",
+                "tuple",
+                "type_alias",
+                "type_constraint",
+                "type_variable",
+                "typeof",
+                "union",
+            ]
+        );
+    }
+
+    #[test]
+    fn output_is_reproducible() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/typescript");
+        let files = [fixtures.join("shapes.ts"), fixtures.join("geometry.ts")];
+
+        let parse = |files: &[PathBuf]| {
+            let entities = TypeScriptParser::new()
+                .parse(
+                    &files.iter().map(|f| f.as_path()).collect::<Vec<_>>(),
+                    &mut dossier_core::Context::new(),
+                )
+                .unwrap();
+
+            dossier_core::serde_json::to_string(&entities).unwrap()
+        };
+
+        let first = parse(&files);
+        let second = parse(&files);
+        let reversed = parse(&[files[1].clone(), files[0].clone()]);
+
+        assert_eq!(first, second);
+        assert_eq!(first, reversed);
+    }
+
+    #[test]
+    fn minified_files_are_parsed_for_names_or_skipped() {
+        let vendor = (0..500)
+            .map(|i| format!("export type Opt{i} = {{ a: string; b: number }};"))
+            .collect::<String>();
+        let index = indoc! { r#"
+        import { Opt42 } from "./vendor.ts";
+
+        export function configure(options: Opt42) {}
+        "#};
+        let files = [("index.ts", index), ("vendor.ts", vendor.as_str())];
+
+        let mut ctx = dossier_core::Context::new();
+        let entities = parse_sources(&files, &mut ctx);
+
+        // Only names from the minified file, but imports still resolve to them
+        let opt = find_entity(&entities, "Opt42");
+        assert_eq!(opt.identity, Identity::FQN("vendor.ts::Opt42".to_owned()));
+        assert!(opt.members.is_empty());
+
+        let options_type = &find_entity(&entities, "configure").members[0].members[0];
+        assert_eq!(
+            options_type.identity,
+            Identity::Reference("vendor.ts::Opt42".to_owned())
+        );
+
+        assert_eq!(ctx.diagnostics.len(), 1);
+        assert_eq!(ctx.diagnostics[0].code, dossier_core::MINIFIED_FILE);
+        assert_eq!(ctx.diagnostics[0].file, Path::new("vendor.ts"));
+
+        let mut ctx = dossier_core::Context::new();
+        ctx.file_limits.minified = dossier_core::MinifiedFiles::Skip;
+        let entities = parse_sources(&files, &mut ctx);
+
+        assert!(entities
+            .iter()
+            .all(|e| e.source.file == Path::new("index.ts")));
+        // The skipped file can't be imported from
+        let codes = ctx.diagnostics.iter().map(|d| d.code.as_str());
+        assert_eq!(
+            codes.collect::<Vec<_>>(),
+            [dossier_core::MINIFIED_FILE, symbol_table::UNRESOLVED_IMPORT]
         );
     }
 }
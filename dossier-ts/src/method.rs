@@ -1,13 +1,14 @@
 use crate::{
-    helpers::*,
-    parameter,
+    callable::{self, Signature},
+    decorator::{self, Decorator},
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
-    type_variable, types, ParserContext,
+    tsdoc::DocTags,
+    types, ParserContext,
 };
 
 use dossier_core::serde_json::json;
 use dossier_core::tree_sitter::{Node, Query, QueryCursor};
-use dossier_core::{helpers::*, Entity, Identity, Result};
+use dossier_core::{helpers::*, Entity, Identity, MemberContext, Result};
 
 use indoc::indoc;
 use lazy_static::lazy_static;
@@ -38,12 +39,14 @@ const QUERY_STRING: &str = indoc! {"
 lazy_static! {
     static ref QUERY: Query =
         Query::new(tree_sitter_typescript::language_typescript(), QUERY_STRING).unwrap();
+    static ref TSX_QUERY: Query =
+        Query::new(tree_sitter_typescript::language_tsx(), QUERY_STRING).unwrap();
 }
 
 pub(crate) const NODE_KIND: &str = "method_signature";
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum Identifier {
+pub enum Identifier {
     Computed(String),
     Name(String),
 }
@@ -57,13 +60,46 @@ impl Identifier {
     }
 }
 
+/// The keyword of a getter or setter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessor {
+    Get,
+    Set,
+}
+
+impl Accessor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Accessor::Get => "get",
+            Accessor::Set => "set",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Method {
+pub struct Method {
     pub identifier: Identifier,
     pub children: Vec<Symbol>,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
     pub is_abstract: bool,
+    /// Marked `private`, or with a `#private` name
     pub is_private: bool,
+    pub is_protected: bool,
+    pub is_static: bool,
+    pub is_async: bool,
+    /// Declared with a `*`, like `*entries()`
+    pub is_generator: bool,
+    pub is_override: bool,
+    /// Whether the method is a getter or a setter
+    pub accessor: Option<Accessor>,
+    /// What an `async` method's `Promise<T>` return type resolves to, `T`,
+    /// see `callable::awaited_type`
+    pub awaited_type: Option<String>,
+    pub decorators: Vec<Decorator>,
+    /// The declaration without its body, on one line, see
+    /// `dossier_core::helpers::signature`. Not set for minified files.
+    pub signature: Option<String>,
 }
 
 impl Method {
@@ -78,13 +114,45 @@ impl Method {
         if self.is_abstract {
             meta["abstract"] = true.into();
         }
+        if self.is_private {
+            meta["private"] = true.into();
+        }
+        if self.is_protected {
+            meta["protected"] = true.into();
+        }
+        if self.is_static {
+            meta["static"] = true.into();
+        }
+        if self.is_async {
+            meta["async"] = true.into();
+        }
+        if self.is_generator {
+            meta["generator"] = true.into();
+        }
+        if let Some(awaited_type) = &self.awaited_type {
+            meta["resolved_return_type"] = awaited_type.as_str().into();
+        }
+        if self.is_override {
+            meta["override"] = true.into();
+        }
+        if let Some(accessor) = self.accessor {
+            meta["accessor"] = accessor.as_str().into();
+        }
+        decorator::add_to_meta(&self.decorators, &mut meta);
+        if types::returns_this(self.return_type()) {
+            meta["chainable"] = true.into();
+        }
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.as_str().to_owned()),
             description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "method".to_owned(),
             identity: Identity::FQN(fqn.expect("Method without FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: "ts".to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -95,24 +163,22 @@ impl Method {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
-    #[allow(dead_code)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Method {
     pub fn parameters(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_parameter().is_some())
     }
 
-    #[cfg(test)]
-    #[allow(dead_code)]
     pub fn type_variables(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_type_variable().is_some())
     }
 
-    #[cfg(test)]
     pub fn return_type(&self) -> Option<&Symbol> {
         self.children
             .iter()
@@ -120,27 +186,24 @@ impl Method {
     }
 }
 
-/// TODO(Nik): This code is almost identical to the code in function.rs. We
-/// should try to find a way to share this code and test it in one place.
 pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
     assert!(matches!(
         node.kind(),
         "method_signature" | "method_definition" | "abstract_method_signature"
     ));
 
-    let mut children = vec![];
-
+    let query = crate::helpers::query_for(node, &QUERY, &TSX_QUERY);
     let mut cursor = QueryCursor::new();
     let method = cursor
-        .matches(&QUERY, *node, ctx.code.as_bytes())
+        .matches(query, *node, ctx.code.as_bytes())
         .next()
         .unwrap();
 
-    let main_node = node_for_capture("method", method.captures, &QUERY).unwrap();
-    let name_node = node_for_capture("method_name", method.captures, &QUERY).unwrap();
-    let type_param_node = node_for_capture("method_type_parameters", method.captures, &QUERY);
-    let parameters_node = node_for_capture("method_parameters", method.captures, &QUERY);
-    let return_type_node = node_for_capture("method_return_type", method.captures, &QUERY);
+    let main_node = node_for_capture("method", method.captures, query).unwrap();
+    let name_node = node_for_capture("method_name", method.captures, query).unwrap();
+    let type_param_node = node_for_capture("method_type_parameters", method.captures, query);
+    let parameters_node = node_for_capture("method_parameters", method.captures, query);
+    let return_type_node = node_for_capture("method_return_type", method.captures, query);
 
     let identifier = if name_node.kind() == "computed_property_name" {
         let mut cursor = name_node.walk();
@@ -157,125 +220,87 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         Identifier::Name(name_node.utf8_text(ctx.code.as_bytes()).unwrap().to_owned())
     };
 
-    ctx.push_scope();
-    ctx.push_fqn(identifier.as_str());
-
-    if let Some(type_parameters) = type_param_node {
-        parse_type_parameters(&type_parameters, &mut children, ctx);
-        ctx.push_scope();
-    }
-
-    if let Some(parameter_nodes) = parameters_node {
-        parse_parameters(&parameter_nodes, &mut children, ctx)?;
-    }
-
-    if let Some(type_node) = return_type_node {
-        parse_return_type(&type_node, &mut children, ctx)?;
-    }
-
-    let docs = find_docs(&main_node, ctx.code);
-
-    if type_param_node.is_some() {
-        ctx.pop_scope();
-    }
-    ctx.pop_scope();
-    ctx.pop_fqn();
+    let modifiers = Modifiers::of(&main_node, &name_node, ctx.code);
+
+    let Signature {
+        children,
+        documentation,
+        doc_tags,
+    } = callable::parse_signature(
+        identifier.as_str(),
+        &main_node,
+        type_param_node,
+        parameters_node,
+        return_type_node,
+        ctx,
+    )?;
 
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::Method(Method {
             identifier,
-            documentation: docs.map(process_comment),
+            documentation,
+            doc_tags,
             children,
             is_abstract: node.kind() == "abstract_method_signature",
-            is_private: name_node.kind() == "private_property_identifier",
+            is_private: modifiers.private || name_node.kind() == "private_property_identifier",
+            is_protected: modifiers.protected,
+            is_static: modifiers.is_static,
+            is_async: modifiers.is_async,
+            is_generator: modifiers.is_generator,
+            is_override: modifiers.is_override,
+            accessor: modifiers.accessor,
+            awaited_type: modifiers
+                .is_async
+                .then(|| callable::awaited_type(return_type_node, ctx.code))
+                .flatten(),
+            decorators: decorator::of(&main_node, ctx.code),
+            signature: Some(signature(&main_node, ctx.code)),
         }),
         Source::for_node(&main_node, ctx),
     ))
 }
 
-fn parse_return_type(
-    node: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) -> Result<()> {
-    let mut type_node_cursor = node.walk();
-    type_node_cursor.goto_first_child();
-
-    while !type_node_cursor.node().is_named() {
-        type_node_cursor.goto_next_sibling();
-    }
-
-    let mut ret_type = types::parse(&type_node_cursor.node(), ctx).unwrap();
-    ret_type.context = Some(SymbolContext::ReturnType);
-    children.push(ret_type);
-
-    Ok(())
+/// The keywords before the name of a method
+#[derive(Debug, Default)]
+struct Modifiers {
+    private: bool,
+    protected: bool,
+    is_static: bool,
+    is_async: bool,
+    is_generator: bool,
+    is_override: bool,
+    accessor: Option<Accessor>,
 }
 
-fn parse_parameters(
-    parameters: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) -> Result<()> {
-    assert_eq!(parameters.kind(), "formal_parameters");
+impl Modifiers {
+    /// Only looks at the children before `name_node`, so methods called
+    /// `get` or `static` aren't mistaken for modifiers
+    fn of(method_node: &Node, name_node: &Node, code: &str) -> Self {
+        let mut modifiers = Modifiers::default();
+        let mut cursor = method_node.walk();
 
-    let mut cursor = parameters.walk();
-    cursor.goto_first_child();
-
-    loop {
-        if cursor.node().kind() == "required_parameter"
-            || cursor.node().kind() == "optional_parameter"
-        {
-            let mut parameter = parameter::parse(&cursor.node(), ctx)?;
-            parameter.context = Some(SymbolContext::Parameter);
-            children.push(parameter);
-        }
-
-        if !cursor.goto_next_sibling() {
-            break;
-        }
-    }
-
-    Ok(())
-}
-
-fn parse_type_parameters(
-    type_parameters: &Node,
-    children: &mut Vec<Symbol>,
-    ctx: &mut ParserContext,
-) {
-    assert_eq!(type_parameters.kind(), "type_parameters");
-
-    let mut cursor = type_parameters.walk();
-    cursor.goto_first_child();
-
-    loop {
-        if cursor.node().kind() == "type_parameter" {
-            let type_variable = type_variable::parse(&cursor.node(), ctx).unwrap();
-            children.push(type_variable);
-        }
-
-        if !cursor.goto_next_sibling() {
-            break;
-        }
-    }
-}
-
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    let parent = node.parent().unwrap();
+        for child in method_node.children(&mut cursor) {
+            if child.start_byte() >= name_node.start_byte() {
+                break;
+            }
 
-    if parent.kind() == "export_statement" {
-        if let Some(maybe_comment) = parent.prev_sibling() {
-            if maybe_comment.kind() == "comment" {
-                return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
+            match child.kind() {
+                "accessibility_modifier" => match child.utf8_text(code.as_bytes()).unwrap() {
+                    "private" => modifiers.private = true,
+                    "protected" => modifiers.protected = true,
+                    _ => {}
+                },
+                "static" => modifiers.is_static = true,
+                "async" => modifiers.is_async = true,
+                "*" => modifiers.is_generator = true,
+                "override_modifier" => modifiers.is_override = true,
+                "get" => modifiers.accessor = Some(Accessor::Get),
+                "set" => modifiers.accessor = Some(Accessor::Set),
+                _ => {}
             }
         }
-    } else if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
 
-    None
+        modifiers
+    }
 }
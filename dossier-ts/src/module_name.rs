@@ -0,0 +1,129 @@
+//! Names files by the packages they are in, for `Context::module_names`.
+//!
+//! A file is named by the `name` in the nearest `package.json` in its
+//! directory or the ones above it, followed by its path in the package
+//! without the extension, the way it would be imported from another package.
+//! `index.ts` files are named by their directory, so
+//! `packages/client/src/index.ts` in `@myorg/client` is `@myorg/client/src`.
+//!
+//! `package.json` files without a `name` are skipped. Files that aren't in a
+//! package keep their paths.
+use dossier_core::serde_json::{self, Value};
+use dossier_core::FileSource;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "package.json";
+
+/// The module name of each of `paths` that is in a package
+pub(crate) fn module_names(paths: &[PathBuf], files: &dyn FileSource) -> HashMap<PathBuf, String> {
+    let mut packages = HashMap::new();
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let (dir, name) = path
+                .ancestors()
+                .skip(1)
+                .find_map(|dir| package_name(dir, files, &mut packages).map(|n| (dir, n)))?;
+            let module = module_path(path.strip_prefix(dir).ok()?);
+
+            let module_name = match module.as_str() {
+                "" => name,
+                module => format!("{}/{}", name, module),
+            };
+            Some((path.clone(), module_name))
+        })
+        .collect()
+}
+
+/// The `name` of the `package.json` in `dir`, if there is one
+fn package_name(
+    dir: &Path,
+    files: &dyn FileSource,
+    packages: &mut HashMap<PathBuf, Option<String>>,
+) -> Option<String> {
+    packages
+        .entry(dir.to_owned())
+        .or_insert_with(|| {
+            let text = files.read_file(&dir.join(FILE_NAME)).ok()?;
+            let json: Value = serde_json::from_str(&text).ok()?;
+            json.get("name")?.as_str().map(|name| name.to_owned())
+        })
+        .clone()
+}
+
+/// `path` without its extension, and without a last `index`
+fn module_path(path: &Path) -> String {
+    let path = dossier_core::portable_path(path);
+    let path = [".d.ts", ".ts", ".tsx", ".mts", ".cts"]
+        .iter()
+        .find_map(|extension| path.strip_suffix(extension))
+        .unwrap_or(&path);
+
+    if path == "index" {
+        return String::new();
+    }
+    path.strip_suffix("/index").unwrap_or(path).to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::InMemoryFileSystem;
+
+    fn names(paths: &[&str], files: InMemoryFileSystem) -> Vec<Option<String>> {
+        let paths = paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+        let names = module_names(&paths, &files);
+
+        paths.iter().map(|p| names.get(p).cloned()).collect()
+    }
+
+    #[test]
+    fn names_files_by_their_package() {
+        let mut files = InMemoryFileSystem::new();
+        files.add_file(
+            "packages/client/package.json",
+            r#"{ "name": "@myorg/client", "version": "1.0.0" }"#,
+        );
+        files.add_file(
+            "packages/client/src/package.json",
+            r#"{ "type": "module" }"#,
+        );
+
+        assert_eq!(
+            names(
+                &[
+                    "packages/client/index.ts",
+                    "packages/client/src/index.ts",
+                    "packages/client/src/models/user.ts",
+                    "packages/client/src/types.d.ts",
+                    "scripts/build.ts",
+                ],
+                files
+            ),
+            [
+                Some("@myorg/client".to_owned()),
+                Some("@myorg/client/src".to_owned()),
+                Some("@myorg/client/src/models/user".to_owned()),
+                Some("@myorg/client/src/types".to_owned()),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_the_nearest_package() {
+        let mut files = InMemoryFileSystem::new();
+        files.add_file("package.json", r#"{ "name": "monorepo" }"#);
+        files.add_file("packages/server/package.json", r#"{ "name": "server" }"#);
+
+        assert_eq!(
+            names(&["packages/server/main.ts", "tools/lint.ts"], files),
+            [
+                Some("server/main".to_owned()),
+                Some("monorepo/tools/lint".to_owned())
+            ]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    helpers::*,
+    symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    tsdoc::{self, DocTags},
+    ParserContext,
+};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, MemberContext, Result};
+
+/// `namespace Foo { ... }`
+pub(crate) const NODE_KIND: &str = "internal_module";
+/// `module Foo { ... }`, the older spelling of a namespace
+pub(crate) const MODULE_NODE_KIND: &str = "module";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Namespace {
+    /// The name of the namespace. `namespace Foo.Bar { ... }` is a namespace
+    /// `Bar` in a namespace `Foo`, like in TypeScript.
+    pub identifier: String,
+    pub documentation: Option<String>,
+    pub doc_tags: DocTags,
+    /// The declarations in the body of the namespace
+    pub children: Vec<Symbol>,
+    pub exported: bool,
+}
+
+impl Namespace {
+    pub fn as_entity(
+        &self,
+        source: &Source,
+        fqn: Option<&str>,
+        symbol_context: Option<SymbolContext>,
+    ) -> Entity {
+        let mut meta = json!({});
+        if self.exported {
+            meta["exported"] = true.into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
+
+        Entity {
+            title: Some(self.identifier.clone()),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
+            kind: "namespace".to_owned(),
+            identity: Identity::FQN(fqn.expect("Namespace without FQN").to_owned()),
+            member_context: symbol_context.map(MemberContext::from),
+            language: crate::LANGUAGE.to_owned(),
+            source: source.as_entity_source(),
+            meta,
+            members: self
+                .children
+                .iter()
+                .map(|s| s.as_entity())
+                .collect::<Vec<_>>(),
+        }
+    }
+}
+
+pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
+    assert!(node.kind() == NODE_KIND || node.kind() == MODULE_NODE_KIND);
+
+    let name = node.child_by_field_name("name").unwrap();
+    let name_text = name.utf8_text(ctx.code.as_bytes()).unwrap();
+    // `declare module "lodash.merge"` names a module, dots and all
+    let identifiers = if name.kind() == "string" {
+        vec![name_text.trim_matches(|c| c == '"' || c == '\'').to_owned()]
+    } else {
+        name_text
+            .split('.')
+            .map(|part| part.trim().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let (identifier, outer_identifiers) = identifiers.split_last().unwrap();
+
+    // `namespace Foo.Bar { ... }` declares `Bar` as an exported namespace in
+    // `Foo`. The namespaces around the innermost one are created in their
+    // own scopes on the way in, and get their children on the way out.
+    let mut outer = vec![];
+    for (i, outer_identifier) in outer_identifiers.iter().enumerate() {
+        let namespace = Namespace {
+            identifier: outer_identifier.clone(),
+            documentation: None,
+            doc_tags: DocTags::default(),
+            children: vec![],
+            exported: i > 0 || is_exported(node),
+        };
+        outer.push(Symbol::in_context(
+            ctx,
+            SymbolKind::Namespace(namespace),
+            Source::for_node(node, ctx),
+        ));
+        ctx.push_scope();
+        ctx.push_fqn(outer_identifier);
+    }
+
+    ctx.push_scope();
+    ctx.push_fqn(identifier);
+
+    // The body is parsed like a file of its own. Its symbols are added to the
+    // table as usual, and then moved under the namespace.
+    let first_child = ctx.symbol_table.symbol_count();
+    if let Some(body) = node.child_by_field_name("body") {
+        crate::parse_statements(&body, ctx)?;
+    }
+    let children = ctx.symbol_table.take_symbols_from(first_child);
+
+    ctx.pop_fqn();
+    ctx.pop_scope();
+
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
+
+    let mut symbol = Symbol::in_context(
+        ctx,
+        SymbolKind::Namespace(Namespace {
+            identifier: identifier.clone(),
+            documentation,
+            doc_tags,
+            children,
+            exported: !outer.is_empty() || is_exported(node),
+        }),
+        Source::for_node(node, ctx),
+    );
+
+    for mut namespace in outer.into_iter().rev() {
+        ctx.pop_fqn();
+        ctx.pop_scope();
+
+        if let SymbolKind::Namespace(n) = &mut namespace.kind {
+            n.children.push(symbol);
+        }
+        symbol = namespace;
+    }
+
+    Ok(symbol)
+}
+
+fn is_exported(node: &Node) -> bool {
+    node.parent()
+        .is_some_and(|p| p.kind() == "export_statement")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_file, ParserContext};
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn parses_nested_namespaces() {
+        let source = indoc! { r#"
+        /** Shapes and such */
+        namespace Shapes {
+            export namespace Polygons {
+                export interface Triangle {}
+            }
+
+            type Size = number;
+        }
+
+        export module Legacy.Geometry {}
+        "#};
+
+        let table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        let symbols = table.all_symbols().collect::<Vec<_>>();
+        assert_eq!(symbols.len(), 2);
+
+        let shapes = symbols[0].kind.as_namespace().unwrap();
+        assert_eq!(shapes.identifier, "Shapes");
+        assert_eq!(shapes.documentation.as_deref(), Some("Shapes and such"));
+        assert!(!shapes.exported);
+        assert_eq!(shapes.children.len(), 2);
+
+        let polygons = &shapes.children[0];
+        assert_eq!(polygons.fqn.as_deref(), Some("index.ts::Shapes::Polygons"));
+        assert!(polygons.kind.as_namespace().unwrap().exported);
+        assert_eq!(
+            polygons.children()[0].fqn.as_deref(),
+            Some("index.ts::Shapes::Polygons::Triangle")
+        );
+        assert_eq!(
+            shapes.children[1].fqn.as_deref(),
+            Some("index.ts::Shapes::Size")
+        );
+
+        let legacy = symbols[1].kind.as_namespace().unwrap();
+        assert_eq!(legacy.identifier, "Legacy");
+        assert!(legacy.exported);
+        assert_eq!(symbols[1].fqn.as_deref(), Some("index.ts::Legacy"));
+    }
+
+    #[test]
+    fn chains_dotted_namespaces() {
+        let source = indoc! { r#"
+        /** Geometry of the past */
+        namespace Legacy.Geometry.Shapes {
+            export interface Point {}
+        }
+
+        module "lodash.merge" {}
+        "#};
+
+        let table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        let symbols = table.all_symbols().collect::<Vec<_>>();
+
+        let legacy = symbols[0].kind.as_namespace().unwrap();
+        assert_eq!(legacy.identifier, "Legacy");
+        assert!(!legacy.exported);
+        assert_eq!(legacy.documentation, None);
+
+        let geometry = &legacy.children[0];
+        assert_eq!(geometry.fqn.as_deref(), Some("index.ts::Legacy::Geometry"));
+        assert!(geometry.is_exported());
+
+        let shapes = &geometry.children()[0];
+        assert_eq!(
+            shapes.fqn.as_deref(),
+            Some("index.ts::Legacy::Geometry::Shapes")
+        );
+        let namespace = shapes.kind.as_namespace().unwrap();
+        assert!(namespace.exported);
+        assert_eq!(
+            namespace.documentation.as_deref(),
+            Some("Geometry of the past")
+        );
+        assert_eq!(
+            shapes.children()[0].fqn.as_deref(),
+            Some("index.ts::Legacy::Geometry::Shapes::Point")
+        );
+
+        assert_eq!(symbols[1].fqn.as_deref(), Some("index.ts::lodash.merge"));
+    }
+}
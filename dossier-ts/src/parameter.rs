@@ -1,21 +1,76 @@
 use crate::{
+    decorator::{self, Decorator},
+    property::Property,
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
-    types, ParserContext,
+    types::{self, Type},
+    ParserContext,
 };
 
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
+/// Rest parameters like `...args: string[]` are required parameters with a
+/// `rest_pattern` for a name
 pub(crate) const NODE_KINDS: &[&str] = &["required_parameter", "optional_parameter"];
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Parameter {
+pub struct Parameter {
     pub identifier: String,
+    /// Set from the `@param` tag of the function or method documentation
+    pub documentation: Option<String>,
     /// Technically will ever only have one child, the type itself, but other
     /// parts of the program will expect a slice of children so this is simpler.
     pub children: Vec<Symbol>,
     pub optional: bool,
     pub readonly: bool,
+    /// Whether the parameter is an object pattern, like `{ a, b }: Options`.
+    /// The identifier is then the text of the pattern.
+    pub destructured: bool,
+    /// `...args`. The identifier doesn't include the dots.
+    pub rest: bool,
+    /// The default value as written in the source, e.g. `3` in `x = 3`
+    pub default: Option<String>,
+    /// Set for constructor parameters that also declare a field, see
+    /// `field::parse_parameter_properties`
+    pub property: Option<ParameterProperty>,
+    pub decorators: Vec<Decorator>,
+}
+
+/// The modifiers that make a constructor parameter declare a field of the
+/// same name, like `private readonly` in
+/// `constructor(private readonly db: Database)`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ParameterProperty {
+    pub private: bool,
+    pub protected: bool,
+    pub readonly: bool,
+}
+
+impl ParameterProperty {
+    /// The modifiers of the parameter `node`, if it has any
+    pub(crate) fn of(node: &Node, code: &str) -> Option<Self> {
+        let mut property = ParameterProperty::default();
+        let mut has_modifiers = false;
+        let mut cursor = node.walk();
+
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                decorator::NODE_KIND => continue,
+                "accessibility_modifier" => match child.utf8_text(code.as_bytes()).unwrap() {
+                    "private" => property.private = true,
+                    "protected" => property.protected = true,
+                    _ => {}
+                },
+                "readonly" => property.readonly = true,
+                "override_modifier" => {}
+                // The pattern, which comes after the modifiers
+                _ => break,
+            }
+            has_modifiers = true;
+        }
+
+        has_modifiers.then_some(property)
+    }
 }
 
 impl Parameter {
@@ -29,13 +84,23 @@ impl Parameter {
         if self.optional {
             meta["optional"] = true.into();
         }
+        if self.rest {
+            meta["rest"] = true.into();
+        }
+        if let Some(default) = &self.default {
+            meta["default"] = json!(default);
+        }
+        if self.property.is_some() {
+            meta["parameter_property"] = true.into();
+        }
+        decorator::add_to_meta(&self.decorators, &mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
-            description: String::new(),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "parameter".to_owned(),
             identity: Identity::FQN(fqn.expect("Parameter without FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: crate::LANGUAGE.to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -46,8 +111,10 @@ impl Parameter {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Parameter {
     pub fn parameter_type(&self) -> Option<&Symbol> {
         self.children.first()
     }
@@ -63,11 +130,22 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
     let mut optional = false;
     let mut readonly = false;
 
-    let identifier = cursor
-        .node()
-        .utf8_text(ctx.code.as_bytes())
-        .unwrap()
-        .to_owned();
+    // Skip decorators and the modifiers of parameter properties
+    let pattern_node = node.child_by_field_name("pattern").unwrap();
+    while cursor.node() != pattern_node {
+        cursor.goto_next_sibling();
+    }
+
+    let mut pattern = cursor.node();
+    let rest = pattern.kind() == "rest_pattern";
+    if rest {
+        pattern = pattern.named_child(0).unwrap_or(pattern);
+    }
+    let identifier = pattern.utf8_text(ctx.code.as_bytes()).unwrap().to_owned();
+    let destructured = pattern.kind() == "object_pattern";
+    let default = node
+        .child_by_field_name("value")
+        .map(|value| value.utf8_text(ctx.code.as_bytes()).unwrap().to_owned());
 
     if cursor.goto_next_sibling() && cursor.node().kind() == "?" {
         optional = true;
@@ -86,14 +164,73 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         children.push(types::parse(&cursor.node(), ctx)?);
     }
 
+    // Without a type annotation, the pattern is the best description we have
+    // of the fields the parameter takes
+    if destructured && children.is_empty() {
+        children.push(object_pattern_type(&pattern, ctx));
+    }
+
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::Parameter(Parameter {
             identifier,
+            documentation: None,
             children,
             optional,
             readonly,
+            destructured,
+            rest,
+            default,
+            property: ParameterProperty::of(node, ctx.code),
+            decorators: decorator::of(node, ctx.code),
         }),
         Source::for_node(node, ctx),
     ))
 }
+
+/// Builds an object type with a property for each field an object pattern
+/// picks out. Rest patterns are skipped, since we can't know their fields.
+fn object_pattern_type(pattern: &Node, ctx: &ParserContext) -> Symbol {
+    let mut properties = vec![];
+    let mut cursor = pattern.walk();
+
+    for field in pattern.named_children(&mut cursor) {
+        let (key, optional) = match field.kind() {
+            "shorthand_property_identifier_pattern" => (field, false),
+            "pair_pattern" => match field.child_by_field_name("key") {
+                Some(key) => (key, false),
+                None => continue,
+            },
+            // Fields with a default value can be left out by the caller
+            "object_assignment_pattern" => match field.child_by_field_name("left") {
+                Some(key) => (key, true),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        let mut symbol = Symbol::in_context(
+            ctx,
+            SymbolKind::Property(Property {
+                identifier: key.utf8_text(ctx.code.as_bytes()).unwrap().to_owned(),
+                documentation: None,
+                doc_tags: Default::default(),
+                children: vec![],
+                optional,
+                readonly: false,
+                private: false,
+                protected: false,
+                accessors: false,
+            }),
+            Source::for_node(&field, ctx),
+        );
+        symbol.context = Some(SymbolContext::Property);
+        properties.push(symbol);
+    }
+
+    Symbol::in_context(
+        ctx,
+        SymbolKind::Type(Type::Object { properties }),
+        Source::for_node(pattern, ctx),
+    )
+}
@@ -1,16 +1,19 @@
 use crate::{
     helpers::*,
+    method::Accessor,
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    tsdoc::{self, DocTags},
     types, ParserContext,
 };
 
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Property {
+pub struct Property {
     pub identifier: String,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
     /// Technically will ever only have one child, the type itself, but other
     /// parts of the program will expect a slice of children so this is simpler.
     pub children: Vec<Symbol>,
@@ -18,6 +21,8 @@ pub(crate) struct Property {
     pub readonly: bool,
     pub private: bool,
     pub protected: bool,
+    /// Declared with a `get` and a `set` accessor, see `pair_accessors`
+    pub accessors: bool,
 }
 
 impl Property {
@@ -40,13 +45,18 @@ impl Property {
         if self.private {
             meta["private"] = true.into();
         }
+        if self.accessors {
+            meta["getter"] = true.into();
+            meta["setter"] = true.into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
-            description: String::new(),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
             kind: "property".to_owned(),
             identity: Identity::FQN(fqn.expect("Parameter without FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: "ts".to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -57,8 +67,10 @@ impl Property {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Property {
     pub fn the_type(&self) -> Option<&Symbol> {
         self.children.first()
     }
@@ -113,23 +125,97 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         ctx.pop_scope();
     }
 
-    let documentation = find_docs(node, ctx.code).map(process_comment);
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
 
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::Property(Property {
             identifier,
             documentation,
+            doc_tags,
             children,
             private,
             protected,
             readonly: is_readonly(node),
             optional: is_optional(node),
+            accessors: false,
         }),
         Source::for_node(node, ctx),
     ))
 }
 
+/// Replaces each `get` accessor among the members of an object type that has
+/// a `set` accessor of the same name with a property, and leaves the setter
+/// out. The type of the property is the return type of the getter, or the
+/// type of the setter's parameter if the getter has none.
+///
+/// Accessors without a counterpart stay methods, with `meta.accessor`.
+pub(crate) fn pair_accessors(members: &mut Vec<Symbol>) {
+    let accessor = |symbol: &Symbol, kind| match &symbol.kind {
+        SymbolKind::Method(m) if m.accessor == Some(kind) => Some(m.identifier.clone()),
+        _ => None,
+    };
+
+    let mut index = 0;
+    while index < members.len() {
+        let Some(identifier) = accessor(&members[index], Accessor::Get) else {
+            index += 1;
+            continue;
+        };
+        let Some(setter_index) = members
+            .iter()
+            .position(|s| accessor(s, Accessor::Set).as_ref() == Some(&identifier))
+        else {
+            index += 1;
+            continue;
+        };
+
+        let setter = members.remove(setter_index);
+        if setter_index < index {
+            index -= 1;
+        }
+
+        let getter = &mut members[index];
+        let SymbolKind::Method(get) = &mut getter.kind else {
+            unreachable!("only methods are accessors");
+        };
+        let SymbolKind::Method(set) = setter.kind else {
+            unreachable!("only methods are accessors");
+        };
+
+        let return_type = get
+            .children
+            .iter()
+            .position(|s| s.context == Some(SymbolContext::ReturnType))
+            .map(|position| get.children.remove(position));
+        let the_type = return_type.or_else(|| {
+            set.children
+                .into_iter()
+                .find(|s| s.context == Some(SymbolContext::Parameter))
+                .and_then(|parameter| parameter.children().first().cloned())
+        });
+
+        getter.kind = SymbolKind::Property(Property {
+            identifier: identifier.as_str().to_owned(),
+            documentation: get.documentation.take().or(set.documentation),
+            doc_tags: std::mem::take(&mut get.doc_tags),
+            children: the_type
+                .map(|mut the_type| {
+                    the_type.context = None;
+                    vec![the_type]
+                })
+                .unwrap_or_default(),
+            optional: false,
+            readonly: false,
+            private: get.is_private,
+            protected: get.is_protected,
+            accessors: true,
+        });
+
+        index += 1;
+    }
+}
+
 fn is_optional(node: &Node) -> bool {
     let mut cursor = node.walk();
     cursor.goto_first_child();
@@ -159,16 +245,6 @@ fn is_readonly(property_node: &Node) -> bool {
     }
 }
 
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
-
-    None
-}
-
 #[cfg(test)]
 mod test {
     /// NOTE ABOUT THESE TESTS
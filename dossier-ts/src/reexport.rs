@@ -0,0 +1,175 @@
+use crate::{
+    import::{Import, ImportedName},
+    symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    ParserContext,
+};
+use dossier_core::{serde_json::json, tree_sitter::Node, Entity, Identity, MemberContext, Result};
+
+/// A name exported from another module, e.g. each of `Foo` and `Baz` in
+/// `export { Foo, Bar as Baz } from "./foo";`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReExport {
+    /// The name it is exported under
+    pub identifier: String,
+    /// The name in the module it is exported from
+    pub original: String,
+    /// The module specifier, e.g. `./foo`
+    pub source: String,
+    /// Exported with `export type`, so only usable as a type
+    pub type_only: bool,
+    /// The FQN of what is exported, if we could resolve it
+    pub resolved: Option<String>,
+}
+
+impl ReExport {
+    pub fn as_entity(
+        &self,
+        source: &Source,
+        fqn: Option<&str>,
+        symbol_context: Option<SymbolContext>,
+    ) -> Entity {
+        let mut meta = json!({
+            "exported": true,
+            "source": self.source,
+        });
+        if self.type_only {
+            meta["type_only"] = true.into();
+        }
+        if self.original != self.identifier {
+            meta["original"] = self.original.as_str().into();
+        }
+
+        let identity = match &self.resolved {
+            Some(target) => Identity::Reference(target.clone()),
+            None => Identity::FQN(fqn.expect("Re-export without FQN").to_owned()),
+        };
+
+        Entity {
+            title: Some(self.identifier.clone()),
+            description: String::new(),
+            kind: "reexport".to_owned(),
+            identity,
+            member_context: symbol_context.map(MemberContext::from),
+            language: crate::LANGUAGE.to_owned(),
+            source: source.as_entity_source(),
+            meta,
+            members: vec![],
+        }
+    }
+
+    /// The import that would bring the original name into scope
+    pub(crate) fn as_import(&self) -> Import {
+        Import {
+            names: vec![ImportedName {
+                type_only: self.type_only,
+                ..ImportedName::unaliased(&self.original)
+            }],
+            source: self.source.clone(),
+            type_only: self.type_only,
+        }
+    }
+}
+
+/// Parses an `export_statement` with a `from` clause, returning a symbol for
+/// each name it exports. `export * from "./foo"` exports no names of its own,
+/// so it results in none.
+pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Vec<Symbol>> {
+    assert_eq!(node.kind(), "export_statement");
+
+    let Some(source) = node.child_by_field_name("source") else {
+        return Ok(vec![]);
+    };
+    let source = source
+        .utf8_text(ctx.code.as_bytes())
+        .unwrap()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_owned();
+
+    let mut cursor = node.walk();
+    let statement_type_only = node.children(&mut cursor).any(|n| n.kind() == "type");
+    let Some(clause) = node
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "export_clause")
+    else {
+        return Ok(vec![]);
+    };
+
+    let mut symbols = vec![];
+    for specifier in clause.named_children(&mut clause.walk()) {
+        if specifier.kind() != "export_specifier" {
+            continue;
+        }
+
+        let text = |field| {
+            specifier
+                .child_by_field_name(field)
+                .map(|n| n.utf8_text(ctx.code.as_bytes()).unwrap().to_owned())
+        };
+        let original = text("name").unwrap();
+        let identifier = text("alias").unwrap_or_else(|| original.clone());
+        // `export { type Foo } from "./foo"` marks a single name as a type
+        let type_only = statement_type_only
+            || specifier
+                .child(0)
+                .is_some_and(|n| !n.is_named() && n.kind() == "type");
+
+        symbols.push(Symbol::in_context(
+            ctx,
+            SymbolKind::ReExport(ReExport {
+                identifier,
+                original,
+                source: source.clone(),
+                type_only,
+                resolved: None,
+            }),
+            Source::for_node(&specifier, ctx),
+        ));
+    }
+
+    Ok(symbols)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::tree_sitter::Parser;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn parse_first_statement(code: &str) -> Vec<ReExport> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_typescript::language_typescript())
+            .expect("Error loading TypeScript grammar");
+        let tree = parser.parse(code, None).unwrap();
+
+        let mut ctx = ParserContext::new(Path::new("index.ts"), code);
+        parse(&tree.root_node().named_child(0).unwrap(), &mut ctx)
+            .unwrap()
+            .into_iter()
+            .map(|s| match s.kind {
+                SymbolKind::ReExport(r) => r,
+                other => panic!("Expected a re-export, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_renamed_and_type_only_names() {
+        let reexports = parse_first_statement(indoc! {r#"
+            export { type User, makeUser as createUser } from "./users";
+        "#});
+
+        assert_eq!(reexports.len(), 2);
+        assert_eq!(reexports[0].identifier, "User");
+        assert!(reexports[0].type_only);
+        assert_eq!(reexports[1].identifier, "createUser");
+        assert_eq!(reexports[1].original, "makeUser");
+        assert_eq!(reexports[1].source, "./users");
+        assert!(!reexports[1].type_only);
+
+        let reexports = parse_first_statement(r#"export type { User, Role } from './users';"#);
+        assert!(reexports.iter().all(|r| r.type_only));
+        assert_eq!(reexports[1].source, "./users");
+    }
+}
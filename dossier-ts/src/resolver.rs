@@ -0,0 +1,226 @@
+//! Name resolution: finding the declaration a name refers to.
+//!
+//! The symbol tables drive resolution. They collect the references in each
+//! file, ask a `NameResolver` what each one refers to, and record the answer
+//! in the referencing symbol. Parsing and converting symbols into entities
+//! don't depend on how the answer was found, so the resolver can be swapped,
+//! e.g. for one built on stack graphs.
+//!
+//! # Contract
+//!
+//! Every resolver has to resolve names the same way, which the `contract`
+//! tests check:
+//!
+//! - **Nearest scope wins.** A name resolves to the declaration in the
+//!   innermost scope around the reference that declares it, e.g. a type
+//!   variable shadows a type alias of the same name in the file.
+//! - **Dotted paths.** `Outer.Inner` resolves `Outer` as above, and then
+//!   finds `Inner` among the members of what it found. `namespace A.B` is a
+//!   namespace `B` exported from a namespace `A`.
+//! - **Aliases.** A name that resolves to an import alias like
+//!   `import Alias = Outer.Inner` resolves to what the alias points at,
+//!   following aliases of aliases. If the target can't be resolved, or the
+//!   aliases form a cycle, the name resolves to the alias itself.
+//! - **Only exports cross files.** A name imported from another file resolves
+//!   to what that file exports under the name, and to nothing if it doesn't
+//!   export it. Dotted paths into an imported namespace only reach the
+//!   members it exports. Re-exports like `export { Foo } from "./foo"` are followed to
+//!   the file they re-export from.
+//! - **Import specifiers.** Relative specifiers are resolved from the
+//!   importing file's directory, and may leave out the extension or
+//!   `/index.ts`. Other specifiers are mapped with the `paths` and `baseUrl`
+//!   of the `tsconfig.json` that applies to the file first. If one could
+//!   refer to several files, the first one TypeScript would try wins. See
+//!   `TableIndex::candidates`.
+//!
+//! Names a resolver can't resolve, that are imported from packages none of
+//! the files belong to, get an `external:` FQN from the symbol tables.
+use crate::import::Import;
+use crate::symbol::SymbolID;
+use crate::symbol_table::{ScopeID, SymbolTable, TableIndex};
+
+/// The resolver the parser uses. Alternative resolvers are picked here, e.g.
+/// behind a feature flag.
+pub(crate) const DEFAULT: &dyn NameResolver = &SymbolTableResolver;
+
+/// Finds the declarations names refer to. See the module documentation for
+/// the contract implementations have to meet.
+pub(crate) trait NameResolver: std::fmt::Debug + Send + Sync {
+    /// The FQN of the declaration `path` refers to in `table`, looking from
+    /// `scope`. `symbol` is the symbol the reference belongs to. Only looks
+    /// at declarations in `table` itself.
+    fn resolve_local(
+        &self,
+        table: &SymbolTable,
+        path: &str,
+        scope: ScopeID,
+        symbol: SymbolID,
+    ) -> Option<String>;
+
+    /// The FQN of the declaration `name` refers to, where `table` imports it
+    /// with `import` from one of the tables in `tables`.
+    fn resolve_import(
+        &self,
+        table: &SymbolTable,
+        import: &Import,
+        name: &str,
+        tables: &TableIndex,
+    ) -> Option<String>;
+}
+
+/// Resolves names by looking them up in the scopes of the symbol tables
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SymbolTableResolver;
+
+impl NameResolver for SymbolTableResolver {
+    fn resolve_local(
+        &self,
+        table: &SymbolTable,
+        path: &str,
+        scope: ScopeID,
+        symbol: SymbolID,
+    ) -> Option<String> {
+        table.resolve_path(path, scope, symbol, 0)
+    }
+
+    fn resolve_import(
+        &self,
+        table: &SymbolTable,
+        import: &Import,
+        name: &str,
+        tables: &TableIndex,
+    ) -> Option<String> {
+        tables
+            .candidates(table, import)
+            .first()?
+            .resolve_export(name, tables, 0)
+    }
+}
+
+/// Tests every resolver has to pass. Run them for a resolver by calling
+/// each of them with it, like `symbol_table_resolver_meets_the_contract`.
+#[cfg(test)]
+mod contract {
+    use super::*;
+    use crate::symbol_table::resolve_imported_types_to_fixpoint_with;
+    use crate::{parse_file, ParserContext};
+    use dossier_core::test_utils::reference;
+    use dossier_core::{Entity, Identity};
+    use indoc::indoc;
+    use std::path::Path;
+
+    /// Parses the files and resolves them with `resolver`, returning the
+    /// top-level entities of all of them
+    fn resolve(resolver: &dyn NameResolver, sources: &[(&str, &str)]) -> Vec<Entity> {
+        let mut tables = sources
+            .iter()
+            .map(|(path, code)| parse_file(ParserContext::new(Path::new(path), code)).unwrap())
+            .collect::<Vec<_>>();
+
+        for table in tables.iter_mut() {
+            table.resolve_types_with(resolver);
+        }
+        resolve_imported_types_to_fixpoint_with(&mut tables, resolver);
+
+        tables
+            .iter()
+            .flat_map(|table| table.all_symbols().map(|s| s.as_entity()))
+            .collect()
+    }
+
+    /// What the type of the type alias called `name` refers to
+    fn alias_target<'a>(entities: &'a [Entity], name: &str) -> &'a Identity {
+        let alias = entities
+            .iter()
+            .find(|e| e.kind == "type_alias" && e.title.as_deref() == Some(name))
+            .unwrap();
+
+        &alias.members[0].identity
+    }
+
+    fn nearest_scope_wins(resolver: &dyn NameResolver) {
+        let source = indoc! { r#"
+        type T = string;
+        interface Box<T> {
+            value: T;
+        }
+        type Outer = T;
+        "#};
+        let entities = resolve(resolver, &[("index.ts", source)]);
+
+        let value = &entities
+            .iter()
+            .find(|e| e.title.as_deref() == Some("Box"))
+            .unwrap()
+            .members
+            .iter()
+            .find(|m| m.kind == "object")
+            .unwrap()
+            .members[0];
+        assert_eq!(value.members[0].identity, reference("index.ts::Box::T"));
+        assert_eq!(alias_target(&entities, "Outer"), &reference("index.ts::T"));
+    }
+
+    fn dotted_paths_and_aliases(resolver: &dyn NameResolver) {
+        let source = indoc! { r#"
+        namespace Shapes {
+            export interface Circle {}
+        }
+        import Round = Shapes.Circle;
+        import Rounder = Round;
+        import Missing = Nowhere.Thing;
+        type ByPath = Shapes.Circle;
+        type ByAlias = Rounder;
+        type Dangling = Missing;
+        "#};
+        let entities = resolve(resolver, &[("index.ts", source)]);
+
+        let circle = reference("index.ts::Shapes::Circle");
+        assert_eq!(alias_target(&entities, "ByPath"), &circle);
+        assert_eq!(alias_target(&entities, "ByAlias"), &circle);
+        assert_eq!(
+            alias_target(&entities, "Dangling"),
+            &reference("index.ts::Missing")
+        );
+    }
+
+    fn only_exports_cross_files(resolver: &dyn NameResolver) {
+        let models = indoc! { r#"
+        export interface User {}
+        interface Secret {}
+        "#};
+        let index = indoc! { r#"
+        export { User as Member } from "./models";
+        "#};
+        let app = indoc! { r#"
+        import { User, Secret } from "./models";
+        import { Member } from "./";
+        type A = User;
+        type B = Secret;
+        type C = Member;
+        "#};
+        let entities = resolve(
+            resolver,
+            &[
+                ("src/models.ts", models),
+                ("src/index.ts", index),
+                ("src/app.ts", app),
+            ],
+        );
+
+        let user = reference("src/models.ts::User");
+        assert_eq!(alias_target(&entities, "A"), &user);
+        assert!(!matches!(
+            alias_target(&entities, "B"),
+            Identity::Reference(fqn) if fqn == "src/models.ts::Secret"
+        ));
+        assert_eq!(alias_target(&entities, "C"), &user);
+    }
+
+    #[test]
+    fn symbol_table_resolver_meets_the_contract() {
+        nearest_scope_wins(&SymbolTableResolver);
+        dotted_paths_and_aliases(&SymbolTableResolver);
+        only_exports_cross_files(&SymbolTableResolver);
+    }
+}
@@ -1,19 +1,16 @@
 use std::path::PathBuf;
 
-use dossier_core::{Entity, Position};
-use std::sync::atomic::AtomicUsize;
+use dossier_core::{Entity, Position, Repository};
 use tree_sitter::Node;
 
-use crate::{symbol_table::ScopeID, ParserContext};
-
-static SYMBOL_ID: AtomicUsize = AtomicUsize::new(1);
+use crate::{symbol_table::ScopeID, types, ParserContext};
 
 pub(crate) const UNUSED_SYMBOL_ID: usize = 0;
-pub(crate) type SymbolID = usize;
+pub type SymbolID = usize;
 
 #[derive(Debug, Clone, PartialEq)]
 /// A symbol we've discovered in the source code.
-pub(crate) struct Symbol {
+pub struct Symbol {
     pub id: usize,
     pub kind: SymbolKind,
     pub source: Source,
@@ -28,12 +25,12 @@ pub(crate) struct Symbol {
 }
 
 impl Symbol {
-    pub fn in_context(ctx: &ParserContext, kind: SymbolKind, source: Source) -> Self {
+    pub(crate) fn in_context(ctx: &ParserContext, kind: SymbolKind, source: Source) -> Self {
         let fqn = kind.identifier().map(|i| ctx.construct_fqn(i));
         let scope_id = ctx.current_scope();
 
         Self {
-            id: SYMBOL_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+            id: ctx.next_symbol_id(),
             kind,
             source,
             fqn,
@@ -44,15 +41,27 @@ impl Symbol {
 
     pub fn is_exported(&self) -> bool {
         match &self.kind {
+            SymbolKind::Class(c) => c.exported,
+            SymbolKind::Function(f) => f.is_exported,
+            SymbolKind::Interface(i) => i.exported,
             SymbolKind::TypeAlias(a) => a.exported,
+            SymbolKind::Namespace(n) => n.exported,
+            SymbolKind::ImportAlias(a) => a.exported,
+            SymbolKind::Variable(v) => v.exported,
+            SymbolKind::ReExport(_) => true,
             _ => false,
         }
     }
 
-    pub fn mark_as_exported(&mut self) {
-        #[allow(clippy::single_match)]
+    pub(crate) fn mark_as_exported(&mut self) {
         match &mut self.kind {
+            SymbolKind::Class(ref mut c) => c.exported = true,
+            SymbolKind::Function(ref mut f) => f.is_exported = true,
+            SymbolKind::Interface(ref mut i) => i.exported = true,
             SymbolKind::TypeAlias(ref mut a) => a.exported = true,
+            SymbolKind::Namespace(ref mut n) => n.exported = true,
+            SymbolKind::ImportAlias(ref mut a) => a.exported = true,
+            SymbolKind::Variable(ref mut v) => v.exported = true,
             _ => {}
         }
     }
@@ -69,7 +78,7 @@ impl Symbol {
             SymbolKind::TypeAlias(a) => {
                 a.as_entity(&self.source, self.fqn.as_deref(), self.context)
             }
-            SymbolKind::Type(t) => t.as_entity(&self.source, self.fqn.as_deref(), self.context),
+            SymbolKind::Type(t) => t.as_entity(&self.source, self.context),
             SymbolKind::Parameter(p) => {
                 p.as_entity(&self.source, self.fqn.as_deref(), self.context)
             }
@@ -80,10 +89,17 @@ impl Symbol {
             SymbolKind::TypeConstraint(t) => {
                 t.as_entity(&self.source, self.fqn.as_deref(), self.context)
             }
+            SymbolKind::Namespace(n) => {
+                n.as_entity(&self.source, self.fqn.as_deref(), self.context)
+            }
+            SymbolKind::ImportAlias(a) => {
+                a.as_entity(&self.source, self.fqn.as_deref(), self.context)
+            }
+            SymbolKind::Variable(v) => v.as_entity(&self.source, self.fqn.as_deref(), self.context),
+            SymbolKind::ReExport(r) => r.as_entity(&self.source, self.fqn.as_deref(), self.context),
         }
     }
 
-    #[cfg(test)]
     pub fn identifier(&self) -> Option<&str> {
         self.kind.identifier()
     }
@@ -101,6 +117,10 @@ impl Symbol {
             SymbolKind::Property(p) => p.children.as_slice(),
             SymbolKind::TypeVariable(t) => t.children.as_slice(),
             SymbolKind::TypeConstraint(t) => t.children.as_slice(),
+            SymbolKind::Namespace(n) => n.children.as_slice(),
+            SymbolKind::ImportAlias(_) => &[],
+            SymbolKind::Variable(v) => v.children.as_slice(),
+            SymbolKind::ReExport(_) => &[],
         }
     }
 
@@ -117,6 +137,49 @@ impl Symbol {
             SymbolKind::Property(ref mut p) => p.children.as_mut_slice(),
             SymbolKind::TypeVariable(ref mut t) => t.children.as_mut_slice(),
             SymbolKind::TypeConstraint(ref mut t) => t.children.as_mut_slice(),
+            SymbolKind::Namespace(ref mut n) => n.children.as_mut_slice(),
+            SymbolKind::ImportAlias(_) => &mut [],
+            SymbolKind::Variable(ref mut v) => v.children.as_mut_slice(),
+            SymbolKind::ReExport(_) => &mut [],
+        }
+    }
+
+    /// The documentation comment of the symbol, for the kinds of symbols
+    /// that have one
+    pub(crate) fn documentation_mut(&mut self) -> Option<&mut Option<String>> {
+        match &mut self.kind {
+            SymbolKind::Class(c) => Some(&mut c.documentation),
+            SymbolKind::Field(f) => Some(&mut f.documentation),
+            SymbolKind::Function(f) => Some(&mut f.documentation),
+            SymbolKind::Interface(i) => Some(&mut i.documentation),
+            SymbolKind::Method(m) => Some(&mut m.documentation),
+            SymbolKind::TypeAlias(a) => Some(&mut a.documentation),
+            SymbolKind::Parameter(p) => Some(&mut p.documentation),
+            SymbolKind::Property(p) => Some(&mut p.documentation),
+            SymbolKind::TypeVariable(t) => Some(&mut t.documentation),
+            SymbolKind::Namespace(n) => Some(&mut n.documentation),
+            SymbolKind::ImportAlias(a) => Some(&mut a.documentation),
+            SymbolKind::Variable(v) => Some(&mut v.documentation),
+            SymbolKind::Type(_) | SymbolKind::TypeConstraint(_) | SymbolKind::ReExport(_) => None,
+        }
+    }
+
+    /// Same as `documentation_mut`, but only for reading it
+    pub(crate) fn documentation(&self) -> Option<&str> {
+        match &self.kind {
+            SymbolKind::Class(c) => c.documentation.as_deref(),
+            SymbolKind::Field(f) => f.documentation.as_deref(),
+            SymbolKind::Function(f) => f.documentation.as_deref(),
+            SymbolKind::Interface(i) => i.documentation.as_deref(),
+            SymbolKind::Method(m) => m.documentation.as_deref(),
+            SymbolKind::TypeAlias(a) => a.documentation.as_deref(),
+            SymbolKind::Parameter(p) => p.documentation.as_deref(),
+            SymbolKind::Property(p) => p.documentation.as_deref(),
+            SymbolKind::TypeVariable(t) => t.documentation.as_deref(),
+            SymbolKind::Namespace(n) => n.documentation.as_deref(),
+            SymbolKind::ImportAlias(a) => a.documentation.as_deref(),
+            SymbolKind::Variable(v) => v.documentation.as_deref(),
+            SymbolKind::Type(_) | SymbolKind::TypeConstraint(_) | SymbolKind::ReExport(_) => None,
         }
     }
 
@@ -128,32 +191,65 @@ impl Symbol {
             SymbolKind::Class(i) => Some(i.identifier.as_str()),
             SymbolKind::TypeVariable(t) => Some(t.identifier.as_str()),
             SymbolKind::Function(f) => Some(f.identifier.as_str()),
+            SymbolKind::Namespace(n) => Some(n.identifier.as_str()),
+            SymbolKind::ImportAlias(a) => Some(a.identifier.as_str()),
             _ => None,
         }
     }
 
-    pub fn resolve_type(&mut self, fqn: &str) {
-        if let SymbolKind::Type(t) = &mut self.kind {
-            t.resolve_type(fqn)
+    /// The name this symbol refers to, which may be a dotted path into a
+    /// namespace. For declarations this is their own identifier.
+    pub fn reference_path(&self) -> Option<&str> {
+        match &self.kind {
+            SymbolKind::ImportAlias(a) => Some(a.target.as_str()),
+            _ => self.resolvable_identifier(),
+        }
+    }
+
+    /// Records that the symbol refers to `fqn`. Returns whether that changed
+    /// what it refers to.
+    pub(crate) fn resolve_type(&mut self, fqn: &str) -> bool {
+        match &mut self.kind {
+            SymbolKind::Type(t) => t.resolve_type(fqn),
+            SymbolKind::ImportAlias(a) => types::set_resolved(&mut a.resolved, fqn),
+            SymbolKind::ReExport(r) => types::set_resolved(&mut r.resolved, fqn),
+            _ => false,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Copy)]
-pub(crate) enum SymbolContext {
+pub enum SymbolContext {
+    /// A type parameter of a declaration or type, `T` in `<T>`
+    TypeParameter,
     ReturnType,
     Property,
     Parameter,
     Extends,
+    Implements,
+    /// The default of a type parameter, `R` in `<T = R>`
+    Default,
+    /// The type a variable's initializer is checked against, `Config` in
+    /// `const config = { ... } satisfies Config`
+    Satisfies,
+    /// An argument of a generic type, `T` in `Promise<T>`
+    TypeArgument,
 }
 
-impl std::fmt::Display for SymbolContext {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SymbolContext::ReturnType => write!(f, "return_type"),
-            SymbolContext::Property => write!(f, "property"),
-            SymbolContext::Parameter => write!(f, "parameter"),
-            SymbolContext::Extends => write!(f, "extends"),
+impl From<SymbolContext> for dossier_core::MemberContext {
+    fn from(context: SymbolContext) -> Self {
+        use dossier_core::MemberContext;
+
+        match context {
+            SymbolContext::TypeParameter => MemberContext::TypeParameter,
+            SymbolContext::ReturnType => MemberContext::ReturnType,
+            SymbolContext::Property => MemberContext::Property,
+            SymbolContext::Parameter => MemberContext::Parameter,
+            SymbolContext::Extends => MemberContext::Extends,
+            SymbolContext::Implements => MemberContext::Implements,
+            SymbolContext::Default => MemberContext::Default,
+            SymbolContext::Satisfies => MemberContext::Satisfies,
+            SymbolContext::TypeArgument => MemberContext::TypeArgument,
         }
     }
 }
@@ -161,7 +257,7 @@ impl std::fmt::Display for SymbolContext {
 /// The type of the symbol.
 /// Contains all the metadata associated with that type of symbol
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum SymbolKind {
+pub enum SymbolKind {
     Class(crate::class::Class),
     Field(crate::field::Field),
     Function(crate::function::Function),
@@ -173,6 +269,10 @@ pub(crate) enum SymbolKind {
     TypeConstraint(crate::type_constraint::TypeConstraint),
     Parameter(crate::parameter::Parameter),
     Property(crate::property::Property),
+    Namespace(crate::namespace::Namespace),
+    ImportAlias(crate::import_alias::ImportAlias),
+    Variable(crate::variable::Variable),
+    ReExport(crate::reexport::ReExport),
 }
 
 impl SymbolKind {
@@ -189,10 +289,16 @@ impl SymbolKind {
             SymbolKind::Property(p) => Some(p.identifier.as_str()),
             SymbolKind::TypeVariable(t) => Some(t.identifier.as_str()),
             SymbolKind::TypeConstraint(_) => None,
+            SymbolKind::Namespace(n) => Some(n.identifier.as_str()),
+            SymbolKind::ImportAlias(a) => Some(a.identifier.as_str()),
+            SymbolKind::Variable(v) => Some(v.identifier.as_str()),
+            SymbolKind::ReExport(r) => Some(r.identifier.as_str()),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl SymbolKind {
     pub fn as_class(&self) -> Option<&crate::class::Class> {
         match self {
             SymbolKind::Class(c) => Some(c),
@@ -200,7 +306,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_field(&self) -> Option<&crate::field::Field> {
         match self {
             SymbolKind::Field(f) => Some(f),
@@ -208,7 +313,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_function(&self) -> Option<&crate::function::Function> {
         match self {
             SymbolKind::Function(f) => Some(f),
@@ -216,7 +320,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_interface(&self) -> Option<&crate::interface::Interface> {
         match self {
             SymbolKind::Interface(f) => Some(f),
@@ -224,7 +327,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_method(&self) -> Option<&crate::method::Method> {
         match self {
             SymbolKind::Method(m) => Some(m),
@@ -232,7 +334,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_type_alias(&self) -> Option<&crate::type_alias::TypeAlias> {
         match self {
             SymbolKind::TypeAlias(a) => Some(a),
@@ -240,7 +341,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_type_variable(&self) -> Option<&crate::type_variable::TypeVariable> {
         match self {
             SymbolKind::TypeVariable(f) => Some(f),
@@ -248,7 +348,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_type_constraint(&self) -> Option<&crate::type_constraint::TypeConstraint> {
         match self {
             SymbolKind::TypeConstraint(t) => Some(t),
@@ -256,7 +355,13 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
+    pub fn as_namespace(&self) -> Option<&crate::namespace::Namespace> {
+        match self {
+            SymbolKind::Namespace(n) => Some(n),
+            _ => None,
+        }
+    }
+
     pub fn as_type(&self) -> Option<&crate::types::Type> {
         match self {
             SymbolKind::Type(t) => Some(t),
@@ -264,7 +369,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_property(&self) -> Option<&crate::property::Property> {
         match self {
             SymbolKind::Property(p) => Some(p),
@@ -272,7 +376,6 @@ impl SymbolKind {
         }
     }
 
-    #[cfg(test)]
     pub fn as_parameter(&self) -> Option<&crate::parameter::Parameter> {
         match self {
             SymbolKind::Parameter(p) => Some(p),
@@ -305,26 +408,34 @@ impl<'a> Iterator for SymbolIterator<'a> {
 
 /// The source of the symbol.
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Source {
+pub struct Source {
     pub file: PathBuf,
     pub start: Position,
     pub end: Position,
+    pub repository: Option<String>,
 }
 
 impl Source {
-    pub fn for_node(node: &Node, ctx: &ParserContext) -> Self {
+    pub(crate) fn for_node(node: &Node, ctx: &ParserContext) -> Self {
+        let start = Position {
+            row: node.start_position().row,
+            column: node.start_position().column,
+            byte_offset: node.start_byte(),
+        };
+        let end = Position {
+            row: node.end_position().row,
+            column: node.end_position().column,
+            byte_offset: node.end_byte(),
+        };
+
         Self {
             file: ctx.file.to_owned(),
-            start: Position {
-                row: node.start_position().row,
-                column: node.start_position().column,
-                byte_offset: node.start_byte(),
-            },
-            end: Position {
-                row: node.end_position().row,
-                column: node.end_position().column,
-                byte_offset: node.end_byte(),
-            },
+            repository: ctx
+                .file_url
+                .as_deref()
+                .map(|url| Repository::source_url(url, &start, &end)),
+            start,
+            end,
         }
     }
 
@@ -333,7 +444,7 @@ impl Source {
             file: self.file.to_owned(),
             start: self.start.clone(),
             end: self.end.clone(),
-            repository: None,
+            repository: self.repository.clone(),
         }
     }
 }
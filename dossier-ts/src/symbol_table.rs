@@ -1,17 +1,48 @@
-use std::collections::VecDeque;
+use dossier_core::Diagnostic;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
 use crate::import::Import;
-use crate::symbol::{self, Symbol, SymbolID, SymbolIterator};
+use crate::resolver::{self, NameResolver};
+use crate::symbol::{self, Symbol, SymbolID, SymbolIterator, SymbolKind};
+use crate::tsconfig::TsConfig;
 
-static SCOPE_ID: AtomicUsize = AtomicUsize::new(0);
+pub type ScopeID = usize;
 
-pub(crate) type ScopeID = usize;
+/// How many import aliases we are willing to follow before giving up, in
+/// case they form a cycle.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// How many passes `resolve_imported_types_to_fixpoint` makes at most. Each
+/// pass resolves one more link of chains of aliases across files.
+const MAX_RESOLUTION_PASSES: usize = 8;
+
+/// Reported when an import specifier matches more than one file
+pub(crate) const AMBIGUOUS_IMPORT: &str = "ambiguous-import";
+
+/// Reported when a relative import specifier matches none of the files
+pub(crate) const UNRESOLVED_IMPORT: &str = "unresolved-import";
+
+/// What the FQNs of names imported from packages start with, e.g.
+/// `external:kysely::Kysely`
+pub(crate) const EXTERNAL_PREFIX: &str = "external:";
+
+/// What an import specifier without an extension may be missing, in the
+/// order TypeScript tries them
+const IMPORT_SUFFIXES: [&str; 6] = [
+    ".ts",
+    ".tsx",
+    ".d.ts",
+    "/index.ts",
+    "/index.tsx",
+    "/index.d.ts",
+];
 
 /// The symbol table for a single file.
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Scope {
+pub struct Scope {
     pub id: ScopeID,
     pub parent: Option<ScopeID>,
     pub imports: Vec<Import>,
@@ -26,25 +57,41 @@ pub(crate) struct Scope {
 ///
 /// When looking up a symbol, the caller needs to know the
 /// scope ID of the scope they're looking in.
-pub(crate) struct SymbolTable {
+///
+/// Scope and symbol IDs are only unique within a single table. They are
+/// assigned in the order the file is parsed, so they don't depend on what
+/// other files are being parsed in parallel.
+pub struct SymbolTable {
     pub file: PathBuf,
     fqn_parts: Vec<String>,
     scopes: Vec<Scope>,
     symbols: Vec<Symbol>,
     current_scope_id: ScopeID,
+    next_scope_id: ScopeID,
+    /// Symbols are created from behind a shared reference to the parser
+    /// context, hence the `Cell`
+    next_symbol_id: Cell<SymbolID>,
+    /// Problems found while parsing the file
+    pub diagnostics: Vec<Diagnostic>,
+    /// The `tsconfig.json` that applies to the file, if there is one
+    pub(crate) tsconfig: Option<Arc<TsConfig>>,
 }
 
 #[allow(dead_code)]
 impl SymbolTable {
     pub fn new<P: Into<PathBuf>>(path: P) -> Self {
-        let root_id = SCOPE_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let root_id = 0;
         let path = path.into();
 
         Self {
-            fqn_parts: vec![format!("{}", path.display())],
+            fqn_parts: vec![dossier_core::portable_path(&path)],
             file: path,
             current_scope_id: root_id,
+            next_scope_id: root_id + 1,
+            next_symbol_id: Cell::new(symbol::UNUSED_SYMBOL_ID + 1),
             symbols: vec![],
+            diagnostics: vec![],
+            tsconfig: None,
             scopes: vec![Scope {
                 id: root_id,
                 parent: None,
@@ -53,42 +100,45 @@ impl SymbolTable {
         }
     }
 
+    /// Names the file `name` in FQNs, rather than by the path it was read
+    /// from
+    pub(crate) fn name_file(&mut self, name: String) {
+        self.fqn_parts[0] = name;
+    }
+
+    /// Finds the declaration of `identifier` visible from the given scope.
+    ///
+    /// Declarations in the innermost scope win, e.g. a method's type variable
+    /// shadows one of the same name on its interface, which shadows a type
+    /// alias in the file. Within a scope, the last declaration wins.
     pub fn lookup(
         &self,
         identifier: &str,
         scope_id: ScopeID,
         symbol_id: SymbolID,
     ) -> Option<&Symbol> {
-        let mut parent_scopes = vec![];
-        let mut scope_id = Some(scope_id);
-
-        while let Some(id) = scope_id {
-            parent_scopes.push(id);
-            scope_id = self
-                .scopes
-                .iter()
-                .find(|s| s.id == id)
-                .and_then(|s| s.parent);
-        }
+        let scope_chain = self.scope_chain(scope_id);
 
         SymbolIterator::new(&self.symbols)
             .filter(|sym| sym.resolvable_identifier() == Some(identifier))
-            .filter(|sym| parent_scopes.contains(&sym.scope_id))
+            // Type identifiers are references to other symbols, not declarations
+            .filter(|sym| !matches!(sym.kind, SymbolKind::Type(_)))
             .filter(|sym| sym.id != symbol_id)
-            .max_by(|sym, other| sym.scope_id.cmp(&other.scope_id))
+            .filter_map(|sym| {
+                let depth = scope_chain.iter().position(|id| *id == sym.scope_id)?;
+                Some((depth, sym))
+            })
+            .max_by(|(depth, _), (other, _)| other.cmp(depth))
+            .map(|(_, sym)| sym)
     }
 
-    /// TODO(Nik): There is a bug here that is not addressed yet: this will only lookup
-    /// symbols at the root level.
-    ///
-    /// We need to create a mutable symbol iterator and call it similarly to how `SymbolIterator`
-    /// is working in the `lookup` method above.
-    pub fn lookup_mut(&mut self, identifier: &str, scope_id: ScopeID) -> Option<&mut Symbol> {
-        let mut parent_scopes = vec![];
+    /// The IDs of the given scope and its ancestors, innermost first
+    fn scope_chain(&self, scope_id: ScopeID) -> Vec<ScopeID> {
+        let mut chain = vec![];
         let mut scope_id = Some(scope_id);
 
         while let Some(id) = scope_id {
-            parent_scopes.push(id);
+            chain.push(id);
             scope_id = self
                 .scopes
                 .iter()
@@ -96,11 +146,63 @@ impl SymbolTable {
                 .and_then(|s| s.parent);
         }
 
-        self.symbols
-            .iter_mut()
-            .filter(|sym| sym.resolvable_identifier() == Some(identifier))
-            .filter(|sym| parent_scopes.contains(&sym.scope_id))
-            .max_by(|sym, other| sym.scope_id.cmp(&other.scope_id))
+        chain
+    }
+
+    /// Resolves a dotted path like `Outer.Inner.Thing` to an FQN, by looking up
+    /// its first part from the given scope and then walking down through the
+    /// members of what it finds. A plain identifier is a path with one part.
+    ///
+    /// Import aliases along the way are followed to what they point at. If an
+    /// alias can't be resolved, the path resolves to the alias itself.
+    pub fn resolve_path(
+        &self,
+        path: &str,
+        scope_id: ScopeID,
+        symbol_id: SymbolID,
+        depth: usize,
+    ) -> Option<String> {
+        let mut parts = path.split('.');
+        let mut symbol = self.lookup(parts.next()?, scope_id, symbol_id)?;
+
+        for part in parts {
+            symbol = symbol
+                .children()
+                .iter()
+                .filter(|child| !matches!(child.kind, SymbolKind::Type(_)))
+                .find(|child| child.identifier() == Some(part))?;
+        }
+
+        if let SymbolKind::ImportAlias(alias) = &symbol.kind {
+            if depth < MAX_ALIAS_DEPTH {
+                let target =
+                    self.resolve_path(&alias.target, symbol.scope_id, symbol.id, depth + 1);
+                if target.is_some() {
+                    return target;
+                }
+            }
+        }
+
+        symbol.fqn.clone()
+    }
+
+    /// Same as `lookup`, but for changing the symbol. Finds symbols nested
+    /// in other symbols too.
+    pub(crate) fn lookup_mut(
+        &mut self,
+        identifier: &str,
+        scope_id: ScopeID,
+    ) -> Option<&mut Symbol> {
+        let id = self
+            .lookup(identifier, scope_id, symbol::UNUSED_SYMBOL_ID)?
+            .id;
+
+        SymbolIterMut::new(&mut self.symbols, |symbol| symbol.id == id).next()
+    }
+
+    /// The symbol with the given ID, wherever it is nested
+    pub(crate) fn symbol_mut(&mut self, id: SymbolID) -> Option<&mut Symbol> {
+        SymbolIterMut::new(&mut self.symbols, |symbol| symbol.id == id).next()
     }
 
     pub fn lookup_import(&self, identifier: &str, scope_id: ScopeID) -> Option<&Import> {
@@ -109,7 +211,7 @@ impl SymbolTable {
         scope
             .imports
             .iter()
-            .find(|i| i.names.contains(&identifier.to_owned()))
+            .find(|i| i.imports(identifier))
             .or_else(|| {
                 if let Some(parent_id) = scope.parent {
                     self.lookup_import(identifier, parent_id)
@@ -123,15 +225,30 @@ impl SymbolTable {
         self.symbols.iter()
     }
 
+    pub fn all_scopes(&self) -> impl Iterator<Item = &Scope> {
+        self.scopes.iter()
+    }
+
     pub fn all_imports(&self) -> impl Iterator<Item = &Import> {
         self.scopes.iter().flat_map(|s| s.imports.iter())
     }
 
-    pub fn add_symbol(&mut self, symbol: Symbol) {
+    pub(crate) fn add_symbol(&mut self, symbol: Symbol) {
         self.symbols.push(symbol);
     }
 
-    pub fn export_symbol(&mut self, identifier: &str) {
+    /// The number of top-level symbols added so far.
+    pub(crate) fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Removes the top-level symbols added since there were `count` of them,
+    /// e.g. to move the declarations in a namespace under the namespace.
+    pub(crate) fn take_symbols_from(&mut self, count: usize) -> Vec<Symbol> {
+        self.symbols.split_off(count)
+    }
+
+    pub(crate) fn export_symbol(&mut self, identifier: &str) {
         if let Some(symbol) = self.lookup_mut(identifier, self.current_scope_id) {
             symbol.mark_as_exported()
         }
@@ -139,6 +256,11 @@ impl SymbolTable {
 
     /// The wildest part of this crate by a long shot. Type resolution!
     pub fn resolve_types(&mut self) {
+        self.resolve_types_with(resolver::DEFAULT);
+    }
+
+    /// Same as `resolve_types`, with the given resolver
+    pub(crate) fn resolve_types_with(&mut self, resolver: &dyn NameResolver) {
         // First pass: collect the actions we need to apply to avoid mutable-immutable borrow conflict
         //
         // We collect a set of actions where the elements are:
@@ -158,11 +280,9 @@ impl SymbolTable {
         //
         // Look up the identifier from its scope. If we find a match, we add it to the resolutions,
         // which is an identical list as above, except the last element is the resolved FQN of the symbol
-        for (child_indexes, identifier, scope_id, symbol_id) in actions {
-            if let Some(matching_symbol) = self.lookup(&identifier, scope_id, symbol_id) {
-                if let Some(fqn) = matching_symbol.fqn.as_ref() {
-                    resolutions.push((child_indexes, fqn.clone()));
-                }
+        for (child_indexes, path, scope_id, symbol_id) in actions {
+            if let Some(fqn) = resolver.resolve_local(self, &path, scope_id, symbol_id) {
+                resolutions.push((child_indexes, fqn));
             }
         }
 
@@ -175,11 +295,10 @@ impl SymbolTable {
         }
     }
 
-    /// Same as `resolve_types`, but resolves imports across files.
-    pub fn resolve_imported_types<'a, T: IntoIterator<Item = &'a SymbolTable>>(
-        &mut self,
-        all_tables: T,
-    ) {
+    /// Same as `resolve_types`, but for the names this table imports from
+    /// the tables in `index`. Types can't be resolved while other tables are
+    /// borrowed, so the resolutions are returned for `apply_resolutions`.
+    fn imported_resolutions(&self, index: &TableIndex, resolver: &dyn NameResolver) -> Resolutions {
         // First pass: collect the actions we need to apply to avoid mutable-immutable borrow conflict
         //
         // We collect a set of actions where the elements are:
@@ -194,39 +313,200 @@ impl SymbolTable {
             Self::collect_actions_recursive(symbol, &mut chain, &mut actions);
         }
 
-        let mut all_tables = all_tables.into_iter();
-        let mut resolutions: Vec<(VecDeque<usize>, String)> = vec![];
+        let mut resolutions = Resolutions::default();
+        let mut reported = HashSet::new();
         // Second pass: perform the lookups and collect the results
         //
         // Look up the identifier from its scope. If we find a match, we add it to the resolutions,
         // which is an identical list as above, except the last element is the resolved FQN of the symbol
         for (child_indexes, identifier, scope_id, _) in actions {
-            if let Some(import) = self.lookup_import(&identifier, scope_id) {
-                if let Some(imported_table) =
-                    all_tables.find(|t| self.matches_import_path(&t.file, import))
+            // `Validation.StringValidator` is imported as `Validation`
+            let imported_name = identifier.split('.').next().unwrap_or_default();
+            if let Some(import) = self.lookup_import(imported_name, scope_id) {
+                self.check_import(index, import, &mut reported, &mut resolutions.diagnostics);
+
+                // `Bar` from `import { Foo as Bar }` is `Foo` in the other file
+                let path = import.imported_path(&identifier);
+                if let Some(fqn) = resolver
+                    .resolve_import(self, import, &path, index)
+                    .or_else(|| self.external_fqn(index, import, &path))
                 {
-                    if let Some(matching_symbol) = imported_table.lookup(
-                        &identifier,
-                        imported_table.root_scope().id,
-                        symbol::UNUSED_SYMBOL_ID,
-                    ) {
-                        if matching_symbol.is_exported() {
-                            if let Some(fqn) = matching_symbol.fqn.as_ref() {
-                                resolutions.push((child_indexes, fqn.clone()));
-                            }
-                        }
-                    }
+                    resolutions.types.push((child_indexes, fqn));
                 }
             }
         }
 
-        // Third pass: apply the resolutions back to the symbols
-        for (mut indexes, fqn) in resolutions.into_iter() {
+        // Re-exports are resolved the same way, as if their names were imported
+        for (position, symbol) in self.symbols.iter().enumerate() {
+            if let SymbolKind::ReExport(reexport) = &symbol.kind {
+                let import = reexport.as_import();
+                self.check_import(index, &import, &mut reported, &mut resolutions.diagnostics);
+
+                if let Some(fqn) = resolver
+                    .resolve_import(self, &import, &reexport.original, index)
+                    .or_else(|| self.external_fqn(index, &import, &reexport.original))
+                {
+                    resolutions.types.push((VecDeque::from([position]), fqn));
+                }
+            }
+        }
+
+        resolutions
+    }
+
+    /// Third pass of resolving imported types: apply the resolutions back to
+    /// the symbols. Returns how many symbols now refer to something else.
+    fn apply_resolutions(&mut self, resolutions: Resolutions) -> usize {
+        let Resolutions { types, diagnostics } = resolutions;
+        // Every pass finds the same problems with the imports again
+        for diagnostic in diagnostics {
+            if !self.diagnostics.contains(&diagnostic) {
+                self.diagnostics.push(diagnostic);
+            }
+        }
+
+        let mut changed = 0;
+        for (mut indexes, fqn) in types.into_iter() {
             if let Some(symbol) = self.symbols.get_mut(indexes.pop_front().unwrap()) {
                 let symbol = Self::resolve_symbol_mut(symbol, indexes); // Use slicing to pass the rest of the indexes
-                symbol.resolve_type(&fqn);
+                if symbol.resolve_type(&fqn) {
+                    changed += 1;
+                }
             }
         }
+        changed
+    }
+
+    /// The FQN of `name` imported with `import` from a package, i.e. with a
+    /// non-relative specifier that none of the files match, like `kysely`,
+    /// `@org/pkg/sub`, or `node:fs`.
+    pub(crate) fn external_fqn(
+        &self,
+        index: &TableIndex,
+        import: &Import,
+        name: &str,
+    ) -> Option<String> {
+        if is_relative(&import.source) || !index.candidates(self, import).is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "{}{}::{}",
+            EXTERNAL_PREFIX,
+            package_name(&import.source),
+            name.replace('.', "::")
+        ))
+    }
+
+    /// Warns about imports that could refer to several files, of which
+    /// resolvers use the first, and about relative imports that refer to
+    /// none of them. Each import source is only reported once.
+    fn check_import(
+        &self,
+        index: &TableIndex,
+        import: &Import,
+        reported: &mut HashSet<String>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let candidates = index.candidates(self, import);
+
+        // Names imported from packages are external, not unresolved
+        if candidates.is_empty()
+            && is_relative(&import.source)
+            && reported.insert(import.source.clone())
+        {
+            diagnostics.push(Diagnostic::warning(
+                UNRESOLVED_IMPORT,
+                format!(
+                    "`{}` doesn't refer to any of the parsed files, so the names imported from it aren't resolved",
+                    import.source
+                ),
+                &self.file,
+            ));
+        }
+
+        if candidates.len() > 1 && reported.insert(import.source.clone()) {
+            diagnostics.push(Diagnostic::warning(
+                AMBIGUOUS_IMPORT,
+                format!(
+                    "`{}` could refer to any of {}; using {}",
+                    import.source,
+                    candidates
+                        .iter()
+                        .map(|t| t.file.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    candidates[0].file.display()
+                ),
+                &self.file,
+            ));
+        }
+    }
+
+    /// The FQN of what this file exports as `name`. Re-exports are followed
+    /// into the files they re-export from, up to `MAX_ALIAS_DEPTH` of them,
+    /// and aliases to their target if it has been resolved.
+    ///
+    /// `name` may be a dotted path like `Validation.StringValidator`, to a
+    /// member a namespace exports.
+    pub(crate) fn resolve_export(
+        &self,
+        name: &str,
+        index: &TableIndex,
+        depth: usize,
+    ) -> Option<String> {
+        let (head, rest) = match name.split_once('.') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (name, None),
+        };
+
+        // Re-exports don't bring their names into scope, so `lookup` doesn't
+        // find them
+        let mut symbol = self
+            .symbols
+            .iter()
+            .find(|s| matches!(&s.kind, SymbolKind::ReExport(r) if r.identifier == head))
+            .or_else(|| self.lookup(head, self.root_scope().id, symbol::UNUSED_SYMBOL_ID))?;
+
+        if !symbol.is_exported() {
+            return None;
+        }
+
+        if let SymbolKind::ReExport(reexport) = &symbol.kind {
+            if depth >= MAX_ALIAS_DEPTH {
+                return None;
+            }
+
+            let original = match rest {
+                Some(rest) => format!("{}.{}", reexport.original, rest),
+                None => reexport.original.clone(),
+            };
+            let import = reexport.as_import();
+            return index.candidates(self, &import).first()?.resolve_export(
+                &original,
+                index,
+                depth + 1,
+            );
+        }
+
+        for part in rest.into_iter().flat_map(|rest| rest.split('.')) {
+            // Only what the namespace exports can be reached from outside
+            symbol = symbol
+                .children()
+                .iter()
+                .filter(|child| !matches!(child.kind, SymbolKind::Type(_)))
+                .find(|child| child.identifier() == Some(part) && child.is_exported())?;
+        }
+
+        // Like within the file, an alias stands for what it aliases, once
+        // that has been resolved
+        if let SymbolKind::ImportAlias(alias) = &symbol.kind {
+            if let Some(target) = &alias.resolved {
+                return Some(target.clone());
+            }
+        }
+
+        symbol.fqn.clone()
     }
 
     /// Helper function to recursively collect a list of actions to perform=
@@ -236,10 +516,10 @@ impl SymbolTable {
         chain: &mut VecDeque<usize>,
         actions: &mut Vec<(VecDeque<usize>, String, ScopeID, SymbolID)>,
     ) {
-        if let Some(resolvable_identifier) = symbol.resolvable_identifier() {
+        if let Some(reference_path) = symbol.reference_path() {
             actions.push((
                 chain.clone(),
-                resolvable_identifier.to_owned(),
+                reference_path.to_owned(),
                 symbol.scope_id,
                 symbol.id,
             ));
@@ -262,50 +542,8 @@ impl SymbolTable {
         }
     }
 
-    /// Returns true if the import path resolves to the symbol table's path
-    /// from the perspective of the current symbol table's path.
-    ///
-    /// i.e. if a file `foo/bar.ts` imports `../fizz.ts`, this function
-    /// returns true for symbol table with the path `fizz.ts`.
-    fn matches_import_path(&self, symbol_table_path: &Path, import: &Import) -> bool {
-        // Get the directory of the current symbol table's file
-        let base_path = self.file.parent().unwrap_or_else(|| Path::new(""));
-
-        // Combine base path with the relative path from import
-        let combined_path = base_path.join(&import.source);
-
-        // Normalize the combined path
-        let normalized_path = self.normalize_path(&combined_path);
-
-        // Compare the normalized paths
-        normalized_path == symbol_table_path
-    }
-
-    // Helper function to normalize a path
-    fn normalize_path(&self, path: &Path) -> PathBuf {
-        let mut components = path.components().peekable();
-        let mut normalized_path = PathBuf::new();
-
-        while let Some(component) = components.next() {
-            match component {
-                std::path::Component::ParentDir => {
-                    // If there's a previous component and it's not "..", go up one level
-                    if let Some(std::path::Component::Normal(_)) = components.peek() {
-                        normalized_path.pop();
-                    } else {
-                        normalized_path.push("..");
-                    }
-                }
-                std::path::Component::Normal(part) => normalized_path.push(part),
-                _ => {} // Ignore other components (RootDir, CurDir, Prefix)
-            }
-        }
-
-        normalized_path
-    }
-
     /// Constructs a fully qualified name for the given identifier in the current scope.
-    pub fn construct_fqn(&self, identifier: &str) -> String {
+    pub(crate) fn construct_fqn(&self, identifier: &str) -> String {
         let mut parts = self.fqn_parts.clone();
 
         parts.push(identifier.to_owned());
@@ -313,21 +551,32 @@ impl SymbolTable {
         parts.join("::")
     }
 
-    pub fn push_fqn(&mut self, part: &str) {
+    pub(crate) fn push_fqn(&mut self, part: &str) {
         self.fqn_parts.push(part.to_owned());
     }
 
-    pub fn pop_fqn(&mut self) -> Option<String> {
+    pub(crate) fn pop_fqn(&mut self) -> Option<String> {
         self.fqn_parts.pop()
     }
 
-    pub fn add_import(&mut self, import: Import) {
+    pub(crate) fn add_import(&mut self, import: Import) {
         self.current_scope_mut().imports.push(import);
     }
 
-    /// Create a new scope with the given name.
-    pub fn push_scope(&mut self) -> ScopeID {
-        let id = SCOPE_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    /// Get a new symbol ID, unique within this table.
+    pub(crate) fn next_symbol_id(&self) -> SymbolID {
+        let id = self.next_symbol_id.get();
+        self.next_symbol_id.set(id + 1);
+        id
+    }
+
+    /// Push a new scope as a child of the current scope, and set the current
+    /// scope to it.
+    ///
+    /// Returns the new scope ID.
+    pub(crate) fn push_scope(&mut self) -> ScopeID {
+        let id = self.next_scope_id;
+        self.next_scope_id += 1;
 
         self.scopes.push(Scope {
             id,
@@ -344,7 +593,7 @@ impl SymbolTable {
     /// If the current scope has no parent, (aka, it's the global scope), this is a no-op.
     ///
     /// Returns the popped scope ID.
-    pub fn pop_scope(&mut self) -> ScopeID {
+    pub(crate) fn pop_scope(&mut self) -> ScopeID {
         let current_id = self.current_scope_id;
 
         if let Some(parent_id) = self.current_scope().parent {
@@ -373,11 +622,191 @@ impl SymbolTable {
     }
 }
 
+/// Resolves the names each table imports from the others with `resolver`,
+/// the same way `SymbolTable::resolve_types` does within a table. Returns how
+/// many symbols now refer to something else.
+///
+/// A pass sees what passes before it resolved, but not what it resolves
+/// itself. So a name imported from an alias that is resolved in the same
+/// pass, like `User` from `export import User = Models.User` where `Models`
+/// is imported too, refers to the alias until the next pass. See
+/// `resolve_imported_types_to_fixpoint`.
+pub(crate) fn resolve_imported_types_with(
+    tables: &mut [SymbolTable],
+    resolver: &dyn NameResolver,
+) -> usize {
+    // The index borrows the tables, so the resolutions are only applied once
+    // they have all been found
+    let resolutions = {
+        let index = TableIndex::new(tables.iter());
+        tables
+            .iter()
+            .map(|table| table.imported_resolutions(&index, resolver))
+            .collect::<Vec<_>>()
+    };
+
+    tables
+        .iter_mut()
+        .zip(resolutions)
+        .map(|(table, resolutions)| table.apply_resolutions(resolutions))
+        .sum()
+}
+
+/// Runs `resolve_imported_types_with` until a pass doesn't change anything, so
+/// that chains of aliases across files resolve to their end, or for at most
+/// `MAX_RESOLUTION_PASSES` passes. Returns the number of passes.
+pub(crate) fn resolve_imported_types_to_fixpoint(tables: &mut [SymbolTable]) -> usize {
+    resolve_imported_types_to_fixpoint_with(tables, resolver::DEFAULT)
+}
+
+/// Same as `resolve_imported_types_to_fixpoint`, with the given resolver
+pub(crate) fn resolve_imported_types_to_fixpoint_with(
+    tables: &mut [SymbolTable],
+    resolver: &dyn NameResolver,
+) -> usize {
+    let mut passes = 0;
+    while passes < MAX_RESOLUTION_PASSES {
+        passes += 1;
+        if resolve_imported_types_with(tables, resolver) == 0 {
+            break;
+        }
+    }
+    passes
+}
+
+/// The symbol tables of all parsed files by path, so that finding the file
+/// an import refers to doesn't mean going through all of them.
+pub(crate) struct TableIndex<'a> {
+    /// By normalized path, so that `./src/foo.ts` and `src/foo.ts` are the same
+    by_path: HashMap<PathBuf, &'a SymbolTable>,
+}
+
+impl<'a> TableIndex<'a> {
+    pub fn new<T: IntoIterator<Item = &'a SymbolTable>>(tables: T) -> Self {
+        Self {
+            by_path: tables
+                .into_iter()
+                .map(|table| (dossier_core::normalize_path(&table.file), table))
+                .collect(),
+        }
+    }
+
+    /// The tables an import in `from` could refer to, in the order TypeScript
+    /// would pick them: the path as written, and then with each of the
+    /// `IMPORT_SUFFIXES`.
+    ///
+    /// i.e. if a file `foo/bar.ts` imports `../fizz`, this returns the tables
+    /// with the paths `fizz`, `fizz.ts`, `fizz/index.ts`, etc. that exist.
+    ///
+    /// Non-relative specifiers are first looked up with the `paths` and
+    /// `baseUrl` of the file's `tsconfig.json`, and then relative to the file.
+    pub fn candidates(&self, from: &SymbolTable, import: &Import) -> Vec<&'a SymbolTable> {
+        let base_path = from.file.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut import_paths = match &from.tsconfig {
+            Some(config) if !is_relative(&import.source) => config.mapped_paths(&import.source),
+            _ => vec![],
+        };
+        import_paths.push(base_path.join(&import.source));
+
+        let mut candidates: Vec<&'a SymbolTable> = vec![];
+        for import_path in import_paths {
+            let import_path = dossier_core::normalize_path(&import_path);
+
+            // Like TypeScript, `./config` may refer to `config.ts` as well as
+            // `config/index.ts`
+            let suffixed = IMPORT_SUFFIXES.iter().map(|suffix| {
+                let mut candidate = import_path.clone().into_os_string();
+                candidate.push(suffix);
+                PathBuf::from(candidate)
+            });
+
+            for path in std::iter::once(import_path.clone()).chain(suffixed) {
+                if let Some(table) = self.by_path.get(&path) {
+                    if !candidates.iter().any(|c| std::ptr::eq(*c, *table)) {
+                        candidates.push(table);
+                    }
+                }
+            }
+        }
+
+        candidates
+    }
+}
+
+/// What `SymbolTable::imported_resolutions` found
+#[derive(Debug, Default)]
+struct Resolutions {
+    /// The chain of indexes to each symbol to resolve, and its FQN
+    types: Vec<(VecDeque<usize>, String)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Whether an import specifier is relative to the importing file, rather than
+/// e.g. the name of a package
+fn is_relative(specifier: &str) -> bool {
+    specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with('/')
+}
+
+/// The package a non-relative import specifier imports from, i.e. its first
+/// component, or first two for scoped packages like `@org/pkg`
+fn package_name(specifier: &str) -> &str {
+    let components = if specifier.starts_with('@') { 2 } else { 1 };
+
+    match specifier.match_indices('/').nth(components - 1) {
+        Some((end, _)) => &specifier[..end],
+        None => specifier,
+    }
+}
+
+/// The package of an FQN made by `SymbolTable::external_fqn`
+pub(crate) fn external_package(fqn: &str) -> Option<&str> {
+    fqn.strip_prefix(EXTERNAL_PREFIX)?.split("::").next()
+}
+
+/// Mutable counterpart of `SymbolIterator`, over the symbols that match a
+/// predicate.
+///
+/// Yields the matching symbols in the same order as `SymbolIterator`, but
+/// doesn't look inside the ones it yields: their children are part of them,
+/// so they can't be handed out at the same time.
+struct SymbolIterMut<'a, P> {
+    queue: VecDeque<&'a mut Symbol>,
+    predicate: P,
+}
+
+impl<'a, P: FnMut(&Symbol) -> bool> SymbolIterMut<'a, P> {
+    fn new(symbols: &'a mut [Symbol], predicate: P) -> Self {
+        let queue = symbols.iter_mut().collect();
+        SymbolIterMut { queue, predicate }
+    }
+}
+
+impl<'a, P: FnMut(&Symbol) -> bool> Iterator for SymbolIterMut<'a, P> {
+    type Item = &'a mut Symbol;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(symbol) = self.queue.pop_front() {
+            if (self.predicate)(symbol) {
+                return Some(symbol);
+            }
+            self.queue.extend(symbol.children_mut());
+        }
+
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use dossier_core::Position;
 
     use super::*;
+    use crate::import::ImportedName;
     use crate::symbol::{Source, SymbolKind};
 
     #[test]
@@ -388,8 +817,13 @@ mod test {
             kind: SymbolKind::Function(crate::function::Function {
                 identifier: "foo".to_owned(),
                 documentation: None,
+                doc_tags: Default::default(),
                 is_exported: false,
+                is_async: false,
+                is_generator: false,
                 children: vec![],
+                awaited_type: None,
+                signature: None,
             }),
             source: Source {
                 file: PathBuf::from("foo.ts"),
@@ -403,6 +837,7 @@ mod test {
                     column: 0,
                     byte_offset: 0,
                 },
+                repository: None,
             },
             fqn: Some("foo.ts::foo".to_owned()),
             context: None,
@@ -434,8 +869,13 @@ mod test {
             kind: SymbolKind::Function(crate::function::Function {
                 identifier: "foo".to_owned(),
                 documentation: None,
+                doc_tags: Default::default(),
                 is_exported: false,
+                is_async: false,
+                is_generator: false,
                 children: vec![],
+                awaited_type: None,
+                signature: None,
             }),
             source: Source {
                 file: PathBuf::from("foo.ts"),
@@ -449,6 +889,7 @@ mod test {
                     column: 0,
                     byte_offset: 0,
                 },
+                repository: None,
             },
             fqn: Some("foo.ts::foo".to_owned()),
             context: None,
@@ -469,8 +910,13 @@ mod test {
             kind: SymbolKind::Function(crate::function::Function {
                 identifier: "foo".to_owned(),
                 documentation: None,
+                doc_tags: Default::default(),
                 is_exported: false,
+                is_async: false,
+                is_generator: false,
                 children: vec![],
+                awaited_type: None,
+                signature: None,
             }),
             source: Source {
                 file: PathBuf::from("foo.ts"),
@@ -484,6 +930,7 @@ mod test {
                     column: 0,
                     byte_offset: 0,
                 },
+                repository: None,
             },
             fqn: Some("foo.ts::foo".to_owned()),
             context: None,
@@ -507,8 +954,13 @@ mod test {
             kind: SymbolKind::Function(crate::function::Function {
                 identifier: "foo".to_owned(),
                 documentation: None,
+                doc_tags: Default::default(),
                 is_exported: false,
+                is_async: false,
+                is_generator: false,
                 children: vec![],
+                awaited_type: None,
+                signature: None,
             }),
             source: Source {
                 file: PathBuf::from("foo.ts"),
@@ -522,6 +974,7 @@ mod test {
                     column: 0,
                     byte_offset: 0,
                 },
+                repository: None,
             },
             fqn: Some("foo.ts::foo".to_owned()),
             context: None,
@@ -537,6 +990,111 @@ mod test {
             .is_some());
     }
 
+    #[test]
+    fn lookup_mut_finds_nested_symbols() {
+        let source = indoc::indoc! { r#"
+        namespace Outer {
+            type Inner = string;
+        }
+        "#};
+        let mut table =
+            crate::parse_file(crate::ParserContext::new(Path::new("foo.ts"), source)).unwrap();
+
+        let inner_scope = table.all_symbols().next().unwrap().children()[0].scope_id;
+        let inner = table.lookup_mut("Inner", inner_scope).unwrap();
+        assert_eq!(inner.fqn.as_deref(), Some("foo.ts::Outer::Inner"));
+
+        inner.mark_as_exported();
+        assert!(table.all_symbols().next().unwrap().children()[0].is_exported());
+
+        assert!(table.lookup_mut("Inner", table.root_scope().id).is_none());
+    }
+
+    #[test]
+    fn symbol_iter_mut_follows_the_order_of_symbol_iterator() {
+        let source = indoc::indoc! { r#"
+        namespace Outer {
+            type Inner = string;
+        }
+        type First = number;
+        "#};
+        let mut table =
+            crate::parse_file(crate::ParserContext::new(Path::new("foo.ts"), source)).unwrap();
+        let is_alias = |symbol: &Symbol| matches!(symbol.kind, SymbolKind::TypeAlias(_));
+
+        let expected = SymbolIterator::new(&table.symbols)
+            .filter(|symbol| is_alias(symbol))
+            .map(|symbol| symbol.fqn.clone())
+            .collect::<Vec<_>>();
+        for symbol in SymbolIterMut::new(&mut table.symbols, is_alias) {
+            symbol.fqn = symbol.fqn.take().map(|fqn| fqn.to_uppercase());
+        }
+
+        let renamed = SymbolIterator::new(&table.symbols)
+            .filter(|symbol| is_alias(symbol))
+            .map(|symbol| symbol.fqn.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            expected,
+            vec![
+                Some("foo.ts::First".to_owned()),
+                Some("foo.ts::Outer::Inner".to_owned())
+            ]
+        );
+        assert_eq!(
+            renamed,
+            vec![
+                Some("FOO.TS::FIRST".to_owned()),
+                Some("FOO.TS::OUTER::INNER".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_what_each_pass_resolves() {
+        let sources = [
+            (
+                "models.ts",
+                "export namespace Models { export interface User {} }",
+            ),
+            (
+                "api.ts",
+                indoc::indoc! { r#"
+                import { Models } from "./models";
+                export namespace Api { export import User = Models.User; }
+                "#},
+            ),
+            (
+                "admin.ts",
+                indoc::indoc! { r#"
+                import { Api } from "./api";
+                export type Admin = Api.User;
+                "#},
+            ),
+        ];
+        let mut tables = sources
+            .iter()
+            .map(|(path, code)| {
+                crate::parse_file(crate::ParserContext::new(Path::new(path), code)).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // The alias, and `Admin` to the alias
+        assert_eq!(
+            resolve_imported_types_with(&mut tables, resolver::DEFAULT),
+            2
+        );
+        // `Admin` to what the alias aliases
+        assert_eq!(
+            resolve_imported_types_with(&mut tables, resolver::DEFAULT),
+            1
+        );
+        assert_eq!(
+            resolve_imported_types_with(&mut tables, resolver::DEFAULT),
+            0
+        );
+    }
+
     #[test]
     fn computes_fqns_for_entries() {
         let mut table = SymbolTable::new("foo.ts");
@@ -547,4 +1105,116 @@ mod test {
 
         assert_eq!(table.construct_fqn("foo"), "foo.ts::Fizz::foo");
     }
+
+    /// The files of the tables `import` in `file` could refer to, in order
+    fn candidate_files(tables: &[SymbolTable], file: &str, source: &str) -> Vec<String> {
+        let import = Import {
+            names: vec![ImportedName::unaliased("Foo")],
+            source: source.to_owned(),
+            type_only: false,
+        };
+
+        TableIndex::new(tables)
+            .candidates(&SymbolTable::new(file), &import)
+            .iter()
+            .map(|table| table.file.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn finds_each_import_candidate() {
+        for file in [
+            "src/types",
+            "src/types.ts",
+            "src/types.tsx",
+            "src/types.d.ts",
+            "src/types/index.ts",
+            "src/types/index.tsx",
+        ] {
+            let tables = [SymbolTable::new(file)];
+
+            assert_eq!(
+                candidate_files(&tables, "src/index.ts", "./types"),
+                [file],
+                "./types should resolve to {}",
+                file
+            );
+            assert_eq!(
+                candidate_files(&tables, "src/nested/index.ts", "../types"),
+                [file]
+            );
+        }
+    }
+
+    #[test]
+    fn orders_import_candidates_like_typescript() {
+        // Deliberately out of order
+        let tables = [
+            SymbolTable::new("types/index.tsx"),
+            SymbolTable::new("types.d.ts"),
+            SymbolTable::new("types/index.ts"),
+            SymbolTable::new("types.tsx"),
+            SymbolTable::new("types.ts"),
+        ];
+
+        assert_eq!(
+            candidate_files(&tables, "index.ts", "./types"),
+            [
+                "types.ts",
+                "types.tsx",
+                "types.d.ts",
+                "types/index.ts",
+                "types/index.tsx"
+            ]
+        );
+        assert_eq!(
+            candidate_files(&tables, "index.ts", "./types.d.ts"),
+            ["types.d.ts"]
+        );
+        assert!(candidate_files(&tables, "index.ts", "./other").is_empty());
+    }
+
+    #[test]
+    fn keeps_leading_parent_directories_in_import_candidates() {
+        let tables = [
+            SymbolTable::new("../lib/types.ts"),
+            SymbolTable::new("lib/types.ts"),
+        ];
+
+        assert_eq!(
+            candidate_files(&tables, "../src/index.ts", "../lib/types"),
+            ["../lib/types.ts"]
+        );
+        assert_eq!(
+            candidate_files(&tables, "src/index.ts", "../lib/types"),
+            ["lib/types.ts"]
+        );
+    }
+
+    #[test]
+    fn finds_the_package_of_specifiers() {
+        assert_eq!(package_name("kysely"), "kysely");
+        assert_eq!(package_name("kysely/helpers/postgres"), "kysely");
+        assert_eq!(package_name("@org/pkg"), "@org/pkg");
+        assert_eq!(package_name("@org/pkg/sub"), "@org/pkg");
+        assert_eq!(package_name("node:fs"), "node:fs");
+        assert_eq!(package_name("node:fs/promises"), "node:fs");
+
+        assert_eq!(external_package("external:@org/pkg::Foo"), Some("@org/pkg"));
+        assert_eq!(external_package("src/index.ts::Foo"), None);
+    }
+
+    #[test]
+    fn ignores_leading_dots_in_import_candidates() {
+        let tables = [SymbolTable::new("./src/types.ts")];
+
+        assert_eq!(
+            candidate_files(&tables, "src/index.ts", "./types"),
+            ["./src/types.ts"]
+        );
+        assert_eq!(
+            candidate_files(&tables, "./src/index.ts", "././types"),
+            ["./src/types.ts"]
+        );
+    }
 }
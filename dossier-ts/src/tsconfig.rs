@@ -0,0 +1,369 @@
+//! The parts of `tsconfig.json` that change what imports refer to: the
+//! `baseUrl` and `paths` compiler options.
+//!
+//! Only the file itself is read. Options inherited with `extends` are not.
+use dossier_core::serde_json::{self, Value};
+use dossier_core::{Diagnostic, FileSource};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Reported when a `tsconfig.json` can't be parsed
+pub(crate) const INVALID_TSCONFIG: &str = "invalid-tsconfig";
+
+const FILE_NAME: &str = "tsconfig.json";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct TsConfig {
+    /// `baseUrl`, relative to the directory of the config
+    base_url: Option<PathBuf>,
+    /// What `paths` are relative to: `baseUrl` if it is set, and otherwise
+    /// the directory of the config
+    paths_base: PathBuf,
+    /// The patterns in `paths`, and the paths each one maps to
+    paths: Vec<(String, Vec<String>)>,
+}
+
+impl TsConfig {
+    /// Parses the config at `path`. Like TypeScript, allows comments and
+    /// trailing commas.
+    pub fn parse(path: &Path, text: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(&strip_comments_and_trailing_commas(text))
+            .map_err(|e| e.to_string())?;
+        let options = json.get("compilerOptions");
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let base_url = match options.and_then(|o| o.get("baseUrl")) {
+            None => None,
+            Some(Value::String(base_url)) => Some(dir.join(base_url)),
+            Some(_) => return Err("`baseUrl` must be a string".to_owned()),
+        };
+
+        let mut paths = vec![];
+        match options.and_then(|o| o.get("paths")) {
+            None => {}
+            Some(Value::Object(patterns)) => {
+                for (pattern, targets) in patterns {
+                    let targets = targets
+                        .as_array()
+                        .and_then(|targets| {
+                            targets
+                                .iter()
+                                .map(|t| t.as_str().map(|t| t.to_owned()))
+                                .collect::<Option<Vec<_>>>()
+                        })
+                        .ok_or_else(|| {
+                            format!("`paths` of `{}` must be a list of strings", pattern)
+                        })?;
+                    paths.push((pattern.clone(), targets));
+                }
+            }
+            Some(_) => return Err("`paths` must be an object".to_owned()),
+        }
+
+        Ok(Self {
+            paths_base: base_url.clone().unwrap_or_else(|| dir.to_owned()),
+            base_url,
+            paths,
+        })
+    }
+
+    /// The paths a non-relative import specifier may refer to, in the order
+    /// TypeScript tries them: the targets of the pattern in `paths` that
+    /// matches it best, and then the specifier under `baseUrl`.
+    ///
+    /// Like in TypeScript, an exact pattern beats a wildcard, and of the
+    /// wildcards the one with the longest prefix wins.
+    pub fn mapped_paths(&self, specifier: &str) -> Vec<PathBuf> {
+        let best = self
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let wildcard = match_pattern(pattern, specifier)?;
+                // Rank exact matches above all wildcards
+                let rank = match wildcard {
+                    None => usize::MAX,
+                    Some(_) => pattern.find('*').unwrap_or(0),
+                };
+                Some((rank, wildcard, targets))
+            })
+            // The first of equally good patterns wins
+            .rev()
+            .max_by_key(|(rank, _, _)| *rank);
+
+        let mut mapped = vec![];
+        if let Some((_, wildcard, targets)) = best {
+            for target in targets {
+                let target = match wildcard {
+                    Some(matched) => target.replacen('*', matched, 1),
+                    None => target.clone(),
+                };
+                mapped.push(self.paths_base.join(target));
+            }
+        }
+        if let Some(base_url) = &self.base_url {
+            mapped.push(base_url.join(specifier));
+        }
+
+        mapped
+    }
+}
+
+/// If `specifier` matches `pattern`, what the `*` in the pattern matched, if
+/// there is one
+fn match_pattern<'s>(pattern: &str, specifier: &'s str) -> Option<Option<&'s str>> {
+    match pattern.split_once('*') {
+        None => (pattern == specifier).then_some(None),
+        Some((prefix, suffix)) => {
+            let matched = specifier.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some(Some(matched))
+        }
+    }
+}
+
+/// Turns the JSON-with-comments TypeScript allows in configs into JSON
+fn strip_comments_and_trailing_commas(text: &str) -> String {
+    let mut json = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|c| *c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (']' | '}', _) => {
+                // Drop a comma before the closing bracket, if there is one
+                let end = json.trim_end().len();
+                if json[..end].ends_with(',') {
+                    json.truncate(end - 1);
+                }
+                json.push(c);
+            }
+            _ => json.push(c),
+        }
+    }
+
+    json
+}
+
+/// The configs of the files being parsed: the one given with `--tsconfig`,
+/// or else the `tsconfig.json` nearest to each file.
+#[derive(Debug)]
+pub(crate) struct TsConfigs<'a> {
+    file_source: &'a dyn FileSource,
+    explicit: Option<Option<Arc<TsConfig>>>,
+    /// The nearest config of each directory looked at so far
+    by_dir: HashMap<PathBuf, Option<Arc<TsConfig>>>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> TsConfigs<'a> {
+    /// Configs read from `file_source`. If `explicit` is given, it is used
+    /// for all files, and an error if it can't be read.
+    pub fn new(
+        file_source: &'a dyn FileSource,
+        explicit: Option<&Path>,
+    ) -> dossier_core::Result<Self> {
+        let mut configs = Self {
+            file_source,
+            explicit: None,
+            by_dir: HashMap::new(),
+            diagnostics: vec![],
+        };
+
+        if let Some(path) = explicit {
+            let text = file_source
+                .read_file(path)
+                .map_err(|e| dossier_core::DossierError::IOError(path.to_owned(), e))?;
+            configs.explicit = Some(configs.parse(path, &text));
+        }
+
+        Ok(configs)
+    }
+
+    /// The config that applies to `file`
+    pub fn for_file(&mut self, file: &Path) -> Option<Arc<TsConfig>> {
+        if let Some(explicit) = &self.explicit {
+            return explicit.clone();
+        }
+
+        let mut visited = vec![];
+        let mut dir = file.parent();
+        let config = loop {
+            let Some(current) = dir else {
+                break None;
+            };
+            if let Some(config) = self.by_dir.get(current) {
+                break config.clone();
+            }
+            visited.push(current.to_owned());
+
+            let path = current.join(FILE_NAME);
+            if let Ok(text) = self.file_source.read_file(&path) {
+                break self.parse(&path, &text);
+            }
+            dir = current.parent();
+        };
+
+        for dir in visited {
+            self.by_dir.insert(dir, config.clone());
+        }
+        config
+    }
+
+    /// Parses a config, warning and ignoring it if it's invalid
+    fn parse(&mut self, path: &Path, text: &str) -> Option<Arc<TsConfig>> {
+        match TsConfig::parse(path, text) {
+            Ok(config) => Some(Arc::new(config)),
+            Err(error) => {
+                self.diagnostics.push(Diagnostic::warning(
+                    INVALID_TSCONFIG,
+                    format!("Ignoring the config, since it isn't valid: {}", error),
+                    path,
+                ));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::InMemoryFileSystem;
+    use indoc::indoc;
+
+    fn config(text: &str) -> TsConfig {
+        TsConfig::parse(Path::new("project/tsconfig.json"), text).unwrap()
+    }
+
+    fn mapped(config: &TsConfig, specifier: &str) -> Vec<String> {
+        config
+            .mapped_paths(specifier)
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn maps_specifiers_with_paths_and_base_url() {
+        let config = config(indoc! { r#"
+        {
+            // Comments and trailing commas are fine
+            "compilerOptions": {
+                "baseUrl": "src", /* relative to the config */
+                "paths": {
+                    "@app/*": ["app/*", "fallback/app/*",],
+                    "@app/models/*": ["models/*"],
+                    "config": ["config/default.ts"],
+                    "*": ["types/*"],
+                },
+            },
+        }
+        "#});
+
+        assert_eq!(
+            mapped(&config, "@app/user"),
+            [
+                "project/src/app/user",
+                "project/src/fallback/app/user",
+                "project/src/@app/user"
+            ]
+        );
+        // The longest prefix wins
+        assert_eq!(
+            mapped(&config, "@app/models/user"),
+            ["project/src/models/user", "project/src/@app/models/user"]
+        );
+        assert_eq!(
+            mapped(&config, "config"),
+            ["project/src/config/default.ts", "project/src/config"]
+        );
+        assert_eq!(
+            mapped(&config, "lodash"),
+            ["project/src/types/lodash", "project/src/lodash"]
+        );
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_config_without_a_base_url() {
+        let config = config(r#"{ "compilerOptions": { "paths": { "~/*": ["./src/*"] } } }"#);
+
+        assert_eq!(mapped(&config, "~/user"), ["project/./src/user"]);
+        assert!(mapped(&config, "react").is_empty());
+        assert!(mapped(&TsConfig::default(), "react").is_empty());
+    }
+
+    #[test]
+    fn keeps_comment_characters_in_strings() {
+        let config = config(r#"{ "compilerOptions": { "paths": { "//*": ["/*,]"] } } }"#);
+
+        assert_eq!(mapped(&config, "//x"), ["/x,]"]);
+    }
+
+    #[test]
+    fn finds_the_nearest_config() {
+        let mut files = InMemoryFileSystem::new();
+        files.add_file(
+            "tsconfig.json",
+            r#"{ "compilerOptions": { "baseUrl": "." } }"#,
+        );
+        files.add_file(
+            "app/tsconfig.json",
+            r#"{ "compilerOptions": { "baseUrl": "src" } }"#,
+        );
+        files.add_file("broken/tsconfig.json", "{");
+
+        let mut configs = TsConfigs::new(&files, None).unwrap();
+        let base_url = |configs: &mut TsConfigs, file: &str| {
+            configs
+                .for_file(Path::new(file))
+                .and_then(|c| c.base_url.clone())
+                .map(|p| p.display().to_string())
+        };
+
+        assert_eq!(
+            base_url(&mut configs, "app/src/a/b.ts").as_deref(),
+            Some("app/src")
+        );
+        assert_eq!(
+            base_url(&mut configs, "app/src/c.ts").as_deref(),
+            Some("app/src")
+        );
+        assert_eq!(base_url(&mut configs, "lib/d.ts").as_deref(), Some("."));
+        assert_eq!(base_url(&mut configs, "broken/e.ts"), None);
+        assert_eq!(configs.diagnostics.len(), 1);
+        assert_eq!(configs.diagnostics[0].code, INVALID_TSCONFIG);
+
+        let mut configs = TsConfigs::new(&files, Some(Path::new("app/tsconfig.json"))).unwrap();
+        assert_eq!(
+            base_url(&mut configs, "lib/d.ts").as_deref(),
+            Some("app/src")
+        );
+
+        assert!(TsConfigs::new(&files, Some(Path::new("missing.json"))).is_err());
+    }
+}
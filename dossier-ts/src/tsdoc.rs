@@ -0,0 +1,350 @@
+//! Extracts JSDoc/TSDoc block tags like `@param` and `@deprecated` from
+//! documentation comments, so that they don't end up in the description.
+use crate::parameter::Parameter;
+use crate::property::Property;
+use crate::symbol::{Symbol, SymbolKind};
+use crate::types::Type;
+use crate::ParserContext;
+use dossier_core::serde_json::{json, Value};
+use dossier_core::tree_sitter::Node;
+
+/// Reported for `@param parameter.field` tags that don't match a field
+pub(crate) const UNMATCHED_PARAM_TAG: &str = "unmatched-param-tag";
+
+/// Starts the section of a comment written for another locale, like
+/// `@locale ja`. It isn't a block tag: the sections stay in the description,
+/// see `dossier_core::locale`.
+pub(crate) const LOCALE_MARKER: &str = "@locale";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DocTags {
+    /// `@param name description`, as pairs of identifiers and descriptions
+    pub params: Vec<(String, String)>,
+    pub returns: Option<String>,
+    /// The message of a `@deprecated` tag. Empty if the tag had no message.
+    pub deprecated: Option<String>,
+    pub examples: Vec<String>,
+    /// Tags we don't treat specially, as pairs of tag names and their content
+    pub other: Vec<(String, String)>,
+}
+
+impl DocTags {
+    pub fn add_to_meta(&self, meta: &mut Value) {
+        if let Some(returns) = &self.returns {
+            meta["returns"] = returns.as_str().into();
+        }
+
+        if let Some(message) = &self.deprecated {
+            meta["deprecated"] = true.into();
+            if !message.is_empty() {
+                meta["deprecation_message"] = message.as_str().into();
+            }
+        }
+
+        if !self.examples.is_empty() {
+            meta["examples"] = self.examples.clone().into();
+        }
+
+        // Any `@param` tags that weren't moved onto a parameter are kept as-is
+        let other = self
+            .params
+            .iter()
+            .map(|(name, text)| ("param", format!("{} {}", name, text)))
+            .chain(
+                self.other
+                    .iter()
+                    .map(|(tag, text)| (tag.as_str(), text.clone())),
+            )
+            .map(|(tag, text)| json!({ "tag": tag, "text": text.trim() }))
+            .collect::<Vec<_>>();
+
+        if !other.is_empty() {
+            meta["tags"] = other.into();
+        }
+    }
+
+    /// Moves `@param` descriptions onto the parameters they describe.
+    ///
+    /// Dotted names like `@param options.retries` document a field of an
+    /// object parameter, and are moved onto the matching property of its type.
+    /// Destructured parameters have no name of their own, so tags name them
+    /// by position instead. Dotted tags that don't match any field are reported
+    /// as warnings against `node`.
+    pub(crate) fn attach_to_parameters(
+        &mut self,
+        children: &mut [Symbol],
+        node: &Node,
+        ctx: &mut ParserContext,
+    ) {
+        let mut parameters = children
+            .iter_mut()
+            .filter_map(|s| match &mut s.kind {
+                SymbolKind::Parameter(p) => Some(p),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut names: Vec<&str> = vec![];
+        for (name, _) in &self.params {
+            let root = name.split('.').next().unwrap_or_default();
+            if !names.contains(&root) {
+                names.push(root);
+            }
+        }
+        let names = names.into_iter().map(str::to_owned).collect::<Vec<_>>();
+
+        self.params.retain(|(name, description)| {
+            let (root, field) = match name.split_once('.') {
+                Some((root, field)) => (root, Some(field)),
+                None => (name.as_str(), None),
+            };
+
+            let index = parameters
+                .iter()
+                .position(|p| p.identifier == root)
+                .or_else(|| {
+                    names
+                        .iter()
+                        .position(|n| n == root)
+                        .filter(|i| parameters.get(*i).is_some_and(|p| p.destructured))
+                });
+
+            let parameter: Option<&mut Parameter> = index.map(|i| &mut *parameters[i]);
+
+            let documentation = match (parameter, field) {
+                (Some(parameter), None) => Some(&mut parameter.documentation),
+                (Some(parameter), Some(field)) => {
+                    find_field(&mut parameter.children, field).map(|p| &mut p.documentation)
+                }
+                (None, _) => None,
+            };
+
+            match documentation {
+                Some(documentation) => {
+                    *documentation = Some(description.clone());
+                    false
+                }
+                None => {
+                    if field.is_some() {
+                        ctx.warn(
+                            UNMATCHED_PARAM_TAG,
+                            format!("`@param {}` does not match a parameter field", name),
+                            node,
+                        );
+                    }
+                    true
+                }
+            }
+        });
+    }
+}
+
+/// Finds the property at a dotted `path`, like `retries` or `backoff.delay`,
+/// inside the object type among `children`.
+fn find_field<'a>(children: &'a mut [Symbol], path: &str) -> Option<&'a mut Property> {
+    let (head, rest) = match path.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (path, None),
+    };
+
+    let properties = children.iter_mut().find_map(|s| match &mut s.kind {
+        SymbolKind::Type(Type::Object { properties }) => Some(properties),
+        _ => None,
+    })?;
+
+    let property = properties.iter_mut().find_map(|s| match &mut s.kind {
+        SymbolKind::Property(p) if p.identifier == head => Some(p),
+        _ => None,
+    })?;
+
+    match rest {
+        Some(rest) => find_field(&mut property.children, rest),
+        None => Some(property),
+    }
+}
+
+/// Splits a comment, as returned by `process_comment`, into its description
+/// and block tags.
+pub(crate) fn parse(comment: Option<String>) -> (Option<String>, DocTags) {
+    let mut tags = DocTags::default();
+
+    let Some(comment) = comment else {
+        return (None, tags);
+    };
+
+    let mut description = vec![];
+    let mut current: Option<(&str, Vec<&str>)> = None;
+    let mut in_code_block = false;
+
+    for line in comment.lines() {
+        let trimmed = line.trim_start();
+
+        if !in_code_block && is_locale_marker(trimmed) {
+            if let Some((tag, lines)) = current.take() {
+                add_tag(&mut tags, tag, &lines.join("\n"));
+            }
+            description.push(line);
+        // Decorators in code examples are not tags
+        } else if !in_code_block && trimmed.starts_with('@') {
+            if let Some((tag, lines)) = current.take() {
+                add_tag(&mut tags, tag, &lines.join("\n"));
+            }
+
+            let (tag, rest) = trimmed[1..]
+                .split_once(char::is_whitespace)
+                .unwrap_or((&trimmed[1..], ""));
+            current = Some((tag, vec![rest]));
+        } else {
+            match current.as_mut() {
+                Some((_, lines)) => lines.push(line),
+                None => description.push(line),
+            }
+        }
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+    }
+
+    if let Some((tag, lines)) = current.take() {
+        add_tag(&mut tags, tag, &lines.join("\n"));
+    }
+
+    let description = description.join("\n").trim().to_owned();
+
+    (Some(description).filter(|d| !d.is_empty()), tags)
+}
+
+fn is_locale_marker(line: &str) -> bool {
+    line.strip_prefix(LOCALE_MARKER)
+        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
+
+fn add_tag(tags: &mut DocTags, tag: &str, content: &str) {
+    match tag {
+        "param" => {
+            if let Some(param) = parse_param(content) {
+                tags.params.push(param);
+            }
+        }
+        "returns" | "return" => tags.returns = Some(content.trim().to_owned()),
+        "deprecated" => tags.deprecated = Some(content.trim().to_owned()),
+        "example" => tags.examples.push(trim_blank_lines(content)),
+        _ => tags.other.push((tag.to_owned(), content.trim().to_owned())),
+    }
+}
+
+/// Parses the content of a `@param` tag. Supports the JSDoc forms with a type
+/// and optional parameters: `@param {string} [name=default] - description`.
+fn parse_param(content: &str) -> Option<(String, String)> {
+    let mut content = content.trim_start();
+
+    if content.starts_with('{') {
+        let end = content.find('}')?;
+        content = content[end + 1..].trim_start();
+    }
+
+    let (name, description) = content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""));
+
+    let name = name
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split('=')
+        .next()
+        .unwrap_or_default();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let description = description.trim_start();
+    let description = description.strip_prefix("- ").unwrap_or(description);
+
+    Some((name.to_owned(), description.trim().to_owned()))
+}
+
+/// Removes leading and trailing blank lines, but keeps indentation.
+fn trim_blank_lines(content: &str) -> String {
+    let lines = content.lines().collect::<Vec<_>>();
+
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helpers::process_comment;
+    use indoc::indoc;
+
+    #[test]
+    fn separates_description_from_tags() {
+        let comment = process_comment(indoc! {r#"
+        /**
+         * Adds two numbers together.
+         *
+         * Works with negative numbers too.
+         *
+         * @param {number} a - The first number
+         * @param [b=0] The second
+         *   number
+         * @returns The sum
+         * @deprecated Use `sum` instead
+         * @since 1.2.0
+         * @example
+         * ```ts
+         * @logged
+         * add(1, 2);
+         * ```
+         */
+        "#});
+
+        let (description, tags) = parse(Some(comment));
+
+        assert_eq!(
+            description.as_deref(),
+            Some("Adds two numbers together.\n\nWorks with negative numbers too.")
+        );
+        assert_eq!(
+            tags.params,
+            vec![
+                ("a".to_owned(), "The first number".to_owned()),
+                ("b".to_owned(), "The second\n  number".to_owned()),
+            ]
+        );
+        assert_eq!(tags.returns.as_deref(), Some("The sum"));
+        assert_eq!(tags.deprecated.as_deref(), Some("Use `sum` instead"));
+        assert_eq!(tags.examples, vec!["```ts\n@logged\nadd(1, 2);\n```"]);
+        assert_eq!(tags.other, vec![("since".to_owned(), "1.2.0".to_owned())]);
+    }
+
+    #[test]
+    fn locale_sections_stay_in_the_description() {
+        let comment =
+            "Greets.\n@returns A greeting\n@locale ja\n挨拶します。\n@param name The name";
+        let (description, tags) = parse(Some(comment.to_owned()));
+
+        assert_eq!(
+            description.as_deref(),
+            Some("Greets.\n@locale ja\n挨拶します。")
+        );
+        assert_eq!(tags.returns.as_deref(), Some("A greeting"));
+        assert_eq!(tags.params.len(), 1);
+        assert!(tags.other.is_empty());
+    }
+
+    #[test]
+    fn comments_without_tags_are_unchanged() {
+        let (description, tags) = parse(Some("Just some **docs**".to_owned()));
+
+        assert_eq!(description.as_deref(), Some("Just some **docs**"));
+        assert_eq!(tags, DocTags::default());
+    }
+}
@@ -1,19 +1,25 @@
 use crate::{
+    callable,
     helpers::*,
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
-    type_variable, types, ParserContext,
+    tsdoc::{self, DocTags},
+    types, ParserContext,
 };
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Result};
+use dossier_core::{tree_sitter::Node, Entity, MemberContext, Result};
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct TypeAlias {
+pub struct TypeAlias {
     pub identifier: String,
     pub documentation: Option<String>,
+    pub doc_tags: DocTags,
     /// Technically will ever only have one child, the type itself, but other
     /// parts of the program will expect a slice of children so this is simpler.
     pub children: Vec<Symbol>,
     pub exported: bool,
+    /// The declaration without its body, on one line, see
+    /// `dossier_core::helpers::signature`. Not set for minified files.
+    pub signature: Option<String>,
 }
 
 impl TypeAlias {
@@ -27,6 +33,10 @@ impl TypeAlias {
         if self.exported {
             meta["exported"] = true.into();
         }
+        if let Some(signature) = &self.signature {
+            meta["signature"] = signature.as_str().into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
 
         Entity {
             title: Some(self.identifier.clone()),
@@ -38,14 +48,16 @@ impl TypeAlias {
                 .iter()
                 .map(|s| s.as_entity())
                 .collect::<Vec<_>>(),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: crate::LANGUAGE.to_owned(),
             source: source.as_entity_source(),
-            meta: json!({}),
+            meta,
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl TypeAlias {
     pub fn the_type(&self) -> &Symbol {
         self.children
             .iter()
@@ -53,7 +65,6 @@ impl TypeAlias {
             .unwrap()
     }
 
-    #[cfg(test)]
     pub fn type_variables(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
@@ -80,36 +91,38 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         .unwrap()
         .to_owned();
 
+    ctx.push_scope();
     ctx.push_fqn(&identifier);
 
+    // The type variables go in a scope of their own, which the value is
+    // parsed in, so they shadow declarations of the same name in the file
+    let type_parameters = node.child_by_field_name("type_parameters");
+    if let Some(params) = type_parameters {
+        callable::parse_type_parameters(&params, &mut children, ctx)?;
+        ctx.push_scope();
+    }
+
     if let Some(value) = node.child_by_field_name("value") {
         children.push(types::parse(&value, ctx)?);
     }
 
-    if let Some(params) = node.child_by_field_name("type_parameters") {
-        let mut cursor = params.walk();
-        cursor.goto_first_child();
-
-        loop {
-            if cursor.node().kind() == crate::type_variable::NODE_KIND {
-                children.push(type_variable::parse(&cursor.node(), ctx)?);
-            }
-
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
+    if type_parameters.is_some() {
+        ctx.pop_scope();
     }
-
+    ctx.pop_scope();
     ctx.pop_fqn();
 
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
+
     Ok(Symbol::in_context(
         ctx,
         SymbolKind::TypeAlias(TypeAlias {
             identifier,
             children,
             exported: is_exported(node),
-            documentation: find_docs(node, ctx.code).map(process_comment),
+            documentation,
+            doc_tags,
+            signature: Some(dossier_core::helpers::signature(node, ctx.code)),
         }),
         Source::for_node(node, ctx),
     ))
@@ -121,24 +134,6 @@ fn is_exported(node: &Node) -> bool {
         .unwrap_or(false)
 }
 
-fn find_docs<'a>(node: &Node<'a>, code: &'a str) -> Option<&'a str> {
-    let parent = node.parent().unwrap();
-
-    if parent.kind() == "export_statement" {
-        if let Some(maybe_comment) = parent.prev_sibling() {
-            if maybe_comment.kind() == "comment" {
-                return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-            }
-        }
-    } else if let Some(maybe_comment) = node.prev_sibling() {
-        if maybe_comment.kind() == "comment" {
-            return Some(maybe_comment.utf8_text(code.as_bytes()).unwrap());
-        }
-    }
-
-    None
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
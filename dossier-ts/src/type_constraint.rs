@@ -4,12 +4,12 @@ use crate::{
 };
 
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 pub(crate) const NODE_KIND: &str = "constraint";
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct TypeConstraint {
+pub struct TypeConstraint {
     pub extends: bool,
     pub children: Vec<Symbol>,
 }
@@ -31,7 +31,7 @@ impl TypeConstraint {
             description: String::new(),
             kind: "type_constraint".to_owned(),
             identity: Identity::Anonymous,
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: crate::LANGUAGE.to_owned(),
             source: source.as_entity_source(),
             meta,
@@ -42,8 +42,10 @@ impl TypeConstraint {
                 .collect::<Vec<_>>(),
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl TypeConstraint {
     pub fn the_type(&self) -> &Symbol {
         &self.children[0]
     }
@@ -72,3 +74,18 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
         Source::for_node(node, ctx),
     ))
 }
+
+/// Parses a constraint that isn't wrapped in a `constraint` node, like in
+/// `infer U extends string`. Takes the node of the type after `extends`.
+pub(crate) fn parse_extends(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
+    let the_type = crate::types::parse(node, ctx)?;
+
+    Ok(Symbol::in_context(
+        ctx,
+        SymbolKind::TypeConstraint(TypeConstraint {
+            extends: true,
+            children: vec![the_type],
+        }),
+        Source::for_node(node, ctx),
+    ))
+}
@@ -1,17 +1,18 @@
 use crate::{
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
-    type_constraint, ParserContext,
+    type_constraint, types, ParserContext,
 };
 
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 pub(crate) const NODE_KIND: &str = "type_parameter";
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct TypeVariable {
+pub struct TypeVariable {
     pub identifier: String,
     pub documentation: Option<String>,
+    /// Constraints, and the default type in the `Default` context
     pub children: Vec<Symbol>,
 }
 
@@ -33,6 +34,12 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
     loop {
         if cursor.node().kind() == type_constraint::NODE_KIND {
             children.push(type_constraint::parse(&cursor.node(), ctx)?);
+        } else if cursor.node().kind() == "default_type" {
+            if let Some(the_type) = cursor.node().named_child(0) {
+                let mut default = types::parse(&the_type, ctx)?;
+                default.context = Some(SymbolContext::Default);
+                children.push(default);
+            }
         }
 
         if !cursor.goto_next_sibling() {
@@ -51,14 +58,16 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
     ))
 }
 
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
 impl TypeVariable {
-    #[cfg(test)]
     pub fn constraints(&self) -> impl Iterator<Item = &Symbol> {
         self.children
             .iter()
             .filter(|s| s.kind.as_type_constraint().is_some())
     }
+}
 
+impl TypeVariable {
     pub fn as_entity(
         &self,
         source: &Source,
@@ -70,9 +79,9 @@ impl TypeVariable {
         Entity {
             title: Some(self.identifier.clone()),
             description: String::new(),
-            kind: "type_constraint".to_owned(),
+            kind: "type_variable".to_owned(),
             identity: Identity::FQN(fqn.expect("Generic type variable withou FQN").to_owned()),
-            member_context: symbol_context.map(|sc| sc.to_string()),
+            member_context: symbol_context.map(MemberContext::from),
             language: crate::LANGUAGE.to_owned(),
             source: source.as_entity_source(),
             meta,
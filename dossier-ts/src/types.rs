@@ -1,16 +1,18 @@
 use crate::{
-    function, method, parameter,
+    callable, method,
     symbol::{Source, Symbol, SymbolContext, SymbolKind},
-    type_variable, ParserContext,
+    type_constraint,
+    type_variable::TypeVariable,
+    ParserContext,
 };
 
 use dossier_core::serde_json::json;
-use dossier_core::{tree_sitter::Node, Entity, Identity, Result};
+use dossier_core::{tree_sitter::Node, Entity, Identity, MemberContext, Result};
 
 type ResolvedTypeFQN = String;
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum Type {
+pub enum Type {
     Predefined(String),
     Parenthesized(Vec<Symbol>),
     Literal(String),
@@ -19,29 +21,42 @@ pub(crate) enum Type {
     /// When the type has been resolved, the second element in the tuple will
     /// contain the FQN of the type.
     Identifier(String, Option<ResolvedTypeFQN>),
+    /// `{ a: string; b: number }`. The text isn't kept, as object types can
+    /// be long, but the symbol's `Source` points at it in the file.
     Object {
-        // TODO(Nik): What is the real identifier here?
-        raw_string: String,
         properties: Vec<Symbol>,
     },
+    /// `Checked extends Extends ? TrueBranch : FalseBranch`, with the parts
+    /// as members in that order
     Conditional {
         members: Vec<Symbol>,
+        /// The source text of each member, with whitespace collapsed
+        texts: Vec<String>,
     },
     Union {
         members: Vec<Symbol>,
+        /// The documentation comment before each member, see `parse_members`
+        documentation: Vec<Option<String>>,
     },
     Intersection {
         members: Vec<Symbol>,
+        /// The documentation comment before each member, see `parse_members`
+        documentation: Vec<Option<String>>,
     },
+    /// A generic like `Promise<T>`, with the type arguments as members and
+    /// the FQN of the type its name was resolved to
     Generic {
         identifier: String,
         members: Vec<Symbol>,
+        reference: Option<ResolvedTypeFQN>,
     },
     Array {
         members: Vec<Symbol>,
     },
     Tuple {
         members: Vec<Symbol>,
+        /// The documentation comment before each member, see `parse_members`
+        documentation: Vec<Option<String>>,
     },
     Function {
         members: Vec<Symbol>,
@@ -61,6 +76,8 @@ pub(crate) enum Type {
     KeyOf(Vec<Symbol>),
     ReadOnly(Vec<Symbol>),
     Lookup(Vec<Symbol>),
+    /// Holds a single type variable that is declared by the `infer` keyword,
+    /// optionally with an `extends` constraint.
     Infer(Vec<Symbol>),
     This,
     Constructor {
@@ -89,11 +106,9 @@ impl Type {
 
     pub fn children(&self) -> &[Symbol] {
         match self {
-            Type::Object {
-                properties: fields, ..
-            } => fields,
-            Type::Union { members } => members,
-            Type::Conditional { members } => members,
+            Type::Object { properties: fields } => fields,
+            Type::Union { members, .. } => members,
+            Type::Conditional { members, .. } => members,
             Type::Generic { members, .. } => members,
             Type::Array { members, .. } => members,
             Type::Tuple { members, .. } => members,
@@ -103,7 +118,7 @@ impl Type {
             Type::ReadOnly(nested) => nested,
             Type::Lookup(nested) => nested,
             Type::Infer(nested) => nested,
-            Type::Intersection { members } => members,
+            Type::Intersection { members, .. } => members,
             Type::Rest { members } => members,
             Type::Constructor { members } => members,
             Type::TypeOf(_) => &[],
@@ -117,11 +132,9 @@ impl Type {
 
     pub fn children_mut(&mut self) -> &mut [Symbol] {
         match self {
-            Type::Object {
-                properties: fields, ..
-            } => fields,
-            Type::Union { members } => members,
-            Type::Conditional { members } => members,
+            Type::Object { properties: fields } => fields,
+            Type::Union { members, .. } => members,
+            Type::Conditional { members, .. } => members,
             Type::Generic { members, .. } => members,
             Type::Array { members, .. } => members,
             Type::Tuple { members, .. } => members,
@@ -131,7 +144,7 @@ impl Type {
             Type::ReadOnly(nested) => nested,
             Type::Lookup(nested) => nested,
             Type::Infer(nested) => nested,
-            Type::Intersection { members } => members,
+            Type::Intersection { members, .. } => members,
             Type::Rest { members } => members,
             Type::Constructor { members } => members,
             Type::TypeOf(_) => &mut [],
@@ -143,12 +156,37 @@ impl Type {
         }
     }
 
-    pub fn as_entity(
-        &self,
-        source: &Source,
-        fqn: Option<&str>,
-        symbol_context: Option<SymbolContext>,
-    ) -> Entity {
+    /// Every variant has a kind of its own:
+    ///
+    /// | Variant | Kind |
+    /// |---|---|
+    /// | `Predefined` | `predefined_type` |
+    /// | `Parenthesized` | `parenthesized_type` |
+    /// | `Literal` | `literal` |
+    /// | `Identifier` | `identifier` |
+    /// | `Object` | `object` |
+    /// | `Conditional` | `conditional_type` |
+    /// | `Union` | `union` |
+    /// | `Intersection` | `intersection` |
+    /// | `Generic` | `generic_type` |
+    /// | `Array` | `array_type` |
+    /// | `Tuple` | `tuple` |
+    /// | `Function` | `function_type` |
+    /// | `Rest` | `rest_type` |
+    /// | `TypeOf` | `typeof` |
+    /// | `TemplateLiteral` | `template_literal_type` |
+    /// | `KeyOf` | `keyof` |
+    /// | `Lookup` | `lookup_type` |
+    /// | `Infer` | `infer_type` |
+    /// | `This` | `this_type` |
+    /// | `Constructor` | `constructor_type` |
+    ///
+    /// except `ReadOnly`, which is the type it wraps with `meta.readonly` set.
+    ///
+    /// Types are uses of types rather than declarations, so none of them have
+    /// an FQN of their own. Identifiers and generics refer to the declaration
+    /// they name, if it was found, and the rest are anonymous.
+    pub fn as_entity(&self, source: &Source, symbol_context: Option<SymbolContext>) -> Entity {
         match &self {
             Type::This => {
                 let meta = json!({});
@@ -158,7 +196,7 @@ impl Type {
                     description: String::new(),
                     kind: "this_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -173,7 +211,7 @@ impl Type {
                     description: String::new(),
                     kind: "rest_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -188,14 +226,17 @@ impl Type {
                     description: String::new(),
                     kind: "infer_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
                     members: members.iter().map(|s| s.as_entity()).collect(),
                 }
             }
-            Type::Tuple { members } => {
+            Type::Tuple {
+                members,
+                documentation,
+            } => {
                 let meta = json!({});
 
                 Entity {
@@ -203,26 +244,53 @@ impl Type {
                     description: String::new(),
                     kind: "tuple".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
-                    members: members.iter().map(|s| s.as_entity()).collect(),
+                    members: documented_members(members, documentation),
                 }
             }
-            Type::Conditional { members } => {
-                let meta = json!({});
+            Type::Conditional { members, texts } => {
+                let mut meta = json!({});
+
+                let branches = [
+                    ("checked", self.conditional_left()),
+                    ("extends", self.conditional_right()),
+                    ("true_branch", self.conditional_consequence()),
+                    ("false_branch", self.conditional_alternative()),
+                ];
+                for (index, (key, branch)) in branches.into_iter().enumerate() {
+                    let (Some(branch), Some(text)) = (branch, texts.get(index)) else {
+                        continue;
+                    };
+
+                    meta[key] = json!({ "short": text });
+                    if let SymbolKind::Type(the_type) = &branch.kind {
+                        if let Some(fqn) = the_type.resolved_fqn() {
+                            meta[key]["reference"] = json!(fqn);
+                        }
+                    }
+                }
+
+                let mut members = members.iter().map(|s| s.as_entity()).collect::<Vec<_>>();
+                // Only the outermost conditional describes its branches
+                for member in members.iter_mut() {
+                    if member.kind == "conditional_type" {
+                        member.meta = json!({});
+                    }
+                }
 
                 Entity {
                     title: None,
                     description: String::new(),
-                    kind: "template_literal_type".to_owned(),
+                    kind: "conditional_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
-                    members: members.iter().map(|s| s.as_entity()).collect(),
+                    members,
                 }
             }
             Type::Lookup(members) => {
@@ -231,9 +299,9 @@ impl Type {
                 Entity {
                     title: None,
                     description: String::new(),
-                    kind: "template_literal_type".to_owned(),
+                    kind: "lookup_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -247,8 +315,8 @@ impl Type {
                     title: Some(literal.to_owned()),
                     description: String::new(),
                     kind: "template_literal_type".to_owned(),
-                    identity: Identity::FQN(fqn.expect("Tempalte literal without FQN").to_owned()),
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    identity: Identity::Anonymous,
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -268,7 +336,7 @@ impl Type {
                     description: String::new(),
                     kind: "keyof".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -283,14 +351,14 @@ impl Type {
                     description: String::new(),
                     kind: "constructor_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
                     members: members.iter().map(|s| s.as_entity()).collect(),
                 }
             }
-            Type::Parenthesized(_name) => {
+            Type::Parenthesized(nested) => {
                 let meta = json!({});
 
                 Entity {
@@ -298,11 +366,11 @@ impl Type {
                     description: String::new(),
                     kind: "parenthesized_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
-                    members: vec![],
+                    members: nested.iter().map(|s| s.as_entity()).collect(),
                 }
             }
             Type::Literal(name) => {
@@ -312,8 +380,8 @@ impl Type {
                     title: Some(format!("\"{}\"", name)),
                     description: String::new(),
                     kind: "literal".to_owned(),
-                    identity: Identity::FQN(fqn.expect("Literal without FQN").to_owned()),
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    identity: Identity::Anonymous,
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -328,7 +396,7 @@ impl Type {
                     description: String::new(),
                     kind: "typeof".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -343,7 +411,7 @@ impl Type {
                     description: String::new(),
                     kind: "function_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -358,7 +426,7 @@ impl Type {
                     description: String::new(),
                     kind: "array_type".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -368,22 +436,37 @@ impl Type {
             Type::Generic {
                 identifier,
                 members,
+                reference,
             } => {
-                let meta = json!({});
+                let meta = match reference
+                    .as_deref()
+                    .and_then(crate::symbol_table::external_package)
+                {
+                    Some(package) => json!({ "external_package": package }),
+                    None => json!({}),
+                };
 
                 Entity {
                     title: Some(identifier.to_owned()),
                     description: String::new(),
                     kind: "generic_type".to_owned(),
-                    identity: Identity::FQN(fqn.expect("Generic withou FQN").to_owned()),
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    // A use of a type, not a declaration, so it has no FQN
+                    // of its own
+                    identity: match reference {
+                        Some(referred_fqn) => Identity::Reference(referred_fqn.to_owned()),
+                        None => Identity::Anonymous,
+                    },
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
                     members: members.iter().map(|s| s.as_entity()).collect(),
                 }
             }
-            Type::Intersection { members } => {
+            Type::Intersection {
+                members,
+                documentation,
+            } => {
                 let meta = json!({});
 
                 Entity {
@@ -391,14 +474,17 @@ impl Type {
                     description: String::new(),
                     kind: "intersection".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
-                    members: members.iter().map(|s| s.as_entity()).collect(),
+                    members: documented_members(members, documentation),
                 }
             }
-            Type::Union { members } => {
+            Type::Union {
+                members,
+                documentation,
+            } => {
                 let meta = json!({});
 
                 Entity {
@@ -406,22 +492,22 @@ impl Type {
                     description: String::new(),
                     kind: "union".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
-                    members: members.iter().map(|s| s.as_entity()).collect(),
+                    members: documented_members(members, documentation),
                 }
             }
             Type::Object { .. } => {
                 let meta = json!({});
 
                 Entity {
-                    title: None,
+                    title: Some("object".to_owned()),
                     description: String::new(),
                     kind: "object".to_owned(),
                     identity: Identity::Anonymous,
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -435,8 +521,8 @@ impl Type {
                     title: Some(type_name.clone()),
                     description: String::new(),
                     kind: "predefined_type".to_owned(),
-                    identity: Identity::FQN(format!("builtin::{}", type_name)),
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    identity: Identity::Anonymous,
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -444,18 +530,23 @@ impl Type {
                 }
             }
             Type::Identifier(type_name, reference) => {
-                let meta = json!({});
+                let meta = match reference
+                    .as_deref()
+                    .and_then(crate::symbol_table::external_package)
+                {
+                    Some(package) => json!({ "external_package": package }),
+                    None => json!({}),
+                };
 
                 Entity {
                     title: Some(type_name.clone()),
                     description: String::new(),
                     kind: "identifier".to_owned(),
-                    identity: if let Some(referred_fqn) = reference {
-                        Identity::Reference(referred_fqn.to_owned())
-                    } else {
-                        Identity::FQN(fqn.expect("Identifier withou FQN").to_owned())
+                    identity: match reference {
+                        Some(referred_fqn) => Identity::Reference(referred_fqn.to_owned()),
+                        None => Identity::Anonymous,
                     },
-                    member_context: symbol_context.map(|sc| sc.to_string()),
+                    member_context: symbol_context.map(MemberContext::from),
                     language: crate::LANGUAGE.to_owned(),
                     source: source.as_entity_source(),
                     meta,
@@ -464,127 +555,167 @@ impl Type {
             }
         }
     }
+}
 
-    #[cfg(test)]
+#[cfg_attr(not(feature = "unstable-symbols"), allow(dead_code))]
+impl Type {
+    /// The parameters of a function type. Other types have none.
     pub fn function_parameters(&self) -> impl Iterator<Item = &Symbol> {
-        match &self {
-            Type::Function { members } => {
-                members.iter().filter(|s| s.kind.as_parameter().is_some())
-            }
-            _ => panic!("Expected a function type"),
-        }
+        let members = match self {
+            Type::Function { members } => members.as_slice(),
+            _ => &[],
+        };
+        members.iter().filter(|s| s.kind.as_parameter().is_some())
     }
 
-    #[cfg(test)]
     pub fn function_return_type(&self) -> Option<&Symbol> {
-        match &self {
+        match self {
             Type::Function { members } => members
                 .iter()
-                .find(|s| s.context == Some(crate::symbol::SymbolContext::ReturnType)),
-            _ => panic!("Expected a function type"),
+                .find(|s| s.context == Some(SymbolContext::ReturnType)),
+            _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn union_left(&self) -> Option<&Symbol> {
         match self {
-            Type::Union { members } => members.first(),
+            Type::Union { members, .. } => members.first(),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn union_right(&self) -> Option<&Symbol> {
         match self {
-            Type::Union { members } => members.get(1),
+            Type::Union { members, .. } => members.get(1),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn conditional_left(&self) -> Option<&Symbol> {
         match self {
-            Type::Conditional { members } => members.first(),
+            Type::Conditional { members, .. } => members.first(),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn conditional_right(&self) -> Option<&Symbol> {
         match self {
-            Type::Conditional { members } => members.get(1),
+            Type::Conditional { members, .. } => members.get(1),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn conditional_consequence(&self) -> Option<&Symbol> {
         match self {
-            Type::Conditional { members } => members.get(2),
+            Type::Conditional { members, .. } => members.get(2),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn conditional_alternative(&self) -> Option<&Symbol> {
         match self {
-            Type::Conditional { members } => members.get(3),
+            Type::Conditional { members, .. } => members.get(3),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn intersection_left(&self) -> Option<&Symbol> {
         match self {
-            Type::Intersection { members } => members.first(),
+            Type::Intersection { members, .. } => members.first(),
             _ => None,
         }
     }
 
-    #[cfg(test)]
     pub fn intersection_right(&self) -> Option<&Symbol> {
         match self {
-            Type::Intersection { members } => members.get(1),
+            Type::Intersection { members, .. } => members.get(1),
             _ => None,
         }
     }
 
-    #[cfg(test)]
+    /// The type parameters of a constructor type. Other types have none.
     pub fn constructor_type_variables(&self) -> impl Iterator<Item = &Symbol> {
-        match self {
-            Type::Constructor { members } => members
-                .iter()
-                .filter(|s| s.kind.as_type_variable().is_some()),
-            _ => panic!("Expected a constructor type"),
-        }
+        self.constructor_members()
+            .iter()
+            .filter(|s| s.kind.as_type_variable().is_some())
     }
 
-    #[cfg(test)]
+    /// The parameters of a constructor type. Other types have none.
     pub fn constructor_parameters(&self) -> impl Iterator<Item = &Symbol> {
+        self.constructor_members()
+            .iter()
+            .filter(|s| s.kind.as_parameter().is_some())
+    }
+
+    fn constructor_members(&self) -> &[Symbol] {
         match self {
-            Type::Constructor { members } => {
-                members.iter().filter(|s| s.kind.as_parameter().is_some())
-            }
-            _ => panic!("Expected a constructor type"),
+            Type::Constructor { members } => members.as_slice(),
+            _ => &[],
         }
     }
 
     pub fn resolvable_identifier(&self) -> Option<&str> {
         match self {
             Type::Identifier(identifier, _referred_fqn) => Some(identifier.as_str()),
+            Type::Generic { identifier, .. } => Some(identifier.as_str()),
             _ => None,
         }
     }
 
-    pub fn resolve_type(&mut self, fqn: &str) {
-        #[allow(clippy::single_match)]
+    /// Whether this is `this`, or a union that includes it like
+    /// `this | undefined`
+    pub fn includes_this(&self) -> bool {
         match self {
-            Type::Identifier(_, referred_fqn) => {
-                *referred_fqn = Some(fqn.to_owned());
-            }
-            _ => {}
+            Type::This => true,
+            Type::Parenthesized(nested)
+            | Type::Union {
+                members: nested, ..
+            } => nested.iter().any(|s| match &s.kind {
+                SymbolKind::Type(the_type) => the_type.includes_this(),
+                _ => false,
+            }),
+            _ => false,
         }
     }
+
+    /// The FQN this type refers to, if it is an identifier we resolved
+    pub fn resolved_fqn(&self) -> Option<&str> {
+        match self {
+            Type::Identifier(_, referred_fqn) => referred_fqn.as_deref(),
+            Type::Generic { reference, .. } => reference.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Records that the type refers to `fqn`. Returns whether that changed
+    /// what it refers to.
+    pub fn resolve_type(&mut self, fqn: &str) -> bool {
+        match self {
+            Type::Identifier(_, referred_fqn)
+            | Type::Generic {
+                reference: referred_fqn,
+                ..
+            } => set_resolved(referred_fqn, fqn),
+            _ => false,
+        }
+    }
+}
+
+/// Sets `resolved` to `fqn`, returning whether it was something else before
+pub(crate) fn set_resolved(resolved: &mut Option<String>, fqn: &str) -> bool {
+    if resolved.as_deref() == Some(fqn) {
+        return false;
+    }
+    *resolved = Some(fqn.to_owned());
+    true
+}
+
+/// Whether a function or method with this return type returns `this`, so
+/// calls to it can be chained.
+pub(crate) fn returns_this(return_type: Option<&Symbol>) -> bool {
+    return_type
+        .and_then(|s| s.kind.as_type())
+        .is_some_and(|the_type| the_type.includes_this())
 }
 
 pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
@@ -608,13 +739,63 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
                 Source::for_node(node, ctx),
             ))
         }
+        // A labeled element of a tuple, like `first: string` or
+        // `...rest: string[]`. Only its type is kept, the label doesn't
+        // change what the tuple accepts.
+        "required_parameter" | "optional_parameter" => {
+            let Some(annotation) = node
+                .child_by_field_name("type")
+                .and_then(|t| t.named_child(0))
+            else {
+                return Ok(unsupported(node, ctx));
+            };
+            let the_type = parse(&annotation, ctx)?;
+
+            let is_rest = node
+                .child_by_field_name("name")
+                .is_some_and(|n| n.kind() == "rest_pattern");
+            if !is_rest {
+                return Ok(the_type);
+            }
+
+            Ok(Symbol::in_context(
+                ctx,
+                SymbolKind::Type(Type::Rest {
+                    members: vec![the_type],
+                }),
+                Source::for_node(node, ctx),
+            ))
+        }
         "infer_type" => {
-            let mut members = vec![];
             let mut cursor = node.walk();
             cursor.goto_first_child();
             cursor.goto_next_sibling();
 
-            members.push(parse(&cursor.node(), ctx)?);
+            let identifier = cursor
+                .node()
+                .utf8_text(ctx.code.as_bytes())
+                .unwrap()
+                .to_owned();
+
+            let mut children = vec![];
+
+            // `infer U extends string`
+            if cursor.goto_next_sibling() && cursor.node().kind() == "extends" {
+                cursor.goto_next_sibling();
+                children.push(type_constraint::parse_extends(&cursor.node(), ctx)?);
+            }
+
+            // The inferred identifier is declared in the scope of the enclosing
+            // conditional type, so the consequence branch can refer to it
+            let members = vec![Symbol::in_context(
+                ctx,
+                SymbolKind::TypeVariable(TypeVariable {
+                    identifier,
+                    documentation: None,
+                    children,
+                }),
+                Source::for_node(node, ctx),
+            )];
 
             Ok(Symbol::in_context(
                 ctx,
@@ -623,31 +804,26 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
             ))
         }
         "tuple_type" => {
-            let mut members = vec![];
-            let mut cursor = node.walk();
-            cursor.goto_first_child();
-
-            loop {
-                if cursor.node().is_named() {
-                    members.push(parse(&cursor.node(), ctx)?);
-                }
-
-                if !cursor.goto_next_sibling() {
-                    break;
-                }
-            }
+            let (members, documentation) = parse_members(node, ctx)?;
 
             Ok(Symbol::in_context(
                 ctx,
-                SymbolKind::Type(Type::Tuple { members }),
+                SymbolKind::Type(Type::Tuple {
+                    members,
+                    documentation,
+                }),
                 Source::for_node(node, ctx),
             ))
         }
         "conditional_type" => {
             let mut members = vec![];
+            let mut texts = vec![];
             let mut cursor = node.walk();
             cursor.goto_first_child();
 
+            // Scope for any type variables declared with `infer`
+            ctx.push_scope();
+
             loop {
                 if !cursor.node().is_named() || cursor.node().kind() == "comment" {
                     cursor.goto_next_sibling();
@@ -655,15 +831,26 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
                 }
 
                 members.push(parse(&cursor.node(), ctx)?);
+                texts.push(
+                    cursor
+                        .node()
+                        .utf8_text(ctx.code.as_bytes())
+                        .unwrap()
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
 
                 if !cursor.goto_next_sibling() {
                     break;
                 }
             }
 
+            ctx.pop_scope();
+
             Ok(Symbol::in_context(
                 ctx,
-                SymbolKind::Type(Type::Conditional { members }),
+                SymbolKind::Type(Type::Conditional { members, texts }),
                 Source::for_node(node, ctx),
             ))
         }
@@ -720,37 +907,7 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
             ))
         }
         "constructor_type" => {
-            let mut members = vec![];
-
-            if let Some(params) = node.child_by_field_name("type_parameters") {
-                let mut cursor = params.walk();
-                cursor.goto_first_child();
-
-                loop {
-                    if cursor.node().kind() == crate::type_variable::NODE_KIND {
-                        members.push(type_variable::parse(&cursor.node(), ctx)?);
-                    }
-
-                    if !cursor.goto_next_sibling() {
-                        break;
-                    }
-                }
-            }
-
-            if let Some(params) = node.child_by_field_name("parameters") {
-                let mut cursor = params.walk();
-                cursor.goto_first_child();
-
-                loop {
-                    if crate::parameter::NODE_KINDS.contains(&cursor.node().kind()) {
-                        members.push(parameter::parse(&cursor.node(), ctx)?);
-                    }
-
-                    if !cursor.goto_next_sibling() {
-                        break;
-                    }
-                }
-            }
+            let members = parse_signature(node, "type", ctx)?;
 
             Ok(Symbol::in_context(
                 ctx,
@@ -804,16 +961,7 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
             ))
         }
         "function_type" => {
-            let mut members = vec![];
-
-            if let Some(params) = node.child_by_field_name("parameters") {
-                function::parse_parameters(&params, &mut members, ctx)?;
-            }
-            if let Some(params) = node.child_by_field_name("return_type") {
-                let mut ret_type = parse(&params, ctx)?;
-                ret_type.context = Some(crate::symbol::SymbolContext::ReturnType);
-                members.push(ret_type);
-            }
+            let members = parse_signature(node, "return_type", ctx)?;
 
             Ok(Symbol::in_context(
                 ctx,
@@ -843,16 +991,15 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
                 .to_owned();
 
             let mut members = vec![];
-            for arg in node.children_by_field_name("type_arguments", &mut node.walk()) {
-                let mut cursor = arg.walk();
-                cursor.goto_first_child();
-                cursor.goto_next_sibling();
-
-                ctx.push_fqn(&identifier);
-
-                members.push(parse(&cursor.node(), ctx)?);
-
-                ctx.pop_fqn();
+            if let Some(arguments) = node.child_by_field_name("type_arguments") {
+                for argument in arguments.named_children(&mut arguments.walk()) {
+                    if argument.kind() == "comment" {
+                        continue;
+                    }
+                    let mut argument = parse(&argument, ctx)?;
+                    argument.context = Some(SymbolContext::TypeArgument);
+                    members.push(argument);
+                }
             }
 
             Ok(Symbol::in_context(
@@ -860,6 +1007,7 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
                 SymbolKind::Type(Type::Generic {
                     identifier,
                     members,
+                    reference: None,
                 }),
                 Source::for_node(node, ctx),
             ))
@@ -880,21 +1028,39 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
                 Source::for_node(node, ctx),
             ))
         }
+        // `Outer.Inner`, resolved as a path into namespaces, and
+        // `import("pkg").Name`, which is written as a member expression
+        "nested_type_identifier" | "member_expression" => {
+            let type_name = node
+                .utf8_text(ctx.code.as_bytes())
+                .unwrap()
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>();
+            Ok(Symbol::in_context(
+                ctx,
+                SymbolKind::Type(Type::Identifier(type_name, None)),
+                Source::for_node(node, ctx),
+            ))
+        }
         "object_type" => {
-            let type_as_string = node.utf8_text(ctx.code.as_bytes()).unwrap().to_owned();
             let mut properties = vec![];
 
             let mut cursor = node.walk();
             cursor.goto_first_child();
             cursor.goto_next_sibling();
 
+            // Scope for the members, popped again below
+            ctx.push_scope();
+
             loop {
-                if cursor.node().kind() == crate::property::NODE_KIND {
+                if crate::has_syntax_error(&cursor.node()) {
+                    crate::skip_member(&cursor.node(), ctx);
+                } else if cursor.node().kind() == crate::property::NODE_KIND {
                     let mut symbol = crate::property::parse(&cursor.node(), ctx)?;
                     symbol.context = Some(SymbolContext::Property);
                     properties.push(symbol);
-                }
-                if cursor.node().kind() == method::NODE_KIND {
+                } else if cursor.node().kind() == method::NODE_KIND {
                     let mut symbol = method::parse(&cursor.node(), ctx)?;
                     symbol.context = Some(SymbolContext::Property);
                     properties.push(symbol);
@@ -905,75 +1071,170 @@ pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Symbol> {
             }
 
             ctx.pop_scope();
+            crate::property::pair_accessors(&mut properties);
 
             Ok(Symbol::in_context(
                 ctx,
-                SymbolKind::Type(Type::Object {
-                    raw_string: type_as_string,
-                    properties,
-                }),
+                SymbolKind::Type(Type::Object { properties }),
                 Source::for_node(node, ctx),
             ))
         }
         "union_type" => {
-            let mut cursor = node.walk();
-            cursor.goto_first_child();
-
-            let mut members = vec![];
-
-            loop {
-                if cursor.node().kind() == "|" || cursor.node().kind() == "comment" {
-                    cursor.goto_next_sibling();
-                    continue;
-                }
-                members.push(parse(&cursor.node(), ctx)?);
-
-                if !cursor.goto_next_sibling() {
-                    break;
-                }
-            }
+            let (members, documentation) = parse_members(node, ctx)?;
 
             Ok(Symbol::in_context(
                 ctx,
-                SymbolKind::Type(Type::Union { members }),
+                SymbolKind::Type(Type::Union {
+                    members,
+                    documentation,
+                }),
                 Source::for_node(node, ctx),
             ))
         }
         "intersection_type" => {
-            let mut cursor = node.walk();
-            cursor.goto_first_child();
-
-            let mut members = vec![];
-
-            loop {
-                if cursor.node().kind() == "&" || cursor.node().kind() == "comment" {
-                    cursor.goto_next_sibling();
-                    continue;
-                }
-                members.push(parse(&cursor.node(), ctx)?);
-
-                if !cursor.goto_next_sibling() {
-                    break;
-                }
-            }
+            let (members, documentation) = parse_members(node, ctx)?;
 
             Ok(Symbol::in_context(
                 ctx,
-                SymbolKind::Type(Type::Intersection { members }),
+                SymbolKind::Type(Type::Intersection {
+                    members,
+                    documentation,
+                }),
                 Source::for_node(node, ctx),
             ))
         }
-        _ => panic!(
-            "Unhandled type kind: {} | {} | {} | file:{} | pos:{}",
-            node.kind(),
-            node.utf8_text(ctx.code.as_bytes()).unwrap(),
-            node.to_sexp(),
-            ctx.file.display(),
-            node.start_position()
-        ),
+        _ => Ok(unsupported(node, ctx)),
+    }
+}
+
+/// A type the parser doesn't understand, kept as the text it is written as,
+/// with an `unsupported-type` warning
+fn unsupported(node: &Node, ctx: &mut ParserContext) -> Symbol {
+    let text = node.utf8_text(ctx.code.as_bytes()).unwrap_or_default();
+    ctx.warn(
+        crate::UNSUPPORTED_TYPE,
+        format!("Unsupported type `{}`, of kind `{}`", text, node.kind()),
+        node,
+    );
+
+    Symbol::in_context(
+        ctx,
+        SymbolKind::Type(Type::Literal(text.to_owned())),
+        Source::for_node(node, ctx),
+    )
+}
+
+/// Parses the type parameters, parameters, and return type of a function or
+/// constructor type, whose return types are in different fields, named by
+/// `return_type`. The type variables are declared in a scope of their own,
+/// like those of functions.
+fn parse_signature(node: &Node, return_type: &str, ctx: &mut ParserContext) -> Result<Vec<Symbol>> {
+    let mut members = vec![];
+
+    ctx.push_scope();
+
+    if let Some(params) = node.child_by_field_name("type_parameters") {
+        callable::parse_type_parameters(&params, &mut members, ctx)?;
+    }
+    if let Some(params) = node.child_by_field_name("parameters") {
+        callable::parse_parameters(&params, &mut members, ctx)?;
+    }
+    if let Some(the_type) = node.child_by_field_name(return_type) {
+        let mut the_type = parse(&the_type, ctx)?;
+        the_type.context = Some(SymbolContext::ReturnType);
+        members.push(the_type);
+    }
+
+    ctx.pop_scope();
+
+    Ok(members)
+}
+
+/// Parses the members of a union, intersection, or tuple type, along with the
+/// documentation comment right before each of them, like in
+/// `/** fired on start */ StartEvent | /** fired on stop */ StopEvent`.
+///
+/// Only `/** */` comments document members. The separators are skipped.
+///
+/// `A | B | C` is parsed as `(A | B) | C`, so the members of a union or
+/// intersection directly inside one of the same kind are taken as members of
+/// the outer one. Parenthesized ones, like `(A | B) & C`, are kept as they are.
+fn parse_members(
+    node: &Node,
+    ctx: &mut ParserContext,
+) -> Result<(Vec<Symbol>, Vec<Option<String>>)> {
+    let mut members = vec![];
+    let mut documentation = vec![];
+    let mut docs = leading_docs(node, ctx.code);
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "comment" {
+            docs = doc_comment(&child, ctx.code);
+            continue;
+        }
+        if !child.is_named() {
+            continue;
+        }
+
+        // The comment before `A` in `A | B | C` is found again by the nested
+        // union, rather than documenting `A | B`
+        if child.kind() == node.kind() && node.kind() != "tuple_type" {
+            let (nested, nested_docs) = parse_members(&child, ctx)?;
+            members.extend(nested);
+            documentation.extend(nested_docs);
+            docs = None;
+            continue;
+        }
+
+        members.push(parse(&child, ctx)?);
+        documentation.push(docs.take());
+    }
+
+    Ok((members, documentation))
+}
+
+/// The documentation of the first member of a union or intersection comes
+/// before the type itself, e.g. after the `=` of a type alias. The first
+/// member of a nested union is the first member of the union around it.
+fn leading_docs(node: &Node, code: &str) -> Option<String> {
+    if node.kind() == "tuple_type" {
+        return None;
+    }
+
+    match node.prev_sibling() {
+        Some(previous) if previous.kind() == "comment" => doc_comment(&previous, code),
+        Some(_) => None,
+        None => node
+            .parent()
+            .filter(|parent| parent.kind() == node.kind())
+            .and_then(|parent| leading_docs(&parent, code)),
     }
 }
 
+fn doc_comment(node: &Node, code: &str) -> Option<String> {
+    let text = node.utf8_text(code.as_bytes()).ok()?;
+
+    text.starts_with("/**")
+        .then(|| crate::helpers::process_comment(text))
+}
+
+/// The entities of the members of a union, intersection, or tuple, described
+/// by their documentation comments
+fn documented_members(members: &[Symbol], documentation: &[Option<String>]) -> Vec<Entity> {
+    members
+        .iter()
+        .zip(documentation.iter().chain(std::iter::repeat(&None)))
+        .map(|(member, docs)| {
+            let mut entity = member.as_entity();
+            if let Some(docs) = docs {
+                entity.description = docs.clone();
+            }
+            entity
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     /// NOTE ABOUT THESE TESTS
@@ -1092,7 +1353,7 @@ mod test {
         let type_def = symbol.kind.as_type().unwrap();
 
         match type_def {
-            Type::Object { properties, .. } => {
+            Type::Object { properties } => {
                 assert_eq!(properties.len(), 2);
 
                 assert_eq!(properties[0].kind.as_property().unwrap().identifier, "name");
@@ -1118,6 +1379,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn object_types_leave_the_scope_they_are_parsed_in() {
+        let code = indoc! {r#"
+            namespace Shapes {
+                interface Point {
+                    x: number;
+                }
+
+                function origin(): Point {}
+            }
+        "#};
+
+        let table = crate::parse_file(ParserContext::new(Path::new("index.ts"), code)).unwrap();
+        let shapes = table.all_symbols().next().unwrap();
+        let point = &shapes.children()[0];
+        let origin = &shapes.children()[1];
+
+        assert!(shapes.scope_id < point.scope_id);
+        assert_eq!(origin.scope_id, point.scope_id);
+        assert_eq!(origin.context, None);
+        assert_eq!(origin.fqn.as_deref(), Some("index.ts::Shapes::origin"));
+    }
+
     #[test]
     fn parses_union_type() {
         let code = indoc! {r#"
@@ -1171,18 +1455,71 @@ mod test {
         let type_def = symbol.kind.as_type().unwrap();
 
         match type_def {
-            Type::Union { .. } => {
-                let left = type_def.union_left().unwrap().kind.as_type().unwrap();
-                let left_left = left.union_left().unwrap().kind.as_type().unwrap();
-                assert_eq!(left_left, &Type::Predefined("string".to_string()));
+            Type::Union { members, .. } => {
+                let members = members
+                    .iter()
+                    .map(|m| m.kind.as_type().unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    members,
+                    vec![
+                        &Type::Predefined("string".to_string()),
+                        &Type::Predefined("number".to_string()),
+                        &Type::Predefined("boolean".to_string()),
+                    ]
+                );
+            }
+            _ => panic!("Expected a type identifier"),
+        }
+    }
 
-                let left_right = left.union_right().unwrap().kind.as_type().unwrap();
-                assert_eq!(left_right, &Type::Predefined("number".to_string()));
+    #[test]
+    fn keeps_parenthesized_unions_in_intersections() {
+        let code = indoc! {r#"
+            type Foo = (A | B) & C & D;
+        #"#};
 
-                let right = type_def.union_right().unwrap().kind.as_type().unwrap();
-                assert_eq!(right, &Type::Predefined("boolean".to_string()));
+        // Setup
+        let tree = init_parser().parse(code, None).unwrap();
+        let mut cursor = tree.root_node().walk();
+        walk_tree_to_type(&mut cursor);
+
+        // Parse
+        let symbol = parse(
+            &cursor.node(),
+            &mut ParserContext::new(Path::new("index.ts"), code),
+        )
+        .unwrap();
+
+        let type_def = symbol.kind.as_type().unwrap();
+
+        match type_def {
+            Type::Intersection { members, .. } => {
+                assert_eq!(members.len(), 3);
+
+                let Type::Parenthesized(nested) = members[0].kind.as_type().unwrap() else {
+                    panic!("Expected a parenthesized type");
+                };
+                let union = nested[0].kind.as_type().unwrap();
+                assert_eq!(
+                    union.union_left().unwrap().kind.as_type().unwrap(),
+                    &Type::Identifier("A".to_string(), None)
+                );
+                assert_eq!(
+                    union.union_right().unwrap().kind.as_type().unwrap(),
+                    &Type::Identifier("B".to_string(), None)
+                );
+
+                assert_eq!(
+                    members[1].kind.as_type().unwrap(),
+                    &Type::Identifier("C".to_string(), None)
+                );
+                assert_eq!(
+                    members[2].kind.as_type().unwrap(),
+                    &Type::Identifier("D".to_string(), None)
+                );
             }
-            _ => panic!("Expected a type identifier"),
+            _ => panic!("Expected an intersection"),
         }
     }
 
@@ -1536,9 +1873,9 @@ mod test {
         assert!(matches!(the_type, Type::Infer { .. }));
 
         assert_eq!(the_type.children().len(), 1);
-        let child = the_type.children()[0].kind.as_type().unwrap();
-        assert!(matches!(child, Type::Identifier(_, None)));
-        assert_eq!(child.identifier().unwrap(), "A");
+        let child = the_type.children()[0].kind.as_type_variable().unwrap();
+        assert_eq!(child.identifier, "A");
+        assert_eq!(child.constraints().count(), 0);
     }
 
     #[test]
@@ -1606,6 +1943,60 @@ mod test {
         assert_eq!(child.identifier().unwrap(), "string");
     }
 
+    #[test]
+    fn parses_labeled_tuple_elements() {
+        let code = indoc! {r#"
+            type Foo = [first: string, second?: number, ...rest: boolean[]];
+        #"#};
+
+        // Setup
+        let tree = init_parser().parse(code, None).unwrap();
+        let mut cursor = tree.root_node().walk();
+        walk_tree_to_type(&mut cursor);
+
+        // Parse
+        let symbol = parse(
+            &cursor.node(),
+            &mut ParserContext::new(Path::new("index.ts"), code),
+        )
+        .unwrap();
+
+        let tuple = symbol.kind.as_type().unwrap();
+        assert_eq!(tuple.children().len(), 3);
+
+        let child = tuple.children()[0].kind.as_type().unwrap();
+        assert_eq!(child.identifier().unwrap(), "string");
+
+        let child = tuple.children()[1].kind.as_type().unwrap();
+        assert_eq!(child.identifier().unwrap(), "number");
+
+        let rest = tuple.children()[2].kind.as_type().unwrap();
+        assert!(matches!(rest, Type::Rest { .. }));
+        assert!(matches!(
+            rest.children()[0].kind.as_type().unwrap(),
+            Type::Array { .. }
+        ));
+    }
+
+    #[test]
+    fn reports_unsupported_types_instead_of_panicking() {
+        let code = "type Foo = string;";
+        let tree = init_parser().parse(code, None).unwrap();
+        let mut ctx = ParserContext::new(Path::new("index.ts"), code);
+
+        // Not a type at all
+        let symbol = parse(&tree.root_node(), &mut ctx).unwrap();
+
+        assert_eq!(
+            symbol.kind.as_type().unwrap(),
+            &Type::Literal(code.to_owned())
+        );
+        let diagnostics = &ctx.symbol_table.diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, crate::UNSUPPORTED_TYPE);
+        assert!(diagnostics[0].message.contains("`program`"));
+    }
+
     #[test]
     fn parses_this_type() {
         let code = indoc! {r#"
@@ -1761,4 +2152,58 @@ mod test {
         )
         .unwrap();
     }
+
+    /// The entity of the type of the type alias in `code`
+    fn alias_type(code: &str) -> Entity {
+        let table = crate::parse_file(ParserContext::new(Path::new("index.ts"), code)).unwrap();
+        let alias = table.all_symbols().next().unwrap().as_entity();
+
+        alias.members.into_iter().next().unwrap()
+    }
+
+    fn descriptions(entity: &Entity) -> Vec<&str> {
+        entity
+            .members
+            .iter()
+            .map(|m| m.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn documents_union_and_intersection_members() {
+        let union = alias_type(
+            "type Event = /** fired on start */ StartEvent | /** fired on stop */ StopEvent;",
+        );
+        assert_eq!(union.kind, "union");
+        assert_eq!(descriptions(&union), ["fired on start", "fired on stop"]);
+        assert_eq!(union.members[0].title.as_deref(), Some("StartEvent"));
+
+        let intersection = alias_type("type Both = /** a */ A & B;");
+        assert_eq!(descriptions(&intersection), ["a", ""]);
+    }
+
+    #[test]
+    fn documents_members_of_unions_on_lines_of_their_own() {
+        let union = alias_type(indoc! {r#"
+            type Event =
+              /** Fired on start */
+              | StartEvent
+              // Not documentation
+              | PauseEvent
+              /** Fired on stop */
+              | StopEvent;
+        "#});
+
+        assert_eq!(
+            descriptions(&union),
+            ["Fired on start", "", "Fired on stop"]
+        );
+    }
+
+    #[test]
+    fn documents_tuple_members() {
+        let tuple = alias_type("type Point = [/** x */ number, /** y */ number, number];");
+        assert_eq!(tuple.kind, "tuple");
+        assert_eq!(descriptions(&tuple), ["x", "y", ""]);
+    }
 }
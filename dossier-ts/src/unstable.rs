@@ -0,0 +1,62 @@
+//! The symbol tables the parser builds before converting them into entities,
+//! for tools that need more than entities provide: scopes, imports, and
+//! symbols that aren't exported.
+//!
+//! Symbols have accessors for the children they are usually looked for by,
+//! e.g. `Function::parameters`, `Interface::properties`, and `Type::union_left`.
+//!
+//! Only available with the `unstable-symbols` feature.
+//!
+//! **There is no stability guarantee.** These types mirror the parser's
+//! internals and change whenever the parser does, including in patch releases.
+//! Pin an exact version of `dossier-ts` if you depend on them.
+//!
+//! ```
+//! use dossier_core::{Context, InMemoryFileSystem};
+//! use dossier_ts::TypeScriptParser;
+//! use std::path::Path;
+//!
+//! let mut files = InMemoryFileSystem::new();
+//! files.add_file(
+//!     "index.ts",
+//!     "import { User } from './user';\n\
+//!      type Id = string;\n\
+//!      export type Admin = User;\n",
+//! );
+//! let mut ctx = Context::new().with_file_source(files);
+//!
+//! let tables = TypeScriptParser::new().parse_to_tables([Path::new("index.ts")], &mut ctx)?;
+//! let table = &tables[0];
+//!
+//! for import in table.all_imports() {
+//!     println!("{:?} from {}", import.names, import.source);
+//! }
+//!
+//! let unexported = table
+//!     .all_symbols()
+//!     .filter(|symbol| !symbol.is_exported())
+//!     .map(|symbol| symbol.fqn.as_deref().unwrap())
+//!     .collect::<Vec<_>>();
+//! assert_eq!(unexported, ["index.ts::Id"]);
+//! # Ok::<(), dossier_core::DossierError>(())
+//! ```
+pub use crate::class::Class;
+pub use crate::decorator::Decorator;
+pub use crate::field::Field;
+pub use crate::function::Function;
+pub use crate::import::{Import, ImportedName};
+pub use crate::import_alias::ImportAlias;
+pub use crate::interface::Interface;
+pub use crate::method::{Identifier, Method};
+pub use crate::namespace::Namespace;
+pub use crate::parameter::Parameter;
+pub use crate::property::Property;
+pub use crate::reexport::ReExport;
+pub use crate::symbol::{Source, Symbol, SymbolContext, SymbolID, SymbolKind};
+pub use crate::symbol_table::{Scope, ScopeID, SymbolTable};
+pub use crate::tsdoc::DocTags;
+pub use crate::type_alias::TypeAlias;
+pub use crate::type_constraint::TypeConstraint;
+pub use crate::type_variable::TypeVariable;
+pub use crate::types::Type;
+pub use crate::variable::Variable;
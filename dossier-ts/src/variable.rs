@@ -0,0 +1,514 @@
+use crate::{
+    helpers::*,
+    symbol::{Source, Symbol, SymbolContext, SymbolKind},
+    tsdoc::{self, DocTags},
+    types, ParserContext,
+};
+use dossier_core::serde_json::json;
+use dossier_core::{tree_sitter::Node, Entity, FileMode, Identity, MemberContext, Result};
+
+/// `const foo = 1, bar = 2;` and `let baz;`
+pub(crate) const NODE_KIND: &str = "lexical_declaration";
+/// `var foo = 1;`
+pub(crate) const VAR_NODE_KIND: &str = "variable_declaration";
+/// `declare let foo: Foo;`, around either of the above
+pub(crate) const AMBIENT_NODE_KIND: &str = "ambient_declaration";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    pub identifier: String,
+    pub documentation: Option<String>,
+    pub doc_tags: DocTags,
+    /// The type annotation, if there is one, and the types the initializer
+    /// `satisfies`, with the `Satisfies` context
+    pub children: Vec<Symbol>,
+    pub exported: bool,
+    /// Declared with `const` rather than `let` or `var`
+    pub constant: bool,
+    /// Declared with `declare`, so it only describes a variable that exists
+    /// elsewhere at runtime
+    pub ambient: bool,
+    /// The initializer as written in the source. Like with fields, we don't
+    /// try to make sense of arbitrary expressions.
+    pub value: Option<String>,
+    /// The literal type of a constant's initializer, e.g. `"GET"` or `42`, if
+    /// it is simple enough to infer. See `infer_literal_type`.
+    pub inferred_type: Option<String>,
+    /// The initializer ends in `as const`
+    pub const_assertion: bool,
+}
+
+impl Variable {
+    pub fn as_entity(
+        &self,
+        source: &Source,
+        fqn: Option<&str>,
+        symbol_context: Option<SymbolContext>,
+    ) -> Entity {
+        let mut meta = json!({});
+        if self.exported {
+            meta["exported"] = true.into();
+        }
+        if self.constant {
+            meta["const"] = true.into();
+        }
+        if self.ambient {
+            meta["ambient"] = true.into();
+        }
+        if let Some(value) = &self.value {
+            meta["value"] = json!(value);
+        }
+        if let Some(inferred_type) = &self.inferred_type {
+            meta["inferred_type"] = json!(inferred_type);
+        }
+        if self.const_assertion {
+            meta["const_assertion"] = true.into();
+        }
+        self.doc_tags.add_to_meta(&mut meta);
+
+        Entity {
+            title: Some(self.identifier.clone()),
+            description: self.documentation.as_deref().unwrap_or_default().to_owned(),
+            kind: "variable".to_owned(),
+            identity: Identity::FQN(fqn.expect("Variable without FQN").to_owned()),
+            member_context: symbol_context.map(MemberContext::from),
+            language: crate::LANGUAGE.to_owned(),
+            source: source.as_entity_source(),
+            meta,
+            members: self
+                .children
+                .iter()
+                .map(|s| s.as_entity())
+                .collect::<Vec<_>>(),
+        }
+    }
+}
+
+/// Parses each declarator of a declaration into a symbol of its own.
+/// Destructuring patterns like `const { a, b } = obj` are skipped.
+///
+/// Definite assignment assertions like `let foo!: Foo;` are allowed, and only
+/// the type is kept. An `ambient_declaration` is parsed as the declaration in
+/// it, if that is a variable declaration. Other ambient declarations result
+/// in no symbols.
+pub(crate) fn parse(node: &Node, ctx: &mut ParserContext) -> Result<Vec<Symbol>> {
+    assert!(matches!(
+        node.kind(),
+        NODE_KIND | VAR_NODE_KIND | AMBIENT_NODE_KIND
+    ));
+
+    let ambient = node.kind() == AMBIENT_NODE_KIND;
+    let declaration = if ambient {
+        match node
+            .named_child(0)
+            .filter(|n| matches!(n.kind(), NODE_KIND | VAR_NODE_KIND))
+        {
+            Some(declaration) => declaration,
+            None => return Ok(vec![]),
+        }
+    } else {
+        *node
+    };
+
+    let constant = declaration
+        .child_by_field_name("kind")
+        .is_some_and(|kind| kind.kind() == "const");
+    // `export` and the documentation come before the `declare`
+    let exported = is_exported(node);
+    let (documentation, doc_tags) = tsdoc::parse(find_docs(node, ctx.code));
+
+    let mut symbols = vec![];
+    let mut cursor = declaration.walk();
+
+    for declarator in declaration.named_children(&mut cursor) {
+        if declarator.kind() != "variable_declarator" {
+            continue;
+        }
+
+        let Some(name) = declarator
+            .child_by_field_name("name")
+            .filter(|name| name.kind() == "identifier")
+        else {
+            continue;
+        };
+        let identifier = name.utf8_text(ctx.code.as_bytes()).unwrap().to_owned();
+
+        let mut children = vec![];
+        let mut value = None;
+        let mut inferred_type = None;
+        let mut const_assertion = false;
+
+        // Minified files can have enormous initializers, and the names are all
+        // we want from them anyway
+        if ctx.mode == FileMode::Full {
+            if let Some(the_type) = declarator
+                .child_by_field_name("type")
+                .and_then(|annotation| annotation.named_child(0))
+            {
+                ctx.push_fqn(&identifier);
+                children.push(types::parse(&the_type, ctx)?);
+                ctx.pop_fqn();
+            }
+
+            if let Some(value_node) = declarator.child_by_field_name("value") {
+                value = Some(
+                    value_node
+                        .utf8_text(ctx.code.as_bytes())
+                        .unwrap()
+                        .to_owned(),
+                );
+
+                if constant {
+                    inferred_type = infer_literal_type(&value_node, ctx.code);
+                }
+
+                ctx.push_fqn(&identifier);
+                let assertions = parse_type_assertions(&value_node, ctx)?;
+                ctx.pop_fqn();
+                children.extend(assertions.satisfies);
+                const_assertion = assertions.const_assertion;
+            }
+        }
+
+        symbols.push(Symbol::in_context(
+            ctx,
+            SymbolKind::Variable(Variable {
+                identifier,
+                documentation: documentation.clone(),
+                doc_tags: doc_tags.clone(),
+                children,
+                exported,
+                constant,
+                ambient,
+                value,
+                inferred_type,
+                const_assertion,
+            }),
+            Source::for_node(node, ctx),
+        ));
+    }
+
+    Ok(symbols)
+}
+
+/// What the outermost expressions of an initializer say about its type
+#[derive(Debug, Default)]
+struct TypeAssertions {
+    /// The types of `satisfies` expressions, from the outside in
+    satisfies: Vec<Symbol>,
+    /// Whether there is an `as const`
+    const_assertion: bool,
+}
+
+/// Parses the `satisfies` and `as const` around an initializer, as in
+/// `{ ... } satisfies Config` or `["admin", "user"] as const`, also through
+/// parentheses and each other.
+fn parse_type_assertions(node: &Node, ctx: &mut ParserContext) -> Result<TypeAssertions> {
+    let mut assertions = TypeAssertions::default();
+    let mut node = *node;
+
+    loop {
+        match node.kind() {
+            "parenthesized_expression" => {}
+            "satisfies_expression" => {
+                if let Some(the_type) = node.named_child(1) {
+                    let mut satisfies = types::parse(&the_type, ctx)?;
+                    satisfies.context = Some(SymbolContext::Satisfies);
+                    assertions.satisfies.push(satisfies);
+                }
+            }
+            "as_expression" if is_const_assertion(&node) => {
+                assertions.const_assertion = true;
+            }
+            _ => return Ok(assertions),
+        }
+
+        match node.named_child(0) {
+            Some(inner) => node = inner,
+            None => return Ok(assertions),
+        }
+    }
+}
+
+/// `const` isn't a type, so it's the only unnamed node an `as` can end with
+fn is_const_assertion(node: &Node) -> bool {
+    node.child(node.child_count().saturating_sub(1))
+        .is_some_and(|target| target.kind() == "const")
+}
+
+/// Infers the literal type of a constant's initializer, the way TypeScript
+/// would for `const`.
+///
+/// Only a small set of expressions are understood: string, number, boolean,
+/// `null` and `undefined` literals, template strings without substitutions,
+/// negative numbers, and any of those wrapped in parentheses, `satisfies`, or
+/// `as const`. Anything else has no inferred type, and only its source text is
+/// kept.
+pub(crate) fn infer_literal_type(node: &Node, code: &str) -> Option<String> {
+    let mut node = *node;
+
+    loop {
+        let text = node.utf8_text(code.as_bytes()).ok()?;
+
+        match node.kind() {
+            "string" | "number" | "true" | "false" | "null" => return Some(text.to_owned()),
+            "undefined" => return Some("undefined".to_owned()),
+            "template_string" => return template_literal_type(&node, text),
+            "unary_expression" => {
+                let operator = node.child_by_field_name("operator")?;
+                let argument = node.child_by_field_name("argument")?;
+
+                return match (operator.kind(), argument.kind()) {
+                    ("-", "number") => Some(text.to_owned()),
+                    _ => None,
+                };
+            }
+            // `satisfies` checks the type of the expression without changing it
+            "parenthesized_expression" | "satisfies_expression" => {
+                node = node.named_child(0)?;
+            }
+            "as_expression" if is_const_assertion(&node) => {
+                node = node.named_child(0)?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// `` `GET` `` has the type `"GET"`. Templates with substitutions don't have a
+/// literal type, and neither do ones we'd have to re-escape.
+fn template_literal_type(node: &Node, text: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    if node
+        .named_children(&mut cursor)
+        .any(|child| child.kind() == "template_substitution")
+    {
+        return None;
+    }
+
+    let contents = text.strip_prefix('`')?.strip_suffix('`')?;
+    if contents.contains(['"', '\\', '\n']) {
+        return None;
+    }
+
+    Some(format!("\"{}\"", contents))
+}
+
+fn is_exported(node: &Node) -> bool {
+    node.parent()
+        .is_some_and(|p| p.kind() == "export_statement")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_file, ParserContext};
+    use dossier_core::{Entity, Identity, MemberContext};
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn variables_in(source: &str) -> Vec<Entity> {
+        let mut table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        table.resolve_types();
+
+        table
+            .all_symbols()
+            .map(|s| s.as_entity())
+            .filter(|e| e.kind == "variable")
+            .collect()
+    }
+
+    #[test]
+    fn parses_declarations() {
+        let source = indoc! { r#"
+        /** The default port */
+        export const PORT: number = 8080;
+        let host = "localhost", retries;
+        const { a, b } = pair;
+        "#};
+
+        let variables = variables_in(source);
+        assert_eq!(variables.len(), 3);
+
+        let port = &variables[0];
+        assert_eq!(port.title.as_deref(), Some("PORT"));
+        assert_eq!(port.description, "The default port");
+        assert_eq!(port.meta["exported"], true);
+        assert_eq!(port.meta["const"], true);
+        assert_eq!(port.meta["value"], "8080");
+        assert_eq!(port.meta["inferred_type"], "8080");
+        assert_eq!(port.members[0].title.as_deref(), Some("number"));
+
+        // Only constants get literal types
+        let host = &variables[1];
+        assert_eq!(host.meta["value"], "\"localhost\"");
+        assert!(host.meta.get("const").is_none());
+        assert!(host.meta.get("inferred_type").is_none());
+
+        let retries = &variables[2];
+        assert_eq!(retries.title.as_deref(), Some("retries"));
+        assert!(retries.meta.get("value").is_none());
+    }
+
+    #[test]
+    fn infers_literal_types_of_simple_constants() {
+        let cases = [
+            ("'single'", "'single'"),
+            ("-1.5", "-1.5"),
+            ("false", "false"),
+            ("null", "null"),
+            ("undefined", "undefined"),
+            ("`GET`", "\"GET\""),
+            ("((42))", "42"),
+            ("\"admin\" as const", "\"admin\""),
+            ("(\"a\" satisfies string) satisfies string", "\"a\""),
+        ];
+
+        for (value, expected) in cases {
+            let source = format!("const x = {};", value);
+            let variables = variables_in(&source);
+
+            assert_eq!(variables[0].meta["value"], value);
+            assert_eq!(variables[0].meta["inferred_type"], expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_source_text_of_other_initializers() {
+        let corpus = [
+            "(config as Options)",
+            "`${a}-${b}`",
+            "`say \"hi\"`",
+            "{ port: 80 } satisfies Config",
+            "await fetch(url)",
+            "function* () { yield 1; yield* other(); }",
+            "yield",
+            "<div className=\"x\">{value}</div>",
+            "<Foo>bar",
+            "/ab+c/gi",
+            "html`<p>${text}</p>`",
+            "-x",
+            "!true",
+            "typeof window",
+            "new Map<string, number>()",
+            "a ? b : c",
+            "a ?? b",
+            "x!",
+            "(a, b)",
+            "(): void => {}",
+            "class {}",
+            "[1, 2, 3] as const",
+            "import.meta.url",
+        ];
+
+        for value in corpus {
+            let source = format!("export const x = {};", value);
+            let variables = variables_in(&source);
+
+            // Some of these aren't valid TypeScript, and tree-sitter may not
+            // find a declaration at all. If it does, all we keep is the text.
+            if let Some(x) = variables.first() {
+                assert_eq!(x.title.as_deref(), Some("x"));
+                assert!(x.meta.get("inferred_type").is_none(), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn captures_satisfies_and_const_assertions() {
+        let source = indoc! { r#"
+        interface Config {
+            port: number;
+        }
+        export const config = { port: 80 } satisfies Config;
+        export const ROLES = ["admin", "user"] as const;
+        const both = ({ port: 80 } as const) satisfies Readonly<Config>;
+        const plain = { port: 80 };
+        "#};
+
+        let variables = variables_in(source);
+
+        let config = &variables[0];
+        assert_eq!(config.members.len(), 1);
+        let satisfies = &config.members[0];
+        assert_eq!(satisfies.member_context, Some(MemberContext::Satisfies));
+        assert_eq!(
+            satisfies.identity,
+            Identity::Reference("index.ts::Config".to_owned())
+        );
+        assert!(config.meta.get("const_assertion").is_none());
+
+        let roles = &variables[1];
+        assert_eq!(roles.meta["const_assertion"], true);
+        assert!(roles.members.is_empty());
+
+        let both = &variables[2];
+        assert_eq!(both.meta["const_assertion"], true);
+        assert_eq!(
+            both.members[0].member_context,
+            Some(MemberContext::Satisfies)
+        );
+        assert_eq!(both.members[0].title.as_deref(), Some("Readonly"));
+
+        let plain = &variables[3];
+        assert!(plain.members.is_empty());
+        assert!(plain.meta.get("const_assertion").is_none());
+    }
+
+    #[test]
+    fn tolerates_definite_and_ambient_declarations() {
+        let source = indoc! { r#"
+        interface Foo {}
+        let first!: Foo;
+        /** Provided by the host page */
+        export declare let host: Foo;
+        declare var a: number, b;
+        declare const VERSION = "1.0";
+        declare function notAVariable(): void;
+
+        try {
+            load();
+        } catch (e: unknown) {
+            let inside = e;
+        }
+
+        export function after(): Foo {}
+        "#};
+
+        let variables = variables_in(source);
+        let names = variables
+            .iter()
+            .map(|v| v.title.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        // Nothing from inside the catch clause
+        assert_eq!(names, ["first", "host", "a", "b", "VERSION"]);
+
+        let first = &variables[0];
+        assert_eq!(first.members[0].title.as_deref(), Some("Foo"));
+        assert!(first.meta.get("ambient").is_none());
+
+        let host = &variables[1];
+        assert_eq!(host.description, "Provided by the host page");
+        assert_eq!(host.meta["exported"], true);
+        assert_eq!(host.meta["ambient"], true);
+        assert_eq!(host.members[0].title.as_deref(), Some("Foo"));
+
+        assert_eq!(variables[4].meta["inferred_type"], "\"1.0\"");
+
+        // The symbols around them are still there
+        let mut table = parse_file(ParserContext::new(Path::new("index.ts"), source)).unwrap();
+        table.resolve_types();
+        let kinds = table
+            .all_symbols()
+            .map(|s| s.as_entity())
+            .filter(|e| e.kind != "variable")
+            .map(|e| (e.kind, e.title.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                ("interface".to_owned(), "Foo".to_owned()),
+                ("function".to_owned(), "after".to_owned())
+            ]
+        );
+    }
+}
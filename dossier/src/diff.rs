@@ -0,0 +1,627 @@
+//! The changes to the public API between two versions of the same code, for
+//! `dossier diff`.
+//!
+//! Declarations are matched by their FQNs, so both versions should be parsed
+//! with the same paths, e.g. from the same directory or with the same
+//! `--root`. Declarations that are in both versions are compared by their
+//! signatures: the number of parameters, the type of each of them and
+//! whether it is optional, the return type, the type of properties, fields,
+//! attributes and variables, the type a type alias names, and whether a
+//! declaration is optional or readonly. Types are compared as they are
+//! written, see `render::type_text`, so `Array<T>` and `T[]` are different.
+//! The members of classes, interfaces, namespaces, and of the object types of
+//! type aliases are compared on their own.
+//!
+//! Only the public API is compared, see `ParserRegistry::is_public`. Declarations
+//! that stop being public, e.g. because they are no longer exported, are
+//! changed rather than removed, since they are still there.
+//!
+//! Removed declarations, ones that are no longer public, and changes to
+//! signatures are breaking. Whether a change to a signature breaks code
+//! depends on how it is used: an optional property breaks code that reads
+//! it, and a required one code that creates objects with it, so every change
+//! counts. Added declarations, and ones that become public, aren't breaking.
+use std::fmt::Write;
+
+use dossier_core::{indexmap::IndexMap, Identity, MemberContext};
+use serde::Serialize;
+
+use crate::render::{return_type, type_text};
+use crate::{Entity, ParserRegistry};
+
+/// Kinds of entities that declare something that can be part of an API.
+/// Parameters are part of the signature of the declaration they belong to.
+const DECLARATION_KINDS: &[&str] = &[
+    "attribute",
+    "class",
+    "field",
+    "function",
+    "interface",
+    "method",
+    "namespace",
+    "property",
+    "type_alias",
+    "variable",
+];
+
+/// Flags of declarations and parameters, set in their `meta`, that are part
+/// of their signature
+const FLAGS: &[&str] = &["optional", "readonly", "rest"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Added => "added",
+            Status::Removed => "removed",
+            Status::Changed => "changed",
+        }
+    }
+}
+
+/// A public declaration that was added, removed, or changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub fqn: String,
+    /// The kind of the declaration, in the new version if it is in both
+    pub kind: String,
+    pub status: Status,
+    /// What changed about a changed declaration, e.g.
+    /// ``return type: `number` → `string` ``
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    /// Whether code written for the old version may not work with the new one
+    pub breaking: bool,
+}
+
+/// The changes to the public API from the `old` entities to the `new` ones.
+/// Removed and changed declarations come first, in the order of the old
+/// version, followed by the added ones, in the order of the new version.
+/// Whether declarations are public is up to their parsers in `parsers`.
+pub fn diff(old: &[Entity], new: &[Entity], parsers: &ParserRegistry) -> Vec<Change> {
+    let old = declarations(old, parsers);
+    let new = declarations(new, parsers);
+    let mut changes = vec![];
+
+    for (fqn, before) in &old {
+        match new.get(fqn) {
+            Some(after) => {
+                let differences = compare(before, after);
+                if !differences.details.is_empty() {
+                    changes.push(Change {
+                        fqn: fqn.to_string(),
+                        kind: after.entity.kind.clone(),
+                        status: Status::Changed,
+                        details: differences.details,
+                        breaking: differences.breaking,
+                    });
+                }
+            }
+            None if before.public => changes.push(Change {
+                fqn: fqn.to_string(),
+                kind: before.entity.kind.clone(),
+                status: Status::Removed,
+                details: vec![],
+                breaking: true,
+            }),
+            None => {}
+        }
+    }
+
+    for (fqn, after) in &new {
+        if after.public && !old.contains_key(fqn) {
+            changes.push(Change {
+                fqn: fqn.to_string(),
+                kind: after.entity.kind.clone(),
+                status: Status::Added,
+                details: vec![],
+                breaking: false,
+            });
+        }
+    }
+
+    changes
+}
+
+/// The report printed by `dossier diff`: a line for each change, followed by
+/// what changed about it, and a summary
+pub fn report(changes: &[Change]) -> String {
+    if changes.is_empty() {
+        return "No changes to the public API\n".to_owned();
+    }
+
+    let mut out = String::new();
+    for change in changes {
+        writeln!(
+            out,
+            "{:<8} {} {}",
+            change.status.as_str(),
+            change.kind,
+            change.fqn
+        )
+        .unwrap();
+        for detail in &change.details {
+            writeln!(out, "    {}", detail).unwrap();
+        }
+    }
+
+    let count = |status| changes.iter().filter(|c| c.status == status).count();
+    writeln!(
+        out,
+        "\n{} added, {} removed, {} changed. {} breaking",
+        count(Status::Added),
+        count(Status::Removed),
+        count(Status::Changed),
+        changes.iter().filter(|c| c.breaking).count()
+    )
+    .unwrap();
+
+    out
+}
+
+/// A declaration, and whether it is part of the public API
+struct Declaration<'a> {
+    entity: &'a Entity,
+    public: bool,
+}
+
+/// The declarations in `entities`, by their FQNs
+fn declarations<'a>(
+    entities: &'a [Entity],
+    parsers: &ParserRegistry,
+) -> IndexMap<&'a str, Declaration<'a>> {
+    let mut out = IndexMap::new();
+
+    for entity in entities {
+        collect(entity, None, parsers, &mut out);
+    }
+
+    out
+}
+
+/// Adds `entity` if it is a declaration, and the declarations in it.
+/// `parent` is the declaration it is in, and whether that is public.
+fn collect<'a>(
+    entity: &'a Entity,
+    parent: Option<(&'a Entity, bool)>,
+    parsers: &ParserRegistry,
+    out: &mut IndexMap<&'a str, Declaration<'a>>,
+) {
+    let Identity::FQN(fqn) = &entity.identity else {
+        return;
+    };
+    if !DECLARATION_KINDS.contains(&entity.kind.as_str()) {
+        return;
+    }
+
+    let public = parent.map_or(true, |(_, public)| public)
+        && parsers.is_public(entity, parent.map(|(parent, _)| parent));
+    // Overloads share an FQN, so only the first of them is compared
+    out.entry(fqn.as_str())
+        .or_insert(Declaration { entity, public });
+
+    let object_members = object_type(entity).into_iter().flat_map(|o| &o.members);
+    for member in entity.members.iter().chain(object_members) {
+        collect(member, Some((entity, public)), parsers, out);
+    }
+}
+
+#[derive(Default)]
+struct Differences {
+    details: Vec<String>,
+    breaking: bool,
+}
+
+impl Differences {
+    fn breaking(&mut self, detail: String) {
+        self.details.push(detail);
+        self.breaking = true;
+    }
+}
+
+/// What changed between two versions of a declaration
+fn compare(before: &Declaration, after: &Declaration) -> Differences {
+    let mut differences = Differences::default();
+
+    // Signatures only matter while they are part of the API
+    match (before.public, after.public) {
+        (true, true) => {}
+        (true, false) => {
+            differences.breaking("no longer public".to_owned());
+            return differences;
+        }
+        (false, true) => {
+            differences.details.push("now public".to_owned());
+            return differences;
+        }
+        (false, false) => return differences,
+    }
+
+    let (before, after) = (before.entity, after.entity);
+    if before.kind != after.kind {
+        differences.breaking(format!("kind: {} → {}", before.kind, after.kind));
+        return differences;
+    }
+
+    compare_flags(before, after, "", &mut differences);
+
+    let (old_parameters, new_parameters) = (parameters(before), parameters(after));
+    if old_parameters.len() != new_parameters.len() {
+        differences.breaking(format!(
+            "parameters: {} → {}",
+            old_parameters.len(),
+            new_parameters.len()
+        ));
+    }
+    for (old, new) in old_parameters.iter().zip(&new_parameters) {
+        let name = new.title.as_deref().unwrap_or_default();
+        let (old_type, new_type) = (type_of(old.members.first()), type_of(new.members.first()));
+        if old_type != new_type {
+            differences.breaking(format!(
+                "type of parameter `{}`: {} → {}",
+                name, old_type, new_type
+            ));
+        }
+        compare_flags(
+            old,
+            new,
+            &format!("parameter `{}` ", name),
+            &mut differences,
+        );
+    }
+
+    let (old_return, new_return) = (type_of(return_type(before)), type_of(return_type(after)));
+    if old_return != new_return {
+        differences.breaking(format!("return type: {} → {}", old_return, new_return));
+    }
+
+    let (old_type, new_type) = (
+        type_of(declared_type(before)),
+        type_of(declared_type(after)),
+    );
+    if old_type != new_type {
+        differences.breaking(format!("type: {} → {}", old_type, new_type));
+    }
+
+    differences
+}
+
+/// Notes each flag that was set or unset, about the thing `subject` names
+fn compare_flags(before: &Entity, after: &Entity, subject: &str, differences: &mut Differences) {
+    let is_set = |entity: &Entity, flag: &str| entity.meta.get(flag) == Some(&true.into());
+
+    for flag in FLAGS {
+        match (is_set(before, flag), is_set(after, flag)) {
+            (false, true) => differences.breaking(format!("{}now {}", subject, flag)),
+            (true, false) => differences.breaking(format!("{}no longer {}", subject, flag)),
+            _ => {}
+        }
+    }
+}
+
+fn parameters(entity: &Entity) -> Vec<&Entity> {
+    entity
+        .members
+        .iter()
+        .filter(|m| m.kind == "parameter")
+        .collect()
+}
+
+/// The object type whose properties are the members of an interface or type
+/// alias
+fn object_type(entity: &Entity) -> Option<&Entity> {
+    if !matches!(entity.kind.as_str(), "interface" | "type_alias") {
+        return None;
+    }
+
+    entity
+        .members
+        .iter()
+        .find(|m| m.kind == "object" && m.member_context == Some(MemberContext::Type))
+}
+
+/// The type of a property, field, attribute or variable, or the type a type
+/// alias names, unless it is an object type whose members are compared on
+/// their own
+fn declared_type(entity: &Entity) -> Option<&Entity> {
+    if object_type(entity).is_some() {
+        return None;
+    }
+
+    entity
+        .members
+        .iter()
+        .find(|m| m.member_context == Some(MemberContext::Type))
+}
+
+fn type_of(the_type: Option<&Entity>) -> String {
+    match the_type {
+        Some(the_type) => format!("`{}`", type_text(the_type)),
+        None => "none".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::serde_json::json;
+    use dossier_core::test_utils::{entity, fqn, EntityBuilder, Visibility};
+
+    /// `diff` with the parsers of the default registry
+    fn diff(old: &[Entity], new: &[Entity]) -> Vec<Change> {
+        super::diff(old, new, &ParserRegistry::default())
+    }
+
+    /// A type that refers to `name`, in `context`
+    fn named(name: &str, context: MemberContext) -> Entity {
+        entity("identifier", Identity::Reference(name.to_owned()))
+            .title(name)
+            .language("ts")
+            .context(context)
+    }
+
+    /// A parameter of `area`
+    fn parameter(name: &str, the_type: &str) -> Entity {
+        entity("parameter", fqn(&format!("index.ts::area::{}", name)))
+            .title(name)
+            .language("ts")
+            .context(MemberContext::Parameter)
+            .members(vec![named(the_type, MemberContext::Type)])
+    }
+
+    /// `function area(shape: Shape, scale: number): number`
+    fn area() -> Entity {
+        entity("function", fqn("index.ts::area"))
+            .title("area")
+            .language("ts")
+            .visibility(Visibility::Exported)
+            .members(vec![
+                parameter("shape", "Shape"),
+                parameter("scale", "number"),
+                named("number", MemberContext::ReturnType),
+            ])
+    }
+
+    /// `interface Shape { name: string }`
+    fn shape(property: Entity) -> Entity {
+        let object = entity("object", Identity::Anonymous)
+            .language("ts")
+            .context(MemberContext::Type)
+            .members(vec![property]);
+
+        entity("interface", fqn("index.ts::Shape"))
+            .title("Shape")
+            .language("ts")
+            .visibility(Visibility::Exported)
+            .members(vec![object])
+    }
+
+    fn name_property() -> Entity {
+        entity("property", fqn("index.ts::Shape::name"))
+            .title("name")
+            .language("ts")
+            .context(MemberContext::Property)
+            .members(vec![named("string", MemberContext::Type)])
+    }
+
+    fn summary(changes: &[Change]) -> Vec<(&str, Status, Vec<&str>, bool)> {
+        changes
+            .iter()
+            .map(|c| {
+                (
+                    c.fqn.as_str(),
+                    c.status,
+                    c.details.iter().map(|d| d.as_str()).collect(),
+                    c.breaking,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_no_changes_between_the_same_entities() {
+        let entities = vec![area(), shape(name_property())];
+
+        assert_eq!(diff(&entities, &entities), vec![]);
+        assert_eq!(report(&[]), "No changes to the public API\n");
+    }
+
+    #[test]
+    fn reports_added_and_removed_declarations() {
+        let old = vec![area(), shape(name_property())];
+        let new = vec![
+            shape(name_property()),
+            entity("function", fqn("index.ts::perimeter"))
+                .title("perimeter")
+                .language("ts")
+                .visibility(Visibility::Exported),
+        ];
+
+        assert_eq!(
+            summary(&diff(&old, &new)),
+            vec![
+                ("index.ts::area", Status::Removed, vec![], true),
+                ("index.ts::perimeter", Status::Added, vec![], false),
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_parameters() {
+        let mut fewer = area();
+        fewer.members.remove(1);
+
+        let mut retyped = area();
+        retyped.members[1] = parameter("scale", "string");
+
+        let mut optional = area();
+        optional.members[1].meta = json!({ "optional": true });
+
+        assert_eq!(
+            summary(&diff(&[area()], &[fewer])),
+            vec![(
+                "index.ts::area",
+                Status::Changed,
+                vec!["parameters: 2 → 1"],
+                true
+            )]
+        );
+        assert_eq!(
+            summary(&diff(&[area()], &[retyped])),
+            vec![(
+                "index.ts::area",
+                Status::Changed,
+                vec!["type of parameter `scale`: `number` → `string`"],
+                true
+            )]
+        );
+        assert_eq!(
+            summary(&diff(&[area()], &[optional])),
+            vec![(
+                "index.ts::area",
+                Status::Changed,
+                vec!["parameter `scale` now optional"],
+                true
+            )]
+        );
+    }
+
+    #[test]
+    fn compares_return_types() {
+        let mut new = area();
+        new.members[2] = named("string", MemberContext::ReturnType);
+        let mut without = area();
+        without.members.pop();
+
+        assert_eq!(
+            diff(&[area()], &[new])[0].details,
+            ["return type: `number` → `string`"]
+        );
+        assert_eq!(
+            diff(&[area()], &[without])[0].details,
+            ["return type: `number` → none"]
+        );
+    }
+
+    #[test]
+    fn compares_the_members_of_interfaces_on_their_own() {
+        let mut retyped = name_property();
+        retyped.members[0] = named("number", MemberContext::Type);
+        let flagged = name_property().meta(json!({ "readonly": true }));
+
+        assert_eq!(
+            summary(&diff(&[shape(name_property())], &[shape(retyped)])),
+            vec![(
+                "index.ts::Shape::name",
+                Status::Changed,
+                vec!["type: `string` → `number`"],
+                true
+            )]
+        );
+        assert_eq!(
+            summary(&diff(&[shape(flagged)], &[shape(name_property())])),
+            vec![(
+                "index.ts::Shape::name",
+                Status::Changed,
+                vec!["no longer readonly"],
+                true
+            )]
+        );
+    }
+
+    #[test]
+    fn compares_whether_declarations_are_public() {
+        let hidden = vec![area().meta(json!({}))];
+
+        assert_eq!(
+            summary(&diff(&[area()], &hidden)),
+            vec![(
+                "index.ts::area",
+                Status::Changed,
+                vec!["no longer public"],
+                true
+            )]
+        );
+        assert_eq!(
+            summary(&diff(&hidden, &[area()])),
+            vec![("index.ts::area", Status::Changed, vec!["now public"], false)]
+        );
+
+        // Changes to declarations that aren't public don't matter
+        let mut changed = hidden.clone();
+        changed[0].members.clear();
+        assert_eq!(diff(&hidden, &changed), vec![]);
+        assert_eq!(diff(&hidden, &[]), vec![]);
+    }
+
+    #[test]
+    fn members_of_private_declarations_are_not_public() {
+        let old = vec![shape(name_property()).meta(json!({}))];
+
+        assert_eq!(diff(&old, &[]), vec![]);
+    }
+
+    #[test]
+    fn compares_python_declarations() {
+        let function = |name: &str, return_type: &str| {
+            entity("function", fqn(&format!("shapes.py::{}", name)))
+                .title(name)
+                .language("py")
+                .visibility(Visibility::Exported)
+                .members(vec![named(return_type, MemberContext::ReturnType)])
+        };
+
+        assert_eq!(
+            summary(&diff(
+                &[function("area", "float"), function("_area", "float")],
+                &[function("area", "int")]
+            )),
+            vec![(
+                "shapes.py::area",
+                Status::Changed,
+                vec!["return type: `float` → `int`"],
+                true
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_changes_for_people() {
+        let mut new = area();
+        new.members[2] = named("string", MemberContext::ReturnType);
+        new.members.remove(1);
+        let changes = diff(&[area()], &[new, shape(name_property())]);
+
+        assert_eq!(
+            report(&changes),
+            "changed  function index.ts::area\n    \
+             parameters: 2 → 1\n    \
+             return type: `number` → `string`\n\
+             added    interface index.ts::Shape\n\
+             added    property index.ts::Shape::name\n\
+             \n2 added, 0 removed, 1 changed. 1 breaking\n"
+        );
+    }
+
+    #[test]
+    fn serializes_changes() {
+        let changes = diff(&[area()], &[]);
+
+        assert_eq!(
+            dossier_core::serde_json::to_value(&changes).unwrap(),
+            json!([{
+                "fqn": "index.ts::area",
+                "kind": "function",
+                "status": "removed",
+                "breaking": true,
+            }])
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! The entities as a flat list with an index, for `--flat`.
+//!
+//! Each entity is listed on its own, without its members, under a key that
+//! is unique in the output. Members point back at the entity they belong to
+//! with the key of their `parent`.
+//!
+//! An entity's key is its FQN, unless another entity with the same FQN was
+//! listed before it, like a type that refers to a builtin another type
+//! already referred to. Top-level entities get their FQNs first, so a class
+//! keeps its FQN even if a type that names it is listed before it. Entities
+//! without a key of their own get the key of their parent, their member
+//! context, and their position among the members of their parent in the
+//! same context, e.g. `index.ts::distance::parameter::1`.
+use std::collections::HashSet;
+
+use dossier::Entity;
+use dossier_core::{indexmap::IndexMap, portable_path, Identity};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FlatOutput {
+    /// Each entity, parents before their members
+    pub entities: Vec<FlatEntity>,
+    /// The position of each entity in `entities`, by its key
+    pub index: IndexMap<String, usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FlatEntity {
+    pub key: String,
+    /// The key of the entity this one is a member of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// The entity, without its members
+    #[serde(flatten)]
+    pub entity: Entity,
+}
+
+pub(crate) fn flatten(entities: &[Entity]) -> FlatOutput {
+    let taken = entities
+        .iter()
+        .filter_map(|e| match &e.identity {
+            Identity::FQN(fqn) => Some(fqn.clone()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let mut flattener = Flattener {
+        out: FlatOutput {
+            entities: vec![],
+            index: IndexMap::new(),
+        },
+        taken,
+    };
+
+    for (position, entity) in entities.iter().enumerate() {
+        let fallback = format!("{}::{}", portable_path(&entity.source.file), position);
+        flattener.add(entity, None, fallback);
+    }
+
+    flattener.out
+}
+
+struct Flattener {
+    out: FlatOutput,
+    /// FQNs that are or will be the key of some entity
+    taken: HashSet<String>,
+}
+
+impl Flattener {
+    /// Adds `entity` and its members. `fallback` is the key to use if the
+    /// entity's FQN can't be.
+    fn add(&mut self, entity: &Entity, parent: Option<&str>, fallback: String) {
+        let key = match &entity.identity {
+            // Top-level entities have their FQN taken for them up front
+            Identity::FQN(fqn) if parent.is_none() && !self.out.index.contains_key(fqn) => {
+                fqn.clone()
+            }
+            Identity::FQN(fqn) if parent.is_some() && self.taken.insert(fqn.clone()) => fqn.clone(),
+            _ => fallback,
+        };
+
+        self.out.index.insert(key.clone(), self.out.entities.len());
+        self.out.entities.push(FlatEntity {
+            key: key.clone(),
+            parent: parent.map(|p| p.to_owned()),
+            entity: Entity {
+                members: vec![],
+                ..entity.clone()
+            },
+        });
+
+        let mut ordinals = IndexMap::<&str, usize>::new();
+        for member in &entity.members {
+            let context = member
+                .member_context
+                .as_ref()
+                .map(|c| c.as_str())
+                .unwrap_or("member");
+            let ordinal = ordinals.entry(context).or_default();
+            let fallback = format!("{}::{}::{}", key, context, ordinal);
+            *ordinal += 1;
+
+            self.add(member, Some(&key), fallback);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::test_utils::{entity, fqn, EntityBuilder};
+
+    /// Puts the members back into the entities they belong to
+    fn nest(flat: &FlatOutput) -> Vec<Entity> {
+        fn build(flat: &FlatOutput, key: &str) -> Entity {
+            let mut entity = flat.entities[flat.index[key]].entity.clone();
+            entity.members = flat
+                .entities
+                .iter()
+                .filter(|e| e.parent.as_deref() == Some(key))
+                .map(|e| build(flat, &e.key))
+                .collect();
+            entity
+        }
+
+        flat.entities
+            .iter()
+            .filter(|e| e.parent.is_none())
+            .map(|e| build(flat, &e.key))
+            .collect()
+    }
+
+    fn sample() -> Vec<Entity> {
+        let number = || entity("predefined_type", fqn("builtin::number")).file("index.ts");
+        let parameter = |name: &str, the_type: Identity| {
+            entity("parameter", fqn(&format!("index.ts::distance::{}", name)))
+                .file("index.ts")
+                .context("parameter")
+                .members(vec![entity("identifier", the_type)
+                    .file("index.ts")
+                    .context("type")])
+        };
+
+        vec![
+            entity("function", fqn("index.ts::distance"))
+                .file("index.ts")
+                .members(vec![
+                    parameter("a", Identity::Reference("index.ts::Point".to_owned())),
+                    parameter("b", fqn("index.ts::Point")),
+                    number().context("return_type"),
+                    entity("union", Identity::Anonymous)
+                        .file("index.ts")
+                        .context("type")
+                        .members(vec![
+                            number().context("type"),
+                            entity("predefined_type", Identity::Anonymous)
+                                .file("index.ts")
+                                .context("type"),
+                        ]),
+                ]),
+            entity("interface", fqn("index.ts::Point")).file("index.ts"),
+        ]
+    }
+
+    #[test]
+    fn lists_every_entity_once_under_a_unique_key() {
+        let flat = flatten(&sample());
+
+        let keys = flat
+            .entities
+            .iter()
+            .map(|e| e.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                "index.ts::distance",
+                "index.ts::distance::a",
+                "index.ts::distance::a::type::0",
+                "index.ts::distance::b",
+                // The interface keeps its FQN
+                "index.ts::distance::b::type::0",
+                "builtin::number",
+                "index.ts::distance::type::0",
+                "index.ts::distance::type::0::type::0",
+                "index.ts::distance::type::0::type::1",
+                "index.ts::Point",
+            ]
+        );
+        for (position, entity) in flat.entities.iter().enumerate() {
+            assert_eq!(flat.index[&entity.key], position);
+            assert!(entity.entity.members.is_empty());
+        }
+        assert_eq!(
+            flat.entities[2].parent.as_deref(),
+            Some("index.ts::distance::a")
+        );
+    }
+
+    #[test]
+    fn has_the_same_entities_as_the_nested_form() {
+        let entities = sample();
+
+        assert_eq!(nest(&flatten(&entities)), entities);
+    }
+
+    #[test]
+    fn serializes_the_parent_next_to_the_entity() {
+        let flat = flatten(&sample());
+        let value = dossier_core::serde_json::to_value(&flat).unwrap();
+
+        assert_eq!(value["index"]["index.ts::distance::a"], 1);
+        assert_eq!(value["entities"][1]["parent"], "index.ts::distance");
+        assert_eq!(value["entities"][1]["fqn"], "index.ts::distance::a");
+        assert_eq!(value["entities"][1]["key"], "index.ts::distance::a");
+        assert!(value["entities"][1].get("members").is_none());
+        assert!(value["entities"][0].get("parent").is_none());
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+
+use crate::{Diagnostic, Options};
+
+/// Name of the dossier-specific ignore file. Uses the same syntax as `.gitignore`.
+pub const IGNORE_FILENAME: &str = ".dossierignore";
+
+/// Reported for files skipped for being larger than `Options::max_file_size`
+pub const LARGE_FILE: &str = "large-file";
+
+/// Reported for files skipped for looking generated, see `looks_generated`
+pub const GENERATED_FILE: &str = "generated-file";
+
+/// How many lines at the start of a file are searched for `GENERATED_MARKERS`
+const GENERATED_MARKER_LINES: usize = 10;
+
+/// Comments code generators put at the top of the files they write, like
+/// `// @generated by protoc-gen-es` or `# Generated by the protocol buffer
+/// compiler.  DO NOT EDIT!`
+const GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT"];
+
+/// Expands the paths given on the command line into a list of files to parse.
+///
+/// Directories are walked recursively. Unless `respect_ignore` is false, files
+/// matched by a `.dossierignore` or `.gitignore` file are skipped. This applies to
+/// files passed in explicitly as well (e.g. via shell globs), so that generated
+/// and vendored code never reaches the parsers.
+///
+/// The returned list is sorted and free of duplicates.
+pub fn collect_input_files(paths: &[PathBuf], respect_ignore: bool) -> Vec<PathBuf> {
+    let mut out = BTreeSet::new();
+    let mut matchers = IgnoreMatchers::new(std::env::current_dir().ok().as_deref());
+
+    for path in paths {
+        if path.is_dir() {
+            for entry in walker(path, respect_ignore).build().flatten() {
+                if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                    out.insert(entry.into_path());
+                }
+            }
+        } else if !respect_ignore || !matchers.is_ignored(path) {
+            // Files that don't exist are passed through as well, so the parsers
+            // can report a proper error for them.
+            out.insert(path.to_owned());
+        }
+    }
+
+    out.into_iter().collect()
+}
+
+/// Leaves out the files that are larger than `Options::max_file_size`, and
+/// with `Options::skip_generated` the ones that look generated, with a warning
+/// in `diagnostics` for each. Like the ignore files, this keeps files nobody
+/// wants documented away from the parsers, which can spend most of their time
+/// on huge generated files.
+///
+/// Files whose size can't be read are kept, so the parsers can report a proper
+/// error for them.
+pub fn skip_unwanted_files(
+    files: Vec<PathBuf>,
+    options: &Options,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<PathBuf> {
+    files
+        .into_iter()
+        .filter(|file| {
+            if let (Some(max), Ok(metadata)) = (options.max_file_size, file.metadata()) {
+                if metadata.len() > max {
+                    diagnostics.push(Diagnostic::warning(
+                        LARGE_FILE,
+                        format!(
+                            "Skipped file of {} bytes, larger than the maximum of {}",
+                            metadata.len(),
+                            max
+                        ),
+                        file,
+                    ));
+                    return false;
+                }
+            }
+
+            if options.skip_generated && looks_generated(file) {
+                diagnostics.push(Diagnostic::warning(
+                    GENERATED_FILE,
+                    "Skipped file that looks generated",
+                    file,
+                ));
+                return false;
+            }
+
+            true
+        })
+        .collect()
+}
+
+/// Whether one of the first lines of `file` has a marker code generators
+/// leave, like `@generated` or `DO NOT EDIT`
+pub fn looks_generated(file: &Path) -> bool {
+    let Ok(file) = File::open(file) else {
+        return false;
+    };
+
+    BufReader::new(file)
+        .split(b'\n')
+        .take(GENERATED_MARKER_LINES)
+        .map_while(|line| line.ok())
+        .any(|line| {
+            let line = String::from_utf8_lossy(&line);
+            GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+        })
+}
+
+fn walker(root: &Path, respect_ignore: bool) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+
+    builder
+        .standard_filters(respect_ignore)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b));
+
+    if respect_ignore {
+        builder.add_custom_ignore_filename(IGNORE_FILENAME);
+    }
+
+    builder
+}
+
+/// Checks individual files against the ignore files in their ancestor directories.
+///
+/// The directory walker handles this for directory arguments, but files passed
+/// in directly are yielded by it unconditionally.
+///
+/// Ignore files above the git repository a file is in don't apply to it. Outside
+/// of a repository, only the ones up to the input root do, if the file is in it.
+struct IgnoreMatchers {
+    by_dir: HashMap<PathBuf, Vec<Gitignore>>,
+    root: Option<PathBuf>,
+}
+
+impl IgnoreMatchers {
+    fn new(root: Option<&Path>) -> Self {
+        Self {
+            by_dir: HashMap::new(),
+            root: root.and_then(|root| root.canonicalize().ok()),
+        }
+    }
+
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        let path = match path.canonicalize() {
+            Ok(path) => path,
+            Err(_) => return false,
+        };
+        let boundary = self.boundary(&path);
+
+        for dir in path.ancestors().skip(1) {
+            let matchers = self
+                .by_dir
+                .entry(dir.to_owned())
+                .or_insert_with(|| Self::load(dir));
+
+            // The closest ignore file with an opinion wins, and within one
+            // directory `.dossierignore` takes precedence over `.gitignore`.
+            for matcher in matchers.iter() {
+                match matcher.matched_path_or_any_parents(&path, false) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+
+            if boundary.as_deref().map_or(true, |boundary| dir == boundary) {
+                break;
+            }
+        }
+
+        false
+    }
+
+    /// The last directory whose ignore files apply to `path`: the root of its
+    /// git repository, or else the input root. `None` if it is in neither, and
+    /// only the ignore files in its own directory apply.
+    fn boundary(&self, path: &Path) -> Option<PathBuf> {
+        let git_root = path
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join(".git").exists());
+
+        git_root
+            .or_else(|| self.root.as_deref().filter(|root| path.starts_with(root)))
+            .map(Path::to_owned)
+    }
+
+    fn load(dir: &Path) -> Vec<Gitignore> {
+        [IGNORE_FILENAME, ".gitignore"]
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|file| file.is_file())
+            .filter_map(|file| {
+                let mut builder = GitignoreBuilder::new(dir);
+                builder.add(file);
+                builder.build().ok()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fixture_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/ignore")
+    }
+
+    fn relative_names(files: Vec<PathBuf>) -> Vec<String> {
+        let root = fixture_root();
+        files
+            .iter()
+            .map(|f| {
+                f.strip_prefix(&root)
+                    .unwrap()
+                    .display()
+                    .to_string()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skips_directories_listed_in_dossierignore() {
+        let files = collect_input_files(&[fixture_root()], true);
+
+        assert_eq!(
+            relative_names(files),
+            vec!["index.ts".to_owned(), "src/models.ts".to_owned()]
+        );
+    }
+
+    #[test]
+    fn includes_everything_when_ignoring_is_disabled() {
+        let files = collect_input_files(&[fixture_root()], false);
+
+        assert_eq!(
+            relative_names(files),
+            vec![
+                ".dossierignore".to_owned(),
+                "generated/client.ts".to_owned(),
+                "index.ts".to_owned(),
+                "src/models.ts".to_owned(),
+            ]
+        );
+    }
+
+    fn unwanted_files_skipped(options: &Options) -> (Vec<String>, Vec<String>) {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/inputs");
+        let files = collect_input_files(std::slice::from_ref(&root), true);
+
+        let mut diagnostics = vec![];
+        let kept = skip_unwanted_files(files, options, &mut diagnostics)
+            .iter()
+            .map(|f| f.strip_prefix(&root).unwrap().display().to_string())
+            .collect();
+        let skipped = diagnostics
+            .iter()
+            .map(|d| {
+                format!(
+                    "{} {}",
+                    d.code,
+                    d.file.strip_prefix(&root).unwrap().display()
+                )
+            })
+            .collect();
+
+        (kept, skipped)
+    }
+
+    #[test]
+    fn skips_files_larger_than_the_maximum() {
+        let options = Options {
+            max_file_size: Some(1024),
+            ..Default::default()
+        };
+
+        let (kept, skipped) = unwanted_files_skipped(&options);
+
+        assert_eq!(kept, vec!["messages_pb.ts", "messages_pb2.py", "point.ts"]);
+        assert_eq!(skipped, vec!["large-file constants.ts"]);
+
+        let options = Options {
+            max_file_size: None,
+            ..Default::default()
+        };
+        assert!(unwanted_files_skipped(&options).1.is_empty());
+    }
+
+    #[test]
+    fn skips_files_that_look_generated() {
+        let options = Options {
+            skip_generated: true,
+            ..Default::default()
+        };
+
+        let (kept, skipped) = unwanted_files_skipped(&options);
+
+        assert_eq!(kept, vec!["constants.ts", "point.ts"]);
+        assert_eq!(
+            skipped,
+            vec![
+                "generated-file messages_pb.ts",
+                "generated-file messages_pb2.py"
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_explicitly_passed_files() {
+        let root = fixture_root();
+        let files = collect_input_files(
+            &[root.join("index.ts"), root.join("generated/client.ts")],
+            true,
+        );
+
+        assert_eq!(relative_names(files), vec!["index.ts".to_owned()]);
+    }
+
+    #[test]
+    fn ignore_files_above_the_git_repository_do_not_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.ts\n").unwrap();
+        std::fs::write(repo.join("index.ts"), "").unwrap();
+
+        let mut matchers = IgnoreMatchers::new(Some(dir.path()));
+
+        assert!(!matchers.is_ignored(&repo.join("index.ts")));
+    }
+
+    #[test]
+    fn ignore_files_above_the_input_root_do_not_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.ts\n").unwrap();
+        std::fs::write(root.join(IGNORE_FILENAME), "generated.ts\n").unwrap();
+        std::fs::write(root.join("src/index.ts"), "").unwrap();
+        std::fs::write(root.join("src/generated.ts"), "").unwrap();
+
+        let mut matchers = IgnoreMatchers::new(Some(&root));
+
+        assert!(!matchers.is_ignored(&root.join("src/index.ts")));
+        assert!(matchers.is_ignored(&root.join("src/generated.ts")));
+    }
+}
@@ -0,0 +1,159 @@
+//! Only the entities of some kinds, for `--kinds`.
+//!
+//! Entities of other kinds are left out along with their members, so that a
+//! class kept for `--kinds class,method` keeps its methods, but not their
+//! parameters. With `--include-signatures`, the parameters and return types
+//! of the entities that are kept are kept too, with all of their members.
+use std::collections::BTreeSet;
+
+use dossier::{Entity, MemberContext};
+use dossier_core::tree::{self, Prune};
+
+/// The kinds of the entities in `entities`, which are the kinds `--kinds`
+/// can select
+pub(crate) fn present(entities: &[Entity]) -> BTreeSet<&str> {
+    tree::walk(entities)
+        .map(|node| node.entity.kind.as_str())
+        .collect()
+}
+
+/// Removes the entities whose kind isn't one of `kinds`, and makes references
+/// to them anonymous
+pub(crate) fn retain(entities: &mut Vec<Entity>, kinds: &[String], include_signatures: bool) {
+    Prune::matching(|entity: &Entity| !kinds.contains(&entity.kind))
+        .sparing(|entity: &Entity| include_signatures && is_signature(entity))
+        .run(entities);
+}
+
+fn is_signature(entity: &Entity) -> bool {
+    matches!(
+        entity.member_context,
+        Some(MemberContext::Parameter | MemberContext::ReturnType)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::test_utils::{entity, fqn, EntityBuilder};
+    use dossier_core::Identity;
+
+    /// A class with a method and a field, a function, and an interface the
+    /// function returns
+    fn fixture() -> Vec<Entity> {
+        let parameter = entity("parameter", fqn("index.ts::Greeter::greet::name"))
+            .context(MemberContext::Parameter)
+            .members(vec![
+                entity("predefined_type", Identity::Anonymous).context(MemberContext::Type)
+            ]);
+        let greet = entity("method", fqn("index.ts::Greeter::greet"))
+            .context(MemberContext::Member)
+            .members(vec![parameter]);
+        let greeter = entity("class", fqn("index.ts::Greeter")).members(vec![
+            entity("field", fqn("index.ts::Greeter::greeting")).context(MemberContext::Member),
+            greet,
+        ]);
+
+        let return_type = entity(
+            "identifier",
+            Identity::Reference("index.ts::Greeting".to_owned()),
+        )
+        .context(MemberContext::ReturnType);
+        let make = entity("function", fqn("index.ts::makeGreeting")).members(vec![return_type]);
+
+        vec![
+            greeter,
+            make,
+            entity("interface", fqn("index.ts::Greeting")),
+        ]
+    }
+
+    fn kinds(entities: &[Entity]) -> Vec<(usize, &str)> {
+        tree::walk(entities)
+            .map(|node| (node.depth, node.entity.kind.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_the_kinds_in_the_output() {
+        assert_eq!(
+            present(&fixture()).into_iter().collect::<Vec<_>>(),
+            [
+                "class",
+                "field",
+                "function",
+                "identifier",
+                "interface",
+                "method",
+                "parameter",
+                "predefined_type"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_selected_kinds() {
+        let mut entities = fixture();
+
+        retain(
+            &mut entities,
+            &[
+                "class".to_owned(),
+                "method".to_owned(),
+                "function".to_owned(),
+            ],
+            false,
+        );
+
+        assert_eq!(
+            kinds(&entities),
+            [(0, "class"), (1, "method"), (0, "function")]
+        );
+        assert!(tree::walk(&entities).count() < tree::walk(&fixture()).count());
+    }
+
+    #[test]
+    fn keeps_the_signatures_of_selected_entities() {
+        let mut entities = fixture();
+
+        retain(
+            &mut entities,
+            &[
+                "class".to_owned(),
+                "method".to_owned(),
+                "function".to_owned(),
+            ],
+            true,
+        );
+
+        assert_eq!(
+            kinds(&entities),
+            [
+                (0, "class"),
+                (1, "method"),
+                (2, "parameter"),
+                (3, "predefined_type"),
+                (0, "function"),
+                (1, "identifier"),
+            ]
+        );
+        // The interface the return type refers to was left out
+        assert_eq!(entities[1].members[0].identity, Identity::Anonymous);
+    }
+
+    #[test]
+    fn keeps_references_to_selected_entities() {
+        let mut entities = fixture();
+
+        retain(
+            &mut entities,
+            &["function".to_owned(), "interface".to_owned()],
+            true,
+        );
+
+        assert_eq!(
+            entities[0].members[0].identity,
+            Identity::Reference("index.ts::Greeting".to_owned())
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Dossier as a library, for programs that want entities rather than the JSON
+//! printed by the command line tool.
+//!
+//! ```no_run
+//! use std::path::PathBuf;
+//!
+//! let entities = dossier::parse_paths(&[PathBuf::from("src")], &dossier::Options::default())?;
+//!
+//! for entity in entities {
+//!     println!("{}: {:?}", entity.kind, entity.title);
+//! }
+//! # Ok::<(), dossier::DossierError>(())
+//! ```
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub mod diff;
+pub mod git;
+pub mod inputs;
+pub mod paths;
+mod registry;
+pub mod render;
+
+pub use dossier_core::{
+    Context, Diagnostic, DocsParser, DossierError, Entity, FileLimits, MemberContext,
+    MinifiedFiles, Repository, Result,
+};
+pub use registry::ParserRegistry;
+
+/// The languages Dossier can parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    TypeScript,
+    Python,
+}
+
+impl Language {
+    /// All supported languages, in the order their entities are output
+    pub const ALL: &'static [Language] = &[Language::TypeScript, Language::Python];
+
+    /// The file extensions of the language's source files
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Language::TypeScript => dossier_ts::FILE_EXTENSIONS,
+            Language::Python => dossier_py::FILE_EXTENSIONS,
+        }
+    }
+
+    /// Whether `path` has one of the extensions of the language's source files
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        self.extensions()
+            .iter()
+            .any(|extension| path.extension() == Some(OsStr::new(extension)))
+    }
+}
+
+/// The default of `Options::max_file_size`
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Options for `parse_paths`.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Languages to parse. Files of other languages are ignored.
+    pub languages: Vec<Language>,
+    /// Skip files matched by `.dossierignore` and `.gitignore` files
+    pub respect_ignore: bool,
+    /// Skip files larger than this many bytes. 1 MiB by default. See
+    /// `inputs::skip_unwanted_files`
+    pub max_file_size: Option<u64>,
+    /// Skip files that look generated, see `inputs::looks_generated`
+    pub skip_generated: bool,
+    /// The context to parse the files with. See `Context` for what its
+    /// options do.
+    pub context: Context,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            languages: Language::ALL.to_vec(),
+            respect_ignore: true,
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+            skip_generated: false,
+            context: Context::new(),
+        }
+    }
+}
+
+impl Options {
+    /// Creates a parser context with these options.
+    pub fn context(&self) -> Context {
+        self.context.clone()
+    }
+}
+
+/// Parses the given files and directories, and returns the entities from all
+/// of them.
+///
+/// Directories are walked recursively, see `inputs::collect_input_files`,
+/// and files that are too large or look generated are skipped, see
+/// `inputs::skip_unwanted_files`. Entities are grouped by language, in the
+/// order of `Language::ALL`.
+///
+/// Diagnostics are discarded. Use `parse_files` with a `Context` from
+/// `Options::context` to get them.
+pub fn parse_paths(paths: &[PathBuf], options: &Options) -> Result<Vec<Entity>> {
+    let mut ctx = options.context();
+    let files = inputs::collect_input_files(paths, options.respect_ignore);
+    let files = inputs::skip_unwanted_files(files, options, &mut ctx.diagnostics);
+
+    parse_files(&files, &options.languages, &mut ctx)
+}
+
+/// Parses each file with the parser for its language, if it is one of
+/// `languages`, and returns the entities from all of them.
+///
+/// Use a `ParserRegistry` to parse other languages as well.
+pub fn parse_files(
+    files: &[PathBuf],
+    languages: &[Language],
+    ctx: &mut Context,
+) -> Result<Vec<Entity>> {
+    ParserRegistry::default().parse_files_of(files, |e| is_one_of(e, languages), ctx)
+}
+
+/// Whether `extension` is one of the extensions of `languages`
+fn is_one_of(extension: &str, languages: &[Language]) -> bool {
+    languages
+        .iter()
+        .any(|l| l.extensions().contains(&extension))
+}
+
+/// Parses the same files over and over, e.g. whenever they change, only
+/// re-parsing the files that changed since the last time.
+///
+/// Types are still resolved across all of the files every time, so a
+/// reference into a file that was deleted no longer resolves.
+#[derive(Debug)]
+pub struct IncrementalParser {
+    parsers: ParserRegistry,
+}
+
+impl Default for IncrementalParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IncrementalParser {
+    pub fn new() -> Self {
+        Self {
+            parsers: ParserRegistry::with_cache(),
+        }
+    }
+
+    /// Same as `parse_files`. Files that aren't in `files` anymore are
+    /// forgotten.
+    pub fn parse_files(
+        &self,
+        files: &[PathBuf],
+        languages: &[Language],
+        ctx: &mut Context,
+    ) -> Result<Vec<Entity>> {
+        self.parsers
+            .parse_files_of(files, |e| is_one_of(e, languages), ctx)
+    }
+}
@@ -1,78 +1,692 @@
-use std::ffi::OsStr;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use dossier_core::DocsParser;
+use clap::{Parser, Subcommand, ValueEnum};
+use dossier::{git, inputs};
+use notify_debouncer_mini::notify::RecursiveMode;
 
-use clap::Parser;
+mod flat;
+mod kinds;
+mod stats;
+mod timings;
+
+/// Exit code when an input file could not be read or parsed
+const EXIT_PARSE_FAILURE: i32 = 1;
+/// Exit code for invalid arguments, including input paths that don't exist.
+/// Matches the exit code clap uses for its own usage errors.
+const EXIT_USAGE: i32 = 2;
+/// Exit code when documentation coverage is below `--min-coverage`
+const EXIT_LOW_COVERAGE: i32 = 3;
+/// Exit code when there are diagnostics and `--deny warnings` is given
+const EXIT_DENIED_WARNINGS: i32 = 4;
+/// Exit code when `dossier diff --fail-on breaking` finds breaking changes
+const EXIT_BREAKING_CHANGES: i32 = 5;
+
+/// How long to wait for more changes before running again in watch mode.
+/// Editors often write a file in several steps.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Files besides the sources that `--watch` runs again for, since they change
+/// how the sources are parsed
+const WATCHED_CONFIG_FILES: &[&str] = &["tsconfig.json", "package.json"];
 
 /// Dossier: A multi-language soure code and docstring parser
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
-    /// Input files to parse
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Input files or directories to parse.
+    ///
+    /// Directories are walked recursively. Files matched by a `.dossierignore`
+    /// or `.gitignore` file (both use gitignore syntax) are skipped.
     #[arg(required = true)]
     files: Vec<PathBuf>,
+    /// Don't respect `.dossierignore` and `.gitignore` files
+    #[arg(long)]
+    no_ignore: bool,
+    /// Only parse files of these languages, e.g. `ts,py`, or with these file
+    /// extensions, e.g. `tsx`. Defaults to all of them
+    #[arg(long, value_delimiter = ',', value_name = "LANGUAGES")]
+    language: Vec<String>,
+    /// Follow references through chains of type aliases, and record the final
+    /// target of each reference as `meta.resolves_to`
+    #[arg(long)]
+    resolve_alias_chains: bool,
+    /// Web URL of the repository, used to link entities to their source.
+    ///
+    /// Detected from the `origin` remote of the Git repository in the current
+    /// directory if not given.
+    #[arg(long)]
+    repository_url: Option<String>,
+    /// Number of threads to parse files with. Defaults to one per CPU
+    #[arg(long, short)]
+    jobs: Option<usize>,
+    /// Emit canonical JSON: object keys sorted at every level, fixed float
+    /// formatting, and LF line endings, so output can be compared byte for byte
+    #[arg(long)]
+    canonical_output: bool,
+    /// Treat files with lines longer than this many bytes as minified
+    #[arg(long, default_value_t = dossier::FileLimits::default().max_line_length)]
+    max_line_length: usize,
+    /// Treat files whose longest line makes up more than this fraction of the
+    /// file as minified
+    #[arg(long, default_value_t = dossier::FileLimits::default().max_single_line_fraction)]
+    max_single_line_fraction: f64,
+    /// Skip minified files, instead of only parsing the names of their
+    /// top-level declarations
+    #[arg(long)]
+    skip_minified: bool,
+    /// Skip files larger than this many bytes, with a warning. 0 for no limit
+    #[arg(long, value_name = "BYTES", default_value_t = dossier::DEFAULT_MAX_FILE_SIZE)]
+    max_file_size: u64,
+    /// Skip files with `@generated` or `DO NOT EDIT` in their first lines,
+    /// with a warning
+    #[arg(long)]
+    skip_generated: bool,
+    /// Only output exported symbols and members that aren't private, along
+    /// with the types they refer to
+    #[arg(long)]
+    public_only: bool,
+    /// Add the members classes and interfaces inherit from their parents to
+    /// their own, with the `inherited` member context
+    #[arg(long)]
+    include_inherited: bool,
+    /// Use the sections of documentation comments written for this locale,
+    /// e.g. `ja`, where there are any
+    #[arg(long, value_name = "LOCALE")]
+    doc_locale: Option<String>,
+    /// Record the FQN of the top-level entity each entity is nested in as
+    /// `meta.root_fqn`, i.e. the page it is on in the HTML output
+    #[arg(long)]
+    root_fqns: bool,
+    /// The `tsconfig.json` whose `paths` and `baseUrl` TypeScript imports are
+    /// resolved with. Defaults to the nearest one to each file
+    #[arg(long, value_name = "PATH")]
+    tsconfig: Option<PathBuf>,
+    /// Record the source code of each entity, as written, as
+    /// `meta.source_text`
+    #[arg(long)]
+    include_source_text: bool,
+    /// With `--include-source-text`, cut the source text off after this many
+    /// bytes, ending it with `…` and setting `meta.source_text_truncated`
+    #[arg(long, requires = "include_source_text", value_name = "BYTES")]
+    max_source_bytes: Option<usize>,
+    /// Name files relative to this directory in FQNs and source paths, e.g.
+    /// `index.ts::createClient` for `packages/client/src/index.ts` with
+    /// `--root packages/client/src`. Files outside of it keep their paths
+    #[arg(long, value_name = "DIR")]
+    root: Option<PathBuf>,
+    /// Name files in FQNs by the modules they are imported as: TypeScript
+    /// files by the name in the nearest `package.json`, like
+    /// `@myorg/client/src`, and Python files by their dotted module name,
+    /// like `mypkg.sub.module`. `source.file` keeps the path
+    #[arg(long)]
+    module_names: bool,
+    /// Leave out TypeScript declarations documented as `@internal` or
+    /// `@hidden`, and their members. References to them become anonymous
+    #[arg(long)]
+    strip_internal: bool,
+    /// Give up on files that take longer than this many milliseconds to
+    /// parse, with a warning
+    #[arg(long, value_name = "MS")]
+    parse_timeout: Option<u64>,
+    /// Only output entities of these kinds, e.g. `class,method,function`.
+    /// Entities of other kinds are left out with their members
+    #[arg(long, value_delimiter = ',', value_name = "KINDS")]
+    kinds: Vec<String>,
+    /// With `--kinds`, also keep the parameters and return types of the
+    /// entities that are kept, with their types
+    #[arg(long, requires = "kinds")]
+    include_signatures: bool,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// With JSON output, list all entities in a flat array under `entities`,
+    /// with the key of the entity they are a member of in `parent`, and their
+    /// positions by key under `index`
+    #[arg(long)]
+    flat: bool,
+    /// Output the entities as a bare array, as before there was a
+    /// `format_version`. Will be removed in the next release
+    #[arg(long, conflicts_with = "flat")]
+    legacy_output: bool,
+    /// Exit with code 4 if any diagnostics are reported. The output is still
+    /// produced
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    deny: Option<Deny>,
+    /// With `--format markdown`, print one document for all files to stdout
+    /// instead of writing one per source file
+    #[arg(long)]
+    single_file: bool,
+    /// Directory to write Markdown documents or the HTML site into
+    #[arg(long, visible_alias = "out")]
+    out_dir: Option<PathBuf>,
+    /// Parse and resolve the files and report diagnostics, but don't produce
+    /// any output. Useful as a fast CI check
+    #[arg(long, conflicts_with_all = ["format", "flat", "single_file", "out_dir", "canonical_output"])]
+    check: bool,
+    /// Instead of the entities, print how many declarations there are of each
+    /// kind, and how many of the public ones are documented, per file and in
+    /// total
+    #[arg(long, conflicts_with_all = ["format", "flat", "single_file", "out_dir", "canonical_output", "check"])]
+    stats: bool,
+    /// With `--stats`, also list the FQNs of public declarations that aren't
+    /// documented
+    #[arg(long, requires = "stats")]
+    list_undocumented: bool,
+    /// With `--stats`, exit with code 3 if less than this percentage of public
+    /// declarations are documented
+    #[arg(long, requires = "stats", value_name = "PERCENT")]
+    min_coverage: Option<f64>,
+    /// Print a JSON report of how long each file took to parse, with totals
+    /// per language, the number of entities of each kind, and the peak memory
+    /// use, to stderr
+    #[arg(long)]
+    timings: bool,
+    /// With `--timings`, write the report to this file instead of stderr
+    #[arg(long, requires = "timings", value_name = "PATH")]
+    timings_out: Option<PathBuf>,
+    /// Keep running, and parse the files and produce the output again whenever
+    /// any of them are created, changed, or deleted. Only changed files are
+    /// parsed again
+    #[arg(long)]
+    watch: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare the public API in two outputs of Dossier, and report the
+    /// declarations that were added, removed, or changed
+    Diff(DiffArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    /// The JSON output of Dossier for the old version
+    old: PathBuf,
+    /// The JSON output of Dossier for the new version
+    new: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+    format: DiffFormat,
+    /// Exit with code 5 if there are changes of this kind. The report is still
+    /// printed
+    #[arg(long, value_enum, value_name = "CHANGES")]
+    fail_on: Option<FailOn>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffFormat {
+    /// A line for each change, followed by what changed about it
+    Text,
+    /// An object with the changes under `changes`
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum FailOn {
+    /// Fail on removed declarations and changed signatures
+    Breaking,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Entities as JSON
+    Json,
+    /// An object with the entities under `entities`, and the diagnostics
+    /// under `diagnostics` instead of on stderr
+    JsonWithDiagnostics,
+    /// Human-readable Markdown documents
+    Markdown,
+    /// A static HTML site, with a page per top-level entity
+    Html,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Deny {
+    /// Fail on any diagnostic
+    Warnings,
 }
 
 fn main() {
     let args = Args::parse_from(wild::args());
 
-    let start = Instant::now();
+    if let Some(Command::Diff(diff_args)) = &args.command {
+        if let Err(code) = diff(diff_args) {
+            std::process::exit(code);
+        }
+        return;
+    }
 
-    let mut input_files = vec![];
-    let mut out = vec![];
+    let usage_error = match args.format {
+        Format::Markdown if !args.single_file && args.out_dir.is_none() => {
+            Some("--format markdown needs --out-dir, or --single-file to print to stdout")
+        }
+        Format::Html if args.out_dir.is_none() => Some("--format html needs --out-dir"),
+        Format::Html if args.single_file => Some("--single-file only applies to --format markdown"),
+        Format::Markdown | Format::Html if args.flat => {
+            Some("--flat only applies to --format json and json-with-diagnostics")
+        }
+        _ => None,
+    };
+    if let Some(message) = usage_error {
+        eprintln!("error: {}", message);
+        std::process::exit(EXIT_USAGE);
+    }
+
+    // Only worth keeping what was parsed of each file if we parse them again
+    let mut parsers = if args.watch {
+        dossier::ParserRegistry::with_cache()
+    } else {
+        dossier::ParserRegistry::default()
+    };
+    if let Some(unknown) = args
+        .language
+        .iter()
+        .find(|l| !parsers.extensions().any(|e| e == l.as_str()))
+    {
+        eprintln!(
+            "error: Unknown language `{}`, expected one of: {}",
+            unknown,
+            parsers.extensions().collect::<Vec<_>>().join(", ")
+        );
+        std::process::exit(EXIT_USAGE);
+    }
+    if !args.language.is_empty() {
+        // `ts` keeps the `.tsx` files too, since they're TypeScript
+        let kept = parsers
+            .extensions()
+            .filter(|&extension| {
+                args.language
+                    .iter()
+                    .any(|l| l == extension || parsers.language_of(extension) == Some(l.as_str()))
+            })
+            .map(|extension| extension.to_owned())
+            .collect::<Vec<_>>();
+        parsers.retain(|extension| kept.iter().any(|k| k == extension));
+    }
 
-    for file in args.files {
-        if file.is_dir() {
-            continue;
+    for path in &args.files {
+        if !path.exists() {
+            eprintln!("error: No such file or directory: {}", path.display());
+            std::process::exit(EXIT_USAGE);
         }
+    }
+
+    let mut context = dossier::Context::new();
+    context.resolve_alias_chains = args.resolve_alias_chains;
+    context.repository = std::env::current_dir()
+        .ok()
+        .and_then(|dir| git::detect_repository(&dir, args.repository_url.as_deref()));
+    context.jobs = args.jobs;
+    context.public_only = args.public_only;
+    context.include_inherited = args.include_inherited;
+    context.doc_locale = args.doc_locale.clone();
+    context.root_fqns = args.root_fqns;
+    context.tsconfig = args.tsconfig.clone();
+    context.include_source_text = args.include_source_text;
+    context.max_source_bytes = args.max_source_bytes;
+    context.root = args.root.clone();
+    context.module_names = args.module_names;
+    context.strip_internal = args.strip_internal;
+    context.parse_timeout = args.parse_timeout.map(Duration::from_millis);
+    context.file_limits = dossier::FileLimits {
+        max_line_length: args.max_line_length,
+        max_single_line_fraction: args.max_single_line_fraction,
+        minified: if args.skip_minified {
+            dossier::MinifiedFiles::Skip
+        } else {
+            dossier::MinifiedFiles::NamesOnly
+        },
+    };
+    let options = dossier::Options {
+        respect_ignore: !args.no_ignore,
+        max_file_size: (args.max_file_size > 0).then_some(args.max_file_size),
+        skip_generated: args.skip_generated,
+        context,
+        ..Default::default()
+    };
 
-        input_files.push(file);
+    let result = run(&args, &options, &parsers);
+    if args.watch {
+        watch(&args, &options, &parsers);
+    } else if let Err(code) = result {
+        std::process::exit(code);
     }
+}
 
-    let typescript_files = input_files
-        .iter()
-        .filter(|f| f.extension() == Some(OsStr::new("ts")))
-        .map(|p| p.as_path())
-        .collect::<Vec<_>>();
+/// Parses the input files and produces the output once. Fails with the exit
+/// code to exit with.
+fn run(
+    args: &Args,
+    options: &dossier::Options,
+    parsers: &dossier::ParserRegistry,
+) -> Result<(), i32> {
+    let start = Instant::now();
 
-    let parser = dossier_ts::TypeScriptParser::new();
+    let mut ctx = options.context();
+    let input_files = inputs::collect_input_files(&args.files, options.respect_ignore);
+    let input_files = inputs::skip_unwanted_files(input_files, options, &mut ctx.diagnostics);
+    let timings = args.timings.then(|| Arc::new(timings::Timings::default()));
+    if let Some(timings) = &timings {
+        ctx.instrumentation = Some(timings.clone());
+    }
 
-    match parser.parse(typescript_files, &mut dossier_core::Context::new()) {
-        Ok(mut entities) => {
-            out.append(&mut entities);
+    let mut out = match parsers.parse_files(&input_files, &mut ctx) {
+        Ok(entities) => entities,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return Err(EXIT_PARSE_FAILURE);
         }
-        Err(_e) => {
-            eprint!("Error parsing docs");
-            std::process::exit(1);
+    };
+
+    if !args.kinds.is_empty() {
+        let present = kinds::present(&out);
+        if let Some(unknown) = args.kinds.iter().find(|k| !present.contains(k.as_str())) {
+            eprintln!(
+                "error: Unknown kind `{}`, expected one of: {}",
+                unknown,
+                present.into_iter().collect::<Vec<_>>().join(", ")
+            );
+            return Err(EXIT_USAGE);
         }
+        kinds::retain(&mut out, &args.kinds, args.include_signatures);
     }
 
-    let python_files = input_files
-        .iter()
-        .filter(|f| f.extension() == Some(OsStr::new(dossier_py::LANGUAGE)))
-        .map(|p| p.as_path())
-        .collect::<Vec<_>>();
+    if let Some(timings) = &timings {
+        let report = timings.report(&out, start.elapsed());
+        let report = serde_json::to_string_pretty(&report).unwrap();
+
+        match &args.timings_out {
+            Some(path) => {
+                if let Err(e) = std::fs::write(path, report + "\n") {
+                    eprintln!("error: Could not write to {}: {}", path.display(), e);
+                    return Err(EXIT_PARSE_FAILURE);
+                }
+            }
+            None => eprintln!("{}", report),
+        }
+    }
 
-    let parser = dossier_py::PythonParser::new();
+    if args.format != Format::JsonWithDiagnostics {
+        for diagnostic in &ctx.diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+    }
 
-    match parser.parse(python_files, &mut dossier_core::Context::new()) {
-        Ok(mut entities) => {
-            out.append(&mut entities);
+    let denied = || {
+        if args.deny.is_some() && !ctx.diagnostics.is_empty() {
+            eprintln!(
+                "error: {} diagnostics were reported, and --deny warnings was given",
+                ctx.diagnostics.len()
+            );
+            return Err(EXIT_DENIED_WARNINGS);
         }
-        Err(_e) => {
-            eprint!("Error parsing docs");
-            std::process::exit(1);
+        Ok(())
+    };
+
+    if args.check {
+        eprintln!(
+            "Checked {} files in {}: {} diagnostics",
+            input_files.len(),
+            pretty_duration::pretty_duration(&start.elapsed(), None),
+            ctx.diagnostics.len()
+        );
+        return denied();
+    }
+
+    if args.stats {
+        let stats = stats::Stats::collect(&out, parsers);
+        print!("{}", stats.report(args.list_undocumented));
+
+        if let Some(min_coverage) = args.min_coverage {
+            if stats.total.coverage() < min_coverage {
+                eprintln!(
+                    "error: {:.1}% of public declarations are documented, less than the minimum of {}%",
+                    stats.total.coverage(),
+                    min_coverage
+                );
+                return Err(EXIT_LOW_COVERAGE);
+            }
         }
+        return denied();
     }
 
     let duration = start.elapsed();
 
-    println!("{}", serde_json::to_string_pretty(&out).unwrap());
+    match args.format {
+        Format::Json if args.legacy_output => print_json(&out, args.canonical_output),
+        Format::Json if args.flat => {
+            let flat = flat::flatten(&out);
+            let out = serde_json::json!({
+                "entities": flat.entities,
+                "index": flat.index,
+            });
+            print_json(&Versioned::new(out), args.canonical_output);
+        }
+        Format::Json => {
+            let out = serde_json::json!({ "entities": out });
+            print_json(&Versioned::new(out), args.canonical_output);
+        }
+        Format::JsonWithDiagnostics if args.flat => {
+            let flat = flat::flatten(&out);
+            let out = serde_json::json!({
+                "entities": flat.entities,
+                "index": flat.index,
+                "diagnostics": ctx.diagnostics,
+            });
+            print_json(&Versioned::new(out), args.canonical_output);
+        }
+        Format::JsonWithDiagnostics => {
+            let out = serde_json::json!({
+                "entities": out,
+                "diagnostics": ctx.diagnostics,
+            });
+            if args.legacy_output {
+                print_json(&out, args.canonical_output);
+            } else {
+                print_json(&Versioned::new(out), args.canonical_output);
+            }
+        }
+        Format::Markdown if args.single_file => {
+            print!("{}", dossier::render::markdown::render_single(&out));
+        }
+        Format::Markdown | Format::Html => {
+            let out_dir = args.out_dir.as_deref().expect("checked above");
+            let files = if args.format == Format::Html {
+                dossier::render::html::render_site(&out)
+                    .into_iter()
+                    .map(|page| (page.path, page.content))
+                    .collect::<Vec<_>>()
+            } else {
+                dossier::render::markdown::render_documents(&out)
+                    .into_iter()
+                    .map(|document| (document.output_path(), document.content))
+                    .collect::<Vec<_>>()
+            };
+
+            if let Err(e) = write_files(out_dir, files) {
+                eprintln!("error: Could not write to {}: {}", out_dir.display(), e);
+                return Err(EXIT_PARSE_FAILURE);
+            }
+        }
+    }
     eprintln!(
         "Processed {} files in {}",
         input_files.len(),
         pretty_duration::pretty_duration(&duration, None)
     );
+
+    denied()
+}
+
+/// Compares the public API in two outputs, for `dossier diff`. Fails with the
+/// exit code to exit with.
+fn diff(args: &DiffArgs) -> Result<(), i32> {
+    let old = read_output(&args.old)?;
+    let new = read_output(&args.new)?;
+    let changes = dossier::diff::diff(&old, &new, &dossier::ParserRegistry::default());
+
+    match args.format {
+        DiffFormat::Text => print!("{}", dossier::diff::report(&changes)),
+        DiffFormat::Json => print_json(&serde_json::json!({ "changes": changes }), false),
+    }
+
+    if args.fail_on.is_some() && changes.iter().any(|c| c.breaking) {
+        eprintln!("error: There are breaking changes, and --fail-on breaking was given");
+        return Err(EXIT_BREAKING_CHANGES);
+    }
+    Ok(())
+}
+
+/// The entities in a file of JSON output, in the current format and without
+/// `--flat`
+fn read_output(path: &Path) -> Result<Vec<dossier::Entity>, i32> {
+    let fail = |message: String| {
+        eprintln!("error: Could not read {}: {}", path.display(), message);
+        EXIT_PARSE_FAILURE
+    };
+
+    let text = std::fs::read_to_string(path).map_err(|e| fail(e.to_string()))?;
+    let output: serde_json::Value = serde_json::from_str(&text).map_err(|e| fail(e.to_string()))?;
+    let entities = dossier_core::format::entities_of(&output).map_err(|e| fail(e.to_string()))?;
+    if output.get("index").is_some() {
+        return Err(fail("--flat output can't be compared".to_owned()));
+    }
+
+    entities
+        .iter()
+        .map(|entity| serde_json::from_value(entity.clone()))
+        .collect::<Result<_, _>>()
+        .map_err(|e| fail(e.to_string()))
+}
+
+/// Runs again whenever source files under the input paths are created,
+/// changed, or deleted, until the process is stopped. So do the
+/// `WATCHED_CONFIG_FILES` under the input paths and in the directories above
+/// them, and the `--tsconfig`. Failed runs are reported, and the next change
+/// is waited for as usual.
+fn watch(args: &Args, options: &dossier::Options, parsers: &dossier::ParserRegistry) {
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let mut debouncer = match notify_debouncer_mini::new_debouncer(WATCH_DEBOUNCE, tx) {
+        Ok(debouncer) => debouncer,
+        Err(e) => {
+            eprintln!("error: Could not watch for changes: {}", e);
+            std::process::exit(EXIT_PARSE_FAILURE);
+        }
+    };
+    for path in &args.files {
+        if let Err(e) = debouncer.watcher().watch(path, RecursiveMode::Recursive) {
+            eprintln!("error: Could not watch {}: {}", path.display(), e);
+            std::process::exit(EXIT_PARSE_FAILURE);
+        }
+    }
+    for path in config_files_above(&args.files).chain(options.context.tsconfig.clone()) {
+        if let Err(e) = debouncer
+            .watcher()
+            .watch(&path, RecursiveMode::NonRecursive)
+        {
+            eprintln!("warning: Could not watch {}: {}", path.display(), e);
+        }
+    }
+
+    eprintln!("Watching for changes...");
+
+    for events in rx {
+        match events {
+            Ok(events) => {
+                // Output written into a watched directory is not a change
+                let changed = events
+                    .iter()
+                    .any(|event| parsers.parses(&event.path) || is_config_file(&event.path));
+
+                if changed {
+                    let _ = run(args, options, parsers);
+                }
+            }
+            Err(e) => eprintln!("warning: {}", e),
+        }
+    }
+}
+
+/// The `WATCHED_CONFIG_FILES` in the directories above `paths`, which the
+/// watches of `paths` themselves don't cover
+fn config_files_above(paths: &[PathBuf]) -> impl Iterator<Item = PathBuf> {
+    let dirs = paths
+        .iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .flat_map(|path| {
+            path.ancestors()
+                .skip(1)
+                .map(Path::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .collect::<std::collections::BTreeSet<_>>();
+
+    dirs.into_iter().flat_map(|dir| {
+        WATCHED_CONFIG_FILES
+            .iter()
+            .map(move |name| dir.join(name))
+            .filter(|file| file.is_file())
+    })
+}
+
+/// Whether `path` is one of the `WATCHED_CONFIG_FILES`
+fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| WATCHED_CONFIG_FILES.contains(&name))
+}
+
+/// JSON output with the version of its format first, see
+/// `dossier_core::format`
+#[derive(serde::Serialize)]
+struct Versioned {
+    format_version: u64,
+    generator: String,
+    #[serde(flatten)]
+    output: serde_json::Value,
+}
+
+impl Versioned {
+    fn new(output: serde_json::Value) -> Self {
+        Self {
+            format_version: dossier_core::FORMAT_VERSION,
+            generator: format!("dossier {}", env!("CARGO_PKG_VERSION")),
+            output,
+        }
+    }
+}
+
+/// Prints `value` as pretty-printed JSON, or as canonical JSON with
+/// `--canonical-output`
+fn print_json<T: serde::Serialize + ?Sized>(value: &T, canonical: bool) {
+    if canonical {
+        print!("{}", dossier_core::canonical::to_string(value).unwrap());
+    } else {
+        println!("{}", serde_json::to_string_pretty(value).unwrap());
+    }
+}
+
+/// Writes the files to their paths relative to `out_dir`
+fn write_files(out_dir: &Path, files: Vec<(PathBuf, String)>) -> std::io::Result<()> {
+    for (path, content) in files {
+        let path = out_dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+
+    Ok(())
 }
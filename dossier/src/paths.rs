@@ -0,0 +1,393 @@
+//! On-disk locations used by Dossier.
+//!
+//! The cache directory is shared between all Dossier processes running as the
+//! same user, so everything that touches it goes through `Cache`, which makes sure
+//! concurrent runs don't trip over each other. The command line tool doesn't
+//! store anything there yet, so it doesn't open the cache either.
+use fs2::FileExt;
+use std::ffi::OsString;
+use std::fs::{DirBuilder, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Environment variable that overrides the cache directory
+pub const CACHE_DIR_ENV: &str = "DOSSIER_CACHE_DIR";
+
+const RUNS_DIRNAME: &str = "runs";
+const TMP_DIRNAME: &str = "tmp";
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("could not determine a cache directory, set {CACHE_DIR_ENV} to choose one")]
+    NoCacheDir,
+    #[error("cache directory {} is in use by another dossier process", .0.display())]
+    Locked(PathBuf),
+    #[error("could not use cache directory {}: {}", .0.display(), .1)]
+    IO(PathBuf, std::io::Error),
+}
+
+/// Resolves the cache directory from the environment.
+///
+/// In order of precedence: `$DOSSIER_CACHE_DIR`, `$XDG_CACHE_HOME/dossier`,
+/// and finally the platform's default cache location.
+pub fn cache_dir() -> Option<PathBuf> {
+    resolve_cache_dir(|key| std::env::var_os(key))
+}
+
+fn resolve_cache_dir<F>(env: F) -> Option<PathBuf>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let non_empty = |key| env(key).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+    if let Some(dir) = non_empty(CACHE_DIR_ENV) {
+        return Some(dir);
+    }
+
+    if let Some(dir) = non_empty("XDG_CACHE_HOME") {
+        return Some(dir.join("dossier"));
+    }
+
+    directories::ProjectDirs::from("com", "Doctave", "dossier").map(|d| d.cache_dir().to_owned())
+}
+
+/// A handle to the cache directory for the duration of a single run.
+///
+/// Each run holds an advisory lock on its own lock file under `runs/`, and
+/// keeps its temporary files in a matching directory under `tmp/`. When a
+/// run crashes, its lock is released by the OS, and the next run to open the
+/// cache removes the temporary files it left behind.
+#[derive(Debug)]
+pub struct Cache {
+    root: PathBuf,
+    run_id: String,
+    _run_lock: File,
+}
+
+impl Cache {
+    /// Opens the cache at the location resolved by `cache_dir`.
+    pub fn open_default() -> Result<Self, CacheError> {
+        Self::open(&cache_dir().ok_or(CacheError::NoCacheDir)?)
+    }
+
+    /// Opens the cache at `root`, creating it if needed.
+    pub fn open(root: &Path) -> Result<Self, CacheError> {
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |e| CacheError::IO(path, e)
+        };
+
+        let runs = root.join(RUNS_DIRNAME);
+        create_private_dir(&runs).map_err(io_err(&runs))?;
+        let tmp = root.join(TMP_DIRNAME);
+        create_private_dir(&tmp).map_err(io_err(&tmp))?;
+
+        let (run_id, run_lock) = lock_new_run(&runs)
+            .map_err(io_err(&runs))?
+            .ok_or_else(|| CacheError::Locked(root.to_owned()))?;
+
+        let cache = Self {
+            root: root.to_owned(),
+            run_id,
+            _run_lock: run_lock,
+        };
+
+        cache.remove_orphans();
+
+        Ok(cache)
+    }
+
+    /// Removes the files of runs that are no longer holding their lock.
+    fn remove_orphans(&self) {
+        let Ok(entries) = std::fs::read_dir(self.root.join(RUNS_DIRNAME)) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(run_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            if run_id == self.run_id {
+                continue;
+            }
+
+            let Ok(file) = File::open(&path) else {
+                continue;
+            };
+
+            // If we can take the lock, the run that created it is gone
+            if file.try_lock_exclusive().is_ok() {
+                let _ = std::fs::remove_dir_all(self.root.join(TMP_DIRNAME).join(run_id));
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
+    /// A directory for temporary files that only this run uses. It is removed
+    /// when the run finishes, or by a later run if this one crashes.
+    pub fn temp_dir(&self) -> Result<PathBuf, CacheError> {
+        let dir = self.root.join(TMP_DIRNAME).join(&self.run_id);
+        create_private_dir(&dir).map_err(|e| CacheError::IO(dir.clone(), e))?;
+
+        Ok(dir)
+    }
+
+    /// Takes an exclusive lock on the shared contents of the cache, for writing.
+    ///
+    /// Fails straight away instead of waiting if another process holds the lock.
+    pub fn lock_exclusive(&self) -> Result<CacheLock, CacheError> {
+        let path = self.root.join("cache.lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| CacheError::IO(path, e))?;
+
+        file.try_lock_exclusive()
+            .map_err(|_| CacheError::Locked(self.root.clone()))?;
+
+        Ok(CacheLock { _file: file })
+    }
+}
+
+impl Drop for Cache {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(self.root.join(TMP_DIRNAME).join(&self.run_id));
+        let _ = std::fs::remove_file(
+            self.root
+                .join(RUNS_DIRNAME)
+                .join(format!("{}.lock", self.run_id)),
+        );
+    }
+}
+
+/// How many run IDs `lock_new_run` tries before giving up
+const MAX_RUN_ATTEMPTS: usize = 16;
+
+/// Creates and locks the lock file of a new run under `runs`.
+///
+/// Process IDs repeat across containers sharing a cache directory, so the run
+/// ID also includes the time, and the lock file is only ever created, never
+/// reused. Another run cleaning up orphans may take the lock in between
+/// creating and locking the file and then delete it, so after locking we check
+/// that the path still points to our file, and start over with a new ID if not.
+///
+/// Returns `None` if no attempt succeeded. Failed attempts don't leave their
+/// lock files behind.
+fn lock_new_run(runs: &Path) -> std::io::Result<Option<(String, File)>> {
+    lock_new_run_with(runs, |file, path| {
+        file.try_lock_exclusive().is_ok() && is_same_file(file, path)
+    })
+}
+
+/// `lock_new_run`, with `lock` to lock a newly created lock file and check
+/// that it is still there
+fn lock_new_run_with(
+    runs: &Path,
+    mut lock: impl FnMut(&File, &Path) -> bool,
+) -> std::io::Result<Option<(String, File)>> {
+    for _ in 0..MAX_RUN_ATTEMPTS {
+        let run_id = new_run_id();
+        let path = runs.join(format!("{}.lock", run_id));
+
+        let file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        };
+        let new_file = NewRunFile {
+            file: Some(file),
+            path: &path,
+        };
+
+        if lock(new_file.file(), &path) {
+            return Ok(Some((run_id, new_file.keep())));
+        }
+    }
+
+    Ok(None)
+}
+
+/// A lock file created by `lock_new_run_with`. Removed when dropped, unless
+/// it was kept, or the path no longer points to it.
+struct NewRunFile<'a> {
+    file: Option<File>,
+    path: &'a Path,
+}
+
+impl NewRunFile<'_> {
+    fn file(&self) -> &File {
+        self.file
+            .as_ref()
+            .expect("the file is only taken when kept")
+    }
+
+    fn keep(mut self) -> File {
+        self.file.take().expect("the file is only taken when kept")
+    }
+}
+
+impl Drop for NewRunFile<'_> {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            // Another run cleaning up orphans may have removed it already,
+            // and then the path is no longer ours to remove
+            let ours = is_same_file(&file, self.path);
+            drop(file);
+            if ours {
+                let _ = std::fs::remove_file(self.path);
+            }
+        }
+    }
+}
+
+fn new_run_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    format!("{}-{}", std::process::id(), nanos)
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+// Elsewhere, fall back to checking that the path still exists
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+/// Exclusive access to the shared contents of the cache. Released when dropped.
+#[derive(Debug)]
+pub struct CacheLock {
+    _file: File,
+}
+
+/// Creates a directory (and its parents) readable only by the current user.
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    let mut builder = DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    builder.create(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), OsString::from(v)))
+            .collect::<HashMap<_, _>>();
+
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn dossier_cache_dir_takes_precedence() {
+        let dir = resolve_cache_dir(env(&[
+            (CACHE_DIR_ENV, "/tmp/override"),
+            ("XDG_CACHE_HOME", "/home/me/.cache"),
+        ]));
+
+        assert_eq!(dir, Some(PathBuf::from("/tmp/override")));
+    }
+
+    #[test]
+    fn falls_back_to_xdg_cache_home() {
+        let dir = resolve_cache_dir(env(&[
+            (CACHE_DIR_ENV, ""),
+            ("XDG_CACHE_HOME", "/home/me/.cache"),
+        ]));
+
+        assert_eq!(dir, Some(PathBuf::from("/home/me/.cache/dossier")));
+    }
+
+    #[test]
+    fn contended_lock_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::open(dir.path()).unwrap();
+
+        let lock = cache.lock_exclusive().unwrap();
+        let err = cache.lock_exclusive().unwrap_err();
+
+        assert!(matches!(err, CacheError::Locked(_)));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "cache directory {} is in use by another dossier process",
+                dir.path().display()
+            )
+        );
+
+        drop(lock);
+        assert!(cache.lock_exclusive().is_ok());
+    }
+
+    #[test]
+    fn errors_name_the_directory_that_could_not_be_created() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("tmp"), "").unwrap();
+
+        let err = Cache::open(dir.path()).unwrap_err();
+
+        assert!(matches!(err, CacheError::IO(path, _) if path == dir.path().join("tmp")));
+    }
+
+    #[test]
+    fn removes_files_of_crashed_runs() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // A run that crashed leaves its unlocked lock file and temp files behind
+        std::fs::create_dir_all(dir.path().join("runs")).unwrap();
+        std::fs::create_dir_all(dir.path().join("tmp/999999")).unwrap();
+        std::fs::write(dir.path().join("runs/999999.lock"), "").unwrap();
+        std::fs::write(dir.path().join("tmp/999999/partial.json"), "{").unwrap();
+
+        let cache = Cache::open(dir.path()).unwrap();
+        let temp_dir = cache.temp_dir().unwrap();
+
+        assert!(!dir.path().join("tmp/999999").exists());
+        assert!(!dir.path().join("runs/999999.lock").exists());
+        assert!(temp_dir.exists());
+
+        drop(cache);
+        assert!(!temp_dir.exists());
+    }
+
+    #[test]
+    fn failed_attempts_leave_no_lock_files_behind() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Another run takes the lock of each new file before we do
+        let run = lock_new_run_with(dir.path(), |file, path| {
+            let other = File::open(path).unwrap();
+            other.lock_exclusive().unwrap();
+            file.try_lock_exclusive().is_ok()
+        })
+        .unwrap();
+
+        assert!(run.is_none());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Which parser parses which files.
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use dossier_core::{Context, DocsParser, Entity, Result};
+
+/// Parsers by the extension of the files they parse.
+///
+/// The default registry has a parser for each of `Language::ALL`. Programs
+/// embedding Dossier can register parsers of their own:
+///
+/// ```no_run
+/// use dossier::{Context, DocsParser, Entity, ParserRegistry, Result};
+/// use std::path::{Path, PathBuf};
+///
+/// struct RubyParser;
+///
+/// impl DocsParser for RubyParser {
+///     fn parse(&self, paths: &[&Path], ctx: &mut Context) -> Result<Vec<Entity>> {
+///         todo!()
+///     }
+///
+///     fn language(&self) -> &'static str {
+///         "rb"
+///     }
+///
+///     fn file_extensions(&self) -> &'static [&'static str] {
+///         &["rb"]
+///     }
+/// }
+///
+/// let mut registry = ParserRegistry::default();
+/// registry.add(RubyParser);
+///
+/// let entities = registry.parse_files(&[PathBuf::from("app.rb")], &mut Context::new())?;
+/// # Ok::<(), dossier::DossierError>(())
+/// ```
+pub struct ParserRegistry {
+    /// In the order their entities are output
+    parsers: Vec<Box<dyn DocsParser>>,
+    /// The index of the parser of each extension, in the order they were
+    /// registered
+    extensions: Vec<(String, usize)>,
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.add(dossier_ts::TypeScriptParser::new());
+        registry.add(dossier_py::PythonParser::new());
+        registry
+    }
+}
+
+impl std::fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParserRegistry")
+            .field("extensions", &self.extensions().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ParserRegistry {
+    /// A registry without any parsers
+    pub fn empty() -> Self {
+        Self {
+            parsers: vec![],
+            extensions: vec![],
+        }
+    }
+
+    /// Like the default registry, but the parsers keep the files they parse,
+    /// so that parsing them again only re-parses the ones that changed. See
+    /// `IncrementalParser`.
+    pub fn with_cache() -> Self {
+        let mut registry = Self::empty();
+        registry.add(dossier_ts::TypeScriptParser::with_cache());
+        registry.add(dossier_py::PythonParser::with_cache());
+        registry
+    }
+
+    /// Parses the files with any of the parser's `file_extensions` with it.
+    /// Extensions registered before are taken over by the new parser, whose
+    /// entities come last.
+    pub fn add<P: DocsParser + 'static>(&mut self, parser: P) {
+        let extensions = parser.file_extensions();
+        self.insert(extensions, Box::new(parser));
+    }
+
+    /// Parses files with the extension `extension`, given without the dot,
+    /// with `parser`, whatever extensions it says it parses. Like `add`
+    /// otherwise.
+    pub fn register<P: DocsParser + 'static>(&mut self, extension: &str, parser: P) {
+        self.insert(&[extension], Box::new(parser));
+    }
+
+    fn insert(&mut self, extensions: &[&str], parser: Box<dyn DocsParser>) {
+        self.parsers.push(parser);
+        let index = self.parsers.len() - 1;
+
+        self.retain(|e| !extensions.contains(&e));
+        for extension in extensions {
+            self.extensions.push((extension.to_string(), index));
+        }
+    }
+
+    /// Only keeps the parsers of the extensions `keep` returns true for
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+        self.extensions.retain(|(extension, _)| keep(extension));
+    }
+
+    /// The extensions there are parsers for, in the order they were
+    /// registered
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.extensions
+            .iter()
+            .map(|(extension, _)| extension.as_str())
+    }
+
+    /// The `language` of the parser of `extension`, if there is one
+    pub fn language_of(&self, extension: &str) -> Option<&'static str> {
+        self.extensions
+            .iter()
+            .find(|(e, _)| e == extension)
+            .map(|(_, index)| self.parsers[*index].language())
+    }
+
+    /// Whether an entity is part of the public API, given its parent, as the
+    /// parser of its `language` sees it. See `DocsParser::is_public`.
+    /// Entities of languages without a parser are public.
+    pub fn is_public(&self, entity: &Entity, parent: Option<&Entity>) -> bool {
+        self.parsers
+            .iter()
+            .find(|p| p.language() == entity.language)
+            .map_or(true, |parser| parser.is_public(entity, parent))
+    }
+
+    /// Whether there is a parser for `path`
+    pub fn parses(&self, path: &Path) -> bool {
+        self.parser_of(path, |_| true).is_some()
+    }
+
+    /// The index of the parser of `path`, if its extension is one `include`
+    /// returns true for
+    fn parser_of<F: Fn(&str) -> bool>(&self, path: &Path, include: F) -> Option<usize> {
+        let extension = path.extension()?;
+
+        self.extensions
+            .iter()
+            .find(|(e, _)| OsStr::new(e) == extension && include(e))
+            .map(|(_, index)| *index)
+    }
+
+    /// Parses each file with the parser of its extension, and returns the
+    /// entities of all of them, grouped by parser in order. Files without a
+    /// parser are skipped.
+    pub fn parse_files(&self, files: &[PathBuf], ctx: &mut Context) -> Result<Vec<Entity>> {
+        self.parse_files_of(files, |_| true, ctx)
+    }
+
+    /// `parse_files` with only the parsers of the extensions `include`
+    /// returns true for
+    pub(crate) fn parse_files_of<F: Fn(&str) -> bool>(
+        &self,
+        files: &[PathBuf],
+        include: F,
+        ctx: &mut Context,
+    ) -> Result<Vec<Entity>> {
+        let mut out = vec![];
+
+        for (index, parser) in self.parsers.iter().enumerate() {
+            let has_extensions = self
+                .extensions
+                .iter()
+                .any(|(e, i)| *i == index && include(e));
+            if !has_extensions {
+                continue;
+            }
+
+            let files = files
+                .iter()
+                .filter(|f| self.parser_of(f, &include) == Some(index))
+                .map(|f| f.as_path())
+                .collect::<Vec<_>>();
+
+            out.append(&mut parser.parse(&files, ctx)?);
+        }
+
+        Ok(out)
+    }
+}
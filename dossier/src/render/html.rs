@@ -0,0 +1,573 @@
+//! Renders entities as a static HTML site.
+//!
+//! The site has an index page listing every source file, and a page for each
+//! top-level entity. Members get anchors on the page of their top-level
+//! entity. The templates are compiled into the binary, so the site has no
+//! other assets.
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use askama::Template;
+use dossier_core::{slug::Slugs, Entity, Identity, MemberContext};
+
+use super::{
+    kind_label, methods, namespace_members, properties, return_type, signature, type_text,
+};
+
+/// Markdown only has six levels of headings, and neither does HTML
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// A page of the site.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// Where to write the page, relative to the output directory
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Renders the index page and a page for each top-level entity.
+pub fn render_site(entities: &[Entity]) -> Vec<Page> {
+    let mut by_file: BTreeMap<&Path, Vec<&Entity>> = BTreeMap::new();
+    for entity in entities {
+        by_file.entry(&entity.source.file).or_default().push(entity);
+    }
+
+    let links = Links::new(entities);
+    let sidebar = sidebar(&by_file, &links);
+
+    let index = IndexTemplate {
+        sidebar: sidebar.clone(),
+        files: by_file
+            .iter()
+            .map(|(path, entities)| FileEntry {
+                path: path.display().to_string(),
+                entries: entities
+                    .iter()
+                    .filter_map(|entity| {
+                        Some(IndexEntry {
+                            kind: kind_label(entity).to_owned(),
+                            title: entity.title.clone()?,
+                            href: links.page_name(entity)?,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    let mut pages = vec![Page {
+        path: PathBuf::from("index.html"),
+        content: index.render().expect("rendering the index failed"),
+    }];
+
+    for entity in by_file.values().flatten() {
+        let Some(page) = links.page_name(entity) else {
+            continue;
+        };
+
+        let mut sections = vec![];
+        collect_sections(entity, 1, &links, &mut sections);
+
+        let template = EntityTemplate {
+            sidebar: sidebar
+                .iter()
+                .cloned()
+                .map(|mut item| {
+                    item.current = item.href.as_deref() == Some(page.as_str());
+                    item
+                })
+                .collect(),
+            title: entity.title.clone().unwrap_or_default(),
+            file: entity.source.file.display().to_string(),
+            sections,
+        };
+
+        pages.push(Page {
+            path: PathBuf::from(&page),
+            content: template.render().expect("rendering an entity page failed"),
+        });
+    }
+
+    pages
+}
+
+/// Where each rendered entity ended up, by FQN
+struct Links {
+    /// Page names and anchors, unique across the site
+    slugs: Slugs,
+    hrefs: HashMap<String, String>,
+}
+
+impl Links {
+    fn new(entities: &[Entity]) -> Self {
+        let mut links = Self {
+            slugs: Slugs::new(entities),
+            hrefs: HashMap::new(),
+        };
+
+        for entity in entities {
+            if let Some(page) = links.page_name(entity) {
+                links.collect(entity, &page);
+            }
+        }
+
+        links
+    }
+
+    /// The file name of the page of a top-level entity
+    fn page_name(&self, entity: &Entity) -> Option<String> {
+        match &entity.identity {
+            Identity::FQN(fqn) if entity.title.is_some() => {
+                Some(format!("{}.html", self.slugs.get(fqn)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// The anchor of an entity on the page it is rendered on
+    fn anchor(&self, entity: &Entity) -> String {
+        match &entity.identity {
+            Identity::FQN(fqn) => self.slugs.get(fqn).unwrap_or_default().to_owned(),
+            _ => String::new(),
+        }
+    }
+
+    /// Collects the links to a top-level entity and to everything rendered on
+    /// its page.
+    fn collect(&mut self, entity: &Entity, page: &str) {
+        if let Identity::FQN(fqn) = &entity.identity {
+            self.hrefs.insert(fqn.clone(), page.to_owned());
+        }
+        self.collect_members(entity, page);
+    }
+
+    fn collect_members(&mut self, entity: &Entity, page: &str) {
+        for member in sections_of(entity).chain(properties(entity)) {
+            if let Identity::FQN(fqn) = &member.identity {
+                let href = format!("{}#{}", page, self.anchor(member));
+                self.hrefs.insert(fqn.clone(), href);
+            }
+            self.collect_members(member, page);
+        }
+    }
+
+    fn get(&self, fqn: &str) -> Option<&str> {
+        self.hrefs.get(fqn).map(|s| s.as_str())
+    }
+}
+
+/// Members that get a section of their own on the page
+fn sections_of(entity: &Entity) -> impl Iterator<Item = &Entity> {
+    methods(entity).chain(namespace_members(entity))
+}
+
+#[derive(Template)]
+#[template(path = "index.html")]
+struct IndexTemplate {
+    sidebar: Vec<SidebarItem>,
+    files: Vec<FileEntry>,
+}
+
+struct FileEntry {
+    path: String,
+    entries: Vec<IndexEntry>,
+}
+
+struct IndexEntry {
+    kind: String,
+    title: String,
+    href: String,
+}
+
+#[derive(Template)]
+#[template(path = "entity.html")]
+struct EntityTemplate {
+    sidebar: Vec<SidebarItem>,
+    title: String,
+    file: String,
+    sections: Vec<Section>,
+}
+
+/// An entity or member with a heading of its own. Sections are flattened into
+/// a list, since templates can't recurse.
+struct Section {
+    level: usize,
+    anchor: String,
+    kind: String,
+    title: String,
+    language: String,
+    signature: Option<String>,
+    /// The deprecation message, if the entity is deprecated
+    deprecated: Option<String>,
+    description: Vec<String>,
+    alias_of: Option<String>,
+    extends: Vec<String>,
+    parameters: Vec<Row>,
+    returns: Option<String>,
+    returns_description: String,
+    properties: Vec<Row>,
+}
+
+struct Row {
+    anchor: String,
+    name: String,
+    /// Already escaped
+    type_html: String,
+    description: String,
+}
+
+#[derive(Clone)]
+struct SidebarItem {
+    depth: usize,
+    label: String,
+    href: Option<String>,
+    current: bool,
+}
+
+/// Builds the sidebar tree from the FQNs of the entities: the directories and
+/// files they are in, and then the entities themselves, with namespaces
+/// nesting their members.
+fn sidebar(by_file: &BTreeMap<&Path, Vec<&Entity>>, links: &Links) -> Vec<SidebarItem> {
+    let mut items = vec![];
+    let mut open_dirs: Vec<String> = vec![];
+
+    for (path, entities) in by_file {
+        let mut parts = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let file_name = parts.pop().unwrap_or_default();
+
+        let shared = open_dirs
+            .iter()
+            .zip(&parts)
+            .take_while(|(a, b)| a == b)
+            .count();
+        open_dirs.truncate(shared);
+
+        for dir in &parts[shared..] {
+            items.push(SidebarItem {
+                depth: open_dirs.len(),
+                label: format!("{}/", dir),
+                href: None,
+                current: false,
+            });
+            open_dirs.push(dir.clone());
+        }
+
+        items.push(SidebarItem {
+            depth: parts.len(),
+            label: file_name,
+            href: None,
+            current: false,
+        });
+
+        for entity in entities {
+            sidebar_entity(entity, parts.len() + 1, links, &mut items);
+        }
+    }
+
+    items
+}
+
+fn sidebar_entity(entity: &Entity, depth: usize, links: &Links, items: &mut Vec<SidebarItem>) {
+    let Some(title) = &entity.title else {
+        return;
+    };
+    let href = match &entity.identity {
+        Identity::FQN(fqn) => links.get(fqn).map(|s| s.to_owned()),
+        _ => None,
+    };
+
+    items.push(SidebarItem {
+        depth,
+        label: title.clone(),
+        href,
+        current: false,
+    });
+
+    for member in namespace_members(entity) {
+        sidebar_entity(member, depth + 1, links, items);
+    }
+}
+
+fn collect_sections(entity: &Entity, level: usize, links: &Links, out: &mut Vec<Section>) {
+    let anchor = links.anchor(entity);
+
+    let alias_of = matches!(entity.kind.as_str(), "alias" | "reexport")
+        .then(|| match &entity.identity {
+            Identity::Reference(fqn) => Some(link(fqn, fqn, links)),
+            _ => entity.meta["target"]
+                .as_str()
+                .map(|t| format!("<code>{}</code>", escape(t))),
+        })
+        .flatten();
+
+    out.push(Section {
+        level: level.min(MAX_HEADING_LEVEL),
+        anchor,
+        kind: kind_label(entity).to_owned(),
+        title: entity.title.clone().unwrap_or_default(),
+        language: entity.language.clone(),
+        signature: signature(entity),
+        deprecated: (entity.meta.get("deprecated") == Some(&true.into())).then(|| {
+            entity.meta["deprecation_message"]
+                .as_str()
+                .unwrap_or_default()
+                .to_owned()
+        }),
+        description: paragraphs(&entity.description),
+        alias_of,
+        extends: entity
+            .members
+            .iter()
+            .filter(|m| {
+                matches!(
+                    m.member_context,
+                    Some(MemberContext::Extends | MemberContext::Base)
+                )
+            })
+            .map(|m| type_html(m, links))
+            .collect(),
+        parameters: entity
+            .members
+            .iter()
+            .filter(|m| m.kind == "parameter")
+            .map(|p| row(p, links))
+            .collect(),
+        returns: return_type(entity).map(|t| type_html(t, links)),
+        returns_description: entity.meta["returns"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned(),
+        properties: properties(entity).map(|p| row(p, links)).collect(),
+    });
+
+    for member in sections_of(entity) {
+        collect_sections(member, level + 1, links, out);
+    }
+}
+
+fn row(entity: &Entity, links: &Links) -> Row {
+    Row {
+        anchor: links.anchor(entity),
+        name: entity.title.clone().unwrap_or_default(),
+        type_html: entity
+            .members
+            .first()
+            .map(|t| type_html(t, links))
+            .unwrap_or_default(),
+        description: entity
+            .description
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn paragraphs(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_owned())
+        .collect()
+}
+
+/// Renders a type as code, linking it to the page of its definition if it is
+/// part of the site.
+fn type_html(entity: &Entity, links: &Links) -> String {
+    if let Identity::Reference(fqn) = &entity.identity {
+        return link(fqn, &type_text(entity), links);
+    }
+
+    // Link the members of compound types, like `Circle | Polygon`
+    let separator = match entity.kind.as_str() {
+        "union" => " | ",
+        "intersection" => " &amp; ",
+        _ => return format!("<code>{}</code>", escape(&type_text(entity))),
+    };
+
+    entity
+        .members
+        .iter()
+        .map(|m| type_html(m, links))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn link(fqn: &str, text: &str, links: &Links) -> String {
+    match links.get(fqn) {
+        Some(href) => format!(
+            "<a href=\"{}\"><code>{}</code></a>",
+            escape(href),
+            escape(text)
+        ),
+        None => format!("<code>{}</code>", escape(text)),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Context;
+
+    fn entities(files: &[(&str, &str)]) -> Vec<Entity> {
+        let mut source = dossier_core::InMemoryFileSystem::new();
+        for (path, contents) in files {
+            source.add_file(*path, *contents);
+        }
+        let mut ctx = Context::new().with_file_source(source);
+
+        let paths = files
+            .iter()
+            .map(|(p, _)| PathBuf::from(p))
+            .collect::<Vec<_>>();
+        crate::parse_files(&paths, crate::Language::ALL, &mut ctx).unwrap()
+    }
+
+    fn site(files: &[(&str, &str)]) -> Vec<Page> {
+        render_site(&entities(files))
+    }
+
+    fn page<'a>(pages: &'a [Page], path: &str) -> &'a str {
+        &pages
+            .iter()
+            .find(|p| p.path == Path::new(path))
+            .unwrap_or_else(|| panic!("no page {}", path))
+            .content
+    }
+
+    #[test]
+    fn renders_an_index_and_a_page_per_top_level_entity() {
+        let pages = site(&[
+            (
+                "src/shapes.ts",
+                "type Id = string;\n\n/** A circle */\ninterface Circle {\n  id: Id;\n  radius: number;\n}\n\nclass Canvas {\n  draw(circle: Circle): void {}\n}\n",
+            ),
+            ("lib/greeter.py", "class Greeter:\n    def greet(self, name: str) -> str:\n        pass\n"),
+        ]);
+
+        let paths = pages
+            .iter()
+            .map(|p| p.path.to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "index.html",
+                "lib-greeter-py-greeter.html",
+                "src-shapes-ts-id.html",
+                "src-shapes-ts-circle.html",
+                "src-shapes-ts-canvas.html",
+            ]
+        );
+
+        let index = page(&pages, "index.html");
+        assert!(index.contains("<h2>src/shapes.ts</h2>"));
+        assert!(index.contains("<a href=\"src-shapes-ts-circle.html\"><code>Circle</code></a>"));
+
+        let circle = page(&pages, "src-shapes-ts-circle.html");
+        assert!(circle.contains("<p>A circle</p>"));
+        assert!(circle.contains(
+            "<tr id=\"src-shapes-ts-circle-radius\"><td><code>radius</code></td><td><code>number</code></td>"
+        ));
+        assert!(circle.contains("<a href=\"src-shapes-ts-id.html\"><code>Id</code></a>"));
+        // The sidebar marks the current page
+        assert!(circle.contains(
+            "<li class=\"current\" style=\"padding-left: 2rem\">\n        <a href=\"src-shapes-ts-circle.html\">Circle</a>"
+        ));
+    }
+
+    #[test]
+    fn builds_the_sidebar_from_the_paths_of_the_entities() {
+        let entities = entities(&[
+            (
+                "src/a.ts",
+                "namespace Outer {\n  export type Inner = string;\n}\n",
+            ),
+            ("src/nested/b.ts", "type B = number;\n"),
+            ("c.ts", "type C = number;\n"),
+        ]);
+
+        let mut by_file: BTreeMap<&Path, Vec<&Entity>> = BTreeMap::new();
+        for entity in &entities {
+            by_file.entry(&entity.source.file).or_default().push(entity);
+        }
+        let links = Links::new(&entities);
+
+        let items = sidebar(&by_file, &links)
+            .into_iter()
+            .map(|item| (item.depth, item.label, item.href))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            items,
+            vec![
+                (0, "c.ts".to_owned(), None),
+                (1, "C".to_owned(), Some("c-ts-c.html".to_owned())),
+                (0, "src/".to_owned(), None),
+                (1, "a.ts".to_owned(), None),
+                (
+                    2,
+                    "Outer".to_owned(),
+                    Some("src-a-ts-outer.html".to_owned())
+                ),
+                (
+                    3,
+                    "Inner".to_owned(),
+                    Some("src-a-ts-outer.html#src-a-ts-outer-inner".to_owned())
+                ),
+                (1, "nested/".to_owned(), None),
+                (2, "b.ts".to_owned(), None),
+                (3, "B".to_owned(), Some("src-nested-b-ts-b.html".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn links_members_to_anchors_and_leaves_unknown_references_as_text() {
+        let pages = site(&[(
+            "index.ts",
+            "class Canvas {\n  draw(): void {}\n}\n\ntype Drawer = Canvas;\n\nnamespace Shapes {\n  export import Timestamp = google.protobuf.Timestamp;\n}\n",
+        )]);
+
+        let canvas = page(&pages, "index-ts-canvas.html");
+        assert!(canvas.contains("<h2 id=\"index-ts-canvas-draw\"><span class=\"kind\">method</span> <code>draw</code></h2>"));
+
+        let drawer = page(&pages, "index-ts-drawer.html");
+        assert!(
+            drawer.contains("<pre><code class=\"language-ts\">type Drawer = Canvas</code></pre>")
+        );
+
+        let shapes = page(&pages, "index-ts-shapes.html");
+        assert!(
+            shapes.contains("<strong>Alias of:</strong> <code>google.protobuf.Timestamp</code>")
+        );
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape("Array<\"a\" & 'b'>"),
+            "Array&lt;&quot;a&quot; &amp; &#x27;b&#x27;&gt;"
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! Renders entities as Markdown, with one document per source file.
+//!
+//! Each top-level entity gets a heading with an anchor named after its
+//! slugified FQN, so that references to it elsewhere in the same document can
+//! link to it.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+
+use dossier_core::{slug::Slugs, Entity, Identity, MemberContext};
+
+use super::{
+    kind_label, methods, namespace_members, properties, return_type, signature, type_text,
+};
+
+/// A Markdown document for a single source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    /// The source file the document describes
+    pub source: PathBuf,
+    pub content: String,
+}
+
+impl Document {
+    /// Where to write the document, relative to an output directory.
+    ///
+    /// Mirrors the path of the source file, with `.md` appended to keep files
+    /// with the same name in different languages apart. Parent directory
+    /// components are dropped, so the result never leaves the output directory.
+    pub fn output_path(&self) -> PathBuf {
+        let mut path = self
+            .source
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part),
+                _ => None,
+            })
+            .collect::<PathBuf>();
+
+        let mut file_name = path.file_name().unwrap_or_default().to_owned();
+        file_name.push(".md");
+        path.set_file_name(file_name);
+
+        path
+    }
+}
+
+/// Renders one document per source file, ordered by path.
+pub fn render_documents(entities: &[Entity]) -> Vec<Document> {
+    let mut by_file: BTreeMap<&Path, Vec<&Entity>> = BTreeMap::new();
+    for entity in entities {
+        by_file.entry(&entity.source.file).or_default().push(entity);
+    }
+
+    by_file
+        .into_iter()
+        .map(|(source, entities)| {
+            let anchors = Anchors::new(&entities);
+            Document {
+                source: source.to_owned(),
+                content: render_file(source, &entities, &anchors),
+            }
+        })
+        .collect()
+}
+
+/// Renders all source files into a single document. References link to
+/// entities in any of the files.
+pub fn render_single(entities: &[Entity]) -> String {
+    let mut by_file: BTreeMap<&Path, Vec<&Entity>> = BTreeMap::new();
+    for entity in entities {
+        by_file.entry(&entity.source.file).or_default().push(entity);
+    }
+
+    let anchors = Anchors::new(&by_file.values().flatten().copied().collect::<Vec<_>>());
+
+    by_file
+        .into_iter()
+        .map(|(source, entities)| render_file(source, &entities, &anchors))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The anchors of the entities that have one in the document, by FQN.
+struct Anchors(Slugs);
+
+impl Anchors {
+    fn new(entities: &[&Entity]) -> Self {
+        let mut slugs = Slugs::default();
+
+        for entity in entities {
+            Self::collect(entity, &mut slugs);
+        }
+
+        Self(slugs)
+    }
+
+    /// Collects the FQNs of an entity and of everything rendered under it
+    fn collect(entity: &Entity, slugs: &mut Slugs) {
+        if let Identity::FQN(fqn) = &entity.identity {
+            slugs.insert(fqn);
+        }
+        for member in methods(entity).chain(namespace_members(entity)) {
+            Self::collect(member, slugs);
+        }
+    }
+
+    fn link(&self, fqn: &str) -> Option<String> {
+        self.0.get(fqn).map(|slug| format!("#{}", slug))
+    }
+}
+
+fn render_file(source: &Path, entities: &[&Entity], anchors: &Anchors) -> String {
+    let mut out = String::new();
+    writeln!(out, "# {}", source.display()).unwrap();
+
+    for entity in entities {
+        out.push('\n');
+        render_entity(&mut out, entity, 2, anchors);
+    }
+
+    out
+}
+
+fn render_entity(out: &mut String, entity: &Entity, level: usize, anchors: &Anchors) {
+    if let Identity::FQN(fqn) = &entity.identity {
+        writeln!(
+            out,
+            "<a id=\"{}\"></a>",
+            anchors.0.get(fqn).unwrap_or_default()
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "{} {} `{}`",
+        // Markdown only has six levels of headings
+        "#".repeat(level.min(6)),
+        kind_label(entity),
+        entity.title.as_deref().unwrap_or_default()
+    )
+    .unwrap();
+
+    if let Some(signature) = signature(entity) {
+        writeln!(out, "\n```{}\n{}\n```", entity.language, signature).unwrap();
+    }
+
+    if entity.meta.get("deprecated") == Some(&true.into()) {
+        out.push_str("\n**Deprecated**");
+        if let Some(message) = entity.meta["deprecation_message"].as_str() {
+            write!(out, ": {}", message).unwrap();
+        }
+        out.push('\n');
+    }
+
+    if !entity.description.is_empty() {
+        writeln!(out, "\n{}", entity.description.trim_end()).unwrap();
+    }
+
+    if matches!(entity.kind.as_str(), "alias" | "reexport") {
+        let target = match &entity.identity {
+            Identity::Reference(fqn) => Some(fqn.as_str()),
+            _ => entity.meta["target"].as_str(),
+        };
+        if let Some(target) = target {
+            let code = format!("`{}`", target);
+            match anchors.link(target) {
+                Some(link) => writeln!(out, "\n**Alias of:** [{}]({})", code, link).unwrap(),
+                None => writeln!(out, "\n**Alias of:** {}", code).unwrap(),
+            }
+        }
+    }
+
+    let bases = entity
+        .members
+        .iter()
+        .filter(|m| {
+            matches!(
+                m.member_context,
+                Some(MemberContext::Extends | MemberContext::Base)
+            )
+        })
+        .map(|m| type_markdown(m, anchors))
+        .collect::<Vec<_>>();
+    if !bases.is_empty() {
+        writeln!(out, "\n**Extends:** {}", bases.join(", ")).unwrap();
+    }
+
+    let parameters = entity
+        .members
+        .iter()
+        .filter(|m| m.kind == "parameter")
+        .collect::<Vec<_>>();
+    if !parameters.is_empty() {
+        out.push_str("\n**Parameters**\n\n");
+        for parameter in parameters {
+            write!(
+                out,
+                "- `{}`",
+                parameter.title.as_deref().unwrap_or_default()
+            )
+            .unwrap();
+            if let Some(the_type) = parameter.members.first() {
+                write!(out, ": {}", type_markdown(the_type, anchors)).unwrap();
+            }
+            if !parameter.description.is_empty() {
+                write!(out, " - {}", single_line(&parameter.description)).unwrap();
+            }
+            out.push('\n');
+        }
+    }
+
+    if let Some(return_type) = return_type(entity) {
+        writeln!(
+            out,
+            "\n**Returns:** {}",
+            type_markdown(return_type, anchors)
+        )
+        .unwrap();
+        if let Some(returns) = entity.meta["returns"].as_str() {
+            writeln!(out, "\n{}", returns).unwrap();
+        }
+    }
+
+    let properties = properties(entity).collect::<Vec<_>>();
+    if !properties.is_empty() {
+        out.push_str("\n| Property | Type | Description |\n| --- | --- | --- |\n");
+        for property in properties {
+            writeln!(
+                out,
+                "| `{}` | {} | {} |",
+                property.title.as_deref().unwrap_or_default(),
+                property
+                    .members
+                    .first()
+                    .map(|t| type_markdown(t, anchors))
+                    .unwrap_or_default()
+                    .replace('|', "\\|"),
+                single_line(&property.description).replace('|', "\\|"),
+            )
+            .unwrap();
+        }
+    }
+
+    for member in methods(entity).chain(namespace_members(entity)) {
+        out.push('\n');
+        render_entity(out, member, level + 1, anchors);
+    }
+}
+
+/// Renders a type as inline code, linking it to its definition if the
+/// document has one.
+fn type_markdown(entity: &Entity, anchors: &Anchors) -> String {
+    let code = format!("`{}`", type_text(entity));
+
+    let link = match &entity.identity {
+        Identity::Reference(fqn) => anchors.link(fqn),
+        _ => None,
+    };
+    if let Some(link) = link {
+        return format!("[{}]({})", code, link);
+    }
+
+    // Link the members of compound types, like `Circle | Polygon`
+    let linked_members = entity
+        .members
+        .iter()
+        .filter(|m| matches!(&m.identity, Identity::Reference(fqn) if anchors.link(fqn).is_some()))
+        .map(|m| type_markdown(m, anchors))
+        .collect::<Vec<_>>();
+
+    if matches!(entity.kind.as_str(), "union" | "intersection") && !linked_members.is_empty() {
+        let separator = if entity.kind == "union" { " | " } else { " & " };
+        return entity
+            .members
+            .iter()
+            .map(|m| type_markdown(m, anchors))
+            .collect::<Vec<_>>()
+            .join(separator);
+    }
+
+    code
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Context, Options};
+
+    fn fixture_entities() -> Vec<Entity> {
+        // Relative paths, so the output doesn't depend on where the repository is
+        crate::parse_paths(
+            &[
+                PathBuf::from("../fixtures/typescript"),
+                PathBuf::from("../fixtures/cli/mixed/greeter.py"),
+            ],
+            &Options::default(),
+        )
+        .unwrap()
+    }
+
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/render/snapshots")
+            .join(name);
+
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, actual).unwrap();
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            actual, expected,
+            "Snapshot {} is out of date. Run with UPDATE_SNAPSHOTS=1 to update it.",
+            name
+        );
+    }
+
+    #[test]
+    fn renders_a_single_document() {
+        assert_snapshot("single.md", &render_single(&fixture_entities()));
+    }
+
+    #[test]
+    fn renders_a_document_per_file() {
+        let documents = render_documents(&fixture_entities());
+
+        let paths = documents
+            .iter()
+            .map(|d| d.output_path())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("fixtures/cli/mixed/greeter.py.md"),
+                PathBuf::from("fixtures/typescript/geometry.ts.md"),
+                PathBuf::from("fixtures/typescript/shapes.ts.md"),
+            ]
+        );
+
+        assert_snapshot("shapes.ts.md", &documents[2].content);
+    }
+
+    #[test]
+    fn links_references_to_entities_in_the_document() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file(
+            "users.ts",
+            "type Id = string;\n\ninterface User {\n  id: Id;\n  email: string;\n}\n",
+        );
+        let mut ctx = Context::new().with_file_source(files);
+
+        let entities = crate::parse_files(
+            &[PathBuf::from("users.ts")],
+            &[crate::Language::TypeScript],
+            &mut ctx,
+        )
+        .unwrap();
+        let output = render_single(&entities);
+
+        assert!(output.contains("<a id=\"users-ts-id\"></a>\n## type `Id`"));
+        assert!(output.contains("| `id` | [`Id`](#users-ts-id) |  |"));
+        assert!(output.contains("| `email` | `string` |  |"));
+    }
+
+    #[test]
+    fn renders_namespace_members_and_aliases() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file(
+            "shapes.ts",
+            "namespace Shapes {\n  export interface Triangle {}\n  export import Tri = Shapes.Triangle;\n}\n",
+        );
+        let mut ctx = Context::new().with_file_source(files);
+
+        let entities = crate::parse_files(
+            &[PathBuf::from("shapes.ts")],
+            &[crate::Language::TypeScript],
+            &mut ctx,
+        )
+        .unwrap();
+        let output = render_single(&entities);
+
+        assert!(output.contains("## namespace `Shapes`"));
+        assert!(
+            output.contains("<a id=\"shapes-ts-shapes-triangle\"></a>\n### interface `Triangle`")
+        );
+        assert!(output.contains(
+            "### alias `Tri`\n\n**Alias of:** [`shapes.ts::Shapes::Triangle`](#shapes-ts-shapes-triangle)"
+        ));
+    }
+
+    #[test]
+    fn renders_python_generics_and_unions() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file(
+            "users.py",
+            "def find(ids: list[int], name: str | None) -> Optional[dict[str, int]]:\n    pass\n",
+        );
+        let mut ctx = Context::new().with_file_source(files);
+
+        let entities = crate::parse_files(
+            &[PathBuf::from("users.py")],
+            &[crate::Language::Python],
+            &mut ctx,
+        )
+        .unwrap();
+        let output = render_single(&entities);
+        assert!(output
+            .contains("def find(ids: list[int], name: str | None) -> Optional[dict[str, int]]\n"));
+
+        // Without the signature as written, it is made from the parsed types
+        let mut entities = entities;
+        entities[0]
+            .meta
+            .as_object_mut()
+            .unwrap()
+            .remove("signature");
+        let output = render_single(&entities);
+        assert!(output
+            .contains("def find(ids: list[int], name: str | None) -> dict[str, int] | None\n"));
+    }
+
+    #[test]
+    fn suffixes_anchors_of_colliding_names() {
+        let mut files = dossier_core::InMemoryFileSystem::new();
+        files.add_file(
+            "api.ts",
+            "type user = string;
+
+interface User {
+  id: user;
+}
+",
+        );
+        let mut ctx = Context::new().with_file_source(files);
+
+        let entities = crate::parse_files(
+            &[PathBuf::from("api.ts")],
+            &[crate::Language::TypeScript],
+            &mut ctx,
+        )
+        .unwrap();
+        let output = render_single(&entities);
+
+        assert!(output.contains("<a id=\"api-ts-user\"></a>\n## type `user`"));
+        assert!(output.contains("<a id=\"api-ts-user-2\"></a>\n## interface `User`"));
+        assert!(output.contains("| `id` | [`user`](#api-ts-user) |  |"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Renderers that turn entities into documents for people to read.
+//!
+//! Renderers only look at `Entity` trees, so they work the same for every
+//! language.
+use std::fmt::Write;
+
+use dossier_core::{Entity, MemberContext};
+
+pub mod html;
+pub mod markdown;
+
+/// See `dossier_core::slug` for how slugs are made, and kept unique
+pub use dossier_core::slug::slugify;
+
+pub(crate) fn kind_label(entity: &Entity) -> &str {
+    match (entity.kind.as_str(), &entity.member_context) {
+        ("function", Some(MemberContext::Method)) => "method",
+        ("type_alias", _) => "type",
+        ("reexport", _) if entity.meta["type_only"] == true => "type export",
+        ("reexport", _) => "export",
+        (kind, _) => kind,
+    }
+}
+
+/// Properties of interfaces and object types, and fields and attributes of
+/// classes
+pub(crate) fn properties(entity: &Entity) -> impl Iterator<Item = &Entity> {
+    let object = entity.members.iter().find(|m| m.kind == "object");
+    let own_members = match entity.kind.as_str() {
+        "class" | "interface" => entity.members.as_slice(),
+        _ => &[],
+    };
+
+    own_members
+        .iter()
+        .chain(object.into_iter().flat_map(|o| o.members.iter()))
+        .filter(|m| matches!(m.kind.as_str(), "property" | "field" | "attribute"))
+}
+
+pub(crate) fn methods(entity: &Entity) -> impl Iterator<Item = &Entity> {
+    entity.members.iter().filter(|m| {
+        m.kind == "method"
+            || (m.kind == "function" && m.member_context == Some(MemberContext::Method))
+    })
+}
+
+pub(crate) fn namespace_members(entity: &Entity) -> impl Iterator<Item = &Entity> {
+    let members = match entity.kind.as_str() {
+        "namespace" => entity.members.as_slice(),
+        _ => &[],
+    };
+
+    members.iter()
+}
+
+pub(crate) fn return_type(entity: &Entity) -> Option<&Entity> {
+    entity
+        .members
+        .iter()
+        .find(|m| m.member_context == Some(MemberContext::ReturnType))
+}
+
+/// The declaration of an entity as written, from its `meta.signature`.
+/// Functions, methods, and type aliases without one have theirs
+/// reconstructed from their members.
+pub(crate) fn signature(entity: &Entity) -> Option<String> {
+    if let Some(signature) = entity.meta.get("signature").and_then(|s| s.as_str()) {
+        return Some(signature.to_owned());
+    }
+
+    let name = entity.title.as_deref()?;
+
+    if entity.kind == "type_alias" {
+        let the_type = entity.members.iter().find(|m| !is_type_parameter(m))?;
+        return Some(format!(
+            "type {}{} = {}",
+            name,
+            type_parameters(entity),
+            type_text(the_type)
+        ));
+    }
+
+    if !matches!(entity.kind.as_str(), "function" | "method") {
+        return None;
+    }
+
+    let parameters = entity
+        .members
+        .iter()
+        .filter(|m| m.kind == "parameter")
+        .map(|p| {
+            let name = p.title.as_deref().unwrap_or_default();
+            let optional = if p.meta.get("optional") == Some(&true.into()) {
+                "?"
+            } else {
+                ""
+            };
+            let rest = if p.meta.get("rest") == Some(&true.into()) {
+                "..."
+            } else {
+                ""
+            };
+            let default = match p.meta.get("default").and_then(|d| d.as_str()) {
+                Some(default) => format!(" = {}", default),
+                None => String::new(),
+            };
+            match p.members.first() {
+                Some(t) => format!("{}{}{}: {}{}", rest, name, optional, type_text(t), default),
+                None => format!("{}{}{}", rest, name, default),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let return_type = return_type(entity).map(type_text);
+
+    if entity.language == "py" {
+        let returns = return_type.map(|t| format!(" -> {}", t));
+        return Some(format!(
+            "def {}({}){}",
+            name,
+            parameters,
+            returns.unwrap_or_default()
+        ));
+    }
+
+    let keyword = if entity.kind == "function" {
+        "function "
+    } else {
+        ""
+    };
+    let returns = return_type.map(|t| format!(": {}", t));
+
+    Some(format!(
+        "{}{}{}({}){}",
+        keyword,
+        name,
+        type_parameters(entity),
+        parameters,
+        returns.unwrap_or_default()
+    ))
+}
+
+fn is_type_parameter(entity: &Entity) -> bool {
+    matches!(entity.kind.as_str(), "type_variable" | "type_constraint") && entity.title.is_some()
+}
+
+/// E.g. `<T extends Point = Point>`
+fn type_parameters(entity: &Entity) -> String {
+    let parameters = entity
+        .members
+        .iter()
+        .filter(|m| is_type_parameter(m))
+        .map(|p| {
+            let mut out = p.title.clone().unwrap_or_default();
+            for constraint in &p.members {
+                if constraint.member_context == Some(MemberContext::Default) {
+                    write!(out, " = {}", type_text(constraint)).unwrap();
+                } else if let Some(the_type) = constraint.members.first() {
+                    let keyword = if constraint.meta.get("extends") == Some(&true.into()) {
+                        "extends"
+                    } else {
+                        "="
+                    };
+                    write!(out, " {} {}", keyword, type_text(the_type)).unwrap();
+                }
+            }
+            out
+        })
+        .collect::<Vec<_>>();
+
+    if parameters.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", parameters.join(", "))
+    }
+}
+
+/// Reconstructs the source text of a type, as well as we can.
+pub(crate) fn type_text(entity: &Entity) -> String {
+    let members = || entity.members.iter().map(type_text).collect::<Vec<_>>();
+
+    match entity.kind.as_str() {
+        "union" => members().join(" | "),
+        "intersection" => members().join(" & "),
+        "array_type" => format!("{}[]", members().join("")),
+        "tuple" => format!("[{}]", members().join(", ")),
+        "keyof" => format!("keyof {}", members().join("")),
+        "parenthesized_type" => format!("({})", members().join("")),
+        "conditional_type" => match members().as_slice() {
+            [checked, extends, true_branch, false_branch] => format!(
+                "{} extends {} ? {} : {}",
+                checked, extends, true_branch, false_branch
+            ),
+            _ => "conditional_type".to_owned(),
+        },
+        "generic_type" if entity.language == "py" => format!(
+            "{}[{}]",
+            entity.title.as_deref().unwrap_or_default(),
+            members().join(", ")
+        ),
+        "generic_type" => format!(
+            "{}<{}>",
+            entity.title.as_deref().unwrap_or_default(),
+            members().join(", ")
+        ),
+        "object" => {
+            let properties = entity
+                .members
+                .iter()
+                .map(|p| match p.members.first() {
+                    Some(t) => format!(
+                        "{}: {}",
+                        p.title.as_deref().unwrap_or_default(),
+                        type_text(t)
+                    ),
+                    None => p.title.clone().unwrap_or_default(),
+                })
+                .collect::<Vec<_>>();
+
+            if properties.is_empty() {
+                "{}".to_owned()
+            } else {
+                format!("{{ {} }}", properties.join("; "))
+            }
+        }
+        kind => entity.title.clone().unwrap_or_else(|| kind.to_owned()),
+    }
+}
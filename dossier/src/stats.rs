@@ -0,0 +1,274 @@
+//! Documentation coverage of the parsed entities, for `--stats`.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use dossier::{Entity, ParserRegistry};
+use dossier_core::Identity;
+
+/// Kinds of entities that declare something, and can have documentation of
+/// their own. Types, parameters, and type variables are documented as part
+/// of the declaration they belong to, so they don't count.
+const DECLARATION_KINDS: &[&str] = &[
+    "class",
+    "field",
+    "function",
+    "interface",
+    "method",
+    "namespace",
+    "property",
+    "type_alias",
+    "variable",
+];
+
+/// Counts of the declarations in some files
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Counts {
+    /// How many declarations there are of each kind
+    pub by_kind: BTreeMap<String, usize>,
+    /// Declarations with a description
+    pub documented: usize,
+    /// Declarations that are part of the public API
+    pub public: usize,
+    /// Public declarations with a description
+    pub public_documented: usize,
+    /// The FQNs of public declarations without a description
+    pub undocumented: Vec<String>,
+}
+
+impl Counts {
+    pub fn declarations(&self) -> usize {
+        self.by_kind.values().sum()
+    }
+
+    /// The percentage of public declarations that have a description. With no
+    /// public declarations, there is nothing left to document.
+    pub fn coverage(&self) -> f64 {
+        if self.public == 0 {
+            return 100.0;
+        }
+
+        self.public_documented as f64 / self.public as f64 * 100.0
+    }
+
+    fn add(&mut self, other: &Counts) {
+        for (kind, count) in &other.by_kind {
+            *self.by_kind.entry(kind.clone()).or_default() += count;
+        }
+        self.documented += other.documented;
+        self.public += other.public;
+        self.public_documented += other.public_documented;
+        self.undocumented.extend(other.undocumented.iter().cloned());
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{} declarations, {} documented. {} of {} public declarations documented ({:.1}%)",
+            self.declarations(),
+            self.documented,
+            self.public_documented,
+            self.public,
+            self.coverage()
+        )
+    }
+}
+
+/// Declaration counts per file, and for all of them
+#[derive(Debug, Default)]
+pub(crate) struct Stats {
+    pub files: BTreeMap<PathBuf, Counts>,
+    pub total: Counts,
+}
+
+impl Stats {
+    /// Counts the declarations in `entities`, with what `parsers` consider
+    /// public
+    pub fn collect(entities: &[Entity], parsers: &ParserRegistry) -> Self {
+        let mut stats = Stats::default();
+
+        for entity in entities {
+            let counts = stats.files.entry(entity.source.file.clone()).or_default();
+            count(entity, None, parsers, counts);
+        }
+
+        for counts in stats.files.values() {
+            stats.total.add(counts);
+        }
+
+        stats
+    }
+
+    /// The report printed by `--stats`, optionally listing the FQNs of the
+    /// undocumented public declarations.
+    pub fn report(&self, list_undocumented: bool) -> String {
+        let mut out = String::new();
+
+        for (file, counts) in &self.files {
+            writeln!(out, "{}", file.display()).unwrap();
+            write_counts(&mut out, counts);
+        }
+
+        writeln!(out, "Total").unwrap();
+        write_counts(&mut out, &self.total);
+
+        if list_undocumented && !self.total.undocumented.is_empty() {
+            writeln!(out, "\nUndocumented public declarations:").unwrap();
+            for fqn in &self.total.undocumented {
+                writeln!(out, "  {}", fqn).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+fn write_counts(out: &mut String, counts: &Counts) {
+    let kinds = counts
+        .by_kind
+        .iter()
+        .map(|(kind, count)| format!("{} {}", kind, count))
+        .collect::<Vec<_>>();
+
+    if !kinds.is_empty() {
+        writeln!(out, "  {}", kinds.join(", ")).unwrap();
+    }
+    writeln!(out, "  {}", counts.summary()).unwrap();
+}
+
+/// Counts the entity and its members, if it and all of its ancestors are
+/// public. `parent` is the parent entity and whether it is public.
+fn count(
+    entity: &Entity,
+    parent: Option<(&Entity, bool)>,
+    parsers: &ParserRegistry,
+    counts: &mut Counts,
+) {
+    let public = parent.map_or(true, |(_, public)| public)
+        && parsers.is_public(entity, parent.map(|(parent, _)| parent));
+
+    // Inherited members refer to the declaration they were copied from, and
+    // types to what they name, so only counting FQNs counts each one once
+    if let Identity::FQN(fqn) = &entity.identity {
+        if DECLARATION_KINDS.contains(&entity.kind.as_str()) {
+            let documented = !entity.description.trim().is_empty();
+
+            *counts.by_kind.entry(entity.kind.clone()).or_default() += 1;
+            counts.documented += documented as usize;
+
+            if public {
+                counts.public += 1;
+                counts.public_documented += documented as usize;
+                if !documented {
+                    counts.undocumented.push(fqn.clone());
+                }
+            }
+        }
+    }
+
+    for member in &entity.members {
+        count(member, Some((entity, public)), parsers, counts);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::test_utils::{entity, fqn, EntityBuilder, Visibility};
+
+    fn sample() -> Vec<Entity> {
+        let factor = entity("parameter", fqn("a.ts::Shape::resize::factor"))
+            .language("ts")
+            .file("a.ts");
+
+        vec![
+            entity("class", fqn("a.ts::Shape"))
+                .language("ts")
+                .file("a.ts")
+                .description("A shape.")
+                .visibility(Visibility::Exported)
+                .members(vec![
+                    entity("method", fqn("a.ts::Shape::resize"))
+                        .language("ts")
+                        .file("a.ts")
+                        .visibility(Visibility::Exported)
+                        .members(vec![factor]),
+                    entity("field", fqn("a.ts::Shape::cache"))
+                        .language("ts")
+                        .file("a.ts")
+                        .visibility(Visibility::Private),
+                ]),
+            entity("function", fqn("b.ts::area"))
+                .language("ts")
+                .file("b.ts")
+                .description("The area.")
+                .visibility(Visibility::Exported),
+            entity("function", fqn("b.ts::helper"))
+                .language("ts")
+                .file("b.ts"),
+        ]
+    }
+
+    #[test]
+    fn counts_declarations_by_kind_and_file() {
+        let stats = Stats::collect(&sample(), &ParserRegistry::default());
+
+        let a = &stats.files[&PathBuf::from("a.ts")];
+        assert_eq!(
+            a.by_kind,
+            BTreeMap::from([
+                ("class".to_owned(), 1),
+                ("field".to_owned(), 1),
+                ("method".to_owned(), 1),
+            ])
+        );
+        assert_eq!(a.documented, 1);
+
+        assert_eq!(stats.total.declarations(), 5);
+        assert_eq!(stats.total.by_kind["function"], 2);
+        assert_eq!(stats.total.documented, 2);
+    }
+
+    #[test]
+    fn measures_coverage_of_public_declarations() {
+        let stats = Stats::collect(&sample(), &ParserRegistry::default());
+
+        // The private field and the function that isn't exported don't count
+        assert_eq!(stats.total.public, 3);
+        assert_eq!(stats.total.public_documented, 2);
+        assert_eq!(stats.total.undocumented, ["a.ts::Shape::resize"]);
+        assert_eq!(format!("{:.1}", stats.total.coverage()), "66.7");
+
+        assert_eq!(Counts::default().coverage(), 100.0);
+    }
+
+    #[test]
+    fn members_of_private_declarations_are_not_public() {
+        let class = entity("class", fqn("a.ts::Internal"))
+            .language("ts")
+            .file("a.ts")
+            .members(vec![entity("method", fqn("a.ts::Internal::run"))
+                .language("ts")
+                .file("a.ts")
+                .visibility(Visibility::Exported)]);
+
+        let stats = Stats::collect(&[class], &ParserRegistry::default());
+        assert_eq!(stats.total.declarations(), 2);
+        assert_eq!(stats.total.public, 0);
+    }
+
+    #[test]
+    fn lists_undocumented_declarations_on_request() {
+        let stats = Stats::collect(&sample(), &ParserRegistry::default());
+
+        let report = stats.report(false);
+        assert!(report.starts_with("a.ts\n  class 1, field 1, method 1\n"));
+        assert!(report.contains(
+            "Total\n  class 1, field 1, function 2, method 1\n  \
+             5 declarations, 2 documented. 2 of 3 public declarations documented (66.7%)\n"
+        ));
+        assert!(!report.contains("a.ts::Shape::resize"));
+
+        let report = stats.report(true);
+        assert!(report.ends_with("Undocumented public declarations:\n  a.ts::Shape::resize\n"));
+    }
+}
@@ -0,0 +1,141 @@
+//! How long parsing took and what it produced, for `--timings`.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use dossier::Entity;
+use dossier_core::{tree, Instrumentation};
+use serde_json::{json, Value};
+
+/// Collects the time each file took to parse, as reported by the parsers
+#[derive(Debug, Default)]
+pub(crate) struct Timings {
+    files: Mutex<Vec<FileTiming>>,
+}
+
+#[derive(Debug, Clone)]
+struct FileTiming {
+    path: PathBuf,
+    language: String,
+    duration: Duration,
+}
+
+impl Instrumentation for Timings {
+    fn file_parsed(&self, path: &Path, language: &str, duration: Duration) {
+        self.files.lock().unwrap().push(FileTiming {
+            path: path.to_owned(),
+            language: language.to_owned(),
+            duration,
+        });
+    }
+}
+
+impl Timings {
+    /// The report as JSON: the parse time of each file, the totals of each
+    /// language, how many entities there are of each kind, including the
+    /// nested ones, and the peak memory use where it is known.
+    ///
+    /// Durations are in milliseconds, and `total_ms` is the time the whole
+    /// run took, not the sum of the files, which are parsed in parallel.
+    pub fn report(&self, entities: &[Entity], total: Duration) -> Value {
+        let mut files = self.files.lock().unwrap().clone();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut languages = BTreeMap::<&str, LanguageTotals>::new();
+        for file in &files {
+            let totals = languages.entry(&file.language).or_default();
+            totals.files += 1;
+            totals.duration += file.duration;
+        }
+
+        let mut kinds = BTreeMap::<&str, usize>::new();
+        for node in tree::walk(entities) {
+            *kinds.entry(&node.entity.kind).or_default() += 1;
+            languages.entry(&node.entity.language).or_default().entities += 1;
+        }
+
+        json!({
+            "total_ms": millis(total),
+            "files": files
+                .iter()
+                .map(|file| json!({
+                    "file": file.path,
+                    "language": file.language,
+                    "duration_ms": millis(file.duration),
+                }))
+                .collect::<Vec<_>>(),
+            "languages": languages
+                .iter()
+                .map(|(language, totals)| (language.to_string(), json!({
+                    "files": totals.files,
+                    "duration_ms": millis(totals.duration),
+                    "entities": totals.entities,
+                })))
+                .collect::<serde_json::Map<_, _>>(),
+            "entities": kinds,
+            "peak_memory_bytes": peak_memory(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct LanguageTotals {
+    files: usize,
+    duration: Duration,
+    entities: usize,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The most memory the process has had resident so far. Only known on Linux,
+/// where the kernel keeps track of it.
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kilobytes = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim();
+
+    kilobytes.parse::<u64>().ok().map(|kb| kb * 1024)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dossier_core::test_utils::{entity, EntityBuilder};
+    use dossier_core::Identity;
+
+    #[test]
+    fn totals_files_and_entities_per_language() {
+        let timings = Timings::default();
+        timings.file_parsed(Path::new("b.ts"), "ts", Duration::from_millis(2));
+        timings.file_parsed(Path::new("a.ts"), "ts", Duration::from_millis(3));
+        timings.file_parsed(Path::new("c.py"), "py", Duration::from_millis(5));
+
+        let entities = vec![
+            entity("class", Identity::Anonymous)
+                .language("ts")
+                .members(vec![entity("method", Identity::Anonymous).language("ts")]),
+            entity("class", Identity::Anonymous).language("py"),
+        ];
+        let report = timings.report(&entities, Duration::from_millis(7));
+
+        assert_eq!(report["total_ms"], 7.0);
+        assert_eq!(report["files"][0]["file"], "a.ts");
+        assert_eq!(report["files"][0]["duration_ms"], 3.0);
+        assert_eq!(
+            report["languages"]["ts"],
+            json!({ "files": 2, "duration_ms": 5.0, "entities": 2 })
+        );
+        assert_eq!(
+            report["languages"]["py"],
+            json!({ "files": 1, "duration_ms": 5.0, "entities": 1 })
+        );
+        assert_eq!(report["entities"], json!({ "class": 2, "method": 1 }));
+    }
+}
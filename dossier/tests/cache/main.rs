@@ -0,0 +1,83 @@
+use dossier::paths::{Cache, CacheError};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+/// Set in the processes `holds_the_cache_in_another_process` starts, to the
+/// cache directory they use
+const CHILD_CACHE_DIR: &str = "DOSSIER_TEST_CHILD_CACHE_DIR";
+
+/// Waits for `path` to exist, for at most ten seconds
+fn wait_for(path: &Path) -> bool {
+    let start = Instant::now();
+    while !path.exists() {
+        if start.elapsed() > Duration::from_secs(10) {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    true
+}
+
+/// Runs this test binary again as the other process, running only
+/// `holds_the_cache_in_another_process`
+fn spawn_other_process(cache: &Path) -> Child {
+    Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "holds_the_cache_in_another_process"])
+        .env(CHILD_CACHE_DIR, cache)
+        .spawn()
+        .unwrap()
+}
+
+/// The other process: opens the cache, locks it and keeps a temporary file in
+/// it until told to stop. Does nothing when run as a test of its own.
+#[test]
+fn holds_the_cache_in_another_process() {
+    let Some(root) = std::env::var_os(CHILD_CACHE_DIR).map(PathBuf::from) else {
+        return;
+    };
+
+    let cache = Cache::open(&root).unwrap();
+    let lock = cache.lock_exclusive().unwrap();
+    std::fs::write(cache.temp_dir().unwrap().join("partial.json"), "{").unwrap();
+    std::fs::write(root.join("child-ready"), "").unwrap();
+
+    assert!(wait_for(&root.join("child-release")));
+    drop(lock);
+}
+
+#[test]
+fn two_processes_share_one_cache_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    let mut child = spawn_other_process(root);
+    assert!(
+        wait_for(&root.join("child-ready")),
+        "the other process never started"
+    );
+    let child_temp_dir = std::fs::read_dir(root.join("tmp"))
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with(&format!("{}-", child.id()))
+        })
+        .expect("the other process has a temporary directory");
+
+    // Both runs can use the cache at once, and this one doesn't take the
+    // other's temporary files for leftovers of a crashed run
+    let cache = Cache::open(root).unwrap();
+    assert!(child_temp_dir.join("partial.json").exists());
+
+    // But only one of them can write to it
+    let err = cache.lock_exclusive().unwrap_err();
+    assert!(matches!(err, CacheError::Locked(_)));
+
+    std::fs::write(root.join("child-release"), "").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert!(cache.lock_exclusive().is_ok());
+    assert!(!child_temp_dir.exists());
+}
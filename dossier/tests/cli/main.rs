@@ -0,0 +1,845 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::PathBuf;
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures")
+}
+
+fn dossier() -> Command {
+    Command::cargo_bin("dossier").unwrap()
+}
+
+fn parse_stdout(output: &[u8]) -> Vec<serde_json::Value> {
+    let output: serde_json::Value =
+        serde_json::from_slice(output).expect("stdout was not valid JSON");
+    dossier_core::format::entities_of(&output)
+        .expect("stdout was not in the current format")
+        .clone()
+}
+
+fn titles(entities: &[serde_json::Value]) -> Vec<&str> {
+    let mut titles = entities
+        .iter()
+        .map(|e| e["title"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn parses_mixed_typescript_and_python_inputs() {
+    let mixed = fixtures().join("cli/mixed");
+
+    let output = dossier()
+        .arg(mixed.join("shapes.ts"))
+        .arg(mixed.join("greeter.py"))
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Processed 2 files in"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let entities = parse_stdout(&output);
+
+    assert_eq!(titles(&entities), vec!["Greeter", "Point", "distance"]);
+}
+
+#[test]
+fn names_files_relative_to_the_root() {
+    let mixed = fixtures().join("cli/mixed");
+
+    let output = dossier()
+        .arg(mixed.join("shapes.ts"))
+        .arg(mixed.join("greeter.py"))
+        .arg("--root")
+        .arg(&mixed)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let entities = parse_stdout(&output);
+    let point = entities.iter().find(|e| e["title"] == "Point").unwrap();
+    assert_eq!(point["fqn"], "shapes.ts::Point");
+    assert_eq!(point["source"]["file"], "shapes.ts");
+    let greeter = entities.iter().find(|e| e["title"] == "Greeter").unwrap();
+    assert_eq!(greeter["fqn"], "greeter.py::Greeter");
+    assert_eq!(greeter["source"]["file"], "greeter.py");
+}
+
+#[test]
+fn skips_large_and_generated_files() {
+    let output = dossier()
+        .arg(fixtures().join("inputs"))
+        .args(["--max-file-size", "1024", "--skip-generated"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning[large-file]:"))
+        .stderr(predicate::str::contains("constants.ts"))
+        .stderr(predicate::str::contains("warning[generated-file]:"))
+        .stderr(predicate::str::contains("Processed 1 files in"))
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(titles(&parse_stdout(&output)), vec!["Point"]);
+
+    // Without a limit, and without skipping generated files
+    let output = dossier()
+        .arg(fixtures().join("inputs"))
+        .args(["--max-file-size", "0"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Processed 4 files in"))
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(parse_stdout(&output).len(), 63);
+}
+
+#[test]
+fn canonical_output_is_identical_across_thread_counts() {
+    use std::hash::{Hash, Hasher};
+
+    let hash_run = |extra_args: &[&str]| {
+        let output = dossier()
+            .arg("--canonical-output")
+            .args(extra_args)
+            .arg(fixtures().join("typescript"))
+            .arg(fixtures().join("python"))
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+
+        assert!(!output.contains(&b'\r'));
+        assert!(!String::from_utf8_lossy(&output)
+            .lines()
+            .any(|l| l.ends_with(char::is_whitespace)));
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        output.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    assert_eq!(hash_run(&[]), hash_run(&["--jobs", "1"]));
+}
+
+#[test]
+fn public_only_leaves_out_private_symbols() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("api.ts"),
+        "interface Result { ok: boolean }\nfunction helper() {}\nexport function run(): Result { return { ok: true }; }\n",
+    )
+    .unwrap();
+
+    let output = dossier()
+        .arg(dir.path())
+        .arg("--public-only")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(titles(&parse_stdout(&output)), vec!["Result", "run"]);
+}
+
+#[test]
+fn walks_directory_arguments() {
+    let output = dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let entities = parse_stdout(&output);
+
+    assert_eq!(titles(&entities), vec!["Greeter", "Point", "distance"]);
+}
+
+#[test]
+fn respects_dossierignore_in_directory_arguments() {
+    let output = dossier()
+        .arg(fixtures().join("ignore"))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(titles(&parse_stdout(&output)), vec!["User", "findUser"]);
+
+    let output = dossier()
+        .arg("--no-ignore")
+        .arg(fixtures().join("ignore"))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(
+        titles(&parse_stdout(&output)),
+        vec!["User", "findUser", "generatedClient"]
+    );
+}
+
+#[test]
+fn parses_only_the_selected_languages() {
+    let output = dossier()
+        .args(["--language", "py"])
+        .arg(fixtures().join("cli/mixed"))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(titles(&parse_stdout(&output)), vec!["Greeter"]);
+
+    dossier()
+        .args(["--language", "ts,rb"])
+        .arg(fixtures().join("cli/mixed"))
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Unknown language `rb`, expected one of: ts, tsx, mts, cts, py",
+        ));
+}
+
+#[test]
+fn languages_select_all_of_their_extensions() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("id.ts"), "export type Id = string;").unwrap();
+    std::fs::write(
+        dir.path().join("app.tsx"),
+        "export function App() { return <main />; }",
+    )
+    .unwrap();
+
+    let parse = |language: &str| {
+        let output = dossier()
+            .args(["--language", language])
+            .arg(dir.path())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let mut titles = titles(&parse_stdout(&output))
+            .into_iter()
+            .map(|t| t.to_owned())
+            .collect::<Vec<_>>();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(parse("ts"), ["App", "Id"]);
+    assert_eq!(parse("tsx"), ["App"]);
+}
+
+#[test]
+fn selects_entity_kinds() {
+    let shapes = fixtures().join("cli/mixed/shapes.ts");
+    let all = dossier().arg(&shapes).output().unwrap();
+    let selected = dossier()
+        .args(["--kinds", "interface,function"])
+        .arg(&shapes)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert!(selected.len() < all.stdout.len());
+    let entities = parse_stdout(&selected);
+    assert_eq!(titles(&entities), vec!["Point", "distance"]);
+    assert!(entities.iter().all(|e| e.get("members").is_none()));
+
+    let output = dossier()
+        .args(["--kinds", "function", "--include-signatures"])
+        .arg(&shapes)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let entities = parse_stdout(&output);
+    assert_eq!(titles(&entities), vec!["distance"]);
+    let contexts = entities[0]["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["member_context"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(contexts, ["parameter", "parameter", "return_type"]);
+    // `Point` was left out, so the parameters' types no longer refer to it
+    let parameter_type = &entities[0]["members"][0]["members"][0];
+    assert!(parameter_type.get("refers_to").is_none());
+
+    dossier()
+        .args(["--kinds", "function,enum"])
+        .arg(&shapes)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Unknown kind `enum`, expected one of:",
+        ));
+}
+
+#[test]
+fn nonexistent_input_is_a_usage_error() {
+    dossier()
+        .arg(fixtures().join("cli/does-not-exist.ts"))
+        .assert()
+        .code(2)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("No such file or directory"))
+        .stderr(predicate::str::contains("does-not-exist.ts"));
+}
+
+#[test]
+fn unreadable_input_is_a_parse_failure() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("binary.ts");
+    std::fs::write(&path, [0xff, 0xfe, 0x00, 0xc3]).unwrap();
+
+    dossier()
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("Could not read"))
+        .stderr(predicate::str::contains("binary.ts"));
+}
+
+#[test]
+fn writes_a_markdown_document_per_source_file() {
+    let out_dir = tempfile::tempdir().unwrap();
+
+    dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .args(["--format", "markdown", "--out-dir"])
+        .arg(out_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let documents = walkdir(out_dir.path());
+    assert_eq!(documents.len(), 2);
+
+    let shapes = documents
+        .iter()
+        .find(|p| p.ends_with("cli/mixed/shapes.ts.md"))
+        .expect("no document for shapes.ts");
+    let content = std::fs::read_to_string(shapes).unwrap();
+    assert!(content.contains("## interface `Point`"));
+    assert!(content.contains("function distance(a: Point, b: Point): number"));
+}
+
+#[test]
+fn prints_a_single_markdown_document() {
+    dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .args(["--format", "markdown", "--single-file"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## class `Greeter`"))
+        .stdout(predicate::str::contains("## function `distance`"));
+}
+
+#[test]
+fn markdown_without_an_output_is_a_usage_error() {
+    dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .args(["--format", "markdown"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("--out-dir"));
+}
+
+#[test]
+fn writes_an_html_site() {
+    let out_dir = tempfile::tempdir().unwrap();
+
+    dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .args(["--format", "html", "--out"])
+        .arg(out_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let index = std::fs::read_to_string(out_dir.path().join("index.html")).unwrap();
+    assert!(index.contains("<code>Greeter</code>"));
+
+    let pages = walkdir(out_dir.path());
+    assert_eq!(pages.len(), 4);
+
+    let distance = pages
+        .iter()
+        .find(|p| p.to_string_lossy().ends_with("shapes-ts-distance.html"))
+        .expect("no page for distance");
+    let content = std::fs::read_to_string(distance).unwrap();
+    // References to entities in the site link to their pages
+    assert!(content.contains("-shapes-ts-point.html\"><code>Point</code></a>"));
+}
+
+#[test]
+fn html_without_an_output_directory_is_a_usage_error() {
+    dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .args(["--format", "html"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--out-dir"));
+}
+
+#[test]
+fn check_reports_the_same_diagnostics_without_output() {
+    // A low limit makes every fixture look minified, so there are warnings
+    let run = |extra_args: &[&str]| {
+        let output = dossier()
+            .args(["--max-line-length", "20"])
+            .args(extra_args)
+            .arg(fixtures().join("typescript"))
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        (output.stdout, stderr)
+    };
+    let diagnostics = |stderr: &str| {
+        stderr
+            .lines()
+            .filter(|l| l.starts_with("warning"))
+            .map(|l| l.to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let (parse_stdout, parse_stderr) = run(&[]);
+    let (check_stdout, check_stderr) = run(&["--check"]);
+
+    assert!(!parse_stdout.is_empty());
+    assert!(check_stdout.is_empty());
+    assert!(!diagnostics(&parse_stderr).is_empty());
+    assert_eq!(diagnostics(&check_stderr), diagnostics(&parse_stderr));
+    assert!(check_stderr.contains("Checked 2 files in"));
+}
+
+#[test]
+fn reports_diagnostics_in_json_and_denies_them() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("broken.ts"),
+        "import { Config } from \"./missing\";\nexport function load(config: Config) {}\nexport interface Broken { name string }\n",
+    )
+    .unwrap();
+
+    // Diagnostics go to stderr by default, and don't fail the run
+    dossier()
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning[syntax-error]"))
+        .stderr(predicate::str::contains("warning[unresolved-import]"));
+
+    let output = dossier()
+        .arg(dir.path())
+        .args(["--format", "json-with-diagnostics"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning[").not())
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let entities = json["entities"].as_array().unwrap();
+    assert!(titles(entities).contains(&"load"));
+    let codes = json["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["code"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(codes, ["syntax-error", "unresolved-import"]);
+    assert_eq!(json["diagnostics"][0]["severity"], "warning");
+
+    let output = dossier()
+        .arg(dir.path())
+        .args(["--deny", "warnings"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("--deny warnings"))
+        .get_output()
+        .stdout
+        .clone();
+    assert!(!parse_stdout(&output).is_empty());
+
+    dossier()
+        .arg(fixtures().join("cli/mixed"))
+        .args(["--deny", "warnings"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_with_an_output_format_is_a_usage_error() {
+    dossier()
+        .args(["--check", "--format", "html"])
+        .arg(fixtures().join("typescript"))
+        .assert()
+        .code(2);
+}
+
+fn walkdir(dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(walkdir(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+#[test]
+fn missing_arguments_is_a_usage_error() {
+    dossier()
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Usage"));
+}
+
+#[test]
+fn leaves_the_cache_dir_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache");
+
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin("dossier"))
+        .env("DOSSIER_CACHE_DIR", &cache)
+        .arg(fixtures().join("cli/mixed"))
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("warning"));
+    // Nothing is stored there yet, so it isn't even created
+    assert!(!cache.exists());
+}
+
+#[test]
+fn prints_version() {
+    dossier()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn prints_help() {
+    dossier()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--no-ignore"));
+}
+
+#[test]
+fn selects_documentation_for_a_locale() {
+    let run = |locale: &str| {
+        let output = dossier()
+            .args(["--doc-locale", locale])
+            .arg(fixtures().join("locale"))
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+
+        parse_stdout(&output)
+    };
+    let find = |entities: &[serde_json::Value], title: &str| {
+        entities
+            .iter()
+            .find(|e| e["title"] == title)
+            .cloned()
+            .unwrap()
+    };
+
+    let japanese = run("ja");
+    let greet = find(&japanese, "greet");
+    assert_eq!(greet["description"], "名前でユーザーに挨拶します。");
+    assert_eq!(
+        greet["meta"]["available_locales"],
+        serde_json::json!(["ja"])
+    );
+    assert_eq!(
+        find(&japanese, "Greeter")["description"],
+        "ユーザーに挨拶します。"
+    );
+
+    // Untranslated documentation is used as-is
+    let farewell = find(&japanese, "farewell");
+    assert_eq!(farewell["description"], "Says goodbye.");
+    assert!(farewell["meta"].get("available_locales").is_none());
+
+    // Locales without a translation fall back to the default text
+    let german = run("de");
+    assert_eq!(
+        find(&german, "greet")["description"],
+        "Greets a user by name."
+    );
+    assert_eq!(find(&german, "Greeter")["description"], "Greets users.");
+}
+
+#[test]
+fn stats_fail_below_the_minimum_coverage() {
+    let shapes = fixtures().join("cli/mixed/shapes.ts");
+
+    // The properties of `Point` are public, but not documented
+    dossier()
+        .args(["--stats", "--list-undocumented", "--min-coverage", "60"])
+        .arg(&shapes)
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains(
+            "2 of 4 public declarations documented (50.0%)",
+        ))
+        .stdout(predicate::str::contains("shapes.ts::Point::x\n"))
+        .stderr(predicate::str::contains("less than the minimum of 60%"));
+
+    dossier()
+        .args(["--stats", "--min-coverage", "50"])
+        .arg(&shapes)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Point::x").not());
+}
+
+#[test]
+fn versions_the_output_format() {
+    let shapes = fixtures().join("cli/mixed/shapes.ts");
+    let output = dossier().arg(&shapes).output().unwrap().stdout;
+
+    let text = String::from_utf8(output.clone()).unwrap();
+    assert!(text.starts_with("{\n  \"format_version\": "));
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["format_version"], dossier_core::FORMAT_VERSION);
+    assert_eq!(
+        json["generator"],
+        format!("dossier {}", env!("CARGO_PKG_VERSION"))
+    );
+
+    let legacy = dossier()
+        .arg("--legacy-output")
+        .arg(&shapes)
+        .output()
+        .unwrap()
+        .stdout;
+    let legacy: serde_json::Value = serde_json::from_slice(&legacy).unwrap();
+    assert_eq!(legacy, json["entities"]);
+    assert_eq!(
+        dossier_core::format::entities_of(&legacy),
+        Err(dossier_core::format::FormatError::UnsupportedVersion(1))
+    );
+}
+
+#[test]
+fn diff_reports_breaking_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let versions = [
+        ("old", "export function area(width: number, height?: number): number { return 0; }\nexport const unit = \"cm\";\n"),
+        ("new", "export function area(width: number, height: number): number { return 0; }\nexport function perimeter(): number { return 0; }\n"),
+    ];
+    for (version, source) in versions {
+        let version_dir = dir.path().join(version);
+        std::fs::create_dir(&version_dir).unwrap();
+        std::fs::write(version_dir.join("shapes.ts"), source).unwrap();
+
+        let output = dossier()
+            .arg(version_dir.join("shapes.ts"))
+            .arg("--root")
+            .arg(&version_dir)
+            .output()
+            .unwrap()
+            .stdout;
+        std::fs::write(dir.path().join(format!("{}.json", version)), output).unwrap();
+    }
+
+    dossier()
+        .arg("diff")
+        .arg(dir.path().join("old.json"))
+        .arg(dir.path().join("new.json"))
+        .assert()
+        .success()
+        .stdout(
+            "changed  function shapes.ts::area\n    \
+             parameter `height` no longer optional\n\
+             removed  variable shapes.ts::unit\n\
+             added    function shapes.ts::perimeter\n\
+             \n1 added, 1 removed, 1 changed. 2 breaking\n",
+        );
+
+    let output = dossier()
+        .args(["diff", "--format", "json", "--fail-on", "breaking"])
+        .arg(dir.path().join("old.json"))
+        .arg(dir.path().join("new.json"))
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("breaking changes"))
+        .get_output()
+        .stdout
+        .clone();
+    let changes: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(changes["changes"][1]["status"], "removed");
+    assert_eq!(changes["changes"][1]["fqn"], "shapes.ts::unit");
+
+    dossier()
+        .args(["diff", "--fail-on", "breaking"])
+        .arg(dir.path().join("new.json"))
+        .arg(dir.path().join("new.json"))
+        .assert()
+        .success()
+        .stdout("No changes to the public API\n");
+}
+
+#[test]
+fn flat_output_has_the_same_entities_as_the_nested_output() {
+    let fixture = fixtures().join("cli/mixed");
+    let nested = dossier().arg(&fixture).output().unwrap();
+    let flat = dossier().arg("--flat").arg(&fixture).output().unwrap();
+    let flat: serde_json::Value = serde_json::from_slice(&flat.stdout).unwrap();
+
+    let entities = flat["entities"].as_array().unwrap();
+    for (key, position) in flat["index"].as_object().unwrap() {
+        assert_eq!(entities[position.as_u64().unwrap() as usize]["key"], *key);
+    }
+    assert_eq!(flat["index"].as_object().unwrap().len(), entities.len());
+
+    // Put the members back under their parents, in order
+    fn nest(entities: &[serde_json::Value], parent: Option<&str>) -> Vec<serde_json::Value> {
+        entities
+            .iter()
+            .filter(|e| e["parent"].as_str() == parent)
+            .map(|e| {
+                let mut entity = e.clone();
+                let members = nest(entities, e["key"].as_str());
+                let object = entity.as_object_mut().unwrap();
+                object.remove("key");
+                object.remove("parent");
+                if !members.is_empty() {
+                    object.insert("members".to_owned(), members.into());
+                }
+                entity
+            })
+            .collect()
+    }
+
+    assert_eq!(nest(entities, None), parse_stdout(&nested.stdout));
+}
+
+#[test]
+fn output_reads_back_into_the_same_entities() {
+    for fixture in ["cli/mixed", "typescript", "python"] {
+        let output = dossier()
+            .arg(fixtures().join(fixture))
+            .output()
+            .unwrap()
+            .stdout;
+        for json in parse_stdout(&output) {
+            let entity: dossier_core::Entity = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&entity).unwrap(), json);
+        }
+    }
+}
+
+#[test]
+fn writes_a_timings_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let report_path = dir.path().join("timings.json");
+
+    dossier()
+        .arg("--timings")
+        .arg("--timings-out")
+        .arg(&report_path)
+        .arg(fixtures().join("cli/mixed"))
+        .assert()
+        .success();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+
+    let files = report["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    assert!(files[0]["file"].as_str().unwrap().ends_with("greeter.py"));
+    assert_eq!(files[0]["language"], "py");
+    assert!(files[1]["duration_ms"].is_f64());
+
+    assert_eq!(report["languages"]["ts"]["files"], 1);
+    assert_eq!(report["languages"]["py"]["files"], 1);
+    assert_eq!(report["entities"]["interface"], 1);
+    assert_eq!(report["entities"]["function"], 2);
+    assert!(report["total_ms"].is_f64());
+    assert!(report.get("peak_memory_bytes").is_some());
+}
+
+#[test]
+fn watch_runs_again_when_files_change() {
+    use std::io::BufRead;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("user.ts"), "export interface User {}\n").unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("dossier"))
+        .args(["--watch", "--check"])
+        .arg(dir.path())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let stderr = child.stderr.take().unwrap();
+    std::thread::spawn(move || {
+        for line in std::io::BufReader::new(stderr).lines() {
+            let _ = tx.send(line.unwrap());
+        }
+    });
+    let wait_for = |text: &str| loop {
+        let line = rx
+            .recv_timeout(std::time::Duration::from_secs(30))
+            .unwrap_or_else(|_| panic!("Timed out waiting for {:?}", text));
+        if line.contains(text) {
+            break;
+        }
+    };
+
+    wait_for("Checked 1 files");
+    wait_for("Watching for changes");
+
+    std::fs::write(dir.path().join("admin.ts"), "export type Admin = {};\n").unwrap();
+    wait_for("Checked 2 files");
+
+    std::fs::remove_file(dir.path().join("user.ts")).unwrap();
+    wait_for("Checked 1 files");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
@@ -0,0 +1,98 @@
+//! Parses files vendored from real libraries, in `fixtures/golden`, and
+//! compares the entities with snapshots in `tests/golden/snapshots`, so that
+//! any change to the output shows up as a diff to review.
+//!
+//! These are slower than the other tests, so they only run when asked for:
+//!
+//! ```sh
+//! cargo test -p dossier --test golden -- --ignored
+//! ```
+//!
+//! Add `UPDATE_SNAPSHOTS=1` to write new or changed snapshots.
+use dossier::{Entity, Options};
+use dossier_core::{parser_pool, test_utils};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+
+/// Diagnostics that mean part of a file wasn't parsed
+const PARSE_FAILURES: &[&str] = &[
+    dossier_core::SYNTAX_ERROR,
+    parser_pool::PARSE_TIMEOUT,
+    dossier_ts::UNSUPPORTED_TYPE,
+];
+
+/// Parses `files`, relative to the workspace root, as one project. Fails if
+/// parsing panics or doesn't parse all of them, and otherwise compares the
+/// entities of each file with the snapshot named after it.
+fn assert_golden(files: &[&str]) {
+    // Relative to the package, so the paths in the snapshots don't depend on
+    // where the repository is checked out
+    let paths = files
+        .iter()
+        .map(|f| Path::new("..").join(f))
+        .collect::<Vec<_>>();
+    let mut ctx = Options::default().context();
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        dossier::parse_files(&paths, dossier::Language::ALL, &mut ctx)
+    }));
+    let entities = match result {
+        Ok(entities) => entities.unwrap(),
+        Err(_) => panic!("Parsing {:?} panicked", files),
+    };
+
+    let failures = ctx
+        .diagnostics
+        .iter()
+        .filter(|d| PARSE_FAILURES.contains(&d.code.as_str()))
+        .collect::<Vec<_>>();
+    assert!(
+        failures.is_empty(),
+        "Parsing {:?} reported {:#?}",
+        files,
+        failures
+    );
+
+    for path in &paths {
+        let of_file = entities
+            .iter()
+            .filter(|e| e.source.file == *path)
+            .collect::<Vec<&Entity>>();
+        assert!(!of_file.is_empty(), "No entities in {}", path.display());
+
+        let name = path.file_name().unwrap().to_str().unwrap();
+        test_utils::assert_json_snapshot(&snapshots().join(format!("{}.json", name)), &of_file);
+    }
+}
+
+fn snapshots() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/snapshots")
+}
+
+#[test]
+#[ignore = "golden tests, run with --ignored"]
+fn typescript_glob() {
+    assert_golden(&[
+        "fixtures/golden/typescript/glob/glob.d.ts",
+        "fixtures/golden/typescript/glob/index.d.ts",
+        "fixtures/golden/typescript/glob/pattern.d.ts",
+    ]);
+}
+
+#[test]
+#[ignore = "golden tests, run with --ignored"]
+fn python_requests_structures() {
+    assert_golden(&["fixtures/golden/python/requests/structures.py"]);
+}
+
+#[test]
+#[ignore = "golden tests, run with --ignored"]
+fn python_attrs_validators() {
+    assert_golden(&["fixtures/golden/python/attrs/validators.py"]);
+}
+
+#[test]
+#[ignore = "golden tests, run with --ignored"]
+fn python_flask_config() {
+    assert_golden(&["fixtures/python/flask_config.py"]);
+}
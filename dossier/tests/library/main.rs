@@ -0,0 +1,251 @@
+use dossier::{Entity, Language, Options, ParserRegistry};
+use dossier_core::{Identity, InMemoryFileSystem};
+use std::path::PathBuf;
+
+fn mixed_fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures/cli/mixed")
+}
+
+fn summary(entities: &[Entity]) -> Vec<(&str, &str, &str)> {
+    entities
+        .iter()
+        .map(|e| {
+            (
+                e.language.as_str(),
+                e.kind.as_str(),
+                e.title.as_deref().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn parses_and_merges_all_languages_in_a_directory() {
+    let entities = dossier::parse_paths(&[mixed_fixture()], &Options::default()).unwrap();
+
+    assert_eq!(
+        summary(&entities),
+        vec![
+            ("ts", "interface", "Point"),
+            ("ts", "function", "distance"),
+            ("py", "class", "Greeter"),
+        ]
+    );
+
+    let greeter = &entities[2];
+    assert_eq!(greeter.description, "Says hello.");
+    assert_eq!(greeter.members[0].title.as_deref(), Some("greet"));
+}
+
+#[test]
+fn parses_only_the_selected_languages() {
+    let options = Options {
+        languages: vec![Language::Python],
+        ..Default::default()
+    };
+
+    let entities = dossier::parse_paths(&[mixed_fixture()], &options).unwrap();
+
+    assert_eq!(summary(&entities), vec![("py", "class", "Greeter")]);
+}
+
+#[test]
+fn incremental_parser_follows_changes_to_the_files() {
+    let parser = dossier::IncrementalParser::new();
+    let parse = |files: &[(&str, &str)]| {
+        let mut source = InMemoryFileSystem::new();
+        for (path, code) in files {
+            source.add_file(*path, *code);
+        }
+        let paths = files
+            .iter()
+            .map(|(path, _)| path.into())
+            .collect::<Vec<_>>();
+
+        parser
+            .parse_files(
+                &paths,
+                Language::ALL,
+                &mut Options::default().context().with_file_source(source),
+            )
+            .unwrap()
+    };
+    let admin_type = |entities: &[Entity]| {
+        entities
+            .iter()
+            .find(|e| e.title.as_deref() == Some("Admin"))
+            .unwrap()
+            .members[0]
+            .identity
+            .clone()
+    };
+
+    let admin = (
+        "admin.ts",
+        "import { User } from './user';\nexport type Admin = User;\n",
+    );
+    let entities = parse(&[
+        admin,
+        ("user.ts", "/** A user */\nexport interface User {}\n"),
+    ]);
+    assert_eq!(
+        admin_type(&entities),
+        Identity::Reference("user.ts::User".to_owned())
+    );
+
+    let entities = parse(&[
+        admin,
+        ("user.ts", "/** Someone */\nexport interface User {}\n"),
+    ]);
+    let user = entities.iter().find(|e| e.kind == "interface").unwrap();
+    assert_eq!(user.description, "Someone");
+    assert_eq!(
+        admin_type(&entities),
+        Identity::Reference("user.ts::User".to_owned())
+    );
+
+    // References into a deleted file don't resolve anymore
+    let entities = parse(&[admin]);
+    assert_eq!(summary(&entities), vec![("ts", "type_alias", "Admin")]);
+    assert_eq!(admin_type(&entities), Identity::Anonymous);
+}
+
+#[test]
+fn incremental_parser_follows_changes_to_package_names() {
+    let parser = dossier::IncrementalParser::new();
+    let parse = |package: &str| {
+        let mut source = InMemoryFileSystem::new();
+        source.add_file("package.json", package);
+        source.add_file("src/user.ts", "export interface User {}\n");
+        let mut ctx = Options::default().context().with_file_source(source);
+        ctx.module_names = true;
+
+        let entities = parser
+            .parse_files(&[PathBuf::from("src/user.ts")], Language::ALL, &mut ctx)
+            .unwrap();
+        entities[0].identity.clone()
+    };
+
+    assert_eq!(
+        parse(r#"{ "name": "client" }"#),
+        Identity::FQN("client/src/user::User".to_owned())
+    );
+    // The file itself didn't change, but what it is named by did
+    assert_eq!(
+        parse(r#"{ "name": "@myorg/client" }"#),
+        Identity::FQN("@myorg/client/src/user::User".to_owned())
+    );
+}
+
+/// Documents each file as a single entity, titled with its contents
+struct LineParser;
+
+impl dossier::DocsParser for LineParser {
+    fn parse(
+        &self,
+        paths: &[&std::path::Path],
+        ctx: &mut dossier::Context,
+    ) -> dossier::Result<Vec<Entity>> {
+        paths
+            .iter()
+            .map(|path| {
+                let code = ctx
+                    .file_source
+                    .read_file(path)
+                    .map_err(|e| dossier::DossierError::IOError(path.to_path_buf(), e))?;
+                let position = dossier_core::Position {
+                    row: 0,
+                    column: 0,
+                    byte_offset: 0,
+                };
+
+                Ok(Entity {
+                    title: Some(code.trim().to_owned()),
+                    description: String::new(),
+                    kind: "line".to_owned(),
+                    identity: Identity::FQN(ctx.generate_fqn(path, [])),
+                    members: vec![],
+                    member_context: None,
+                    language: "line".to_owned(),
+                    meta: dossier_core::serde_json::json!({}),
+                    source: dossier_core::Source {
+                        file: path.to_path_buf(),
+                        start: position.clone(),
+                        end: position,
+                        repository: None,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn language(&self) -> &'static str {
+        "line"
+    }
+
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["line", "lines"]
+    }
+}
+
+#[test]
+fn parses_files_with_registered_parsers() {
+    let mut source = InMemoryFileSystem::new();
+    source.add_file("notes.line", "Hello");
+    source.add_file("index.ts", "export type Id = string;");
+    source.add_file("main.py", "class Greeter:\n    pass\n");
+    source.add_file("more.lines", "World");
+    let files = ["notes.line", "index.ts", "main.py", "more.lines"].map(PathBuf::from);
+    let mut ctx = Options::default().context().with_file_source(source);
+
+    let mut registry = ParserRegistry::default();
+    registry.add(LineParser);
+    assert_eq!(
+        registry.extensions().collect::<Vec<_>>(),
+        ["ts", "tsx", "mts", "cts", "py", "line", "lines"]
+    );
+
+    let entities = registry.parse_files(&files, &mut ctx).unwrap();
+    assert_eq!(
+        summary(&entities),
+        vec![
+            ("ts", "type_alias", "Id"),
+            ("py", "class", "Greeter"),
+            ("line", "line", "Hello"),
+            ("line", "line", "World"),
+        ]
+    );
+
+    registry.retain(|extension| extension != "py");
+    assert!(registry.parses(std::path::Path::new("notes.line")));
+    assert!(!registry.parses(std::path::Path::new("main.py")));
+
+    let entities = registry.parse_files(&files, &mut ctx).unwrap();
+    assert_eq!(summary(&entities).len(), 3);
+
+    // Registering an extension takes it over from the parser it had
+    registry.register("ts", LineParser);
+    let entities = registry.parse_files(&files, &mut ctx).unwrap();
+    assert_eq!(summary(&entities).len(), 3);
+    assert!(entities.iter().all(|e| e.language == "line"));
+}
+
+#[test]
+fn parsers_decide_what_is_public() {
+    let mut source = InMemoryFileSystem::new();
+    source.add_file("index.ts", "type Id = string;\nexport type Name = string;");
+    source.add_file("notes.line", "Hello");
+    let files = ["index.ts", "notes.line"].map(PathBuf::from);
+    let mut ctx = Options::default().context().with_file_source(source);
+
+    let mut registry = ParserRegistry::default();
+    registry.add(LineParser);
+    let entities = registry.parse_files(&files, &mut ctx).unwrap();
+
+    let public = entities
+        .iter()
+        .map(|e| (e.title.as_deref().unwrap(), registry.is_public(e, None)))
+        .collect::<Vec<_>>();
+    assert_eq!(public, [("Id", false), ("Name", true), ("Hello", true)]);
+    assert_eq!(registry.language_of("tsx"), Some("ts"));
+}
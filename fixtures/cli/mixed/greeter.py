@@ -0,0 +1,6 @@
+class Greeter:
+    """Says hello."""
+
+    def greet(self, name: str) -> str:
+        """Greet someone by name."""
+        return f"Hello, {name}!"
@@ -0,0 +1,14 @@
+/**
+ * A point in 2D space
+ */
+export interface Point {
+  x: number;
+  y: number;
+}
+
+/**
+ * Distance between two points
+ */
+export function distance(a: Point, b: Point): number {
+  return Math.sqrt((a.x - b.x) ** 2 + (a.y - b.y) ** 2);
+}
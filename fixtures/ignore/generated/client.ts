@@ -0,0 +1,2 @@
+// @generated This file is generated. Do not edit.
+export function generatedClient(): void {}
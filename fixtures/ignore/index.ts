@@ -0,0 +1,6 @@
+import { User } from "./src/models.ts";
+
+/**
+ * Looks up a user by name.
+ */
+export function findUser(name: string): User {}
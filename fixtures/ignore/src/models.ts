@@ -0,0 +1,6 @@
+/**
+ * A user of the system.
+ */
+export interface User {
+  name: string;
+}
@@ -0,0 +1,61 @@
+/** A lookup table, large enough to go over a small --max-file-size */
+export const VALUE_0 = 0;
+export const VALUE_1 = 7919;
+export const VALUE_2 = 15838;
+export const VALUE_3 = 23757;
+export const VALUE_4 = 31676;
+export const VALUE_5 = 39595;
+export const VALUE_6 = 47514;
+export const VALUE_7 = 55433;
+export const VALUE_8 = 63352;
+export const VALUE_9 = 71271;
+export const VALUE_10 = 79190;
+export const VALUE_11 = 87109;
+export const VALUE_12 = 95028;
+export const VALUE_13 = 102947;
+export const VALUE_14 = 110866;
+export const VALUE_15 = 118785;
+export const VALUE_16 = 126704;
+export const VALUE_17 = 134623;
+export const VALUE_18 = 142542;
+export const VALUE_19 = 150461;
+export const VALUE_20 = 158380;
+export const VALUE_21 = 166299;
+export const VALUE_22 = 174218;
+export const VALUE_23 = 182137;
+export const VALUE_24 = 190056;
+export const VALUE_25 = 197975;
+export const VALUE_26 = 205894;
+export const VALUE_27 = 213813;
+export const VALUE_28 = 221732;
+export const VALUE_29 = 229651;
+export const VALUE_30 = 237570;
+export const VALUE_31 = 245489;
+export const VALUE_32 = 253408;
+export const VALUE_33 = 261327;
+export const VALUE_34 = 269246;
+export const VALUE_35 = 277165;
+export const VALUE_36 = 285084;
+export const VALUE_37 = 293003;
+export const VALUE_38 = 300922;
+export const VALUE_39 = 308841;
+export const VALUE_40 = 316760;
+export const VALUE_41 = 324679;
+export const VALUE_42 = 332598;
+export const VALUE_43 = 340517;
+export const VALUE_44 = 348436;
+export const VALUE_45 = 356355;
+export const VALUE_46 = 364274;
+export const VALUE_47 = 372193;
+export const VALUE_48 = 380112;
+export const VALUE_49 = 388031;
+export const VALUE_50 = 395950;
+export const VALUE_51 = 403869;
+export const VALUE_52 = 411788;
+export const VALUE_53 = 419707;
+export const VALUE_54 = 427626;
+export const VALUE_55 = 435545;
+export const VALUE_56 = 443464;
+export const VALUE_57 = 451383;
+export const VALUE_58 = 459302;
+export const VALUE_59 = 467221;
@@ -0,0 +1,7 @@
+// @generated by protoc-gen-es v1.4.0 with parameter "target=ts"
+// @generated from file messages.proto (package example, syntax proto3)
+/* eslint-disable */
+
+export interface Greeting {
+  text: string;
+}
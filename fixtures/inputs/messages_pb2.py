@@ -0,0 +1,8 @@
+# -*- coding: utf-8 -*-
+# Generated by the protocol buffer compiler.  DO NOT EDIT!
+# source: messages.proto
+"""Generated protocol buffer code."""
+
+
+class Greeting:
+    text: str
@@ -0,0 +1,5 @@
+/** A point on a plane */
+export interface Point {
+  x: number;
+  y: number;
+}
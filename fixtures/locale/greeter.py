@@ -0,0 +1,10 @@
+class Greeter:
+    """Greets users.
+
+    .. locale:: ja
+    ユーザーに挨拶します。
+    """
+
+    def greet(self, name):
+        """Greets a user by name."""
+        return f"Hello, {name}"
@@ -0,0 +1,16 @@
+/**
+ * Greets a user by name.
+ * @locale ja
+ * 名前でユーザーに挨拶します。
+ * @param name The name to greet
+ */
+export function greet(name: string): string {
+    return `Hello, ${name}`;
+}
+
+/**
+ * Says goodbye.
+ */
+export function farewell(): string {
+    return "Goodbye";
+}
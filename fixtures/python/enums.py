@@ -0,0 +1,23 @@
+from enum import Enum, IntEnum, auto
+
+
+class Color(Enum):
+    """The colors a shape can be filled with."""
+
+    RED = "red"
+    # Also used for the default theme
+    GREEN = "green"
+    BLUE = auto()  # Picked by `auto`
+
+    _ignore_ = ["SECRET"]
+
+    def describe(self) -> str:
+        return f"The color {self.value}"
+
+
+class Priority(IntEnum):
+    """How soon a task has to be done."""
+
+    LOW = 1
+    NORMAL = 2
+    HIGH = 3
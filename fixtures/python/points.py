@@ -0,0 +1,18 @@
+from dataclasses import dataclass, field
+from typing import ClassVar
+
+
+@dataclass
+class Point:
+    """A point on a plane."""
+
+    # Distance from the y axis
+    x: float
+    y: float = 0.0
+    label: str = field(default="origin")
+    tags: list[str] = field(default_factory=list)
+    cache: dict[str, float] = field(default_factory=dict, init=False)
+    dimensions: ClassVar[int] = 2
+
+    def distance(self, other: "Point") -> float:
+        return ((self.x - other.x) ** 2 + (self.y - other.y) ** 2) ** 0.5
@@ -0,0 +1,11 @@
+/**
+ * A point in 2D space
+ */
+export interface Point {
+  x: number;
+  y: number;
+}
+
+export function distance<T extends Point>(a: T, b: T): number {
+  return Math.sqrt((a.x - b.x) ** 2 + (a.y - b.y) ** 2);
+}
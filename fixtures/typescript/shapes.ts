@@ -0,0 +1,22 @@
+import { Point, distance } from "./geometry.ts";
+
+/**
+ * A circle, defined by its center and radius
+ */
+export interface Circle {
+  center: Point;
+  radius: number;
+}
+
+export type Shape = Circle | Polygon;
+
+export class Polygon {
+  points: Point[];
+
+  /**
+   * Sum of the lengths of all the sides
+   */
+  perimeter(): number {
+    return 0;
+  }
+}